@@ -0,0 +1,219 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{config, error};
+
+// How many backups to keep; older ones are pruned when a new one is taken
+pub const KEEP_BACKUPS: usize = 10;
+
+// Files copied into each backup (relative to the config directory)
+const FILES: [&str; 2] = ["config.toml", "tunnels.toml"];
+
+// Why the backup was taken, stored next to the copies
+const REASON_FILE: &str = "reason";
+
+// Set once this process has taken a backup (see snapshot)
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+// A timestamped copy of config.toml and tunnels.toml under backups/<id>/
+#[derive(Debug, Clone)]
+pub struct Backup {
+    // UTC timestamp, e.g. 20261016-153012 (sorts chronologically)
+    pub id: String,
+    pub path: PathBuf,
+    pub reason: String,
+    // Which of the files existed when the backup was taken
+    pub files: Vec<String>,
+}
+
+pub fn backups_dir() -> Result<PathBuf> {
+    Ok(config::config_dir()?.join("backups"))
+}
+
+// Back up the files before the first destructive step of this command, so
+// `ytunnel backup restore` returns to how things were before it ran (bulk deletes
+// take one backup, not one per tunnel)
+pub fn snapshot(reason: &str) -> Result<Option<Backup>> {
+    if TAKEN.swap(true, Ordering::SeqCst) {
+        return Ok(None);
+    }
+    create(reason)
+        .with_context(|| "Failed to back up config.toml and tunnels.toml (nothing was changed)")
+}
+
+// Copy the current files into a new backup and prune old ones. None when there is
+// nothing to back up.
+pub fn create(reason: &str) -> Result<Option<Backup>> {
+    let config_dir = config::config_dir()?;
+    let present: Vec<&str> = FILES
+        .into_iter()
+        .filter(|f| config_dir.join(f).exists())
+        .collect();
+    if present.is_empty() {
+        return Ok(None);
+    }
+
+    let dir = backups_dir()?;
+    let base = timestamp(now_secs());
+    // Two backups within a second get a suffix
+    let (id, path) = (1..)
+        .map(|n| match n {
+            1 => base.clone(),
+            n => format!("{}-{}", base, n),
+        })
+        .map(|id| (id.clone(), dir.join(id)))
+        .find(|(_, path)| !path.exists())
+        .unwrap();
+    fs::create_dir_all(&path)
+        .with_context(|| format!("Failed to create backup directory {}", path.display()))?;
+
+    for file in &present {
+        // fs::copy keeps the permissions, so the credentials stay as private as the originals
+        fs::copy(config_dir.join(file), path.join(file))
+            .with_context(|| format!("Failed to back up {}", file))?;
+    }
+    fs::write(path.join(REASON_FILE), format!("{}\n", reason))?;
+
+    prune(KEEP_BACKUPS)?;
+    Ok(Some(Backup {
+        id,
+        path,
+        reason: reason.to_string(),
+        files: present.into_iter().map(String::from).collect(),
+    }))
+}
+
+// All backups, newest first
+pub fn list() -> Result<Vec<Backup>> {
+    let dir = backups_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let id = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let reason = fs::read_to_string(path.join(REASON_FILE))
+            .map(|r| r.trim().to_string())
+            .unwrap_or_default();
+        let files = FILES
+            .into_iter()
+            .filter(|f| path.join(f).exists())
+            .map(String::from)
+            .collect();
+        backups.push(Backup {
+            id,
+            path,
+            reason,
+            files,
+        });
+    }
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(backups)
+}
+
+pub fn find(id: &str) -> Result<Backup> {
+    list()?.into_iter().find(|b| b.id == id).ok_or_else(|| {
+        error::not_found(format!(
+            "Backup '{}' not found. Run `ytunnel backup list` to see available backups.",
+            id
+        ))
+    })
+}
+
+// Put a backup's files back, first backing up the current ones (returned) so the
+// restore can be undone too. Files the backup doesn't have (e.g. no tunnels yet)
+// are removed, so the directory matches the moment the backup was taken.
+pub fn restore(backup: &Backup) -> Result<Option<Backup>> {
+    if backup.files.is_empty() {
+        bail!("Backup '{}' is empty", backup.id);
+    }
+    // Read everything before taking the new backup, which may prune this one
+    let mut contents = Vec::new();
+    for file in FILES {
+        let saved = if backup.files.iter().any(|f| f == file) {
+            Some(
+                fs::read_to_string(backup.path.join(file))
+                    .with_context(|| format!("Failed to read {} from backup", file))?,
+            )
+        } else {
+            None
+        };
+        contents.push((file, saved));
+    }
+    let _lock = config::lock()?;
+    let previous = create(&format!("before restoring {}", backup.id))?;
+
+    let config_dir = config::config_dir()?;
+    for (file, saved) in contents {
+        let target = config_dir.join(file);
+        match saved {
+            Some(saved) => config::write_atomic(&target, &saved)?,
+            None if target.exists() => fs::remove_file(&target)
+                .with_context(|| format!("Failed to remove {}", target.display()))?,
+            None => {}
+        }
+    }
+    Ok(previous)
+}
+
+// Remove all but the newest `keep` backups
+fn prune(keep: usize) -> Result<()> {
+    for old in list()?.into_iter().skip(keep) {
+        fs::remove_dir_all(&old.path)
+            .with_context(|| format!("Failed to remove old backup {}", old.path.display()))?;
+    }
+    Ok(())
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+// Seconds since the epoch as a UTC YYYYMMDD-HHMMSS stamp
+fn timestamp(secs: u64) -> String {
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp_is_utc_and_sortable() {
+        assert_eq!(timestamp(0), "19700101-000000");
+        assert_eq!(timestamp(951_782_400), "20000229-000000");
+        assert_eq!(timestamp(1_792_153_812), "20261016-123012");
+        assert!(timestamp(1_792_153_812) < timestamp(1_792_153_813));
+    }
+}
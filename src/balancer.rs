@@ -0,0 +1,154 @@
+use anyhow::{Context, Result};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use ytunnel::state::backend_addr;
+
+// How often backends are probed, and how long a probe or connect may take
+const HEALTH_INTERVAL: Duration = Duration::from_secs(5);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+// A local round-robin TCP proxy in front of a tunnel's backends (`ytunnel add` with
+// several comma-separated targets). cloudflared sends requests to it, and it spreads
+// the connections over the backends that accept connections.
+struct Backend {
+    // host:port to connect to
+    addr: String,
+    healthy: AtomicBool,
+}
+
+// Serve on 127.0.0.1:<port> until the process is stopped
+pub async fn run(port: u16, backends: &[String]) -> Result<()> {
+    let backends: Arc<Vec<Backend>> = Arc::new(
+        backends
+            .iter()
+            .map(|target| {
+                Ok(Backend {
+                    addr: backend_addr(target)?,
+                    // Optimistic until the first probe says otherwise
+                    healthy: AtomicBool::new(true),
+                })
+            })
+            .collect::<Result<_>>()?,
+    );
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .with_context(|| format!("Failed to listen on 127.0.0.1:{}", port))?;
+    println!(
+        "Balancing 127.0.0.1:{} across {}",
+        port,
+        backends
+            .iter()
+            .map(|b| b.addr.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    tokio::spawn(probe_backends(backends.clone()));
+
+    let next = Arc::new(AtomicUsize::new(0));
+    loop {
+        let (client, _) = listener.accept().await?;
+        let backends = backends.clone();
+        let next = next.clone();
+        tokio::spawn(async move {
+            if let Some(mut upstream) = connect_next(&backends, &next).await {
+                let mut client = client;
+                tokio::io::copy_bidirectional(&mut client, &mut upstream)
+                    .await
+                    .ok();
+            }
+        });
+    }
+}
+
+// Connect to the next healthy backend in turn, skipping (and marking) ones that
+// refuse. With none healthy every backend is tried, so recovery isn't delayed
+// until the next probe.
+async fn connect_next(backends: &[Backend], next: &AtomicUsize) -> Option<TcpStream> {
+    let start = next.fetch_add(1, Ordering::Relaxed);
+    let order: Vec<&Backend> = (0..backends.len())
+        .map(|i| &backends[(start + i) % backends.len()])
+        .collect();
+    let healthy = order.iter().filter(|b| b.healthy.load(Ordering::Relaxed));
+    let unhealthy = order.iter().filter(|b| !b.healthy.load(Ordering::Relaxed));
+    for backend in healthy.chain(unhealthy) {
+        match connect(&backend.addr).await {
+            Some(stream) => {
+                backend.healthy.store(true, Ordering::Relaxed);
+                return Some(stream);
+            }
+            None => mark(backend, false),
+        }
+    }
+    eprintln!("No backend accepted the connection");
+    None
+}
+
+async fn probe_backends(backends: Arc<Vec<Backend>>) {
+    loop {
+        for backend in backends.iter() {
+            mark(backend, connect(&backend.addr).await.is_some());
+        }
+        tokio::time::sleep(HEALTH_INTERVAL).await;
+    }
+}
+
+// Record a backend's health, logging when it changes
+fn mark(backend: &Backend, healthy: bool) {
+    if backend.healthy.swap(healthy, Ordering::Relaxed) != healthy {
+        println!(
+            "Backend {} is {}",
+            backend.addr,
+            if healthy { "back up" } else { "down" }
+        );
+    }
+}
+
+async fn connect(addr: &str) -> Option<TcpStream> {
+    tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(addr))
+        .await
+        .ok()?
+        .ok()
+}
+
+// Whether a backend accepts connections (for `ytunnel status`)
+pub async fn check_backend(target: &str) -> bool {
+    match backend_addr(target) {
+        Ok(addr) => connect(&addr).await.is_some(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_connections_skip_backends_that_are_down() {
+        let up = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let up_addr = up.local_addr().unwrap().to_string();
+        // Bound then dropped: nothing listens there any more
+        let down_addr = {
+            let down = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            down.local_addr().unwrap().to_string()
+        };
+        let backends = vec![
+            Backend {
+                addr: down_addr,
+                healthy: AtomicBool::new(true),
+            },
+            Backend {
+                addr: up_addr.clone(),
+                healthy: AtomicBool::new(true),
+            },
+        ];
+        let next = AtomicUsize::new(0);
+        for _ in 0..3 {
+            let stream = connect_next(&backends, &next).await.unwrap();
+            assert_eq!(stream.peer_addr().unwrap().to_string(), up_addr);
+        }
+        assert!(!backends[0].healthy.load(Ordering::Relaxed));
+    }
+}
@@ -1,4 +1,7 @@
+use crate::output::FORMATS;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use ytunnel::config::{LOG_LEVELS, PROTOCOLS, THEMES};
 
 #[derive(Parser)]
 #[command(name = "ytunnel")]
@@ -9,6 +12,32 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub account: Option<String>,
 
+    // Set to "never" to skip update checks for this invocation
+    #[arg(long, global = true, value_parser = ["auto", "never"], default_value = "auto")]
+    pub version_check: String,
+
+    // Use this directory for config, state, and tunnel files (overrides YTUNNEL_CONFIG_DIR)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config_dir: Option<PathBuf>,
+
+    // Disable colors in table output (also honors NO_COLOR)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    // Draw the TUI with plain ASCII and no colors (automatic with TERM=dumb)
+    #[arg(long, global = true, visible_alias = "no-unicode")]
+    pub ascii: bool,
+
+    // TUI color theme: dark, light, or high-contrast (overrides [tui] theme; by
+    // default picked from the terminal's background)
+    #[arg(long, global = true, value_parser = THEMES)]
+    pub theme: Option<String>,
+
+    // How list, status, stats, zones, and account list print results: table, json,
+    // yaml, or plain (tab-separated, no headers)
+    #[arg(long, global = true, value_parser = FORMATS, default_value = "table")]
+    pub output: String,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -16,7 +45,19 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     // Initialize ytunnel with your Cloudflare API token
-    Init,
+    //
+    // Examples:
+    //   ytunnel init
+    //   ytunnel init --api-key --email me@example.com   # Global API Key (not recommended)
+    Init {
+        // Authenticate with a Global API Key instead of an API token (prompts for the key)
+        #[arg(long)]
+        api_key: bool,
+
+        // Email address of the Cloudflare login that owns the Global API Key
+        #[arg(long, requires = "api_key")]
+        email: Option<String>,
+    },
 
     // Create and run an ephemeral tunnel (foreground, stops on Ctrl+C)
     //
@@ -24,15 +65,44 @@ pub enum Commands {
     //   ytunnel run localhost:3000                    # auto-generated subdomain
     //   ytunnel run myapp localhost:3000              # myapp.<default-zone>
     //   ytunnel run api -z dev.example.com localhost:8080
+    //   ytunnel run --detect                          # find the local dev server
+    //   ytunnel run myapp --detect --detect-range 3000-3010
+    //   ytunnel run demo localhost:3000 --timeout 30m  # shut down after 30 minutes
     Run {
         // Subdomain name and target. If one argument: target only (auto-generated name).
-        // If two arguments: name and target.
-        #[arg(required = true, num_args = 1..=2)]
+        // If two arguments: name and target. With --detect: at most the name.
+        #[arg(required_unless_present = "detect", num_args = 1..=2)]
         args: Vec<String>,
 
+        // Find the target among localhost ports something listens on (asks when
+        // several are open)
+        #[arg(long)]
+        detect: bool,
+
+        // Ports to scan with --detect, e.g. 3000-3010,8080 (default: [run] detect_ports)
+        #[arg(long, value_name = "PORTS", requires = "detect")]
+        detect_range: Option<String>,
+
         // Zone/domain to use (overrides default)
         #[arg(short, long)]
         zone: Option<String>,
+
+        // Service for requests to other hostnames (default: http_status:404)
+        #[arg(long, value_name = "SERVICE")]
+        fallback: Option<String>,
+
+        // On exit, keep the tunnel and DNS record for the next run instead of asking
+        #[arg(long, conflicts_with = "delete")]
+        keep: bool,
+
+        // On exit, delete the tunnel and DNS record instead of asking
+        #[arg(long)]
+        delete: bool,
+
+        // Shut down after this long, e.g. 90s, 30m, 2h. Unless --keep is given, the
+        // tunnel and DNS record are then deleted without asking
+        #[arg(long, value_name = "DURATION", value_parser = parse_timeout)]
+        timeout: Option<u64>,
     },
 
     // Add a persistent tunnel (non-interactive)
@@ -40,6 +110,7 @@ pub enum Commands {
     // Examples:
     //   ytunnel add myapp localhost:3000
     //   ytunnel add api localhost:8080 -z dev.example.com
+    //   ytunnel add shop localhost:4000 --description "Storefront preview for the client"
     Add {
         // Tunnel name (subdomain part)
         name: String,
@@ -54,30 +125,275 @@ pub enum Commands {
         // Start the tunnel immediately after adding
         #[arg(short, long)]
         start: bool,
+
+        // cloudflared transport protocol (default: let cloudflared decide)
+        #[arg(long, value_parser = PROTOCOLS)]
+        protocol: Option<String>,
+
+        // cloudflared log verbosity (default: cloudflared's own, info)
+        #[arg(long, value_parser = LOG_LEVELS)]
+        log_level: Option<String>,
+
+        // Port for the cloudflared metrics endpoint (default: derived from the name)
+        #[arg(long, value_parser = clap::value_parser!(u16).range(1024..))]
+        metrics_port: Option<u16>,
+
+        // Service for requests to other hostnames, e.g. http_status:403 or a URL
+        // (default: http_status:404)
+        #[arg(long, value_name = "SERVICE")]
+        fallback: Option<String>,
+
+        // After starting, wait until the hostname responds (timeout in seconds, default 60)
+        #[arg(long, value_name = "SECS", num_args = 0..=1, default_missing_value = "60", requires = "start")]
+        wait_healthy: Option<u64>,
+
+        // Extra hostname served by the same tunnel, as zone=subdomain or a full hostname
+        // (repeatable or comma-separated)
+        #[arg(
+            long = "hostname",
+            value_name = "ZONE=SUBDOMAIN",
+            value_delimiter = ','
+        )]
+        hostnames: Vec<String>,
+
+        // Keep partially created resources if a step fails (default: roll back)
+        #[arg(long)]
+        no_rollback: bool,
+
+        // Replace existing DNS records (e.g. A records) at the hostname instead of refusing
+        #[arg(long, conflicts_with = "no_dns")]
+        force: bool,
+
+        // Don't create or touch DNS records (they are managed elsewhere)
+        #[arg(long)]
+        no_dns: bool,
+
+        // Note describing what the tunnel is for
+        #[arg(long)]
+        description: Option<String>,
+
+        // Apply defaults from a [presets.<name>] table in config.toml
+        #[arg(long)]
+        preset: Option<String>,
+    },
+
+    // Create a new tunnel with the settings of an existing one
+    //
+    // Copies the zone, protocol, log level, fallback, description, auto-start and
+    // DNS management; the new tunnel gets its own Cloudflare tunnel, credentials,
+    // DNS record and daemon. Extra hostnames and the metrics port aren't copied.
+    //
+    // Examples:
+    //   ytunnel clone api api-staging
+    //   ytunnel clone api api-v2 --target localhost:8081 --start
+    Clone {
+        // Tunnel to copy settings from
+        source: String,
+
+        // Name for the new tunnel (subdomain part)
+        name: String,
+
+        // Target service for the new tunnel (default: the source's target)
+        #[arg(long)]
+        target: Option<String>,
+
+        // Zone/domain to use (default: the source's zone)
+        #[arg(short, long)]
+        zone: Option<String>,
+
+        // Start the tunnel immediately after cloning
+        #[arg(short, long)]
+        start: bool,
     },
 
     // Start a stopped tunnel
+    //
+    // A name with wildcards (*, ?, [..]) starts every matching tunnel in the account:
+    //   ytunnel start 'staging-*'
     Start {
-        // Tunnel name
+        // Tunnel name or glob pattern
         name: String,
+
+        // Stop managing this tunnel's DNS records (they are managed elsewhere)
+        #[arg(long)]
+        no_dns: bool,
+
+        // Treat the name as a regular expression (matched against the whole name)
+        #[arg(long)]
+        regex: bool,
+
+        // Skip the Cloudflare DNS check and start with the records as they are
+        // (also happens automatically when the API can't be reached)
+        #[arg(long, conflicts_with = "verify_dns")]
+        offline: bool,
+
+        // Check (and repair) the DNS records even if they were verified within
+        // the last 24 hours
+        #[arg(long)]
+        verify_dns: bool,
+
+        // If the Cloudflare tunnel was deleted outside ytunnel, create a new one
+        // (new id and credentials) and repoint the DNS records at it
+        #[arg(long, conflicts_with = "offline")]
+        force_recreate: bool,
     },
 
     // Stop a running tunnel
+    //
+    // A name with wildcards (*, ?, [..]) stops every matching tunnel in the account:
+    //   ytunnel stop 'staging-*'
+    //   ytunnel stop --regex 'staging-(api|web)'
     Stop {
-        // Tunnel name
+        // Tunnel name or glob pattern
         name: String,
+
+        // Treat the name as a regular expression (matched against the whole name)
+        #[arg(long)]
+        regex: bool,
+
+        // Kill leftover cloudflared processes immediately instead of after a grace period
+        #[arg(long)]
+        force: bool,
     },
 
     // Restart a tunnel (stop, update config, start)
+    //
+    // A name with wildcards (*, ?, [..]) restarts every matching tunnel in the account.
     Restart {
+        // Tunnel name or glob pattern
+        name: String,
+
+        // Treat the name as a regular expression (matched against the whole name)
+        #[arg(long)]
+        regex: bool,
+
+        // Skip the Cloudflare zone and DNS checks and restart with the records as
+        // they are (also happens automatically when the API can't be reached)
+        #[arg(long, conflicts_with = "verify_dns")]
+        offline: bool,
+
+        // Check (and repair) the DNS records even if they were verified within
+        // the last 24 hours
+        #[arg(long)]
+        verify_dns: bool,
+
+        // If the Cloudflare tunnel was deleted outside ytunnel, create a new one
+        // (new id and credentials) and repoint the DNS records at it
+        #[arg(long, conflicts_with = "offline")]
+        force_recreate: bool,
+    },
+
+    // Change per-tunnel settings (prints the current settings when no flags are given)
+    //
+    // Examples:
+    //   ytunnel set myapp
+    //   ytunnel set myapp --target localhost:4000 --restart
+    //   ytunnel set myapp --auto-start on --metrics-port 21500
+    //   ytunnel set myapp --unset protocol
+    //   ytunnel set myapp --log-level debug --restart
+    //   ytunnel set myapp --fallback https://example.com
+    //   ytunnel set myapp --description "Webhook receiver for staging"
+    Set {
+        // Tunnel name
+        name: String,
+
+        // Target service (e.g., localhost:3000)
+        #[arg(long)]
+        target: Option<String>,
+
+        // Start the tunnel automatically on login (on/off)
+        #[arg(long, value_name = "on|off", value_parser = clap::builder::BoolishValueParser::new(), hide_possible_values = true)]
+        auto_start: Option<bool>,
+
+        // Port for the cloudflared metrics endpoint
+        #[arg(long, value_parser = clap::value_parser!(u16).range(1024..))]
+        metrics_port: Option<u16>,
+
+        // cloudflared transport protocol
+        #[arg(long, value_parser = PROTOCOLS)]
+        protocol: Option<String>,
+
+        // cloudflared log verbosity
+        #[arg(long, value_parser = LOG_LEVELS)]
+        log_level: Option<String>,
+
+        // Service for requests to other hostnames (e.g., http_status:403, https://example.com)
+        #[arg(long, value_name = "SERVICE")]
+        fallback: Option<String>,
+
+        // Let ytunnel create and clean up the DNS records (on/off)
+        #[arg(long, value_name = "on|off", value_parser = clap::builder::BoolishValueParser::new(), hide_possible_values = true)]
+        manage_dns: Option<bool>,
+
+        // Note describing what the tunnel is for
+        #[arg(long)]
+        description: Option<String>,
+
+        // Reset settings to their defaults (repeatable or comma-separated)
+        #[arg(long, value_delimiter = ',', value_parser = ["metrics_port", "protocol", "log_level", "fallback", "description"])]
+        unset: Vec<String>,
+
+        // Restart the tunnel so the changes take effect
+        #[arg(long)]
+        restart: bool,
+    },
+
+    // Put a tunnel into maintenance mode or take it out again
+    //
+    // While on, every hostname answers 503 (or is routed to --origin, e.g. a
+    // maintenance page); off restores the target. Running tunnels are restarted.
+    //
+    // Examples:
+    //   ytunnel maintenance myapp on
+    //   ytunnel maintenance myapp on --origin localhost:8081
+    //   ytunnel maintenance myapp off
+    Maintenance {
+        // Tunnel name
+        name: String,
+
+        // Turn maintenance mode on or off
+        #[arg(value_name = "on|off", action = clap::ArgAction::Set, value_parser = clap::builder::BoolishValueParser::new(), hide_possible_values = true)]
+        enabled: bool,
+
+        // Serve this origin instead of a plain 503 (e.g., localhost:8081)
+        #[arg(long)]
+        origin: Option<String>,
+    },
+
+    // Show a tunnel's status and the hostnames DNS actually routes to it
+    Status {
         // Tunnel name
         name: String,
     },
 
+    // Open a tunnel's public URL in the browser
+    //
+    // Examples:
+    //   ytunnel open myapp
+    //   ytunnel open myapp --print | pbcopy
+    Open {
+        // Tunnel name
+        name: String,
+
+        // Print the URL instead of opening it
+        #[arg(long)]
+        print: bool,
+    },
+
     // View logs for a tunnel
+    //
+    // With --all, every tunnel's log in the account is merged by timestamp and each
+    // line is prefixed with its tunnel's name:
+    //   ytunnel logs --all --lines 200
+    //   ytunnel logs --all -f
     Logs {
         // Tunnel name
-        name: String,
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        name: Option<String>,
+
+        // Merge the logs of all tunnels in the account
+        #[arg(long)]
+        all: bool,
 
         // Follow log output (like tail -f)
         #[arg(short, long)]
@@ -86,28 +402,173 @@ pub enum Commands {
         // Number of lines to show (default: 50)
         #[arg(short, long, default_value = "50")]
         lines: usize,
+
+        // Print lines exactly as cloudflared wrote them (JSON logs are otherwise
+        // formatted on a terminal)
+        #[arg(long)]
+        raw: bool,
     },
 
     // Manage zones/domains
     Zones {
         #[command(subcommand)]
         command: Option<ZonesCommands>,
+
+        // Check each zone against the Cloudflare API and flag ones that are gone
+        #[arg(long)]
+        verify: bool,
+
+        // Output as JSON (same as --output json)
+        #[arg(long)]
+        json: bool,
     },
 
     // List all tunnels (for scripting)
-    List,
+    //
+    // Examples:
+    //   ytunnel list --columns name,status,uptime
+    //   ytunnel list --wide
+    //   ytunnel list --auto-start
+    //   ytunnel list --json
+    List {
+        // Comma-separated columns to show
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_parser = [
+                "name", "hostname", "target", "status", "account", "auto_start", "enabled", "uptime",
+                "pid", "memory", "metrics", "description",
+            ],
+            conflicts_with = "wide"
+        )]
+        columns: Vec<String>,
+
+        // Show every column
+        #[arg(long)]
+        wide: bool,
+
+        // Only show tunnels that start automatically on login
+        #[arg(long)]
+        auto_start: bool,
+
+        // Only show tunnels with an installed daemon (started via ytunnel)
+        #[arg(long)]
+        enabled: bool,
+
+        // Output as JSON (same as --output json: every field, ignores --columns and --wide)
+        #[arg(long, conflicts_with_all = ["columns", "wide"])]
+        json: bool,
+
+        // Compare each tunnel's local state with Cloudflare's view of it (status,
+        // connections, last seen) and flag disagreements
+        #[arg(long, conflicts_with_all = ["columns", "wide"])]
+        remote: bool,
+    },
+
+    // Export running tunnels' cloudflared metrics
+    Metrics {
+        #[command(subcommand)]
+        command: MetricsCommands,
+    },
+
+    // One table of the account's tunnels with their live metrics: requests, errors,
+    // request rate, edge connections and colos, and health. Running tunnels are
+    // scraped concurrently, twice a second apart to measure the rate.
+    //
+    // Examples:
+    //   ytunnel stats
+    //   ytunnel stats --json
+    Stats {
+        // Output as JSON (same as --output json)
+        #[arg(long)]
+        json: bool,
+    },
+
+    // Restart running tunnels whose cloudflared has had no edge connections for
+    // longer than the grace period; runs until interrupted and logs each action to
+    // logs/watchdog.log (all accounts unless --account is given)
+    //
+    // Examples:
+    //   ytunnel watch
+    //   ytunnel watch --interval 10 --grace 120
+    Watch {
+        // Seconds between checks (default: [watchdog] interval_secs, 15)
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        interval: Option<u64>,
+
+        // Seconds without edge connections before restarting (default: [watchdog] grace_secs, 60)
+        #[arg(long, value_name = "SECS")]
+        grace: Option<u64>,
+    },
+
+    // Inspect and clean up DNS records that point at Cloudflare tunnels
+    Dns {
+        #[command(subcommand)]
+        command: DnsCommands,
+    },
+
+    // Check that every managed hostname's CNAME points at its tunnel with no records
+    // shadowing it, and that Cloudflare's view of each tunnel matches the local one
+    Doctor,
 
     // Delete a tunnel
+    //
+    // A name with wildcards (*, ?, [..]) deletes every matching tunnel in the account,
+    // asking first when more than one matches.
     Delete {
-        // Tunnel name (with or without "ytunnel-" prefix)
+        // Tunnel name (with or without the tunnel_prefix, "ytunnel-" by default) or
+        // glob pattern
         name: String,
+
+        // Skip the confirmation when deleting a tunnel ytunnel doesn't manage or
+        // several tunnels matching a pattern
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        // Treat the name as a regular expression (matched against the whole name)
+        #[arg(long)]
+        regex: bool,
+
+        // Re-attempt failed cleanup operations once before reporting
+        #[arg(long)]
+        retry: bool,
+
+        // Keep the tunnel's DNS records (they will 530 until repointed)
+        #[arg(long)]
+        keep_dns: bool,
+    },
+
+    // Delete ytunnel-* tunnels (or the configured tunnel_prefix) left over from
+    // `ytunnel run` in the account
+    //
+    // Lists every ephemeral tunnel not in use (no connector, no live run here,
+    // including runs kept for reuse) and the DNS records pointing at it, then
+    // deletes the tunnels, records, credentials and stale config files.
+    //
+    // Examples:
+    //   ytunnel clean-ephemeral
+    //   ytunnel clean-ephemeral --older-than 7d --yes
+    CleanEphemeral {
+        // Only tunnels created longer ago than this (e.g. 30m, 12h, 7d, 2w)
+        #[arg(long, value_name = "AGE", value_parser = parse_age)]
+        older_than: Option<u64>,
+
+        // Skip confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
     },
 
     // Reset ytunnel configuration (allows re-initializing with new credentials)
+    //
+    // With --account, only that account's tunnels, files, and config entry are removed.
     Reset {
         // Skip confirmation prompt
         #[arg(short = 'y', long)]
         yes: bool,
+
+        // Re-attempt failed cleanup operations once before reporting
+        #[arg(long)]
+        retry: bool,
     },
 
     // Manage Cloudflare accounts
@@ -116,6 +577,13 @@ pub enum Commands {
         command: Option<AccountCommands>,
     },
 
+    // Restore config.toml and tunnels.toml from the backups taken before reset,
+    // account removal, delete, and file migrations
+    Backup {
+        #[command(subcommand)]
+        command: BackupCommands,
+    },
+
     // Launch TUI with fake tunnel data for screenshots and demos (no config required)
     Demo,
 
@@ -125,6 +593,13 @@ pub enum Commands {
         #[arg(long)]
         check: bool,
     },
+
+    // Run a balanced tunnel's local balancer in the foreground (started by its daemon)
+    #[command(hide = true)]
+    Balance {
+        // Tunnel name
+        name: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -144,6 +619,37 @@ pub enum AccountCommands {
         name: String,
     },
 
+    // Pick the default account from a menu (the name is required when stdin
+    // isn't a terminal)
+    Switch {
+        // Account name to select as default, skipping the menu
+        name: Option<String>,
+    },
+
+    // Replace an account's API token (or Global API Key), e.g. after rotating it.
+    // Tunnels, daemons, and DNS records are left untouched; the zone list is refreshed.
+    //
+    // The new value comes from --token, then CLOUDFLARE_API_TOKEN (CLOUDFLARE_API_KEY
+    // for Global API Key accounts), then a prompt (or stdin when it isn't a terminal).
+    //
+    // Examples:
+    //   ytunnel account token work
+    //   CLOUDFLARE_API_TOKEN=... ytunnel account token work
+    Token {
+        // Account name
+        name: String,
+
+        // The new API token (or Global API Key); visible in the process list, so
+        // prefer the environment variable or the prompt
+        #[arg(long)]
+        token: Option<String>,
+
+        // Accept credentials that belong to a different Cloudflare account, and
+        // switch the account over to it
+        #[arg(long)]
+        force: bool,
+    },
+
     // Remove an account
     Remove {
         // Account name to remove
@@ -152,6 +658,31 @@ pub enum AccountCommands {
         // Skip confirmation prompt
         #[arg(short = 'y', long)]
         yes: bool,
+
+        // Re-attempt failed cleanup operations once before reporting
+        #[arg(long)]
+        retry: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BackupCommands {
+    // List backups, newest first
+    List,
+
+    // Replace config.toml and tunnels.toml with a backup (the current files are
+    // backed up first). Daemons, DNS, and Cloudflare tunnels are not touched.
+    Restore {
+        // Backup timestamp, as shown by `ytunnel backup list`
+        timestamp: String,
+
+        // Skip confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        // Restore even while tunnels are running
+        #[arg(long)]
+        force: bool,
     },
 }
 
@@ -161,5 +692,101 @@ pub enum ZonesCommands {
     Default {
         // Domain name to set as default
         domain: String,
+
+        // Skip confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MetricsCommands {
+    // Push each running tunnel's metrics to a Prometheus Pushgateway, grouped by
+    // tunnel and account (all accounts unless --account is given)
+    //
+    // Examples:
+    //   ytunnel metrics push --url http://localhost:9091
+    //   ytunnel metrics push --url http://localhost:9091 --interval 30   # keep pushing
+    Push {
+        // Pushgateway base URL
+        #[arg(long)]
+        url: String,
+
+        // Keep running and push every SECS seconds instead of once
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        interval: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DnsCommands {
+    // List CNAME records pointing at *.cfargotunnel.com
+    List {
+        // Only list records in this zone
+        #[arg(short, long)]
+        zone: Option<String>,
     },
+
+    // Delete a tunnel DNS record by hostname
+    Delete {
+        // Full hostname of the record (e.g., myapp.example.com)
+        hostname: String,
+
+        // Skip confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+}
+
+// An age like 90s, 30m, 12h, 7d, or 2w, in seconds
+fn parse_age(age: &str) -> Result<u64, String> {
+    let split = age.find(|c: char| !c.is_ascii_digit()).unwrap_or(age.len());
+    let (count, unit) = age.split_at(split);
+    let count: u64 = count
+        .parse()
+        .map_err(|_| format!("expected a number and unit like 7d, got '{}'", age))?;
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => return Err(format!("unknown unit in '{}' (use s, m, h, d, or w)", age)),
+    };
+    count
+        .checked_mul(unit_secs)
+        .ok_or_else(|| format!("'{}' is too long", age))
+}
+
+// A --timeout duration: like an age, but more than zero
+fn parse_timeout(timeout: &str) -> Result<u64, String> {
+    match parse_age(timeout)? {
+        0 => Err("the timeout must be longer than 0".to_string()),
+        secs => Ok(secs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("90s"), Ok(90));
+        assert_eq!(parse_age("12h"), Ok(43_200));
+        assert_eq!(parse_age("7d"), Ok(604_800));
+        assert_eq!(parse_age("2w"), Ok(1_209_600));
+        assert!(parse_age("7").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("7y").is_err());
+        assert_eq!(parse_timeout("30m"), Ok(1_800));
+        assert!(parse_timeout("0s").is_err());
+    }
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        // clap only checks argument definitions when a command is parsed
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
 }
@@ -0,0 +1,47 @@
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+// Put text on the system clipboard with the platform's tool: pbcopy on macOS, clip
+// on Windows, and wl-copy (Wayland), xclip, or xsel elsewhere, whichever is installed
+pub fn copy(text: &str) -> io::Result<()> {
+    #[cfg(target_os = "macos")]
+    let candidates: &[&[&str]] = &[&["pbcopy"]];
+    #[cfg(target_os = "windows")]
+    let candidates: &[&[&str]] = &[&["clip"]];
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let candidates: &[&[&str]] = &[
+        &["wl-copy"],
+        &["xclip", "-selection", "clipboard"],
+        &["xsel", "--clipboard", "--input"],
+    ];
+
+    let mut last_err = io::Error::new(io::ErrorKind::NotFound, "no clipboard tool found");
+    for command in candidates {
+        match pipe_to(command, text) {
+            Ok(()) => return Ok(()),
+            Err(e) => last_err = e,
+        }
+    }
+    Err(last_err)
+}
+
+fn pipe_to(command: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(command[0])
+        .args(&command[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "{} exited with {}",
+            command[0], status
+        )))
+    }
+}
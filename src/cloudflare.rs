@@ -1,8 +1,21 @@
 use anyhow::{Context, Result};
 use rand::Rng;
+use reqwest::Method;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-const API_BASE: &str = "https://api.cloudflare.com/client/v4";
+use crate::error;
+
+const DEFAULT_API_BASE: &str = "https://api.cloudflare.com/client/v4";
+
+// Overrides the API base URL, e.g. to point at a mock server in tests
+pub const API_BASE_ENV: &str = "YTUNNEL_API_BASE";
+
+// Per-request timeout unless configured otherwise ([api] timeout_secs)
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Cloudflare error codes that mean the credentials were rejected
+const AUTH_ERROR_CODES: &[u32] = &[1000, 6003, 6111, 9103, 9106, 9109, 10000, 10001];
 
 fn format_errors(errors: &[ApiError]) -> String {
     errors
@@ -12,9 +25,156 @@ fn format_errors(errors: &[ApiError]) -> String {
         .join(", ")
 }
 
+// Build an error from a failed API response, flagging rejected credentials as AuthError
+fn api_error(prefix: &str, errors: &[ApiError], account: Option<&str>) -> anyhow::Error {
+    if errors.iter().any(|e| AUTH_ERROR_CODES.contains(&e.code)) {
+        AuthError {
+            message: format_errors(errors),
+            account: account.map(String::from),
+        }
+        .into()
+    } else {
+        error::api(format!("{}: {}", prefix, format_errors(errors)))
+    }
+}
+
+// The API token was rejected (revoked, expired, or missing permissions)
+#[derive(Debug)]
+pub struct AuthError {
+    pub message: String,
+    // Configured account the rejected credentials belong to, when known
+    pub account: Option<String>,
+}
+
+impl AuthError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            account: None,
+        }
+    }
+}
+
+impl std::fmt::Display for AuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.account {
+            Some(account) => write!(
+                f,
+                "API token for account '{}' is invalid or expired — run `ytunnel account token {}` \
+                 to update it (Cloudflare: {})",
+                account, account, self.message
+            ),
+            None => write!(f, "Cloudflare authentication failed: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+// Check whether an error was caused by rejected credentials
+pub fn is_auth_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<AuthError>())
+}
+
+// The Cloudflare API couldn't be reached at all (no network, DNS failure, or
+// a timeout), as opposed to reached and refusing the request
+#[derive(Debug)]
+pub struct UnreachableError {
+    pub message: String,
+}
+
+impl std::fmt::Display for UnreachableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for UnreachableError {}
+
+// Check whether an error means we're offline rather than rejected
+pub fn is_unreachable(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<UnreachableError>())
+}
+
+// Whether creating/updating a tunnel CNAME at this record's name would overwrite it.
+// Other types (TXT, MX, ...) can't be replaced by a CNAME at all, so the API rejects those.
+fn is_conflicting_record(record: &DnsRecord) -> bool {
+    match record.record_type.as_str() {
+        "A" | "AAAA" => true,
+        "CNAME" => !record.content.ends_with(".cfargotunnel.com"),
+        _ => false,
+    }
+}
+
+// What ensure_dns_record had to do to point a hostname at a tunnel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DnsChange {
+    // There was no CNAME, so one was created
+    Created,
+    // The CNAME pointed somewhere else (`previous`) and was repointed
+    Updated { previous: String },
+    // The CNAME already pointed at the tunnel
+    Unchanged,
+}
+
+impl DnsChange {
+    // One line for the CLI, e.g. "Created DNS record: app.example.com"
+    pub fn describe(&self, hostname: &str) -> String {
+        match self {
+            DnsChange::Created => format!("Created DNS record: {}", hostname),
+            DnsChange::Updated { previous } => {
+                format!("Repointed DNS record: {} (was {})", hostname, previous)
+            }
+            DnsChange::Unchanged => format!("DNS already correct: {}", hostname),
+        }
+    }
+}
+
+// Explain which records are in the way of a tunnel CNAME, one per line
+pub fn describe_dns_conflicts(hostname: &str, records: &[DnsRecord]) -> String {
+    let mut out = format!(
+        "{} has {} DNS record(s) that would take precedence over the tunnel:",
+        hostname,
+        records.len()
+    );
+    for r in records {
+        out.push_str(&format!(
+            "\n  {} {} (id {})",
+            r.record_type, r.content, r.id
+        ));
+    }
+    out
+}
+
+// How requests authenticate: a scoped API token (recommended) or a legacy Global API Key
+#[derive(Debug, Clone)]
+pub enum Credentials {
+    Token(String),
+    ApiKey { key: String, email: String },
+}
+
+trait Authorize {
+    fn authorize(self, credentials: &Credentials) -> Self;
+}
+
+impl Authorize for reqwest::RequestBuilder {
+    fn authorize(self, credentials: &Credentials) -> Self {
+        match credentials {
+            Credentials::Token(token) => self.bearer_auth(token),
+            Credentials::ApiKey { key, email } => {
+                self.header("X-Auth-Key", key).header("X-Auth-Email", email)
+            }
+        }
+    }
+}
+
 pub struct Client {
     http: reqwest::Client,
-    token: String,
+    credentials: Credentials,
+    // Named in auth errors so users know which account's token to replace
+    account_name: Option<String>,
+    base_url: String,
+    timeout: Duration,
 }
 
 #[derive(Debug, Deserialize)]
@@ -28,6 +188,8 @@ pub struct Zone {
 #[derive(Debug, Deserialize)]
 pub struct Account {
     pub id: String,
+    #[serde(default)]
+    pub name: String,
 }
 
 // Flatten for config storage
@@ -47,23 +209,150 @@ pub struct FlatZone {
     pub account_id: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Tunnel {
     pub id: String,
     pub name: String,
     pub deleted_at: Option<String>,
+    // inactive (never run), down, degraded, or healthy
+    #[serde(default)]
+    pub status: Option<String>,
+    // RFC 3339, e.g. 2024-01-15T10:30:00.123456Z
+    #[serde(default)]
+    pub created_at: Option<String>,
+    // Edge connections of every connector (cloudflared) serving the tunnel
+    #[serde(default)]
+    pub connections: Vec<TunnelConnection>,
+    // When the tunnel last went from no connections to some, and back
+    #[serde(default)]
+    pub conns_active_at: Option<String>,
+    #[serde(default)]
+    pub conns_inactive_at: Option<String>,
+}
+
+// One connector's connection to a Cloudflare data center
+#[derive(Debug, Clone, Deserialize)]
+pub struct TunnelConnection {
+    // Data center code, e.g. "ams01"
+    #[serde(default)]
+    pub colo_name: String,
+    #[serde(default)]
+    pub origin_ip: Option<String>,
+    #[serde(default)]
+    pub opened_at: Option<String>,
+    #[serde(default)]
+    pub client_version: Option<String>,
+    // Being replaced by a new connection (e.g. during a cloudflared restart)
+    #[serde(default)]
+    pub is_pending_reconnect: bool,
 }
 
 pub struct TunnelWithCredentials {
     pub tunnel: Tunnel,
     pub credentials_path: std::path::PathBuf,
+    // Whether the tunnel was just created, rather than found in the account
+    pub created: bool,
 }
 
 impl Tunnel {
+    // Whether any cloudflared connector is connected to the edge right now. The
+    // status can lag behind a connector that just connected, so its connections
+    // count too.
+    pub fn is_connected(&self) -> bool {
+        matches!(self.status.as_deref(), Some("healthy" | "degraded"))
+            || self.connections.iter().any(|c| !c.is_pending_reconnect)
+    }
+
+    // Data centers the tunnel is connected to, ignoring connections on their way out
+    pub fn colos(&self) -> Vec<&str> {
+        let mut colos: Vec<&str> = self
+            .connections
+            .iter()
+            .filter(|c| !c.is_pending_reconnect)
+            .map(|c| c.colo_name.as_str())
+            .collect();
+        colos.sort_unstable();
+        colos.dedup();
+        colos
+    }
+
+    // Cloudflare's view, e.g. "healthy (2 connections: ams01, fra06)"
+    pub fn remote_summary(&self) -> String {
+        let status = self.status.as_deref().unwrap_or("unknown");
+        let active = self
+            .connections
+            .iter()
+            .filter(|c| !c.is_pending_reconnect)
+            .count();
+        if active == 0 {
+            return status.to_string();
+        }
+        format!(
+            "{} ({} connection{}: {})",
+            status,
+            active,
+            if active == 1 { "" } else { "s" },
+            self.colos().join(", ")
+        )
+    }
+
     pub fn credentials_path(&self) -> anyhow::Result<std::path::PathBuf> {
-        let config_dir = crate::config::config_dir()?;
-        Ok(config_dir.join(format!("{}.json", self.id)))
+        Ok(crate::config::credentials_dir()?.join(format!("{}.json", self.id)))
+    }
+
+    // Creation time in seconds since the epoch, when the API reported one
+    pub fn created_secs(&self) -> Option<u64> {
+        self.created_at.as_deref().and_then(parse_timestamp)
+    }
+}
+
+// Seconds since the epoch of an RFC 3339 timestamp at the start of `s` (API
+// fields, cloudflared log lines). Both are UTC ("...Z" or "+00:00"), so the
+// offset is not applied.
+pub fn parse_timestamp(s: &str) -> Option<u64> {
+    if s.get(4..5) != Some("-") || s.get(10..11) != Some("T") {
+        return None;
     }
+    let field = |range: std::ops::Range<usize>| s.get(range)?.parse::<u64>().ok();
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    let (hour, min, sec) = (field(11..13)?, field(14..16)?, field(17..19)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || year < 1970 {
+        return None;
+    }
+    // Days since 1970-01-01 from a civil date (Howard Hinnant's algorithm)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = (era * 146_097 + doe).checked_sub(719_468)?;
+    Some(days * 86_400 + hour * 3_600 + min * 60 + sec)
+}
+
+// Seconds since the epoch as an RFC 3339 UTC timestamp, the way cloudflared starts
+// its log lines (so ytunnel's own log lines sort with them)
+pub fn format_timestamp(secs: u64) -> String {
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
 }
 
 #[derive(Debug, Serialize)]
@@ -76,7 +365,7 @@ struct TunnelCredentials {
     tunnel_secret: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[allow(dead_code)]
 pub struct DnsRecord {
     pub id: String,
@@ -84,6 +373,34 @@ pub struct DnsRecord {
     pub content: String,
     #[serde(rename = "type")]
     pub record_type: String,
+    #[serde(default)]
+    pub zone_id: String,
+}
+
+impl DnsRecord {
+    // Tunnel ID this record routes to, if it's a tunnel CNAME
+    pub fn tunnel_id(&self) -> Option<&str> {
+        if self.record_type != "CNAME" {
+            return None;
+        }
+        self.content.strip_suffix(".cfargotunnel.com")
+    }
+}
+
+// Filters for list_dns_records, applied by the API
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DnsRecordFilter<'a> {
+    pub record_type: Option<&'a str>,
+    pub name: Option<&'a str>,
+}
+
+impl DnsRecordFilter<'_> {
+    pub fn cnames() -> Self {
+        Self {
+            record_type: Some("CNAME"),
+            name: None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -91,10 +408,21 @@ struct ApiResponse<T> {
     success: bool,
     result: Option<T>,
     errors: Vec<ApiError>,
+    #[serde(default)]
+    result_info: Option<ResultInfo>,
+}
+
+// Pagination details returned by list endpoints
+#[derive(Debug, Deserialize)]
+struct ResultInfo {
+    #[serde(default)]
+    total_pages: u32,
 }
 
 #[derive(Debug, Deserialize)]
 struct ApiError {
+    #[serde(default)]
+    code: u32,
     message: String,
 }
 
@@ -115,27 +443,94 @@ struct CreateDnsRecordRequest {
 
 impl Client {
     pub fn new(token: &str) -> Self {
+        Self::with_credentials(Credentials::Token(token.to_string()))
+    }
+
+    pub fn with_credentials(credentials: Credentials) -> Self {
+        let base_url = std::env::var(API_BASE_ENV)
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_API_BASE.to_string());
         Self {
             http: reqwest::Client::new(),
-            token: token.to_string(),
+            credentials,
+            account_name: None,
+            base_url: base_url.trim().trim_end_matches('/').to_string(),
+            timeout: DEFAULT_TIMEOUT,
         }
     }
 
+    // Send requests to another API endpoint (mock server, regional gateway)
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn credentials(&self) -> &Credentials {
+        &self.credentials
+    }
+
+    fn request(&self, method: Method, url: &str) -> reqwest::RequestBuilder {
+        self.http
+            .request(method, url)
+            .timeout(self.timeout)
+            .authorize(&self.credentials)
+    }
+
+    fn api_error(&self, prefix: &str, errors: &[ApiError]) -> anyhow::Error {
+        api_error(prefix, errors, self.account_name.as_deref())
+    }
+
+    // Tell a hanging API apart from one that can't be reached at all
+    fn send_error(&self, err: reqwest::Error, action: &str) -> anyhow::Error {
+        let message = if err.is_timeout() {
+            format!(
+                "{}: Cloudflare API did not respond within {}s ({})",
+                action,
+                self.timeout.as_secs_f32(),
+                self.base_url
+            )
+        } else if err.is_connect() {
+            format!(
+                "{}: could not connect to the Cloudflare API at {} (connection refused or \
+                 network unreachable)",
+                action, self.base_url
+            )
+        } else {
+            return anyhow::Error::new(err).context(action.to_string());
+        };
+        UnreachableError { message }.into()
+    }
+
+    // Client using whichever credentials the account was set up with
+    pub fn for_account(account: &crate::config::Account) -> Self {
+        let mut client = Self::with_credentials(account.credentials());
+        client.account_name = Some(account.name.clone());
+        client
+    }
+
     pub async fn list_zones(&self) -> Result<Vec<FlatZone>> {
-        let url = format!("{}/zones", API_BASE);
+        let url = format!("{}/zones", self.base_url);
         let resp: ApiResponse<Vec<Zone>> = self
-            .http
-            .get(&url)
-            .bearer_auth(&self.token)
+            .request(Method::GET, &url)
             .send()
             .await
-            .context("Failed to fetch zones")?
+            .map_err(|e| self.send_error(e, "Failed to fetch zones"))?
             .json()
             .await
             .context("Failed to parse zones response")?;
 
         if !resp.success {
-            anyhow::bail!("Cloudflare API error: {}", format_errors(&resp.errors));
+            return Err(self.api_error("Cloudflare API error", &resp.errors));
         }
 
         Ok(resp
@@ -146,26 +541,70 @@ impl Client {
             .collect())
     }
 
+    // Accounts the token can access; works for account-scoped tokens without zone access
+    pub async fn list_accounts(&self) -> Result<Vec<Account>> {
+        let url = format!("{}/accounts", self.base_url);
+        let resp: ApiResponse<Vec<Account>> = self
+            .request(Method::GET, &url)
+            .send()
+            .await
+            .map_err(|e| self.send_error(e, "Failed to fetch accounts"))?
+            .json()
+            .await
+            .context("Failed to parse accounts response")?;
+
+        if !resp.success {
+            return Err(self.api_error("Cloudflare API error", &resp.errors));
+        }
+
+        Ok(resp.result.unwrap_or_default())
+    }
+
     pub async fn list_tunnels(&self, account_id: &str) -> Result<Vec<Tunnel>> {
-        let url = format!("{}/accounts/{}/cfd_tunnel", API_BASE, account_id);
+        let url = format!("{}/accounts/{}/cfd_tunnel", self.base_url, account_id);
         let resp: ApiResponse<Vec<Tunnel>> = self
-            .http
-            .get(&url)
-            .bearer_auth(&self.token)
+            .request(Method::GET, &url)
             .send()
             .await
-            .context("Failed to fetch tunnels")?
+            .map_err(|e| self.send_error(e, "Failed to fetch tunnels"))?
             .json()
             .await
             .context("Failed to parse tunnels response")?;
 
         if !resp.success {
-            anyhow::bail!("Cloudflare API error: {}", format_errors(&resp.errors));
+            return Err(self.api_error("Cloudflare API error", &resp.errors));
         }
 
         Ok(resp.result.unwrap_or_default())
     }
 
+    // The tunnel with this id, or None once it's gone: Cloudflare answers 404
+    // for unknown ids and still returns deleted tunnels, with deleted_at set
+    pub async fn get_tunnel(&self, account_id: &str, tunnel_id: &str) -> Result<Option<Tunnel>> {
+        let url = format!(
+            "{}/accounts/{}/cfd_tunnel/{}",
+            self.base_url, account_id, tunnel_id
+        );
+        let response = self
+            .request(Method::GET, &url)
+            .send()
+            .await
+            .map_err(|e| self.send_error(e, "Failed to fetch tunnel"))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let resp: ApiResponse<Tunnel> = response
+            .json()
+            .await
+            .context("Failed to parse tunnel response")?;
+
+        if !resp.success {
+            return Err(self.api_error("Cloudflare API error", &resp.errors));
+        }
+
+        Ok(resp.result.filter(|t| t.deleted_at.is_none()))
+    }
+
     pub async fn get_tunnel_by_name(&self, account_id: &str, name: &str) -> Result<Option<Tunnel>> {
         let tunnels = self.list_tunnels(account_id).await?;
         Ok(tunnels
@@ -178,7 +617,7 @@ impl Client {
         account_id: &str,
         name: &str,
     ) -> Result<TunnelWithCredentials> {
-        let url = format!("{}/accounts/{}/cfd_tunnel", API_BASE, account_id);
+        let url = format!("{}/accounts/{}/cfd_tunnel", self.base_url, account_id);
 
         // Generate a random tunnel secret (32 bytes, base64 encoded)
         let mut secret = [0u8; 32];
@@ -191,19 +630,17 @@ impl Client {
         };
 
         let resp: ApiResponse<Tunnel> = self
-            .http
-            .post(&url)
-            .bearer_auth(&self.token)
+            .request(Method::POST, &url)
             .json(&body)
             .send()
             .await
-            .context("Failed to create tunnel")?
+            .map_err(|e| self.send_error(e, "Failed to create tunnel"))?
             .json()
             .await
             .context("Failed to parse create tunnel response")?;
 
         if !resp.success {
-            anyhow::bail!("Failed to create tunnel: {}", format_errors(&resp.errors));
+            return Err(self.api_error("Failed to create tunnel", &resp.errors));
         }
 
         let tunnel = resp.result.context("No tunnel returned from API")?;
@@ -215,9 +652,10 @@ impl Client {
             tunnel_secret: secret_b64,
         };
 
-        let config_dir = crate::config::config_dir()?;
-        std::fs::create_dir_all(&config_dir)?;
-        let credentials_path = config_dir.join(format!("{}.json", tunnel.id));
+        let credentials_path = tunnel.credentials_path()?;
+        if let Some(dir) = credentials_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
 
         let credentials_json = serde_json::to_string_pretty(&credentials)
             .context("Failed to serialize credentials")?;
@@ -231,28 +669,56 @@ impl Client {
         Ok(TunnelWithCredentials {
             tunnel,
             credentials_path,
+            created: true,
+        })
+    }
+
+    // Reuse the account's tunnel with this name, or create it. A reused tunnel
+    // needs the credentials file written when ytunnel created it; cloudflared
+    // can't run it without one.
+    pub async fn create_or_get_tunnel(
+        &self,
+        account_id: &str,
+        name: &str,
+    ) -> Result<TunnelWithCredentials> {
+        let Some(tunnel) = self.get_tunnel_by_name(account_id, name).await? else {
+            return self.create_tunnel(account_id, name).await;
+        };
+        let credentials_path = tunnel.credentials_path()?;
+        if !credentials_path.exists() {
+            anyhow::bail!(
+                "Credentials file not found: {}\n\
+                 Tunnel '{}' may have been created outside ytunnel.\n\
+                 Delete it with `ytunnel delete {}` and try again.",
+                credentials_path.display(),
+                name,
+                crate::config::short_tunnel_name(name).unwrap_or(name)
+            );
+        }
+        Ok(TunnelWithCredentials {
+            tunnel,
+            credentials_path,
+            created: false,
         })
     }
 
     pub async fn delete_tunnel(&self, account_id: &str, tunnel_id: &str) -> Result<()> {
         let url = format!(
             "{}/accounts/{}/cfd_tunnel/{}",
-            API_BASE, account_id, tunnel_id
+            self.base_url, account_id, tunnel_id
         );
 
         let resp: ApiResponse<serde_json::Value> = self
-            .http
-            .delete(&url)
-            .bearer_auth(&self.token)
+            .request(Method::DELETE, &url)
             .send()
             .await
-            .context("Failed to delete tunnel")?
+            .map_err(|e| self.send_error(e, "Failed to delete tunnel"))?
             .json()
             .await
             .context("Failed to parse delete tunnel response")?;
 
         if !resp.success {
-            anyhow::bail!("Failed to delete tunnel: {}", format_errors(&resp.errors));
+            return Err(self.api_error("Failed to delete tunnel", &resp.errors));
         }
 
         Ok(())
@@ -263,58 +729,166 @@ impl Client {
         zone_id: &str,
         hostname: &str,
         tunnel_id: &str,
-    ) -> Result<()> {
+    ) -> Result<DnsChange> {
         let tunnel_cname = format!("{}.cfargotunnel.com", tunnel_id);
 
-        // Check if record exists
-        let existing = self.get_dns_record(zone_id, hostname).await?;
+        // Look at every record for the name, not just CNAMEs: a leftover A record
+        // keeps winning resolution even once the CNAME is right
+        let (conflicts, tunnel_records): (Vec<_>, Vec<_>) = self
+            .dns_records_for(zone_id, hostname)
+            .await?
+            .into_iter()
+            .filter(|r| r.record_type == "CNAME" || is_conflicting_record(r))
+            .partition(is_conflicting_record);
 
-        match existing {
-            Some(record) if record.content == tunnel_cname => {
-                // Already correct
-                Ok(())
-            }
+        // Never clobber a record that isn't a tunnel CNAME
+        if !conflicts.is_empty() {
+            return Err(error::conflict(format!(
+                "{}\nRemove them in the Cloudflare dashboard and try again.",
+                describe_dns_conflicts(hostname, &conflicts)
+            )));
+        }
+
+        if tunnel_records.iter().any(|r| r.content == tunnel_cname) {
+            return Ok(DnsChange::Unchanged);
+        }
+        match tunnel_records.first() {
+            // Repoint the CNAME from another tunnel
             Some(record) => {
-                // Update existing record
                 self.update_dns_record(zone_id, &record.id, hostname, &tunnel_cname)
-                    .await
+                    .await?;
+                Ok(DnsChange::Updated {
+                    previous: record.content.clone(),
+                })
             }
             None => {
-                // Create new record
                 self.create_dns_record(zone_id, hostname, &tunnel_cname)
-                    .await
+                    .await?;
+                Ok(DnsChange::Created)
             }
         }
     }
 
-    async fn get_dns_record(&self, zone_id: &str, name: &str) -> Result<Option<DnsRecord>> {
+    // Get the CNAME record for a hostname, if any
+    pub async fn get_dns_record(&self, zone_id: &str, name: &str) -> Result<Option<DnsRecord>> {
         let url = format!(
             "{}/zones/{}/dns_records?type=CNAME&name={}",
-            API_BASE, zone_id, name
+            self.base_url, zone_id, name
         );
         let resp: ApiResponse<Vec<DnsRecord>> = self
-            .http
-            .get(&url)
-            .bearer_auth(&self.token)
+            .request(Method::GET, &url)
             .send()
             .await
-            .context("Failed to fetch DNS records")?
+            .map_err(|e| self.send_error(e, "Failed to fetch DNS records"))?
             .json()
             .await
             .context("Failed to parse DNS records response")?;
 
         if !resp.success {
-            anyhow::bail!(
-                "Failed to fetch DNS records: {}",
-                format_errors(&resp.errors)
-            );
+            return Err(self.api_error("Failed to fetch DNS records", &resp.errors));
         }
 
         Ok(resp.result.and_then(|records| records.into_iter().next()))
     }
 
+    // Records at a hostname that a tunnel CNAME would replace: anything other
+    // than a CNAME pointing at *.cfargotunnel.com
+    pub async fn find_dns_conflicts(&self, zone_id: &str, name: &str) -> Result<Vec<DnsRecord>> {
+        Ok(self
+            .dns_records_for(zone_id, name)
+            .await?
+            .into_iter()
+            .filter(is_conflicting_record)
+            .collect())
+    }
+
+    // Every record at a hostname, whatever its type
+    pub async fn dns_records_for(&self, zone_id: &str, name: &str) -> Result<Vec<DnsRecord>> {
+        let filter = DnsRecordFilter {
+            record_type: None,
+            name: Some(name),
+        };
+        Ok(self
+            .list_dns_records(zone_id, &filter)
+            .await?
+            .into_iter()
+            .map(|mut r| {
+                r.zone_id = zone_id.to_string();
+                r
+            })
+            .collect())
+    }
+
+    // List DNS records in a zone matching the filter, following pagination
+    pub async fn list_dns_records(
+        &self,
+        zone_id: &str,
+        filter: &DnsRecordFilter<'_>,
+    ) -> Result<Vec<DnsRecord>> {
+        let mut records = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let mut url = format!(
+                "{}/zones/{}/dns_records?per_page=100&page={}",
+                self.base_url, zone_id, page
+            );
+            if let Some(t) = filter.record_type {
+                url.push_str(&format!("&type={}", t));
+            }
+            if let Some(name) = filter.name {
+                url.push_str(&format!("&name={}", name));
+            }
+
+            let resp: ApiResponse<Vec<DnsRecord>> = self
+                .request(Method::GET, &url)
+                .send()
+                .await
+                .map_err(|e| self.send_error(e, "Failed to fetch DNS records"))?
+                .json()
+                .await
+                .context("Failed to parse DNS records response")?;
+
+            if !resp.success {
+                return Err(self.api_error("Failed to fetch DNS records", &resp.errors));
+            }
+
+            let total_pages = resp.result_info.map(|i| i.total_pages).unwrap_or(1);
+            records.extend(resp.result.unwrap_or_default());
+
+            if page >= total_pages {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(records)
+    }
+
+    // CNAMEs in the given zones that route to a tunnel (<tunnel_id>.cfargotunnel.com)
+    pub async fn find_records_pointing_at(
+        &self,
+        zone_ids: &[&str],
+        tunnel_id: &str,
+    ) -> Result<Vec<DnsRecord>> {
+        let mut records = Vec::new();
+        for zone_id in zone_ids {
+            records.extend(
+                self.list_dns_records(zone_id, &DnsRecordFilter::cnames())
+                    .await?
+                    .into_iter()
+                    .filter(|r| r.tunnel_id() == Some(tunnel_id))
+                    .map(|mut r| {
+                        r.zone_id = zone_id.to_string();
+                        r
+                    }),
+            );
+        }
+        Ok(records)
+    }
+
     async fn create_dns_record(&self, zone_id: &str, name: &str, content: &str) -> Result<()> {
-        let url = format!("{}/zones/{}/dns_records", API_BASE, zone_id);
+        let url = format!("{}/zones/{}/dns_records", self.base_url, zone_id);
         let body = CreateDnsRecordRequest {
             record_type: "CNAME".to_string(),
             name: name.to_string(),
@@ -323,22 +897,17 @@ impl Client {
         };
 
         let resp: ApiResponse<DnsRecord> = self
-            .http
-            .post(&url)
-            .bearer_auth(&self.token)
+            .request(Method::POST, &url)
             .json(&body)
             .send()
             .await
-            .context("Failed to create DNS record")?
+            .map_err(|e| self.send_error(e, "Failed to create DNS record"))?
             .json()
             .await
             .context("Failed to parse create DNS record response")?;
 
         if !resp.success {
-            anyhow::bail!(
-                "Failed to create DNS record: {}",
-                format_errors(&resp.errors)
-            );
+            return Err(self.api_error("Failed to create DNS record", &resp.errors));
         }
 
         Ok(())
@@ -351,7 +920,10 @@ impl Client {
         name: &str,
         content: &str,
     ) -> Result<()> {
-        let url = format!("{}/zones/{}/dns_records/{}", API_BASE, zone_id, record_id);
+        let url = format!(
+            "{}/zones/{}/dns_records/{}",
+            self.base_url, zone_id, record_id
+        );
         let body = CreateDnsRecordRequest {
             record_type: "CNAME".to_string(),
             name: name.to_string(),
@@ -360,22 +932,17 @@ impl Client {
         };
 
         let resp: ApiResponse<DnsRecord> = self
-            .http
-            .put(&url)
-            .bearer_auth(&self.token)
+            .request(Method::PUT, &url)
             .json(&body)
             .send()
             .await
-            .context("Failed to update DNS record")?
+            .map_err(|e| self.send_error(e, "Failed to update DNS record"))?
             .json()
             .await
             .context("Failed to parse update DNS record response")?;
 
         if !resp.success {
-            anyhow::bail!(
-                "Failed to update DNS record: {}",
-                format_errors(&resp.errors)
-            );
+            return Err(self.api_error("Failed to update DNS record", &resp.errors));
         }
 
         Ok(())
@@ -387,25 +954,30 @@ impl Client {
         let record = self.get_dns_record(zone_id, hostname).await?;
 
         if let Some(record) = record {
-            let url = format!("{}/zones/{}/dns_records/{}", API_BASE, zone_id, record.id);
+            self.delete_dns_record_by_id(zone_id, &record.id).await?;
+        }
 
-            let resp: ApiResponse<serde_json::Value> = self
-                .http
-                .delete(&url)
-                .bearer_auth(&self.token)
-                .send()
-                .await
-                .context("Failed to delete DNS record")?
-                .json()
-                .await
-                .context("Failed to parse delete DNS record response")?;
+        Ok(())
+    }
 
-            if !resp.success {
-                anyhow::bail!(
-                    "Failed to delete DNS record: {}",
-                    format_errors(&resp.errors)
-                );
-            }
+    // Delete a DNS record by its record ID
+    pub async fn delete_dns_record_by_id(&self, zone_id: &str, record_id: &str) -> Result<()> {
+        let url = format!(
+            "{}/zones/{}/dns_records/{}",
+            self.base_url, zone_id, record_id
+        );
+
+        let resp: ApiResponse<serde_json::Value> = self
+            .request(Method::DELETE, &url)
+            .send()
+            .await
+            .map_err(|e| self.send_error(e, "Failed to delete DNS record"))?
+            .json()
+            .await
+            .context("Failed to parse delete DNS record response")?;
+
+        if !resp.success {
+            return Err(self.api_error("Failed to delete DNS record", &resp.errors));
         }
 
         Ok(())
@@ -440,3 +1012,176 @@ fn base64_encode(data: &[u8]) -> String {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(record_type: &str, content: &str) -> DnsRecord {
+        DnsRecord {
+            id: "id".to_string(),
+            name: "api.example.com".to_string(),
+            content: content.to_string(),
+            record_type: record_type.to_string(),
+            zone_id: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_only_non_tunnel_address_records_conflict() {
+        assert!(is_conflicting_record(&record("A", "203.0.113.10")));
+        assert!(is_conflicting_record(&record("AAAA", "2001:db8::1")));
+        assert!(is_conflicting_record(&record("CNAME", "app.herokudns.com")));
+        assert!(!is_conflicting_record(&record(
+            "CNAME",
+            "abc-123.cfargotunnel.com"
+        )));
+        assert!(!is_conflicting_record(&record("TXT", "v=spf1 -all")));
+    }
+
+    #[test]
+    fn test_tunnel_is_connected_only_with_a_live_connector() {
+        let tunnel = |status: &str| -> Tunnel {
+            serde_json::from_str(&format!(
+                r#"{{"id":"t1","name":"ytunnel-a","deleted_at":null{}}}"#,
+                status
+            ))
+            .unwrap()
+        };
+        assert!(tunnel(r#","status":"healthy""#).is_connected());
+        assert!(tunnel(r#","status":"degraded""#).is_connected());
+        assert!(!tunnel(r#","status":"down""#).is_connected());
+        assert!(!tunnel(r#","status":"inactive""#).is_connected());
+        assert!(!tunnel("").is_connected());
+        // Another host's connector, before the status catches up
+        assert!(tunnel(r#","status":"down","connections":[{"colo_name":"ams01"}]"#).is_connected());
+        assert!(!tunnel(
+            r#","status":"down","connections":[{"colo_name":"ams01","is_pending_reconnect":true}]"#
+        )
+        .is_connected());
+    }
+
+    #[test]
+    fn test_tunnel_deserializes_captured_api_response() {
+        // GET /accounts/:id/cfd_tunnel, trimmed to two tunnels
+        let body = r#"{
+            "success": true,
+            "errors": [],
+            "messages": [],
+            "result": [
+                {
+                    "id": "f70ff985-a4ef-4643-bbbc-4a0ed4fc8415",
+                    "account_tag": "699d98642c564d2e855e9661899b7252",
+                    "created_at": "2026-10-01T10:00:00.123456Z",
+                    "deleted_at": null,
+                    "name": "ytunnel-api",
+                    "connections": [
+                        {
+                            "colo_name": "ams01",
+                            "id": "1bedc50d-42b3-473c-b108-ff3d10c0d925",
+                            "is_pending_reconnect": false,
+                            "origin_ip": "203.0.113.7",
+                            "opened_at": "2026-10-01T10:00:05.5Z",
+                            "client_id": "c0a0b3f2-1d4e-4f5a-9b8c-7d6e5f4a3b2c",
+                            "client_version": "2026.9.1",
+                            "uuid": "1bedc50d-42b3-473c-b108-ff3d10c0d925"
+                        },
+                        {
+                            "colo_name": "fra06",
+                            "id": "5e1c3ad2-8f0b-4c7e-a1d9-2b3c4d5e6f70",
+                            "is_pending_reconnect": false,
+                            "origin_ip": "203.0.113.7",
+                            "opened_at": "2026-10-01T10:00:05.9Z",
+                            "client_version": "2026.9.1"
+                        },
+                        {
+                            "colo_name": "ams01",
+                            "id": "9a8b7c6d-5e4f-4a3b-2c1d-0e9f8a7b6c5d",
+                            "is_pending_reconnect": true,
+                            "origin_ip": "203.0.113.7",
+                            "opened_at": "2026-09-30T08:00:00Z"
+                        }
+                    ],
+                    "conns_active_at": "2026-10-01T10:00:05.5Z",
+                    "conns_inactive_at": null,
+                    "tun_type": "cfd_tunnel",
+                    "metadata": {},
+                    "status": "healthy",
+                    "remote_config": false
+                },
+                {
+                    "id": "0c1d2e3f-4a5b-4c6d-8e7f-9a0b1c2d3e4f",
+                    "account_tag": "699d98642c564d2e855e9661899b7252",
+                    "created_at": "2026-10-14T09:30:00Z",
+                    "deleted_at": null,
+                    "name": "ytunnel-k3x9q2",
+                    "connections": [],
+                    "conns_active_at": null,
+                    "conns_inactive_at": "2026-10-14T11:00:00Z",
+                    "tun_type": "cfd_tunnel",
+                    "status": "down"
+                }
+            ],
+            "result_info": {"page": 1, "per_page": 20, "count": 2, "total_count": 2}
+        }"#;
+        let resp: ApiResponse<Vec<Tunnel>> = serde_json::from_str(body).unwrap();
+        let tunnels = resp.result.unwrap();
+
+        let api = &tunnels[0];
+        assert!(api.is_connected());
+        assert_eq!(api.connections.len(), 3);
+        assert_eq!(api.colos(), ["ams01", "fra06"]);
+        assert_eq!(
+            api.connections[0].client_version.as_deref(),
+            Some("2026.9.1")
+        );
+        assert_eq!(
+            api.remote_summary(),
+            "healthy (2 connections: ams01, fra06)"
+        );
+        assert!(api.created_secs().is_some());
+
+        let leftover = &tunnels[1];
+        assert!(!leftover.is_connected());
+        assert_eq!(
+            leftover.conns_inactive_at.as_deref(),
+            Some("2026-10-14T11:00:00Z")
+        );
+        assert!(leftover.colos().is_empty());
+        assert_eq!(leftover.remote_summary(), "down");
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_timestamp("2000-02-29T00:00:00Z"), Some(951_782_400));
+        assert_eq!(
+            parse_timestamp("2026-10-16T12:30:12.123456Z"),
+            Some(1_792_153_812)
+        );
+        // cloudflared log lines start with one
+        assert_eq!(
+            parse_timestamp("2026-10-16T12:30:12Z INF Registered tunnel connection"),
+            Some(1_792_153_812)
+        );
+        assert_eq!(parse_timestamp("not a date"), None);
+        assert_eq!(parse_timestamp("2026 10 16 12:30:12 panic"), None);
+        assert_eq!(parse_timestamp("2026-13-01T00:00:00Z"), None);
+
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1_792_153_812), "2026-10-16T12:30:12Z");
+        for secs in [951_782_400, 4_107_542_399] {
+            assert_eq!(parse_timestamp(&format_timestamp(secs)), Some(secs));
+        }
+    }
+
+    #[test]
+    fn test_record_tunnel_id() {
+        assert_eq!(
+            record("CNAME", "abc-123.cfargotunnel.com").tunnel_id(),
+            Some("abc-123")
+        );
+        assert_eq!(record("CNAME", "app.herokudns.com").tunnel_id(), None);
+        assert_eq!(record("TXT", "abc-123.cfargotunnel.com").tunnel_id(), None);
+    }
+}
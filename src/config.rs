@@ -1,27 +1,796 @@
 use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::{cloudflare, error, idn};
 
 // A single Cloudflare account configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Account {
     pub name: String,
     pub api_token: String,
+    // Global API Key auth for setups without API tokens (api_token is empty then)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
     pub account_id: String,
     pub default_zone_id: String,
     pub default_zone_name: String,
     pub zones: Vec<ZoneConfig>,
 }
 
+impl Account {
+    // Credentials for the Cloudflare API; an API key is only used together with its email
+    pub fn credentials(&self) -> cloudflare::Credentials {
+        match (&self.api_key, &self.email) {
+            (Some(key), Some(email)) => cloudflare::Credentials::ApiKey {
+                key: key.clone(),
+                email: email.clone(),
+            },
+            _ => cloudflare::Credentials::Token(self.api_token.clone()),
+        }
+    }
+
+    pub fn uses_api_key(&self) -> bool {
+        matches!(self.credentials(), cloudflare::Credentials::ApiKey { .. })
+    }
+
+    // Replace the stored credentials (e.g. after rotating the token)
+    pub fn set_credentials(&mut self, credentials: cloudflare::Credentials) {
+        match credentials {
+            cloudflare::Credentials::Token(token) => {
+                self.api_token = token;
+                self.api_key = None;
+                self.email = None;
+            }
+            cloudflare::Credentials::ApiKey { key, email } => {
+                self.api_token = String::new();
+                self.api_key = Some(key);
+                self.email = Some(email);
+            }
+        }
+    }
+
+    // Find the zone a hostname belongs to (longest matching zone suffix wins).
+    // Unicode and punycode hostnames both match.
+    pub fn zone_for_hostname(&self, hostname: &str) -> Option<&ZoneConfig> {
+        let hostname = idn::normalize(hostname);
+        self.zones
+            .iter()
+            .filter(|z| hostname == z.name || hostname.ends_with(&format!(".{}", z.name)))
+            .max_by_key(|z| z.name.len())
+    }
+
+    // Find a zone by its name, in Unicode or punycode
+    pub fn zone_by_name(&self, name: &str) -> Option<&ZoneConfig> {
+        let name = idn::normalize(name);
+        self.zones.iter().find(|z| z.name == name)
+    }
+
+    // Accounts set up with a token that can't read zones have none (and no default zone)
+    pub fn has_zones(&self) -> bool {
+        !self.zones.is_empty()
+    }
+
+    fn zone_required_error(&self) -> anyhow::Error {
+        error::not_found(format!(
+            "Account '{}' has no zones (its API token can't read any), so a zone is required.\n\
+             Use a full hostname or --zone for a zone in another configured account, or give the \
+             token Zone→Zone→Read and add the account again with `ytunnel init`.",
+            self.name
+        ))
+    }
+
+    // Resolve a user-supplied name into (zone, subdomain).
+    // A full hostname within one of the account's zones selects that zone automatically;
+    // anything else is a subdomain of the explicit zone (or the default zone).
+    // Unicode names are accepted; the subdomain comes back in punycode.
+    pub fn resolve_hostname(&self, name: &str, zone: Option<&str>) -> Result<(ZoneConfig, String)> {
+        let ascii = idn::to_ascii(name)?;
+        let name = ascii.as_str();
+        let explicit_zone = match zone {
+            Some(z) => Some(self.zone_by_name(z).ok_or_else(|| {
+                error::not_found(format!(
+                    "Zone '{}' not found. Run `ytunnel zones` to see available zones.",
+                    z
+                ))
+            })?),
+            None => None,
+        };
+
+        if let Some(matched) = self.zone_for_hostname(name) {
+            if name == matched.name {
+                bail!(
+                    "'{}' is a zone apex. Use a subdomain like 'myapp.{}'.",
+                    name,
+                    matched.name
+                );
+            }
+            if let Some(explicit) = explicit_zone {
+                if explicit.name != matched.name {
+                    bail!(
+                        "'{}' belongs to zone '{}', but --zone {} was given",
+                        name,
+                        matched.name,
+                        explicit.name
+                    );
+                }
+            }
+            let subdomain = name[..name.len() - matched.name.len() - 1].to_string();
+            return Ok((matched.clone(), subdomain));
+        }
+
+        let chosen = match explicit_zone {
+            Some(zc) => zc.clone(),
+            // Without --zone a dotted name is most likely a hostname in a zone we don't own
+            None if name.contains('.') => bail!(
+                "'{}' is not in any zone for account '{}'. \
+                 Use a hostname in one of your zones or pass --zone to treat it as a subdomain.",
+                name,
+                self.name
+            ),
+            None if !self.has_zones() => return Err(self.zone_required_error()),
+            // Only reachable after hand edits; without the check the hostname would be "name."
+            None if self.default_zone_name.is_empty() => bail!(
+                "No default zone set for account '{}'; run `ytunnel zones default <domain>` or pass --zone.",
+                self.name
+            ),
+            None => ZoneConfig {
+                id: self.default_zone_id.clone(),
+                name: self.default_zone_name.clone(),
+            },
+        };
+
+        Ok((chosen, name.to_string()))
+    }
+}
+
+// Minimum allowed HTTP timeout for health checks and metrics scraping
+pub const MIN_TIMEOUT_SECS: u64 = 1;
+
+// cloudflared transport protocols a tunnel can be pinned to
+pub const PROTOCOLS: [&str; 3] = ["quic", "http2", "auto"];
+
+// cloudflared --loglevel values
+pub const LOG_LEVELS: [&str; 5] = ["debug", "info", "warn", "error", "fatal"];
+
+// TUI color themes
+pub const THEMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+// Bounds for the TUI's tunnel list width, as a percentage of the split view
+pub const MIN_LIST_PERCENT: u16 = 20;
+pub const MAX_LIST_PERCENT: u16 = 70;
+
+// What `ytunnel run` can do with its tunnel when it exits
+pub const RUN_EXIT_CHOICES: [&str; 2] = ["keep", "delete"];
+
+// `ytunnel run` settings ([run] section)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunConfig {
+    // The answer the "delete or keep" prompt on exit defaults to
+    #[serde(default = "default_run_on_exit")]
+    pub on_exit: String,
+    // Ports `ytunnel run --detect` looks for a dev server on
+    #[serde(default = "default_detect_ports")]
+    pub detect_ports: Vec<u16>,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            on_exit: default_run_on_exit(),
+            detect_ports: default_detect_ports(),
+        }
+    }
+}
+
+impl RunConfig {
+    pub fn keeps_by_default(&self) -> bool {
+        self.on_exit == "keep"
+    }
+}
+
+fn default_run_on_exit() -> String {
+    "keep".to_string()
+}
+
+// Defaults of common dev servers: Rails/Next/Express, Angular, Astro, Flask, Vite,
+// Django, and the usual alternates
+fn default_detect_ports() -> Vec<u16> {
+    vec![
+        3000, 3001, 4000, 4200, 4321, 5000, 5173, 5500, 8000, 8080, 8888,
+    ]
+}
+
+// Names of the generated launchd jobs and systemd units ([daemon] section). Unset
+// prefixes keep the defaults (com.ytunnel and ytunnel-).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DaemonConfig {
+    // launchd label prefix, e.g. com.acme.tunnels (labels are <prefix>.<account>.<name>)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launchd_prefix: Option<String>,
+    // systemd unit prefix, e.g. acme-tunnel- (units are <prefix><account>-<name>.service)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub systemd_prefix: Option<String>,
+    // IP cloudflared's metrics server listens on (127.0.0.1 when unset), or "off" to
+    // run cloudflared without --metrics
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_bind: Option<String>,
+}
+
+impl DaemonConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // Address for cloudflared's metrics server, None when metrics are off
+    pub fn metrics_bind(&self) -> Option<IpAddr> {
+        match self.metrics_bind.as_deref() {
+            None => Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            Some(METRICS_OFF) => None,
+            // Checked by Config::validate; a bad value in an unvalidated read stays local
+            Some(addr) => Some(addr.parse().unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))),
+        }
+    }
+}
+
+// [daemon] metrics_bind value that leaves cloudflared's metrics server out
+pub const METRICS_OFF: &str = "off";
+
+// The [daemon] settings from config.toml, read once per process. Daemon names are
+// needed in places that have no Config at hand (and before `init`), so this reads
+// the file directly and falls back to the defaults when it is missing or invalid.
+pub fn daemon_config() -> &'static DaemonConfig {
+    static DAEMON: OnceLock<DaemonConfig> = OnceLock::new();
+    DAEMON.get_or_init(|| {
+        config_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| parse_config(&contents).ok())
+            .map(|(config, _)| config.daemon)
+            .unwrap_or_default()
+    })
+}
+
+// `ytunnel watch` settings ([watchdog] section)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchdogConfig {
+    // How often to check running tunnels' edge connections
+    #[serde(default = "default_watchdog_interval_secs")]
+    pub interval_secs: u64,
+    // How long a tunnel may have no edge connections before it is restarted
+    #[serde(default = "default_watchdog_grace_secs")]
+    pub grace_secs: u64,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_watchdog_interval_secs(),
+            grace_secs: default_watchdog_grace_secs(),
+        }
+    }
+}
+
+impl WatchdogConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn default_watchdog_interval_secs() -> u64 {
+    15
+}
+
+fn default_watchdog_grace_secs() -> u64 {
+    60
+}
+
+// TUI settings ([tui] section)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TuiConfig {
+    // Timeout for tunnel health check requests
+    #[serde(default = "default_health_timeout_secs")]
+    pub health_timeout_secs: u64,
+    // Color theme (one of THEMES); unset picks dark or light from the terminal
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    // Share of the split view's width given to the tunnel list (`<`/`>` in the TUI)
+    #[serde(default = "default_list_percent")]
+    pub list_percent: u16,
+    // Show the metrics panel (`M` in the TUI)
+    #[serde(default = "default_true")]
+    pub show_metrics: bool,
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        Self {
+            health_timeout_secs: default_health_timeout_secs(),
+            theme: None,
+            list_percent: default_list_percent(),
+            show_metrics: true,
+        }
+    }
+}
+
+impl TuiConfig {
+    pub fn health_timeout(&self) -> Duration {
+        Duration::from_secs(self.health_timeout_secs)
+    }
+}
+
+fn default_health_timeout_secs() -> u64 {
+    5
+}
+
+fn default_list_percent() -> u16 {
+    40
+}
+
+// Metrics settings ([metrics] section)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    // Timeout for scraping cloudflared's metrics endpoint
+    #[serde(default = "default_metrics_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: default_metrics_timeout_secs(),
+        }
+    }
+}
+
+impl MetricsConfig {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+}
+
+fn default_metrics_timeout_secs() -> u64 {
+    2
+}
+
+// Cloudflare API settings ([api] section)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiConfig {
+    // Timeout for each Cloudflare API request
+    #[serde(default = "default_api_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: default_api_timeout_secs(),
+        }
+    }
+}
+
+impl ApiConfig {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+}
+
+fn default_api_timeout_secs() -> u64 {
+    cloudflare::DEFAULT_TIMEOUT.as_secs()
+}
+
+// Defaults for `ytunnel add --preset <name>`, from a [presets.<name>] table. Flags
+// given on the command line win over the preset.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Preset {
+    // Zone to create the hostname in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<String>,
+    // cloudflared transport protocol (quic, http2, auto)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    // Start the tunnel right after adding it
+    #[serde(default)]
+    pub start: bool,
+    // After starting, wait this long for the hostname to respond
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_healthy_secs: Option<u64>,
+    // Start the tunnel on login
+    #[serde(default)]
+    pub auto_start: bool,
+    // false leaves DNS records to someone else (like --no-dns)
+    #[serde(default = "default_true")]
+    pub manage_dns: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// What changed when an account's credentials were replaced
+#[derive(Debug, Default)]
+pub struct CredentialUpdate {
+    pub zones_added: Vec<String>,
+    pub zones_removed: Vec<String>,
+    // The Cloudflare account id the ytunnel account used before, when --force moved
+    // it onto the (single) other account the credentials belong to
+    pub previous_account: Option<String>,
+}
+
+// Schema version written to config.toml. Bump it (with a step in migrate_config)
+// whenever the format changes, including new fields: older builds refuse to save a
+// newer file rather than silently dropping what they don't know about.
+pub const CONFIG_VERSION: u32 = 11;
+
 // The main configuration with multi-account support
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
+    // Schema version (0 = written before versioning)
+    #[serde(default)]
+    pub version: u32,
     pub selected_account: String,
     pub accounts: Vec<Account>,
+    #[serde(default)]
+    pub tui: TuiConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub run: RunConfig,
+    #[serde(default, skip_serializing_if = "DaemonConfig::is_default")]
+    pub daemon: DaemonConfig,
+    #[serde(default, skip_serializing_if = "WatchdogConfig::is_default")]
+    pub watchdog: WatchdogConfig,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub presets: BTreeMap<String, Preset>,
+    // Where tunnel credentials (<tunnel-id>.json) are kept, e.g. on an encrypted
+    // volume; defaults to the config directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials_dir: Option<PathBuf>,
+    // Prefix of the Cloudflare tunnel names ytunnel creates and recognizes as its
+    // own; defaults to DEFAULT_TUNNEL_PREFIX
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tunnel_prefix: Option<String>,
+    // Set to false to disable update checks against GitHub
+    #[serde(default = "default_check_updates")]
+    pub check_updates: bool,
+}
+
+fn default_check_updates() -> bool {
+    true
 }
 
 impl Config {
+    // Create an empty config with default settings
+    pub fn new(selected_account: String) -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            selected_account,
+            accounts: Vec::new(),
+            tui: TuiConfig::default(),
+            metrics: MetricsConfig::default(),
+            api: ApiConfig::default(),
+            run: RunConfig::default(),
+            daemon: DaemonConfig::default(),
+            watchdog: WatchdogConfig::default(),
+            presets: BTreeMap::new(),
+            credentials_dir: None,
+            tunnel_prefix: None,
+            check_updates: default_check_updates(),
+        }
+    }
+
+    // Reject settings that would make the app misbehave
+    pub fn validate(&self) -> Result<()> {
+        if self.tui.health_timeout_secs < MIN_TIMEOUT_SECS {
+            bail!(
+                "[tui] health_timeout_secs must be at least {} (got {})",
+                MIN_TIMEOUT_SECS,
+                self.tui.health_timeout_secs
+            );
+        }
+        if let Some(theme) = &self.tui.theme {
+            if !THEMES.contains(&theme.as_str()) {
+                bail!(
+                    "[tui] theme must be one of {} (got '{}')",
+                    THEMES.join(", "),
+                    theme
+                );
+            }
+        }
+        if !(MIN_LIST_PERCENT..=MAX_LIST_PERCENT).contains(&self.tui.list_percent) {
+            bail!(
+                "[tui] list_percent must be between {} and {} (got {})",
+                MIN_LIST_PERCENT,
+                MAX_LIST_PERCENT,
+                self.tui.list_percent
+            );
+        }
+        if self.metrics.timeout_secs < MIN_TIMEOUT_SECS {
+            bail!(
+                "[metrics] timeout_secs must be at least {} (got {})",
+                MIN_TIMEOUT_SECS,
+                self.metrics.timeout_secs
+            );
+        }
+        if self.api.timeout_secs < MIN_TIMEOUT_SECS {
+            bail!(
+                "[api] timeout_secs must be at least {} (got {})",
+                MIN_TIMEOUT_SECS,
+                self.api.timeout_secs
+            );
+        }
+        // Zero would check (and restart) in a busy loop
+        if self.watchdog.interval_secs < 1 {
+            bail!(
+                "[watchdog] interval_secs must be at least 1 (got {})",
+                self.watchdog.interval_secs
+            );
+        }
+        if !RUN_EXIT_CHOICES.contains(&self.run.on_exit.as_str()) {
+            bail!(
+                "[run] on_exit must be one of {} (got '{}')",
+                RUN_EXIT_CHOICES.join(", "),
+                self.run.on_exit
+            );
+        }
+        if self.run.detect_ports.is_empty() || self.run.detect_ports.contains(&0) {
+            bail!("[run] detect_ports must list ports from 1 to 65535");
+        }
+        // cloudflared configs and daemon units embed the path
+        if let Some(dir) = &self.credentials_dir {
+            if !dir.is_absolute() {
+                bail!(
+                    "credentials_dir must be an absolute path (got '{}')",
+                    dir.display()
+                );
+            }
+        }
+        for (key, prefix) in [
+            ("launchd_prefix", &self.daemon.launchd_prefix),
+            ("systemd_prefix", &self.daemon.systemd_prefix),
+        ] {
+            // Part of file and unit names, so nothing a path or systemd would mangle
+            if let Some(prefix) = prefix {
+                if !is_name_prefix(prefix) {
+                    bail!(
+                        "[daemon] {} may only use letters, digits, '.', '_', and '-' (got '{}')",
+                        key,
+                        prefix
+                    );
+                }
+            }
+        }
+        // Empty would claim every tunnel in the account as ytunnel's (and
+        // clean-ephemeral would offer to delete them)
+        if let Some(prefix) = &self.tunnel_prefix {
+            if !is_name_prefix(prefix) {
+                bail!(
+                    "tunnel_prefix must be non-empty and may only use letters, digits, '.', '_', and '-' (got '{}')",
+                    prefix
+                );
+            }
+        }
+        if let Some(bind) = &self.daemon.metrics_bind {
+            if bind != METRICS_OFF && bind.parse::<IpAddr>().is_err() {
+                bail!(
+                    "[daemon] metrics_bind must be an IP address or '{}' (got '{}')",
+                    METRICS_OFF,
+                    bind
+                );
+            }
+        }
+        for (name, preset) in &self.presets {
+            if let Some(protocol) = &preset.protocol {
+                if !PROTOCOLS.contains(&protocol.as_str()) {
+                    bail!(
+                        "[presets.{}] protocol must be one of {} (got '{}')",
+                        name,
+                        PROTOCOLS.join(", "),
+                        protocol
+                    );
+                }
+            }
+            if preset.wait_healthy_secs.is_some() && !preset.start {
+                bail!("[presets.{}] wait_healthy_secs needs start = true", name);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn preset(&self, name: &str) -> Result<&Preset> {
+        self.presets.get(name).ok_or_else(|| {
+            let available = if self.presets.is_empty() {
+                "No presets are defined; add a [presets.<name>] table to config.toml.".to_string()
+            } else {
+                let names: Vec<&str> = self.presets.keys().map(String::as_str).collect();
+                format!("Available presets: {}", names.join(", "))
+            };
+            error::not_found(format!("Preset '{}' not found. {}", name, available))
+        })
+    }
+
+    // Check new credentials against an account's Cloudflare account, then store them
+    // (the caller saves the config)
+    pub async fn update_credentials(
+        &mut self,
+        name: &str,
+        credentials: cloudflare::Credentials,
+        force: bool,
+    ) -> Result<CredentialUpdate> {
+        let client =
+            cloudflare::Client::with_credentials(credentials).with_timeout(self.api.timeout());
+        self.update_credentials_with(name, &client, force).await
+    }
+
+    // update_credentials with a ready-made client for the new credentials. Only the
+    // account's credentials, zone list, and (with force) Cloudflare account id change;
+    // tunnels and DNS are left alone.
+    pub async fn update_credentials_with(
+        &mut self,
+        name: &str,
+        client: &cloudflare::Client,
+        force: bool,
+    ) -> Result<CredentialUpdate> {
+        let acct = self.get_account_mut(Some(name))?;
+        let zones = client
+            .list_zones()
+            .await
+            .context("The new credentials were not accepted")?;
+
+        // Which Cloudflare accounts the credentials reach: their zones' accounts, or
+        // (for account-scoped tokens without zone access) the accounts they can list
+        let mut reachable: Vec<String> = zones.iter().map(|z| z.account_id.clone()).collect();
+        if reachable.is_empty() {
+            reachable = client
+                .list_accounts()
+                .await
+                .context("The new credentials were not accepted")?
+                .into_iter()
+                .map(|a| a.id)
+                .collect();
+        }
+        reachable.sort();
+        reachable.dedup();
+
+        let previous_account = if reachable.contains(&acct.account_id) {
+            client
+                .list_tunnels(&acct.account_id)
+                .await
+                .context("The new credentials were not accepted")?;
+            None
+        } else if force && reachable.len() == 1 {
+            Some(acct.account_id.clone())
+        } else if force {
+            // Mixing the old account's id with another account's zones would send
+            // every later call to the wrong place, so the target must be unambiguous
+            return Err(error::conflict(format!(
+                "The new credentials reach {} Cloudflare accounts ({}), none of them {} \
+                 (account '{}'). Use a token scoped to a single account.",
+                reachable.len(),
+                if reachable.is_empty() {
+                    "none visible".to_string()
+                } else {
+                    reachable.join(", ")
+                },
+                acct.account_id,
+                name
+            )));
+        } else {
+            return Err(error::conflict(format!(
+                "The new credentials belong to Cloudflare account {}, not {} (account '{}'). \
+                 Use `ytunnel account token {} --force` to store them anyway.",
+                if reachable.is_empty() {
+                    "(none visible)".to_string()
+                } else {
+                    reachable.join(", ")
+                },
+                acct.account_id,
+                name,
+                name
+            )));
+        };
+
+        // Refresh the cached zones (only the account's own; with force, the new account's)
+        let account_id = match previous_account {
+            Some(_) => reachable[0].clone(),
+            None => acct.account_id.clone(),
+        };
+        let zones: Vec<ZoneConfig> = zones
+            .into_iter()
+            .filter(|z| z.account_id == account_id)
+            .map(|z| ZoneConfig {
+                id: z.id,
+                name: z.name,
+            })
+            .collect();
+        let names =
+            |list: &[ZoneConfig]| -> Vec<String> { list.iter().map(|z| z.name.clone()).collect() };
+        let (before, after) = (names(&acct.zones), names(&zones));
+        let update = CredentialUpdate {
+            zones_added: after
+                .iter()
+                .filter(|z| !before.contains(z))
+                .cloned()
+                .collect(),
+            zones_removed: before
+                .iter()
+                .filter(|z| !after.contains(z))
+                .cloned()
+                .collect(),
+            previous_account,
+        };
+
+        if !zones.iter().any(|z| z.id == acct.default_zone_id) {
+            let (id, name) = zones
+                .first()
+                .map(|z| (z.id.clone(), z.name.clone()))
+                .unwrap_or_default();
+            acct.default_zone_id = id;
+            acct.default_zone_name = name;
+        }
+        acct.zones = zones;
+        acct.account_id = account_id;
+        acct.set_credentials(client.credentials().clone());
+        Ok(update)
+    }
+
+    // Cloudflare client for an account, using the configured request timeout
+    pub fn client(&self, acct: &Account) -> cloudflare::Client {
+        cloudflare::Client::for_account(acct).with_timeout(self.api.timeout())
+    }
+
+    // Resolve a name like Account::resolve_hostname, falling back to zones that only
+    // another configured account can see (e.g. DNS kept on a different account)
+    pub fn resolve_hostname(
+        &self,
+        acct: &Account,
+        name: &str,
+        zone: Option<&str>,
+    ) -> Result<(ZoneConfig, String)> {
+        let own = acct.resolve_hostname(name, zone);
+        if own.is_ok() {
+            return own;
+        }
+        let other = self
+            .accounts
+            .iter()
+            .filter(|a| a.name != acct.name)
+            .find(|a| match zone {
+                Some(z) => a.zone_by_name(z).is_some(),
+                None => a.zone_for_hostname(name).is_some(),
+            });
+        match other {
+            Some(a) => a.resolve_hostname(name, zone),
+            None => own,
+        }
+    }
+
+    // Account whose token manages DNS in a zone: acct itself when it has the zone,
+    // otherwise another configured account that does
+    pub fn dns_account<'a>(&'a self, acct: &'a Account, zone_id: &str) -> &'a Account {
+        if acct.zones.iter().any(|z| z.id == zone_id) {
+            return acct;
+        }
+        self.accounts
+            .iter()
+            .find(|a| a.zones.iter().any(|z| z.id == zone_id))
+            .unwrap_or(acct)
+    }
+
     // Get an account by name, or the selected account if name is None
     pub fn get_account(&self, name: Option<&str>) -> Result<&Account> {
         let account_name = name.unwrap_or(&self.selected_account);
@@ -29,10 +798,10 @@ impl Config {
             .iter()
             .find(|a| a.name == account_name)
             .ok_or_else(|| {
-                anyhow::anyhow!(
+                error::not_found(format!(
                     "Account '{}' not found. Run `ytunnel account list` to see available accounts.",
                     account_name
-                )
+                ))
             })
     }
 
@@ -43,10 +812,10 @@ impl Config {
             .iter_mut()
             .find(|a| a.name == account_name)
             .ok_or_else(|| {
-                anyhow::anyhow!(
+                error::not_found(format!(
                     "Account '{}' not found. Run `ytunnel account list` to see available accounts.",
                     account_name
-                )
+                ))
             })
     }
 
@@ -65,7 +834,7 @@ impl Config {
             .accounts
             .iter()
             .position(|a| a.name == name)
-            .ok_or_else(|| anyhow::anyhow!("Account '{}' not found", name))?;
+            .ok_or_else(|| error::not_found(format!("Account '{}' not found", name)))?;
 
         let account = self.accounts.remove(pos);
 
@@ -80,10 +849,10 @@ impl Config {
     // Set the selected account
     pub fn select_account(&mut self, name: &str) -> Result<()> {
         if !self.accounts.iter().any(|a| a.name == name) {
-            bail!(
+            return Err(error::not_found(format!(
                 "Account '{}' not found. Run `ytunnel account list` to see available accounts.",
                 name
-            );
+            )));
         }
         self.selected_account = name.to_string();
         Ok(())
@@ -96,63 +865,393 @@ pub struct ZoneConfig {
     pub name: String,
 }
 
-// Legacy config format for migration
-#[derive(Debug, Deserialize)]
-struct LegacyConfig {
-    api_token: String,
-    account_id: String,
-    default_zone_id: String,
-    default_zone_name: String,
-    zones: Vec<ZoneConfig>,
+// Bring a parsed config.toml up to CONFIG_VERSION. Returns true when the layout
+// changed and the file should be rewritten. Newer files are left untouched.
+fn migrate_config(doc: &mut toml::Table) -> Result<bool> {
+    let version = schema_version(doc, "config.toml")?;
+    let mut rewritten = false;
+    if version == 0 {
+        rewritten = migrate_config_v0(doc);
+        doc.insert("version".to_string(), toml::Value::Integer(1));
+    }
+    // v1 -> v2 added [presets]; nothing to convert
+    if version < 2 {
+        doc.insert("version".to_string(), toml::Value::Integer(2));
+    }
+    // v2 -> v3 added [run]; nothing to convert
+    if version < 3 {
+        doc.insert("version".to_string(), toml::Value::Integer(3));
+    }
+    // v3 -> v4 added [run] detect_ports; nothing to convert
+    if version < 4 {
+        doc.insert("version".to_string(), toml::Value::Integer(4));
+    }
+    // v4 -> v5 added [daemon]; nothing to convert
+    if version < 5 {
+        doc.insert("version".to_string(), toml::Value::Integer(5));
+    }
+    // v5 -> v6 added [watchdog]; nothing to convert
+    if version < 6 {
+        doc.insert("version".to_string(), toml::Value::Integer(6));
+    }
+    // v6 -> v7 added credentials_dir; nothing to convert (the files themselves
+    // move with state::migrate_credentials)
+    if version < 7 {
+        doc.insert("version".to_string(), toml::Value::Integer(7));
+    }
+    // v7 -> v8 added [tui] theme; nothing to convert
+    if version < 8 {
+        doc.insert("version".to_string(), toml::Value::Integer(8));
+    }
+    // v8 -> v9 added [tui] list_percent and show_metrics; nothing to convert
+    if version < 9 {
+        doc.insert("version".to_string(), toml::Value::Integer(9));
+    }
+    // v9 -> v10 added [daemon] metrics_bind; nothing to convert
+    if version < 10 {
+        doc.insert("version".to_string(), toml::Value::Integer(10));
+    }
+    // v10 -> v11 added tunnel_prefix; nothing to convert
+    if version < 11 {
+        doc.insert("version".to_string(), toml::Value::Integer(11));
+    }
+    Ok(rewritten)
+}
+
+// v0 -> v1: unversioned files are either the original single-account layout (token
+// and zones at the top level) or the multi-account layout, which needs no changes
+fn migrate_config_v0(doc: &mut toml::Table) -> bool {
+    if doc.contains_key("accounts") || !doc.contains_key("api_token") {
+        return false;
+    }
+    let mut account = toml::Table::new();
+    account.insert("name".to_string(), "default".into());
+    for key in [
+        "api_token",
+        "account_id",
+        "default_zone_id",
+        "default_zone_name",
+        "zones",
+    ] {
+        if let Some(value) = doc.remove(key) {
+            account.insert(key.to_string(), value);
+        }
+    }
+    doc.insert("selected_account".to_string(), "default".into());
+    doc.insert(
+        "accounts".to_string(),
+        toml::Value::Array(vec![toml::Value::Table(account)]),
+    );
+    true
+}
+
+// The `version` key of a config or state file (0 when missing)
+pub(crate) fn schema_version(doc: &toml::Table, file: &str) -> Result<u32> {
+    match doc.get("version") {
+        None => Ok(0),
+        Some(toml::Value::Integer(v)) => {
+            u32::try_from(*v).with_context(|| format!("Invalid version {} in {}", v, file))
+        }
+        Some(other) => bail!("Invalid version {} in {}", other, file),
+    }
+}
+
+// Saving a file written by a newer ytunnel would drop whatever it added
+pub(crate) fn ensure_writable(version: u32, supported: u32, file: &str) -> Result<()> {
+    if version > supported {
+        bail!(
+            "{} was written by a newer ytunnel (schema version {}, this build supports {}). \
+             Upgrade ytunnel before changing it.",
+            file,
+            version,
+            supported
+        );
+    }
+    Ok(())
+}
+
+// Points every ytunnel file (config, state, credentials, logs) at another directory
+pub const CONFIG_DIR_ENV: &str = "YTUNNEL_CONFIG_DIR";
+
+static CONFIG_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+// Use `dir` as the config directory for the rest of the process (--config-dir).
+// Takes precedence over YTUNNEL_CONFIG_DIR and the platform default.
+pub fn set_config_dir(dir: PathBuf) -> Result<()> {
+    let dir = std::path::absolute(&dir)
+        .with_context(|| format!("Invalid config directory: {}", dir.display()))?;
+    let _ = CONFIG_DIR_OVERRIDE.set(dir);
+    Ok(())
 }
 
 pub fn config_dir() -> Result<PathBuf> {
+    if let Some(dir) = CONFIG_DIR_OVERRIDE.get() {
+        return Ok(dir.clone());
+    }
+    if let Some(dir) = std::env::var_os(CONFIG_DIR_ENV).filter(|d| !d.is_empty()) {
+        // Daemon units embed these paths, so they must not depend on the working directory
+        return std::path::absolute(PathBuf::from(&dir))
+            .with_context(|| format!("Invalid {}: {}", CONFIG_DIR_ENV, dir.to_string_lossy()));
+    }
     let dir = dirs::config_dir()
         .context("Could not determine config directory")?
         .join("ytunnel");
     Ok(dir)
 }
 
+// Directory holding the tunnel credentials files: credentials_dir from config.toml,
+// read once per process like the [daemon] settings, or else the config directory
+pub fn credentials_dir() -> Result<PathBuf> {
+    static CREDENTIALS_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    let configured = CREDENTIALS_DIR.get_or_init(|| {
+        config_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| parse_config(&contents).ok())
+            .and_then(|(config, _)| config.credentials_dir)
+            .filter(|dir| dir.is_absolute())
+    });
+    match configured {
+        Some(dir) => Ok(dir.clone()),
+        None => config_dir(),
+    }
+}
+
+// Prefix of ytunnel's Cloudflare tunnel names when config.toml doesn't set one
+pub const DEFAULT_TUNNEL_PREFIX: &str = "ytunnel-";
+
+// Prefix of the Cloudflare tunnel names ytunnel creates and recognizes:
+// tunnel_prefix from config.toml, read once per process like credentials_dir, or
+// else DEFAULT_TUNNEL_PREFIX
+pub fn tunnel_prefix() -> &'static str {
+    static TUNNEL_PREFIX: OnceLock<String> = OnceLock::new();
+    TUNNEL_PREFIX.get_or_init(|| {
+        config_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| parse_config(&contents).ok())
+            .and_then(|(config, _)| config.tunnel_prefix)
+            .filter(|prefix| is_name_prefix(prefix))
+            .unwrap_or_else(|| DEFAULT_TUNNEL_PREFIX.to_string())
+    })
+}
+
+// Cloudflare name of tunnel `name`, e.g. "ytunnel-api"
+pub fn cloudflare_tunnel_name(name: &str) -> String {
+    format!("{}{}", tunnel_prefix(), name)
+}
+
+// ytunnel's name for a Cloudflare tunnel; None when it lacks the prefix (not ours)
+pub fn short_tunnel_name(cloudflare_name: &str) -> Option<&str> {
+    cloudflare_name.strip_prefix(tunnel_prefix())
+}
+
+// Prefixes that end up in names: non-empty letters, digits, '.', '_', and '-'
+fn is_name_prefix(prefix: &str) -> bool {
+    !prefix.is_empty()
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._-".contains(c))
+}
+
 pub fn config_path() -> Result<PathBuf> {
     Ok(config_dir()?.join("config.toml"))
 }
 
+// Lock file that serializes ytunnel processes changing config.toml/tunnels.toml
+const LOCK_FILE: &str = "ytunnel.lock";
+
+// How long to wait for another ytunnel operation before giving up
+const LOCK_WAIT: Duration = Duration::from_secs(10);
+
+// The open lock file while this process holds the lock, and how many guards share it
+static LOCK_HOLD: Mutex<(usize, Option<fs::File>)> = Mutex::new((0, None));
+
+// Proof that this process holds the config lock; released when the last guard drops
+#[must_use = "the lock is released when the guard is dropped"]
+pub struct ConfigLock(());
+
+impl Drop for ConfigLock {
+    fn drop(&mut self) {
+        let mut hold = LOCK_HOLD.lock().unwrap_or_else(|e| e.into_inner());
+        hold.0 -= 1;
+        if hold.0 == 0 {
+            // Closing the file releases the flock
+            hold.1 = None;
+        }
+    }
+}
+
+// Take the advisory lock on the config directory. Hold it across load, modify, and
+// save so two ytunnel processes can't overwrite each other's changes. Reentrant
+// within a process; waits up to LOCK_WAIT for another process to finish.
+pub fn lock() -> Result<ConfigLock> {
+    lock_within(LOCK_WAIT)
+}
+
+// Take the lock only if no other process holds it, without waiting or printing
+// anything (for the TUI, which can't stall a frame on another ytunnel command)
+pub fn try_lock() -> Result<ConfigLock> {
+    lock_within(Duration::ZERO)
+}
+
+fn lock_within(wait: Duration) -> Result<ConfigLock> {
+    let mut hold = LOCK_HOLD.lock().unwrap_or_else(|e| e.into_inner());
+    if hold.0 == 0 {
+        let dir = config_dir()?;
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create config directory: {}", dir.display()))?;
+        let path = dir.join(LOCK_FILE);
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+        acquire(&file, &path, wait)?;
+        hold.1 = Some(file);
+    }
+    hold.0 += 1;
+    Ok(ConfigLock(()))
+}
+
+#[cfg(unix)]
+fn acquire(file: &fs::File, path: &Path, wait: Duration) -> Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let deadline = Instant::now() + wait;
+    let mut announced = false;
+    loop {
+        // SAFETY: the descriptor belongs to `file`, which outlives the call
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+            return Ok(());
+        }
+        let err = std::io::Error::last_os_error();
+        if err.raw_os_error() != Some(libc::EWOULDBLOCK) {
+            return Err(err).with_context(|| format!("Failed to lock {}", path.display()));
+        }
+        if wait.is_zero() {
+            return Err(error::conflict(
+                "Another ytunnel operation is in progress. Try again once it finishes.",
+            ));
+        }
+        if Instant::now() >= deadline {
+            return Err(error::conflict(format!(
+                "Another ytunnel operation is in progress (waited {}s for {}). \
+                 Try again once it finishes.",
+                wait.as_secs(),
+                path.display()
+            )));
+        }
+        if !announced {
+            eprintln!("Waiting for another ytunnel operation to finish...");
+            announced = true;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+// No advisory locks outside unix; concurrent invocations are last-writer-wins there
+#[cfg(not(unix))]
+fn acquire(_file: &fs::File, _path: &Path, _wait: Duration) -> Result<()> {
+    Ok(())
+}
+
+// Replace a file's contents all at once: write a temp file next to it, then rename it
+// into place, so a crash or a concurrent ytunnel never leaves a truncated file behind.
+// A symlink is written through (the file it points at is replaced), and the file keeps
+// its permissions; new files are private (0600), since config.toml holds API tokens.
+pub fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("Invalid file path: {}", path.display()))?;
+    let tmp = dir.join(format!(
+        ".{}.{}-{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = (|| -> std::io::Result<()> {
+        let mut file = create_private(&tmp)?;
+        if let Ok(existing) = fs::metadata(&path) {
+            file.set_permissions(existing.permissions())?;
+        }
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp, &path)
+    })();
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp);
+        return Err(e).with_context(|| format!("Failed to write {}", path.display()));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_private(path: &Path) -> std::io::Result<fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn create_private(path: &Path) -> std::io::Result<fs::File> {
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+}
+
 pub fn load_config() -> Result<Config> {
     let path = config_path()?;
     if !path.exists() {
-        anyhow::bail!("ytunnel is not configured. Run `ytunnel init` first.");
+        return Err(error::not_configured(
+            "ytunnel is not configured. Run `ytunnel init` first.",
+        ));
     }
     let contents = fs::read_to_string(&path)
         .with_context(|| format!("Failed to read config from {}", path.display()))?;
-
-    // Try new format first
-    if let Ok(config) = toml::from_str::<Config>(&contents) {
-        return Ok(config);
-    }
-
-    // Try legacy format and migrate
-    if let Ok(legacy) = toml::from_str::<LegacyConfig>(&contents) {
-        let account_name = "default".to_string();
-        eprintln!(
-            "Migrating config to multi-account format (account: '{}')...",
-            account_name
-        );
-        let config = Config {
-            selected_account: account_name.clone(),
-            accounts: vec![Account {
-                name: account_name,
-                api_token: legacy.api_token,
-                account_id: legacy.account_id,
-                default_zone_id: legacy.default_zone_id,
-                default_zone_name: legacy.default_zone_name,
-                zones: legacy.zones,
-            }],
-        };
+    let (config, rewritten) = parse_config(&contents)?;
+    config.validate()?;
+    if rewritten {
+        eprintln!("Migrating config to multi-account format (account: 'default')...");
+        crate::backup::snapshot("config migration")?;
         save_config(&config)?;
-        return Ok(config);
     }
+    Ok(config)
+}
 
-    bail!("Invalid config format")
+// Parse config.toml, migrating older layouts; true when the layout changed
+fn parse_config(contents: &str) -> Result<(Config, bool)> {
+    let mut doc: toml::Table = toml::from_str(contents).context("Invalid config format")?;
+    let rewritten = migrate_config(&mut doc)?;
+    let version = schema_version(&doc, "config.toml")?;
+    let config: Config = toml::Value::Table(doc).try_into().with_context(|| {
+        if version > CONFIG_VERSION {
+            "Invalid config format (config.toml was written by a newer ytunnel)"
+        } else {
+            "Invalid config format"
+        }
+    })?;
+    Ok((config, rewritten))
+}
+
+// Load, change, and save config.toml under the config lock, so another ytunnel
+// can't save in between. Keep prompts and network waits out of `change`.
+pub fn update_config<T>(change: impl FnOnce(&mut Config) -> Result<T>) -> Result<T> {
+    let _lock = lock()?;
+    let mut config = load_config()?;
+    let result = change(&mut config)?;
+    save_config(&config)?;
+    Ok(result)
 }
 
 pub fn save_config(config: &Config) -> Result<()> {
@@ -160,10 +1259,457 @@ pub fn save_config(config: &Config) -> Result<()> {
     fs::create_dir_all(&dir)
         .with_context(|| format!("Failed to create config directory: {}", dir.display()))?;
 
+    ensure_writable(config.version, CONFIG_VERSION, "config.toml")?;
+    let _lock = lock()?;
     let path = config_path()?;
     let contents = toml::to_string_pretty(config).context("Failed to serialize config")?;
-    fs::write(&path, contents)
+    write_atomic(&path, &contents)
         .with_context(|| format!("Failed to write config to {}", path.display()))?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeouts_default_when_sections_missing() {
+        let config: Config = toml::from_str(
+            r#"
+selected_account = "default"
+accounts = []
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.tui.health_timeout_secs, 5);
+        assert_eq!(config.metrics.timeout_secs, 2);
+
+        // Sections survive a save/load round trip
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        let reparsed: Config = toml::from_str(&serialized).unwrap();
+        assert_eq!(reparsed.tui.health_timeout_secs, 5);
+
+        let mut config = config;
+        config.metrics.timeout_secs = 0;
+        assert!(config.validate().is_err());
+
+        config.metrics.timeout_secs = 2;
+        config.tui.theme = Some("light".to_string());
+        config.validate().unwrap();
+        config.tui.theme = Some("solarized".to_string());
+        assert!(config.validate().is_err());
+
+        config.tui.theme = None;
+        assert_eq!(config.tui.list_percent, 40);
+        assert!(config.tui.show_metrics);
+        config.tui.list_percent = MAX_LIST_PERCENT + 5;
+        assert!(config.validate().is_err());
+    }
+
+    fn account() -> Account {
+        Account {
+            name: "dev".to_string(),
+            api_token: String::new(),
+            api_key: None,
+            email: None,
+            account_id: String::new(),
+            default_zone_id: "z1".to_string(),
+            default_zone_name: "rhuk.net".to_string(),
+            zones: vec![
+                ZoneConfig {
+                    id: "z1".to_string(),
+                    name: "rhuk.net".to_string(),
+                },
+                ZoneConfig {
+                    id: "z2".to_string(),
+                    name: "tunnel.rhuk.net".to_string(),
+                },
+                ZoneConfig {
+                    id: "z3".to_string(),
+                    name: "example.com".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_resolve_hostname() {
+        let acct = account();
+
+        // Plain names go to the default zone, or the explicit one
+        let (zone, sub) = acct.resolve_hostname("myapp", None).unwrap();
+        assert_eq!((zone.name.as_str(), sub.as_str()), ("rhuk.net", "myapp"));
+        let (zone, sub) = acct.resolve_hostname("myapp", Some("example.com")).unwrap();
+        assert_eq!((zone.name.as_str(), sub.as_str()), ("example.com", "myapp"));
+
+        // Full hostnames pick the most specific configured zone
+        let (zone, sub) = acct
+            .resolve_hostname("license.tunnel.rhuk.net", None)
+            .unwrap();
+        assert_eq!((zone.id.as_str(), sub.as_str()), ("z2", "license"));
+        let (zone, sub) = acct.resolve_hostname("api.example.com", None).unwrap();
+        assert_eq!((zone.id.as_str(), sub.as_str()), ("z3", "api"));
+
+        // Matching --zone is fine, conflicting --zone is rejected instead of double-appending
+        let (zone, sub) = acct
+            .resolve_hostname("api.example.com", Some("example.com"))
+            .unwrap();
+        assert_eq!((zone.id.as_str(), sub.as_str()), ("z3", "api"));
+        assert!(acct
+            .resolve_hostname("api.example.com", Some("rhuk.net"))
+            .is_err());
+
+        // Dotted names outside our zones need an explicit --zone
+        assert!(acct.resolve_hostname("app.other.org", None).is_err());
+        let (zone, sub) = acct.resolve_hostname("a.b", Some("example.com")).unwrap();
+        assert_eq!((zone.id.as_str(), sub.as_str()), ("z3", "a.b"));
+
+        // Apex and unknown zones are errors
+        assert!(acct.resolve_hostname("rhuk.net", None).is_err());
+        assert!(acct.resolve_hostname("myapp", Some("nope.io")).is_err());
+
+        // Without a default zone only names that pick their zone still work
+        let no_default = Account {
+            default_zone_id: String::new(),
+            default_zone_name: String::new(),
+            ..account()
+        };
+        let err = no_default.resolve_hostname("myapp", None).unwrap_err();
+        assert!(err.to_string().contains("No default zone set"), "{}", err);
+        assert!(no_default
+            .resolve_hostname("myapp", Some("rhuk.net"))
+            .is_ok());
+        assert!(no_default.resolve_hostname("api.example.com", None).is_ok());
+    }
+
+    #[test]
+    fn test_idn_zones_match_unicode_and_punycode() {
+        // Cloudflare returns IDN zones in punycode
+        let mut acct = account();
+        acct.zones.push(ZoneConfig {
+            id: "z4".to_string(),
+            name: "xn--mnchen-dev-9db.de".to_string(),
+        });
+
+        // add/run with a Unicode hostname, a Unicode --zone, or punycode throughout
+        for (name, zone) in [
+            ("api.münchen-dev.de", None),
+            ("api", Some("münchen-dev.de")),
+            ("api.xn--mnchen-dev-9db.de", Some("münchen-dev.de")),
+            ("api", Some("xn--mnchen-dev-9db.de")),
+        ] {
+            let (zc, sub) = acct.resolve_hostname(name, zone).unwrap();
+            assert_eq!((zc.id.as_str(), sub.as_str()), ("z4", "api"), "{}", name);
+        }
+        // A Unicode subdomain becomes its DNS label
+        let (zc, sub) = acct
+            .resolve_hostname("bücher.münchen-dev.de", None)
+            .unwrap();
+        assert_eq!((zc.id.as_str(), sub.as_str()), ("z4", "xn--bcher-kva"));
+        let (zc, sub) = acct.resolve_hostname("bücher", None).unwrap();
+        assert_eq!((zc.id.as_str(), sub.as_str()), ("z1", "xn--bcher-kva"));
+
+        // Ephemeral runs' hostnames infer their zone either way
+        for hostname in ["dev.münchen-dev.de", "dev.xn--mnchen-dev-9db.de"] {
+            assert_eq!(acct.zone_for_hostname(hostname).unwrap().id, "z4");
+        }
+        assert!(acct.zone_by_name("münchen-dev.de").is_some());
+    }
+
+    #[test]
+    fn test_api_key_credentials_need_email() {
+        let mut acct = account();
+        acct.api_token = "token".to_string();
+        assert!(!acct.uses_api_key());
+
+        // A key without its email can't authenticate, so the token is used
+        acct.api_key = Some("key".to_string());
+        assert!(!acct.uses_api_key());
+
+        acct.email = Some("me@example.com".to_string());
+        assert!(acct.uses_api_key());
+
+        // Token configs don't grow empty key fields when saved
+        let toml = toml::to_string(&account()).unwrap();
+        assert!(!toml.contains("api_key") && !toml.contains("email"));
+    }
+
+    #[test]
+    fn test_zoneless_account_resolves_through_other_accounts() {
+        let dns = account();
+        let tunnels_only = Account {
+            name: "tunnels".to_string(),
+            default_zone_id: String::new(),
+            default_zone_name: String::new(),
+            zones: Vec::new(),
+            ..account()
+        };
+        let mut config = Config::new("tunnels".to_string());
+        config.accounts = vec![tunnels_only.clone(), dns.clone()];
+
+        // A bare name has no default zone to go in
+        assert!(tunnels_only.resolve_hostname("api", None).is_err());
+        assert!(config.resolve_hostname(&tunnels_only, "api", None).is_err());
+
+        // Hostnames and --zone can use another account's zones
+        let (zone, sub) = config
+            .resolve_hostname(&tunnels_only, "api.rhuk.net", None)
+            .unwrap();
+        assert_eq!((zone.id.as_str(), sub.as_str()), ("z1", "api"));
+        let (zone, _) = config
+            .resolve_hostname(&tunnels_only, "api", Some("tunnel.rhuk.net"))
+            .unwrap();
+        assert_eq!(zone.id, "z2");
+
+        assert_eq!(config.dns_account(&tunnels_only, "z2").name, "dev");
+        assert_eq!(config.dns_account(&dns, "z1").name, "dev");
+    }
+
+    #[test]
+    fn test_legacy_single_account_config_migrates_to_current() {
+        let (config, rewritten) = parse_config(
+            r#"
+api_token = "tok"
+account_id = "acct"
+default_zone_id = "z1"
+default_zone_name = "example.com"
+
+[[zones]]
+id = "z1"
+name = "example.com"
+"#,
+        )
+        .unwrap();
+        assert!(rewritten);
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.selected_account, "default");
+        let acct = config.get_account(None).unwrap();
+        assert_eq!(acct.api_token, "tok");
+        assert_eq!(acct.account_id, "acct");
+        assert_eq!(acct.zones.len(), 1);
+        assert!(config.check_updates);
+    }
+
+    #[test]
+    fn test_unversioned_multi_account_config_only_gains_a_version() {
+        let text = toml::to_string_pretty(&Config {
+            version: 0,
+            accounts: vec![account()],
+            ..Config::new("dev".to_string())
+        })
+        .unwrap();
+        let (config, rewritten) = parse_config(&text.replace("version = 0\n", "")).unwrap();
+        assert!(!rewritten);
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.accounts[0].name, "dev");
+        assert!(toml::to_string_pretty(&config)
+            .unwrap()
+            .starts_with(&format!("version = {}\n", CONFIG_VERSION)));
+    }
+
+    #[test]
+    fn test_presets_parse_and_validate() {
+        let (config, _) = parse_config(
+            r#"
+version = 2
+selected_account = "default"
+accounts = []
+
+[presets.grpc]
+protocol = "http2"
+start = true
+wait_healthy_secs = 30
+manage_dns = false
+"#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+        let grpc = config.preset("grpc").unwrap();
+        assert_eq!(grpc.protocol.as_deref(), Some("http2"));
+        assert!(grpc.start && !grpc.manage_dns && !grpc.auto_start);
+
+        let err = config.preset("static").unwrap_err();
+        assert!(
+            err.to_string().contains("Available presets: grpc"),
+            "{}",
+            err
+        );
+
+        // Typos in a preset are errors rather than silently ignored
+        let typo = "selected_account = \"d\"\naccounts = []\n[presets.x]\nprotocl = \"quic\"\n";
+        assert!(parse_config(typo).is_err());
+
+        let mut config = config;
+        config.presets.get_mut("grpc").unwrap().protocol = Some("udp".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_daemon_prefixes_are_optional_and_validated() {
+        let mut config = Config::new("default".to_string());
+        // Defaults don't add a [daemon] table to the file
+        assert!(!toml::to_string(&config).unwrap().contains("[daemon]"));
+
+        config.daemon.launchd_prefix = Some("com.acme.tunnels".to_string());
+        config.daemon.systemd_prefix = Some("acme-tunnel-".to_string());
+        config.validate().unwrap();
+        let (parsed, _) = parse_config(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(parsed.daemon, config.daemon);
+
+        for bad in ["", "com/acme", "acme tunnel"] {
+            config.daemon.systemd_prefix = Some(bad.to_string());
+            assert!(config.validate().is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_metrics_bind_defaults_to_loopback_and_can_be_off() {
+        let mut config = Config::new("default".to_string());
+        assert_eq!(
+            config.daemon.metrics_bind(),
+            Some(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        );
+
+        config.daemon.metrics_bind = Some("::1".to_string());
+        config.validate().unwrap();
+        assert_eq!(config.daemon.metrics_bind(), Some("::1".parse().unwrap()));
+
+        config.daemon.metrics_bind = Some("off".to_string());
+        config.validate().unwrap();
+        assert_eq!(config.daemon.metrics_bind(), None);
+
+        for bad in ["localhost", "/run/cloudflared.sock", "127.0.0.1:2000"] {
+            config.daemon.metrics_bind = Some(bad.to_string());
+            assert!(config.validate().is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_tunnel_prefix_is_optional_and_validated() {
+        let mut config = Config::new("default".to_string());
+        assert!(!toml::to_string(&config).unwrap().contains("tunnel_prefix"));
+
+        config.tunnel_prefix = Some("acme-".to_string());
+        config.validate().unwrap();
+        let (parsed, _) = parse_config(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(parsed.tunnel_prefix.as_deref(), Some("acme-"));
+
+        for bad in ["", "acme tunnels-", "acme/"] {
+            config.tunnel_prefix = Some(bad.to_string());
+            assert!(config.validate().is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_watchdog_interval_must_be_positive() {
+        let mut config = Config::new("default".to_string());
+        config.watchdog.interval_secs = 1;
+        config.validate().unwrap();
+        config.watchdog.interval_secs = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_credentials_dir_is_optional_and_absolute() {
+        let mut config = Config::new("default".to_string());
+        assert!(!toml::to_string(&config)
+            .unwrap()
+            .contains("credentials_dir"));
+
+        config.credentials_dir = Some(PathBuf::from("/Volumes/Secure/ytunnel"));
+        config.validate().unwrap();
+        let (parsed, _) = parse_config(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(parsed.credentials_dir, config.credentials_dir);
+
+        config.credentials_dir = Some(PathBuf::from("secrets"));
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_newer_config_loads_but_refuses_to_save() {
+        let (config, rewritten) = parse_config(
+            r#"
+version = 99
+selected_account = "dev"
+accounts = []
+future_setting = true
+"#,
+        )
+        .unwrap();
+        assert!(!rewritten);
+        assert_eq!(config.version, 99);
+        let err = ensure_writable(config.version, CONFIG_VERSION, "config.toml").unwrap_err();
+        assert!(err.to_string().contains("newer ytunnel"), "{}", err);
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_without_leftovers() {
+        let dir = std::env::temp_dir().join(format!("ytunnel-atomic-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+
+        write_atomic(&path, "first = 1\n").unwrap();
+        write_atomic(&path, "second = 2\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second = 2\n");
+        let entries: Vec<_> = fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+
+        // A missing directory fails cleanly
+        assert!(write_atomic(&dir.join("missing/config.toml"), "x").is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_keeps_permissions_and_follows_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("ytunnel-atomic-mode-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mode = |p: &Path| fs::metadata(p).unwrap().permissions().mode() & 0o777;
+
+        // New files are private
+        let path = dir.join("config.toml");
+        write_atomic(&path, "a = 1\n").unwrap();
+        assert_eq!(mode(&path), 0o600);
+        // Existing files keep their mode
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+        write_atomic(&path, "a = 2\n").unwrap();
+        assert_eq!(mode(&path), 0o640);
+
+        // A symlinked config stays a symlink; its target gets the new contents
+        let link = dir.join("link.toml");
+        std::os::unix::fs::symlink(&path, &link).unwrap();
+        write_atomic(&link, "a = 3\n").unwrap();
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a = 3\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_lock_is_exclusive_between_open_files() {
+        let path = std::env::temp_dir().join(format!("ytunnel-lock-{}", std::process::id()));
+        let open = || {
+            fs::OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&path)
+                .unwrap()
+        };
+        let (first, second) = (open(), open());
+
+        acquire(&first, &path, Duration::ZERO).unwrap();
+        let err = acquire(&second, &path, Duration::from_millis(200)).unwrap_err();
+        assert!(err.to_string().contains("in progress"), "{}", err);
+
+        // Closing the holder releases it
+        drop(first);
+        acquire(&second, &path, Duration::ZERO).unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -2,20 +2,45 @@ use anyhow::{Context, Result};
 use std::fs;
 use std::path::PathBuf;
 use std::process::Stdio;
+use std::time::Duration;
 use tokio::process::Command;
 
-use crate::state::{ensure_logs_dir, write_tunnel_config, PersistentTunnel, TunnelStatus};
+use ytunnel::metrics::TunnelMetrics;
+use ytunnel::state::{
+    ensure_parent_dir, write_tunnel_config, PersistentTunnel, TunnelState, TunnelStatus,
+};
+
+// Process details for a running daemon, as far as the platform exposes them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DaemonInfo {
+    pub pid: Option<u32>,
+    pub uptime: Option<Duration>,
+    // Resident memory in bytes
+    pub memory_bytes: Option<u64>,
+}
 
 // ============================================================================
 // Platform-specific constants and paths
 // ============================================================================
 
+// Defaults; [daemon] in config.toml can replace them. Daemons installed under the
+// default names are still found after a custom prefix is set, until reinstalled.
 #[cfg(target_os = "macos")]
 const LAUNCHD_LABEL_PREFIX: &str = "com.ytunnel";
 
 #[cfg(target_os = "linux")]
 const SYSTEMD_SERVICE_PREFIX: &str = "ytunnel-";
 
+// The prefix new daemons are installed under, then the default when it differs
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn name_prefixes(configured: Option<&String>, default: &str) -> Vec<String> {
+    let mut prefixes = vec![default.to_string()];
+    if let Some(prefix) = configured.filter(|p| p.as_str() != default) {
+        prefixes.insert(0, prefix.clone());
+    }
+    prefixes
+}
+
 // ============================================================================
 // macOS (launchd) implementation
 // ============================================================================
@@ -27,15 +52,42 @@ fn launch_agents_dir() -> Result<PathBuf> {
 }
 
 #[cfg(target_os = "macos")]
-fn launchd_label(account_name: &str, tunnel_name: &str) -> String {
+fn label_prefixes() -> Vec<String> {
+    name_prefixes(
+        ytunnel::config::daemon_config().launchd_prefix.as_ref(),
+        LAUNCHD_LABEL_PREFIX,
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn format_launchd_label(prefix: &str, account_name: &str, tunnel_name: &str) -> String {
     if account_name.is_empty() {
         // Legacy format for migration compatibility
-        format!("{}.{}", LAUNCHD_LABEL_PREFIX, tunnel_name)
+        format!("{}.{}", prefix, tunnel_name)
     } else {
-        format!("{}.{}.{}", LAUNCHD_LABEL_PREFIX, account_name, tunnel_name)
+        format!("{}.{}.{}", prefix, account_name, tunnel_name)
     }
 }
 
+// The label new daemons are installed under
+#[cfg(target_os = "macos")]
+fn launchd_label(account_name: &str, tunnel_name: &str) -> String {
+    format_launchd_label(&label_prefixes()[0], account_name, tunnel_name)
+}
+
+// Every label the tunnel's daemon may be installed under, preferred first: the
+// configured prefix, the default prefix, then the pre-account legacy label
+#[cfg(target_os = "macos")]
+fn launchd_labels(account_name: &str, tunnel_name: &str) -> Vec<String> {
+    let mut labels: Vec<String> = label_prefixes()
+        .iter()
+        .map(|prefix| format_launchd_label(prefix, account_name, tunnel_name))
+        .collect();
+    labels.push(legacy_launchd_label(tunnel_name));
+    labels.dedup();
+    labels
+}
+
 #[cfg(target_os = "macos")]
 fn legacy_launchd_label(tunnel_name: &str) -> String {
     format!("{}.{}", LAUNCHD_LABEL_PREFIX, tunnel_name)
@@ -50,47 +102,91 @@ fn plist_path(account_name: &str, tunnel_name: &str) -> Result<PathBuf> {
     )))
 }
 
+// The balancer of a balanced tunnel runs as its own job next to cloudflared's
 #[cfg(target_os = "macos")]
-fn legacy_plist_path(tunnel_name: &str) -> Result<PathBuf> {
-    let agents_dir = launch_agents_dir()?;
-    Ok(agents_dir.join(format!("{}.plist", legacy_launchd_label(tunnel_name))))
+fn balancer_plist_path(account_name: &str, tunnel_name: &str) -> Result<PathBuf> {
+    Ok(balancer_plist_for(&plist_path(account_name, tunnel_name)?))
 }
 
-// Find the actual plist path - checks new naming first, then legacy
+// <label>.balancer.plist next to a tunnel's <label>.plist
 #[cfg(target_os = "macos")]
-fn find_plist_path(account_name: &str, tunnel_name: &str) -> Result<Option<PathBuf>> {
-    // First check the new naming convention
-    let new_path = plist_path(account_name, tunnel_name)?;
-    if new_path.exists() {
-        return Ok(Some(new_path));
-    }
+fn balancer_plist_for(plist: &std::path::Path) -> PathBuf {
+    plist.with_extension("balancer.plist")
+}
 
-    // Fall back to legacy naming (without account)
-    let legacy_path = legacy_plist_path(tunnel_name)?;
-    if legacy_path.exists() {
-        return Ok(Some(legacy_path));
-    }
+#[cfg(target_os = "macos")]
+fn generate_balancer_plist(tunnel: &PersistentTunnel) -> Result<String> {
+    let label = format!(
+        "{}.balancer",
+        launchd_label(&tunnel.account_name, &tunnel.name)
+    );
+    let args: String = balancer_command(tunnel)?
+        .iter()
+        .map(|arg| format!("        <string>{}</string>\n", arg))
+        .collect();
+    Ok(format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+{args}    </array>
+    <key>RunAtLoad</key>
+    <{run_at_load}/>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+    <key>StandardOutPath</key>
+    <string>{log}</string>
+    <key>StandardErrorPath</key>
+    <string>{log}</string>
+    <key>ProcessType</key>
+    <string>Background</string>
+</dict>
+</plist>
+"#,
+        label = label,
+        args = args,
+        run_at_load = if tunnel.auto_start { "true" } else { "false" },
+        log = tunnel.log_path()?.display()
+    ))
+}
 
-    Ok(None)
+// Plists of every label the daemon may be installed under, preferred first
+#[cfg(target_os = "macos")]
+fn candidate_plist_paths(account_name: &str, tunnel_name: &str) -> Result<Vec<PathBuf>> {
+    let agents_dir = launch_agents_dir()?;
+    Ok(launchd_labels(account_name, tunnel_name)
+        .iter()
+        .map(|label| agents_dir.join(format!("{}.plist", label)))
+        .collect())
 }
 
-// Find the actual launchd label for a tunnel - checks new naming first, then legacy
+// Find the actual plist path - checks the configured prefix first, then the
+// default prefix, then legacy naming (without account)
 #[cfg(target_os = "macos")]
-async fn find_launchd_label(account_name: &str, tunnel_name: &str) -> String {
-    // First check if the new label exists in launchctl
-    let new_label = launchd_label(account_name, tunnel_name);
-    if is_label_loaded(&new_label).await {
-        return new_label;
-    }
+fn find_plist_path(account_name: &str, tunnel_name: &str) -> Result<Option<PathBuf>> {
+    Ok(candidate_plist_paths(account_name, tunnel_name)?
+        .into_iter()
+        .find(|path| path.exists()))
+}
 
-    // Check if legacy label exists
-    let legacy_label = legacy_launchd_label(tunnel_name);
-    if is_label_loaded(&legacy_label).await {
-        return legacy_label;
+// Find the actual launchd label for a tunnel - same order as find_plist_path
+#[cfg(target_os = "macos")]
+async fn find_launchd_label(account_name: &str, tunnel_name: &str) -> String {
+    for label in launchd_labels(account_name, tunnel_name) {
+        if is_label_loaded(&label).await {
+            return label;
+        }
     }
 
-    // Default to new label (for new installations)
-    new_label
+    // Default to the configured label (for new installations)
+    launchd_label(account_name, tunnel_name)
 }
 
 #[cfg(target_os = "macos")]
@@ -110,8 +206,11 @@ fn generate_plist(tunnel: &PersistentTunnel) -> Result<String> {
     let config_path = tunnel.config_path()?;
     let log_path = tunnel.log_path()?;
     let label = launchd_label(&tunnel.account_name, &tunnel.name);
-    let metrics_port = tunnel.get_metrics_port();
     let run_at_load = if tunnel.auto_start { "true" } else { "false" };
+    let args: String = cloudflared_args(tunnel)
+        .iter()
+        .map(|arg| format!("        <string>{}</string>\n", arg))
+        .collect();
 
     let cloudflared_path =
         which_cloudflared().unwrap_or_else(|| "/opt/homebrew/bin/cloudflared".to_string());
@@ -129,9 +228,7 @@ fn generate_plist(tunnel: &PersistentTunnel) -> Result<String> {
         <string>tunnel</string>
         <string>--config</string>
         <string>{config}</string>
-        <string>--metrics</string>
-        <string>localhost:{metrics_port}</string>
-        <string>run</string>
+{args}        <string>run</string>
     </array>
     <key>RunAtLoad</key>
     <{run_at_load}/>
@@ -152,7 +249,7 @@ fn generate_plist(tunnel: &PersistentTunnel) -> Result<String> {
         label = label,
         cloudflared = cloudflared_path,
         config = config_path.display(),
-        metrics_port = metrics_port,
+        args = args,
         run_at_load = run_at_load,
         log = log_path.display()
     );
@@ -162,7 +259,7 @@ fn generate_plist(tunnel: &PersistentTunnel) -> Result<String> {
 
 #[cfg(target_os = "macos")]
 pub async fn install_daemon(tunnel: &PersistentTunnel) -> Result<()> {
-    ensure_logs_dir()?;
+    ensure_parent_dir(&tunnel.log_path()?)?;
     let agents_dir = launch_agents_dir()?;
     fs::create_dir_all(&agents_dir).with_context(|| {
         format!(
@@ -175,9 +272,41 @@ pub async fn install_daemon(tunnel: &PersistentTunnel) -> Result<()> {
 
     let plist_content = generate_plist(tunnel)?;
     let path = plist_path(&tunnel.account_name, &tunnel.name)?;
+
+    // Replace a daemon installed under another label prefix. If it was running, the
+    // new one is started in its place, so a reinstall (`set`, a TUI edit) after the
+    // prefix changed doesn't take the tunnel down.
+    let was_running = is_daemon_running(&tunnel.name, &tunnel.account_name).await;
+    let mut replaced = false;
+    for old in candidate_plist_paths(&tunnel.account_name, &tunnel.name)? {
+        if old != path && old.exists() {
+            replaced = true;
+            for plist in [balancer_plist_for(&old), old] {
+                if plist.exists() {
+                    launchctl_unload(&plist).await.ok();
+                    fs::remove_file(&plist).ok();
+                }
+            }
+        }
+    }
+
     fs::write(&path, &plist_content)
         .with_context(|| format!("Failed to write plist to {}", path.display()))?;
 
+    let balancer_path = balancer_plist_path(&tunnel.account_name, &tunnel.name)?;
+    if tunnel.backends.is_empty() {
+        if balancer_path.exists() {
+            launchctl_unload(&balancer_path).await.ok();
+            fs::remove_file(&balancer_path).ok();
+        }
+    } else {
+        fs::write(&balancer_path, generate_balancer_plist(tunnel)?)
+            .with_context(|| format!("Failed to write plist to {}", balancer_path.display()))?;
+    }
+
+    if replaced && was_running {
+        start_daemon(&tunnel.name, &tunnel.account_name).await?;
+    }
     Ok(())
 }
 
@@ -185,17 +314,14 @@ pub async fn install_daemon(tunnel: &PersistentTunnel) -> Result<()> {
 pub async fn uninstall_daemon(tunnel_name: &str, account_name: &str) -> Result<()> {
     stop_daemon(tunnel_name, account_name).await.ok();
 
-    // Remove both new and legacy plist paths if they exist
-    let new_path = plist_path(account_name, tunnel_name)?;
-    if new_path.exists() {
-        fs::remove_file(&new_path)
-            .with_context(|| format!("Failed to remove plist: {}", new_path.display()))?;
-    }
-
-    let legacy_path = legacy_plist_path(tunnel_name)?;
-    if legacy_path.exists() {
-        fs::remove_file(&legacy_path)
-            .with_context(|| format!("Failed to remove legacy plist: {}", legacy_path.display()))?;
+    // Remove the plists (and balancer plists) under every label, including legacy
+    for path in candidate_plist_paths(account_name, tunnel_name)? {
+        for plist in [balancer_plist_for(&path), path] {
+            if plist.exists() {
+                fs::remove_file(&plist)
+                    .with_context(|| format!("Failed to remove plist: {}", plist.display()))?;
+            }
+        }
     }
 
     Ok(())
@@ -214,9 +340,20 @@ pub async fn start_daemon(tunnel_name: &str, account_name: &str) -> Result<()> {
         }
     };
 
+    // The balancer first, so cloudflared's first requests find it listening
+    let balancer_path = balancer_plist_for(&path);
+    if balancer_path.exists() {
+        launchctl_load(&balancer_path).await?;
+    }
+    mark_run_start(tunnel_name, account_name);
+    launchctl_load(&path).await
+}
+
+#[cfg(target_os = "macos")]
+async fn launchctl_load(path: &std::path::Path) -> Result<()> {
     let output = Command::new("launchctl")
         .args(["load", "-w"])
-        .arg(&path)
+        .arg(path)
         .output()
         .await
         .context("Failed to run launchctl load")?;
@@ -239,9 +376,19 @@ pub async fn stop_daemon(tunnel_name: &str, account_name: &str) -> Result<()> {
         None => return Ok(()), // No plist found, nothing to stop
     };
 
+    launchctl_unload(&path).await?;
+    let balancer_path = balancer_plist_for(&path);
+    if balancer_path.exists() {
+        launchctl_unload(&balancer_path).await?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+async fn launchctl_unload(path: &std::path::Path) -> Result<()> {
     let output = Command::new("launchctl")
         .args(["unload"])
-        .arg(&path)
+        .arg(path)
         .output()
         .await
         .context("Failed to run launchctl unload")?;
@@ -258,9 +405,8 @@ pub async fn stop_daemon(tunnel_name: &str, account_name: &str) -> Result<()> {
 
 #[cfg(target_os = "macos")]
 pub async fn is_daemon_running(tunnel_name: &str, account_name: &str) -> bool {
-    // Check both new and legacy labels
-    let new_label = launchd_label(account_name, tunnel_name);
-    let legacy_label = legacy_launchd_label(tunnel_name);
+    // Check every label the daemon may be installed under
+    let labels = launchd_labels(account_name, tunnel_name);
 
     let output = Command::new("launchctl")
         .args(["list"])
@@ -274,7 +420,7 @@ pub async fn is_daemon_running(tunnel_name: &str, account_name: &str) -> bool {
             let stdout = String::from_utf8_lossy(&out.stdout);
             stdout.lines().any(|line| {
                 let parts: Vec<&str> = line.split('\t').collect();
-                if parts.len() >= 3 && (parts[2] == new_label || parts[2] == legacy_label) {
+                if parts.len() >= 3 && labels.iter().any(|label| *label == parts[2]) {
                     parts[0].parse::<u32>().is_ok()
                 } else {
                     false
@@ -285,6 +431,66 @@ pub async fn is_daemon_running(tunnel_name: &str, account_name: &str) -> bool {
     }
 }
 
+// PID, uptime, and memory of the daemon's cloudflared process. Slower than
+// get_daemon_status (two extra commands), so only fetch it when it is shown.
+#[cfg(target_os = "macos")]
+pub async fn get_daemon_info(tunnel: &PersistentTunnel) -> DaemonInfo {
+    let label = find_launchd_label(&tunnel.account_name, &tunnel.name).await;
+    let output = match Command::new("launchctl")
+        .args(["list", &label])
+        .stderr(Stdio::null())
+        .output()
+        .await
+    {
+        Ok(output) => output,
+        Err(_) => return DaemonInfo::default(),
+    };
+
+    // launchctl prints `"PID" = 1234;` while the job is running
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let pid = stdout.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("\"PID\" = ")?
+            .trim_end_matches(';')
+            .parse::<u32>()
+            .ok()
+    });
+    let Some(pid) = pid else {
+        return DaemonInfo::default();
+    };
+
+    let ps = Command::new("ps")
+        .args(["-o", "etime=,rss=", "-p", &pid.to_string()])
+        .output()
+        .await
+        .map(|out| String::from_utf8_lossy(&out.stdout).into_owned())
+        .unwrap_or_default();
+    // rss is in kilobytes
+    let mut fields = ps.split_whitespace();
+    DaemonInfo {
+        pid: Some(pid),
+        uptime: fields.next().and_then(parse_etime),
+        memory_bytes: fields
+            .next()
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .map(|kb| kb * 1024),
+    }
+}
+
+// Parse ps elapsed time ([[dd-]hh:]mm:ss)
+#[cfg(target_os = "macos")]
+fn parse_etime(etime: &str) -> Option<Duration> {
+    let (days, rest) = match etime.split_once('-') {
+        Some((days, rest)) => (days.parse::<u64>().ok()?, rest),
+        None => (0, etime),
+    };
+    let mut secs = 0;
+    for part in rest.split(':') {
+        secs = secs * 60 + part.parse::<u64>().ok()?;
+    }
+    Some(Duration::from_secs(days * 24 * 60 * 60 + secs))
+}
+
 #[cfg(target_os = "macos")]
 pub async fn get_daemon_status(tunnel: &PersistentTunnel) -> TunnelStatus {
     // Find the actual label being used (new or legacy)
@@ -329,29 +535,99 @@ fn systemd_user_dir() -> Result<PathBuf> {
 }
 
 #[cfg(target_os = "linux")]
-fn service_name(account_name: &str, tunnel_name: &str) -> String {
+fn format_service_name(prefix: &str, account_name: &str, tunnel_name: &str) -> String {
     if account_name.is_empty() {
         // Legacy format for migration compatibility
-        format!("{}{}.service", SYSTEMD_SERVICE_PREFIX, tunnel_name)
+        format!("{}{}.service", prefix, tunnel_name)
     } else {
-        format!(
-            "{}{}-{}.service",
-            SYSTEMD_SERVICE_PREFIX, account_name, tunnel_name
-        )
+        format!("{}{}-{}.service", prefix, account_name, tunnel_name)
     }
 }
 
+// Every unit name the tunnel's daemon may be installed as: the configured prefix
+// first, then the default one
+#[cfg(target_os = "linux")]
+fn service_names(account_name: &str, tunnel_name: &str) -> Vec<String> {
+    name_prefixes(
+        ytunnel::config::daemon_config().systemd_prefix.as_ref(),
+        SYSTEMD_SERVICE_PREFIX,
+    )
+    .iter()
+    .map(|prefix| format_service_name(prefix, account_name, tunnel_name))
+    .collect()
+}
+
+// The unit new daemons are installed as
+#[cfg(target_os = "linux")]
+fn configured_service_name(account_name: &str, tunnel_name: &str) -> String {
+    service_names(account_name, tunnel_name).remove(0)
+}
+
+// The tunnel's unit: the installed one under whichever prefix, else the configured name
+#[cfg(target_os = "linux")]
+fn service_name(account_name: &str, tunnel_name: &str) -> String {
+    let names = service_names(account_name, tunnel_name);
+    let installed = systemd_user_dir()
+        .ok()
+        .and_then(|dir| names.iter().find(|name| dir.join(name).exists()).cloned());
+    installed.unwrap_or_else(|| names[0].clone())
+}
+
 #[cfg(target_os = "linux")]
 fn service_path(account_name: &str, tunnel_name: &str) -> Result<PathBuf> {
     let systemd_dir = systemd_user_dir()?;
     Ok(systemd_dir.join(service_name(account_name, tunnel_name)))
 }
 
+// The balancer of a balanced tunnel runs as its own unit next to cloudflared's
+#[cfg(target_os = "linux")]
+fn balancer_service_name(account_name: &str, tunnel_name: &str) -> String {
+    service_name(account_name, tunnel_name).replace(".service", ".balancer.service")
+}
+
+#[cfg(target_os = "linux")]
+fn generate_balancer_service(tunnel: &PersistentTunnel) -> Result<String> {
+    Ok(format!(
+        r#"[Unit]
+Description=Load balancer for Cloudflare Tunnel - {name}
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=simple
+ExecStart={command}
+Restart=on-failure
+RestartSec=5
+StandardOutput=append:{log}
+StandardError=append:{log}
+
+[Install]
+WantedBy=default.target
+"#,
+        name = tunnel.name,
+        command = balancer_command(tunnel)?.join(" "),
+        log = tunnel.log_path()?.display()
+    ))
+}
+
+#[cfg(target_os = "linux")]
+async fn systemctl_user(args: &[&str]) -> Result<std::process::Output> {
+    Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .output()
+        .await
+        .with_context(|| format!("Failed to run systemctl {}", args.join(" ")))
+}
+
 #[cfg(target_os = "linux")]
 fn generate_service(tunnel: &PersistentTunnel) -> Result<String> {
     let config_path = tunnel.config_path()?;
     let log_path = tunnel.log_path()?;
-    let metrics_port = tunnel.get_metrics_port();
+    let args: String = cloudflared_args(tunnel)
+        .iter()
+        .map(|arg| format!(" {}", arg))
+        .collect();
 
     let cloudflared_path =
         which_cloudflared().unwrap_or_else(|| "/usr/local/bin/cloudflared".to_string());
@@ -364,7 +640,7 @@ Wants=network-online.target
 
 [Service]
 Type=simple
-ExecStart={cloudflared} tunnel --config {config} --metrics localhost:{metrics_port} run
+ExecStart={cloudflared} tunnel --config {config}{args} run
 Restart=on-failure
 RestartSec=5
 StandardOutput=append:{log}
@@ -376,7 +652,7 @@ WantedBy=default.target
         name = tunnel.name,
         cloudflared = cloudflared_path,
         config = config_path.display(),
-        metrics_port = metrics_port,
+        args = args,
         log = log_path.display()
     );
 
@@ -401,7 +677,7 @@ async fn daemon_reload() -> Result<()> {
 
 #[cfg(target_os = "linux")]
 pub async fn install_daemon(tunnel: &PersistentTunnel) -> Result<()> {
-    ensure_logs_dir()?;
+    ensure_parent_dir(&tunnel.log_path()?)?;
     let systemd_dir = systemd_user_dir()?;
     fs::create_dir_all(&systemd_dir).with_context(|| {
         format!(
@@ -413,30 +689,65 @@ pub async fn install_daemon(tunnel: &PersistentTunnel) -> Result<()> {
     write_tunnel_config(tunnel)?;
 
     let service_content = generate_service(tunnel)?;
-    let path = service_path(&tunnel.account_name, &tunnel.name)?;
+    let svc = configured_service_name(&tunnel.account_name, &tunnel.name);
+    let path = systemd_dir.join(&svc);
+
+    // Replace a daemon installed under another unit prefix, starting the new one if
+    // the old one was running (see the macOS install_daemon)
+    let was_running = is_daemon_running(&tunnel.name, &tunnel.account_name).await;
+    let mut replaced = false;
+    for old in service_names(&tunnel.account_name, &tunnel.name) {
+        if old != svc && systemd_dir.join(&old).exists() {
+            replaced = true;
+            for unit in [old.replace(".service", ".balancer.service"), old] {
+                if systemd_dir.join(&unit).exists() {
+                    systemctl_user(&["stop", &unit]).await.ok();
+                    systemctl_user(&["disable", &unit]).await.ok();
+                    fs::remove_file(systemd_dir.join(&unit)).ok();
+                }
+            }
+        }
+    }
+
     fs::write(&path, &service_content)
         .with_context(|| format!("Failed to write service file to {}", path.display()))?;
 
+    let balancer_svc = balancer_service_name(&tunnel.account_name, &tunnel.name);
+    let balancer_path = systemd_dir.join(&balancer_svc);
+    if tunnel.backends.is_empty() {
+        if balancer_path.exists() {
+            systemctl_user(&["stop", &balancer_svc]).await.ok();
+            systemctl_user(&["disable", &balancer_svc]).await.ok();
+            fs::remove_file(&balancer_path).ok();
+        }
+    } else {
+        fs::write(&balancer_path, generate_balancer_service(tunnel)?).with_context(|| {
+            format!(
+                "Failed to write service file to {}",
+                balancer_path.display()
+            )
+        })?;
+    }
+
     daemon_reload().await?;
 
-    // Enable if auto_start is set
-    if tunnel.auto_start {
-        let svc = service_name(&tunnel.account_name, &tunnel.name);
-        Command::new("systemctl")
-            .args(["--user", "enable", &svc])
-            .output()
-            .await
-            .context("Failed to enable service")?;
-    } else {
-        // Disable if auto_start is false
-        let svc = service_name(&tunnel.account_name, &tunnel.name);
-        Command::new("systemctl")
-            .args(["--user", "disable", &svc])
-            .output()
-            .await
-            .ok(); // Ignore errors if not enabled
+    let mut units = vec![service_name(&tunnel.account_name, &tunnel.name)];
+    if !tunnel.backends.is_empty() {
+        units.push(balancer_svc);
+    }
+    for svc in &units {
+        if tunnel.auto_start {
+            // Enable if auto_start is set
+            systemctl_user(&["enable", svc]).await?;
+        } else {
+            // Disable if auto_start is false (ignore errors if not enabled)
+            systemctl_user(&["disable", svc]).await.ok();
+        }
     }
 
+    if replaced && was_running {
+        start_daemon(&tunnel.name, &tunnel.account_name).await?;
+    }
     Ok(())
 }
 
@@ -464,6 +775,16 @@ pub async fn uninstall_daemon(tunnel_name: &str, account_name: &str) -> Result<(
             .with_context(|| format!("Failed to remove service file: {}", path.display()))?;
     }
 
+    let balancer_svc = balancer_service_name(account_name, tunnel_name);
+    let balancer_path = systemd_user_dir()?.join(&balancer_svc);
+    if balancer_path.exists() {
+        systemctl_user(&["stop", &balancer_svc]).await.ok();
+        systemctl_user(&["disable", &balancer_svc]).await.ok();
+        fs::remove_file(&balancer_path).with_context(|| {
+            format!("Failed to remove service file: {}", balancer_path.display())
+        })?;
+    }
+
     daemon_reload().await?;
 
     Ok(())
@@ -480,16 +801,20 @@ pub async fn start_daemon(tunnel_name: &str, account_name: &str) -> Result<()> {
         );
     }
 
-    let svc = service_name(account_name, tunnel_name);
-    let output = Command::new("systemctl")
-        .args(["--user", "start", &svc])
-        .output()
-        .await
-        .context("Failed to run systemctl start")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Failed to start daemon: {}", stderr.trim());
+    // The balancer first, so cloudflared's first requests find it listening
+    let mut units = Vec::new();
+    let balancer_svc = balancer_service_name(account_name, tunnel_name);
+    if systemd_user_dir()?.join(&balancer_svc).exists() {
+        units.push(balancer_svc);
+    }
+    units.push(service_name(account_name, tunnel_name));
+    mark_run_start(tunnel_name, account_name);
+    for svc in &units {
+        let output = systemctl_user(&["start", svc]).await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Failed to start daemon: {}", stderr.trim());
+        }
     }
 
     Ok(())
@@ -503,18 +828,19 @@ pub async fn stop_daemon(tunnel_name: &str, account_name: &str) -> Result<()> {
         return Ok(());
     }
 
-    let svc = service_name(account_name, tunnel_name);
-    let output = Command::new("systemctl")
-        .args(["--user", "stop", &svc])
-        .output()
-        .await
-        .context("Failed to run systemctl stop")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        // Ignore "not loaded" type errors
-        if !stderr.contains("not loaded") && !stderr.is_empty() {
-            anyhow::bail!("Failed to stop daemon: {}", stderr.trim());
+    let mut units = vec![service_name(account_name, tunnel_name)];
+    let balancer_svc = balancer_service_name(account_name, tunnel_name);
+    if systemd_user_dir()?.join(&balancer_svc).exists() {
+        units.push(balancer_svc);
+    }
+    for svc in &units {
+        let output = systemctl_user(&["stop", svc]).await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // Ignore "not loaded" type errors
+            if !stderr.contains("not loaded") && !stderr.is_empty() {
+                anyhow::bail!("Failed to stop daemon: {}", stderr.trim());
+            }
         }
     }
 
@@ -522,7 +848,6 @@ pub async fn stop_daemon(tunnel_name: &str, account_name: &str) -> Result<()> {
 }
 
 #[cfg(target_os = "linux")]
-#[allow(dead_code)] // Public API for consistency with macOS
 pub async fn is_daemon_running(tunnel_name: &str, account_name: &str) -> bool {
     let svc = service_name(account_name, tunnel_name);
 
@@ -542,6 +867,88 @@ pub async fn is_daemon_running(tunnel_name: &str, account_name: &str) -> bool {
     }
 }
 
+// PID, uptime, and memory of the daemon's service. Slower than get_daemon_status,
+// so only fetch it when it is shown.
+#[cfg(target_os = "linux")]
+pub async fn get_daemon_info(tunnel: &PersistentTunnel) -> DaemonInfo {
+    let svc = service_name(&tunnel.account_name, &tunnel.name);
+    let output = match Command::new("systemctl")
+        .args([
+            "--user",
+            "show",
+            "-p",
+            "MainPID",
+            "-p",
+            "ActiveEnterTimestampMonotonic",
+            "-p",
+            "MemoryCurrent",
+            &svc,
+        ])
+        .stderr(Stdio::null())
+        .output()
+        .await
+    {
+        Ok(output) => output,
+        Err(_) => return DaemonInfo::default(),
+    };
+
+    let mut now = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: clock_gettime only writes to the timespec we pass in
+    let now_us = if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) } == 0 {
+        Some(now.tv_sec as u64 * 1_000_000 + now.tv_nsec as u64 / 1_000)
+    } else {
+        None
+    };
+
+    let mut info = parse_systemctl_show(&String::from_utf8_lossy(&output.stdout), now_us);
+    // Without memory accounting on the unit, read the process's own RSS
+    if info.memory_bytes.is_none() {
+        info.memory_bytes = info.pid.and_then(|pid| {
+            let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+            parse_vm_rss(&status)
+        });
+    }
+    info
+}
+
+// Parse `systemctl show` KEY=VALUE output. now_us is CLOCK_MONOTONIC in microseconds.
+#[cfg(target_os = "linux")]
+fn parse_systemctl_show(output: &str, now_us: Option<u64>) -> DaemonInfo {
+    let mut info = DaemonInfo::default();
+    for line in output.lines() {
+        match line.split_once('=') {
+            // 0 when the service has no running process
+            Some(("MainPID", pid)) => info.pid = pid.parse().ok().filter(|&pid| pid != 0),
+            // Microseconds on CLOCK_MONOTONIC; 0 when the unit never became active
+            Some(("ActiveEnterTimestampMonotonic", started)) => {
+                info.uptime = match (started.parse::<u64>(), now_us) {
+                    (Ok(started_us), Some(now_us)) if started_us != 0 => {
+                        Some(Duration::from_micros(now_us.saturating_sub(started_us)))
+                    }
+                    _ => None,
+                }
+            }
+            // "[not set]" (or u64::MAX on older systemd) without memory accounting
+            Some(("MemoryCurrent", bytes)) => {
+                info.memory_bytes = bytes.parse().ok().filter(|&b| b != u64::MAX)
+            }
+            _ => {}
+        }
+    }
+    info
+}
+
+// VmRSS from /proc/<pid>/status, in bytes (the file reports kB)
+#[cfg(target_os = "linux")]
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
 #[cfg(target_os = "linux")]
 pub async fn get_daemon_status(tunnel: &PersistentTunnel) -> TunnelStatus {
     let svc = service_name(&tunnel.account_name, &tunnel.name);
@@ -570,6 +977,321 @@ pub async fn get_daemon_status(tunnel: &PersistentTunnel) -> TunnelStatus {
 // Shared utilities
 // ============================================================================
 
+// How long to watch a freshly started daemon, and how many consecutive Running
+// polls count as "came up" (cloudflared exits within a second on a bad config)
+const START_VERIFY_TIMEOUT: Duration = Duration::from_secs(5);
+const START_VERIFY_INTERVAL: Duration = Duration::from_millis(500);
+const START_VERIFY_STABLE_POLLS: u32 = 3;
+
+// How long to keep waiting for the first edge connection to time it, and how
+// often to ask the metrics endpoint (cloudflared serves it before connecting)
+const CONNECT_WAIT: Duration = Duration::from_secs(15);
+const CONNECT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Poll the daemon after start_daemon until it has stayed Running for a moment.
+// Errors with the last log lines if it crashed or never came up. Meanwhile times
+// how long cloudflared takes to report its first edge connection; that time is
+// returned and saved as the tunnel's connect_time_ms (None if it didn't connect
+// within CONNECT_WAIT).
+pub async fn verify_daemon_started(tunnel: &PersistentTunnel) -> Result<Option<Duration>> {
+    let started = tokio::time::Instant::now();
+    let stable = wait_until_stable(tunnel);
+    let connected = first_edge_connection(tunnel, started);
+    tokio::pin!(stable, connected);
+
+    let mut connect_time = None;
+    let verified = loop {
+        tokio::select! {
+            result = &mut stable => break result,
+            time = &mut connected, if connect_time.is_none() => connect_time = Some(time),
+        }
+    };
+    if let Err(status) = verified {
+        return Err(StartFailed {
+            tunnel: tunnel.name.clone(),
+            status,
+            log_errors: recent_log_errors(tunnel, START_FAILURE_LOG_LINES),
+        }
+        .into());
+    }
+
+    let connect_time = match connect_time {
+        Some(time) => time,
+        None => connected.await,
+    };
+    // Only informational, so a failed save doesn't fail the start
+    let _ = record_connect_time(tunnel, connect_time);
+    Ok(connect_time)
+}
+
+// Wait until the daemon has been Running for START_VERIFY_STABLE_POLLS polls in a
+// row; Err with the last status when it failed or didn't come up in time
+async fn wait_until_stable(tunnel: &PersistentTunnel) -> std::result::Result<(), TunnelStatus> {
+    let deadline = tokio::time::Instant::now() + START_VERIFY_TIMEOUT;
+    let mut running_polls = 0;
+
+    loop {
+        tokio::time::sleep(START_VERIFY_INTERVAL).await;
+        let status = get_daemon_status(tunnel).await;
+
+        match status {
+            TunnelStatus::Running => {
+                running_polls += 1;
+                if running_polls >= START_VERIFY_STABLE_POLLS {
+                    return Ok(());
+                }
+            }
+            // The service manager already gave up on it
+            TunnelStatus::Error => return Err(status),
+            _ => running_polls = 0,
+        }
+
+        if tokio::time::Instant::now() >= deadline {
+            return Err(status);
+        }
+    }
+}
+
+// Time from `started` until the metrics endpoint reports an HA connection
+async fn first_edge_connection(
+    tunnel: &PersistentTunnel,
+    started: tokio::time::Instant,
+) -> Option<Duration> {
+    let url = tunnel.metrics_url()?;
+    while started.elapsed() < CONNECT_WAIT {
+        let metrics = TunnelMetrics::fetch(Some(&url), CONNECT_POLL_INTERVAL).await;
+        if metrics.ha_connections > 0 {
+            return Some(started.elapsed());
+        }
+        tokio::time::sleep(CONNECT_POLL_INTERVAL).await;
+    }
+    None
+}
+
+// Save the latest start's connect time (None clears an older one). Fails rather
+// than waits when another ytunnel holds the lock, since it's only informational.
+fn record_connect_time(tunnel: &PersistentTunnel, time: Option<Duration>) -> Result<()> {
+    let _lock = ytunnel::config::try_lock()?;
+    let mut state = TunnelState::load()?;
+    if let Some(t) = state.find_for_account_mut(&tunnel.name, &tunnel.account_name) {
+        t.connect_time_ms = time.map(|time| time.as_millis() as u64);
+        state.save()?;
+    }
+    Ok(())
+}
+
+// How many cloudflared error lines to show when a start fails
+const START_FAILURE_LOG_LINES: usize = 5;
+
+// The daemon started but cloudflared didn't stay up; carries the errors it logged
+#[derive(Debug)]
+pub struct StartFailed {
+    pub tunnel: String,
+    pub status: TunnelStatus,
+    pub log_errors: Vec<String>,
+}
+
+impl StartFailed {
+    // One line for the TUI status bar: the most recent cloudflared error, if any
+    pub fn summary(&self) -> String {
+        match self.log_errors.last() {
+            Some(line) => format!(
+                "{} failed to start: {}",
+                self.tunnel,
+                strip_log_prefix(line)
+            ),
+            None => format!("{} failed to start (see logs)", self.tunnel),
+        }
+    }
+}
+
+impl std::fmt::Display for StartFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Tunnel '{}' did not stay running after start (status: {}).",
+            self.tunnel,
+            if self.status == TunnelStatus::Error {
+                "error"
+            } else {
+                "stopped"
+            }
+        )?;
+        if !self.log_errors.is_empty() {
+            write!(f, "\ncloudflared reported:")?;
+            for line in &self.log_errors {
+                write!(f, "\n  {}", line)?;
+            }
+        }
+        write!(f, "\nSee the full log with `ytunnel logs {}`.", self.tunnel)
+    }
+}
+
+impl std::error::Error for StartFailed {}
+
+// Short description of a failed start/restart for one-line status displays
+pub fn start_error_summary(err: &anyhow::Error) -> String {
+    match err.downcast_ref::<StartFailed>() {
+        Some(failed) => failed.summary(),
+        None => err.to_string(),
+    }
+}
+
+// Appended to a tunnel's log before each start, so the latest run's lines can be
+// found at any log level (cloudflared's "Starting tunnel" line is INF)
+const RUN_MARKER: &str = "ytunnel: starting tunnel";
+
+// Write the run marker to the log the daemon appends to (best-effort)
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn mark_run_start(tunnel_name: &str, account_name: &str) {
+    let Ok(config_dir) = ytunnel::config::config_dir() else {
+        return;
+    };
+    let path = config_dir
+        .join("logs")
+        .join(account_name)
+        .join(format!("{}.log", tunnel_name));
+    if ensure_parent_dir(&path).is_err() {
+        return;
+    }
+    if let Ok(mut file) = fs::OpenOptions::new().create(true).append(true).open(&path) {
+        use std::io::Write;
+        writeln!(
+            file,
+            "{} {} {}",
+            ytunnel::cloudflare::format_timestamp(crate::now_secs()),
+            RUN_MARKER,
+            tunnel_name
+        )
+        .ok();
+    }
+}
+
+// Error lines cloudflared logged since it last started (falling back to the last
+// few lines when it logged no errors, e.g. when it was killed)
+fn recent_log_errors(tunnel: &PersistentTunnel, limit: usize) -> Vec<String> {
+    let lines = read_log_tail(tunnel, 200).unwrap_or_default();
+    last_run_errors(&lines, limit)
+}
+
+fn last_run_errors(lines: &[String], limit: usize) -> Vec<String> {
+    // Older runs may have logged errors that are already fixed. Logs from before
+    // the marker only have cloudflared's own start line, and only at info level.
+    let run_start = lines
+        .iter()
+        .rposition(|l| l.contains(RUN_MARKER))
+        .or_else(|| lines.iter().rposition(|l| l.contains("Starting tunnel")))
+        .unwrap_or(0);
+    let run = &lines[run_start..];
+
+    // logfmt reads the level from JSON logs (--logformat json) as well as plain ones
+    let errors: Vec<&String> = run
+        .iter()
+        .filter(|l| crate::logfmt::render(l, false).0 == crate::logfmt::Level::Error)
+        .collect();
+    let picked: Vec<&String> = if errors.is_empty() {
+        run.iter().collect()
+    } else {
+        errors
+    };
+    picked[picked.len().saturating_sub(limit)..]
+        .iter()
+        .map(|l| l.to_string())
+        .collect()
+}
+
+// Drop cloudflared's "<timestamp> ERR " prefix from a log line (JSON lines are
+// put into that layout first)
+fn strip_log_prefix(line: &str) -> String {
+    let (_, text) = crate::logfmt::render(line, true);
+    [" ERR ", " FTL "]
+        .iter()
+        .find_map(|level| text.split_once(level).map(|(_, rest)| rest))
+        .unwrap_or(&text)
+        .trim()
+        .to_string()
+}
+
+// Command line of a balanced tunnel's balancer (`ytunnel balance`), run as a second
+// service next to cloudflared. Paths are absolute since services start elsewhere.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn balancer_command(tunnel: &PersistentTunnel) -> Result<Vec<String>> {
+    let exe = std::env::current_exe().context("Could not determine the ytunnel executable")?;
+    let config_dir = ytunnel::config::config_dir()?;
+    Ok(vec![
+        exe.display().to_string(),
+        "--config-dir".to_string(),
+        config_dir.display().to_string(),
+        "--version-check".to_string(),
+        "never".to_string(),
+        "--account".to_string(),
+        tunnel.account_name.clone(),
+        "balance".to_string(),
+        tunnel.name.clone(),
+    ])
+}
+
+// Optional per-tunnel cloudflared flags, inserted before the `run` subcommand
+pub fn cloudflared_args(tunnel: &PersistentTunnel) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(addr) = tunnel.metrics_addr() {
+        args.push("--metrics".to_string());
+        args.push(addr.to_string());
+    }
+    if let Some(ref protocol) = tunnel.protocol {
+        args.push("--protocol".to_string());
+        args.push(protocol.clone());
+    }
+    if let Some(ref level) = tunnel.log_level {
+        args.push("--loglevel".to_string());
+        args.push(level.clone());
+    }
+    args
+}
+
+// Read the cloudflared arguments (between --config <path> and run) from the installed
+// plist/unit, so callers can report what a reinstall changed
+pub fn installed_cloudflared_args(tunnel: &PersistentTunnel) -> Option<Vec<String>> {
+    let tokens = installed_program_tokens(tunnel)?;
+    let start = tokens.iter().position(|t| t == "--config")? + 2;
+    let end = tokens.iter().rposition(|t| t == "run")?;
+    tokens.get(start..end).map(|args| args.to_vec())
+}
+
+#[cfg(target_os = "macos")]
+fn installed_program_tokens(tunnel: &PersistentTunnel) -> Option<Vec<String>> {
+    let path = find_plist_path(&tunnel.account_name, &tunnel.name).ok()??;
+    let content = std::fs::read_to_string(path).ok()?;
+    let program_args = content.split("<key>ProgramArguments</key>").nth(1)?;
+    let array = program_args.split("</array>").next()?;
+    Some(
+        array
+            .lines()
+            .filter_map(|line| {
+                line.trim()
+                    .strip_prefix("<string>")?
+                    .strip_suffix("</string>")
+                    .map(|s| s.to_string())
+            })
+            .collect(),
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn installed_program_tokens(tunnel: &PersistentTunnel) -> Option<Vec<String>> {
+    let path = service_path(&tunnel.account_name, &tunnel.name).ok()?;
+    let content = std::fs::read_to_string(path).ok()?;
+    let exec = content
+        .lines()
+        .find_map(|line| line.strip_prefix("ExecStart="))?;
+    Some(exec.split_whitespace().map(|s| s.to_string()).collect())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn installed_program_tokens(_tunnel: &PersistentTunnel) -> Option<Vec<String>> {
+    None
+}
+
 // Find the path to cloudflared
 fn which_cloudflared() -> Option<String> {
     #[cfg(target_os = "macos")]
@@ -600,9 +1322,198 @@ fn which_cloudflared() -> Option<String> {
         .map(|s| s.trim().to_string())
 }
 
+// Move legacy flat config/log files into per-account directories and regenerate
+// the daemon units that still point at the old locations
+pub async fn migrate_legacy_paths() -> Result<()> {
+    let state = TunnelState::load()?;
+    for tunnel in &state.tunnels {
+        if tunnel.migrate_legacy_files()? && is_daemon_installed(tunnel) {
+            install_daemon(tunnel).await?;
+        }
+    }
+    Ok(())
+}
+
+// How long a leftover cloudflared gets to exit after SIGTERM before SIGKILL
+const STRAY_GRACE: Duration = Duration::from_secs(5);
+const STRAY_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+// What happened to a cloudflared process that outlived stop_daemon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrayOutcome {
+    // Exited after SIGTERM
+    Terminated,
+    // Needed SIGKILL
+    Killed,
+    // Still running (e.g. owned by another user)
+    Survived,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrayProcess {
+    pub pid: u32,
+    pub outcome: StrayOutcome,
+}
+
+// Stop the daemon, then make sure no cloudflared is still serving the tunnel. The
+// service manager sometimes reports success but loses track of the process (seen
+// after sleep/wake), which would keep the hostname serving the old target. Any
+// leftover gets SIGTERM, then SIGKILL after STRAY_GRACE (immediately with force).
+pub async fn stop_daemon_fully(
+    tunnel: &PersistentTunnel,
+    force: bool,
+) -> Result<Vec<StrayProcess>> {
+    // The PID the service manager knows about, in case it loses track of it
+    let recorded_pid = get_daemon_info(tunnel).await.pid;
+    stop_daemon(&tunnel.name, &tunnel.account_name).await?;
+
+    let config_path = tunnel.config_path()?;
+    let mut strays = Vec::new();
+    for pid in find_cloudflared_processes(&config_path.to_string_lossy(), recorded_pid).await {
+        strays.push(StrayProcess {
+            pid,
+            outcome: terminate(pid, force).await,
+        });
+    }
+    Ok(strays)
+}
+
+// PIDs of cloudflared processes whose command line mentions the config file, or
+// that still run under the daemon's recorded PID
+#[cfg(unix)]
+async fn find_cloudflared_processes(config_path: &str, recorded_pid: Option<u32>) -> Vec<u32> {
+    let output = match Command::new("ps")
+        .args(["-axo", "pid=,command="])
+        .stderr(Stdio::null())
+        .output()
+        .await
+    {
+        Ok(output) => output,
+        Err(_) => return Vec::new(),
+    };
+    parse_cloudflared_pids(
+        &String::from_utf8_lossy(&output.stdout),
+        config_path,
+        recorded_pid,
+    )
+}
+
+#[cfg(not(unix))]
+async fn find_cloudflared_processes(_config_path: &str, _recorded_pid: Option<u32>) -> Vec<u32> {
+    Vec::new()
+}
+
+// Pick cloudflared processes using config_path, or running as recorded_pid, out of
+// `ps -axo pid=,command=` output. ps doesn't quote arguments, so paths with spaces
+// (macOS's "Application Support") are matched within the whole command line.
+fn parse_cloudflared_pids(
+    ps_output: &str,
+    config_path: &str,
+    recorded_pid: Option<u32>,
+) -> Vec<u32> {
+    ps_output
+        .lines()
+        .filter_map(|line| {
+            let (pid, command) = line.trim_start().split_once(char::is_whitespace)?;
+            let pid: u32 = pid.parse().ok()?;
+            let command = command.trim();
+            // The program is everything before the `tunnel` subcommand, so it may
+            // contain spaces too
+            let program = command.split(" tunnel ").next()?;
+            let is_cloudflared = program.ends_with("/cloudflared") || program == "cloudflared";
+            let matches = Some(pid) == recorded_pid || uses_config(command, config_path);
+            (is_cloudflared && matches).then_some(pid)
+        })
+        .collect()
+}
+
+// Whether config_path appears in the command line as a whole argument (so web.yml
+// doesn't match web.yml.bak): after a space or `=`, and before a space or the end
+fn uses_config(command: &str, config_path: &str) -> bool {
+    command.match_indices(config_path).any(|(at, _)| {
+        let before = command[..at].chars().next_back();
+        let after = command[at + config_path.len()..].chars().next();
+        matches!(before, Some(' ' | '=')) && after.is_none_or(char::is_whitespace)
+    })
+}
+
+#[cfg(unix)]
+async fn terminate(pid: u32, force: bool) -> StrayOutcome {
+    let pid = pid as libc::pid_t;
+    // SAFETY: kill only sends a signal; signal 0 checks whether the process exists
+    let alive = || unsafe { libc::kill(pid, 0) } == 0;
+
+    if !force {
+        unsafe { libc::kill(pid, libc::SIGTERM) };
+        let deadline = tokio::time::Instant::now() + STRAY_GRACE;
+        while tokio::time::Instant::now() < deadline {
+            if !alive() {
+                return StrayOutcome::Terminated;
+            }
+            tokio::time::sleep(STRAY_POLL_INTERVAL).await;
+        }
+    }
+
+    unsafe { libc::kill(pid, libc::SIGKILL) };
+    tokio::time::sleep(STRAY_POLL_INTERVAL).await;
+    if alive() {
+        StrayOutcome::Survived
+    } else {
+        StrayOutcome::Killed
+    }
+}
+
+#[cfg(not(unix))]
+async fn terminate(_pid: u32, _force: bool) -> StrayOutcome {
+    StrayOutcome::Survived
+}
+
+// One line per leftover process, for the CLI and the TUI status bar
+pub fn describe_strays(strays: &[StrayProcess]) -> Vec<String> {
+    strays
+        .iter()
+        .map(|stray| match stray.outcome {
+            StrayOutcome::Terminated => {
+                format!(
+                    "cloudflared (pid {}) was still running; terminated it",
+                    stray.pid
+                )
+            }
+            StrayOutcome::Killed => format!(
+                "cloudflared (pid {}) was still running; killed it (SIGKILL)",
+                stray.pid
+            ),
+            StrayOutcome::Survived => format!(
+                "cloudflared (pid {}) is still running and could not be killed",
+                stray.pid
+            ),
+        })
+        .collect()
+}
+
+// Whether a plist/unit exists for this tunnel
+pub fn is_daemon_installed(tunnel: &PersistentTunnel) -> bool {
+    #[cfg(target_os = "macos")]
+    return matches!(
+        find_plist_path(&tunnel.account_name, &tunnel.name),
+        Ok(Some(_))
+    );
+
+    #[cfg(target_os = "linux")]
+    return service_path(&tunnel.account_name, &tunnel.name)
+        .map(|p| p.exists())
+        .unwrap_or(false);
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        let _ = tunnel;
+        false
+    }
+}
+
 // Read recent log lines for a tunnel
 pub fn read_log_tail(tunnel: &PersistentTunnel, lines: usize) -> Result<Vec<String>> {
-    let log_path = tunnel.log_path()?;
+    let log_path = tunnel.existing_log_path()?;
 
     if !log_path.exists() {
         return Ok(vec!["No logs yet".to_string()]);
@@ -655,3 +1566,191 @@ pub async fn is_daemon_running(_tunnel_name: &str, _account_name: &str) -> bool
 pub async fn get_daemon_status(_tunnel: &PersistentTunnel) -> TunnelStatus {
     TunnelStatus::Stopped
 }
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub async fn get_daemon_info(_tunnel: &PersistentTunnel) -> DaemonInfo {
+    DaemonInfo::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
+    #[test]
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    fn test_name_prefixes_prefer_the_configured_one() {
+        assert_eq!(name_prefixes(None, "ytunnel-"), ["ytunnel-"]);
+        let custom = "acme-tunnel-".to_string();
+        assert_eq!(
+            name_prefixes(Some(&custom), "ytunnel-"),
+            ["acme-tunnel-", "ytunnel-"]
+        );
+        let same = "ytunnel-".to_string();
+        assert_eq!(name_prefixes(Some(&same), "ytunnel-"), ["ytunnel-"]);
+    }
+
+    #[test]
+    fn test_last_run_errors_skips_earlier_runs() {
+        let log = lines(
+            "2025-01-01T00:00:00Z INF Starting tunnel tunnelID=abc\n\
+             2025-01-01T00:00:01Z ERR old failure\n\
+             2025-01-02T00:00:00Z INF Starting tunnel tunnelID=abc\n\
+             2025-01-02T00:00:01Z INF Registered tunnel connection\n\
+             2025-01-02T00:00:02Z ERR failed to serve: listen tcp 127.0.0.1:20241: bind: address already in use",
+        );
+        let errors = last_run_errors(&log, 5);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            strip_log_prefix(&errors[0]),
+            "failed to serve: listen tcp 127.0.0.1:20241: bind: address already in use"
+        );
+    }
+
+    #[test]
+    fn test_last_run_errors_anchors_on_the_run_marker() {
+        // At --loglevel error there is no "Starting tunnel" line
+        let log = lines(&format!(
+            "2025-01-01T00:00:00Z {RUN_MARKER} api\n\
+             2025-01-01T00:00:01Z ERR old failure\n\
+             2025-01-02T00:00:00Z {RUN_MARKER} api\n\
+             2025-01-02T00:00:02Z ERR new failure"
+        ));
+        let errors = last_run_errors(&log, 5);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(strip_log_prefix(&errors[0]), "new failure");
+    }
+
+    #[test]
+    fn test_last_run_errors_reads_json_logs() {
+        let log = lines(&format!(
+            "2025-01-02T00:00:00Z {RUN_MARKER} api\n\
+             {{\"level\":\"info\",\"time\":\"2025-01-02T00:00:01Z\",\"message\":\"Registered tunnel connection\"}}\n\
+             {{\"level\":\"error\",\"time\":\"2025-01-02T00:00:02Z\",\"message\":\"Failed to serve\"}}"
+        ));
+        let errors = last_run_errors(&log, 5);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(strip_log_prefix(&errors[0]), "Failed to serve");
+    }
+
+    #[test]
+    fn test_last_run_errors_falls_back_to_tail() {
+        let log = lines("a\nb\nc\nd");
+        assert_eq!(last_run_errors(&log, 2), vec!["c", "d"]);
+        assert!(last_run_errors(&[], 2).is_empty());
+    }
+
+    #[test]
+    fn test_parse_cloudflared_pids_matches_config_exactly() {
+        let ps = "  101 /opt/homebrew/bin/cloudflared tunnel --config /cfg/dev/web.yml --metrics localhost:21000 run\n\
+                    102 /opt/homebrew/bin/cloudflared tunnel --config /cfg/dev/web.yml.bak run\n\
+                    103 vim /cfg/dev/web.yml\n\
+                    104 cloudflared tunnel --config /cfg/dev/web.yml run\n";
+        assert_eq!(
+            parse_cloudflared_pids(ps, "/cfg/dev/web.yml", None),
+            vec![101, 104]
+        );
+        assert!(parse_cloudflared_pids(ps, "/cfg/dev/api.yml", None).is_empty());
+        // The recorded PID counts even when the config path isn't matched, but only
+        // while it is still a cloudflared process
+        assert_eq!(
+            parse_cloudflared_pids(ps, "/cfg/dev/api.yml", Some(104)),
+            vec![104]
+        );
+        assert!(parse_cloudflared_pids(ps, "/cfg/dev/api.yml", Some(103)).is_empty());
+    }
+
+    #[test]
+    fn test_parse_cloudflared_pids_handles_paths_with_spaces() {
+        let config = "/Users/me/Library/Application Support/ytunnel/tunnels/dev/web.yml";
+        let ps = format!(
+            "  201 /opt/homebrew/bin/cloudflared tunnel --config {config} --metrics 127.0.0.1:21000 run\n\
+               202 /opt/homebrew/bin/cloudflared tunnel --config {config}.bak run\n\
+               203 /Users/me/My Tools/cloudflared tunnel --config {config} run\n\
+               204 /opt/homebrew/bin/cloudflared tunnel --config /Users/me/Library/Application run\n"
+        );
+        assert_eq!(parse_cloudflared_pids(&ps, config, None), vec![201, 203]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_terminate_stops_a_process() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        // Reap it as soon as it exits, like init would for a real stray
+        std::thread::spawn(move || child.wait());
+
+        assert_eq!(terminate(pid, false).await, StrayOutcome::Terminated);
+    }
+
+    #[tokio::test]
+    async fn test_first_edge_connection_waits_for_ha_connections() {
+        use std::io::{Read, Write};
+
+        // A metrics endpoint that reports no connections, then one
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for connections in [0, 1] {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    return;
+                };
+                let _ = stream.read(&mut [0u8; 1024]);
+                let body = format!("cloudflared_tunnel_ha_connections {}\n", connections);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+            }
+        });
+        let tunnel: PersistentTunnel = toml::from_str(&format!(
+            "name = \"t\"\ntarget = \"localhost:1\"\nzone_id = \"z\"\nzone_name = \"example.com\"\n\
+             hostname = \"t.example.com\"\ntunnel_id = \"id\"\nenabled = true\nmetrics_port = {}\n",
+            port
+        ))
+        .unwrap();
+
+        let started = tokio::time::Instant::now();
+        let time = first_edge_connection(&tunnel, started).await.unwrap();
+        assert!(
+            time >= CONNECT_POLL_INTERVAL && time < CONNECT_WAIT,
+            "{:?}",
+            time
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_systemctl_show_parses_pid_uptime_and_memory() {
+        let info = parse_systemctl_show(
+            "MainPID=4242\nActiveEnterTimestampMonotonic=1000000\nMemoryCurrent=31457280\n",
+            Some(91_000_000),
+        );
+        assert_eq!(
+            info,
+            DaemonInfo {
+                pid: Some(4242),
+                uptime: Some(Duration::from_secs(90)),
+                memory_bytes: Some(31_457_280),
+            }
+        );
+
+        // Stopped service, no memory accounting
+        let info = parse_systemctl_show(
+            "MainPID=0\nActiveEnterTimestampMonotonic=0\nMemoryCurrent=[not set]\n",
+            Some(91_000_000),
+        );
+        assert_eq!(info, DaemonInfo::default());
+
+        let status = "Name:\tcloudflared\nVmPeak:\t  90000 kB\nVmRSS:\t   30720 kB\n";
+        assert_eq!(parse_vm_rss(status), Some(30_720 * 1024));
+    }
+}
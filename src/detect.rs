@@ -0,0 +1,191 @@
+use anyhow::{bail, Context, Result};
+use std::time::Duration;
+use tokio::net::TcpStream;
+
+use crate::parallel;
+
+// Ports with nothing listening refuse instantly; this only bounds filtered ones
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(300);
+
+// Probes in flight at once, so a wide --detect-range doesn't open thousands of
+// sockets together and run into the file descriptor limit
+const SCAN_LIMIT: usize = 64;
+
+// A local port something is listening on (`ytunnel run --detect`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevServer {
+    pub port: u16,
+    // Name of the listening process, when the OS lets us see it
+    pub process: Option<String>,
+}
+
+// Parse a port list like "3000-3010,5173,8080" (ranges are inclusive)
+pub fn parse_ports(spec: &str) -> Result<Vec<u16>> {
+    let parse = |port: &str| -> Result<u16> {
+        port.trim()
+            .parse::<u16>()
+            .ok()
+            .filter(|p| *p != 0)
+            .with_context(|| format!("Invalid port '{}' in '{}'", port.trim(), spec))
+    };
+    let mut ports = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if start > end {
+                    bail!("Invalid port range '{}' (start is after end)", part);
+                }
+                ports.extend(start..=end);
+            }
+            None => ports.push(parse(part)?),
+        }
+    }
+    if ports.is_empty() {
+        bail!("No ports to scan in '{}'", spec);
+    }
+    ports.sort_unstable();
+    ports.dedup();
+    Ok(ports)
+}
+
+// Check the ports, SCAN_LIMIT at a time, and return the open ones, lowest first
+pub async fn scan(ports: &[u16]) -> Vec<DevServer> {
+    let mut open: Vec<u16> = parallel::run_bounded(
+        ports.to_vec(),
+        SCAN_LIMIT,
+        |port| async move { is_listening(port).await.then_some(port) },
+        |_| {},
+    )
+    .await
+    .into_iter()
+    .flatten()
+    .collect();
+    open.sort_unstable();
+    open.into_iter()
+        .map(|port| DevServer {
+            port,
+            process: process_name(port),
+        })
+        .collect()
+}
+
+// Dev servers bind 127.0.0.1, ::1 (Node's "localhost"), or both
+async fn is_listening(port: u16) -> bool {
+    let connect = |addr: &'static str| async move {
+        matches!(
+            tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect((addr, port))).await,
+            Ok(Ok(_))
+        )
+    };
+    let (v4, v6) = tokio::join!(connect("127.0.0.1"), connect("::1"));
+    v4 || v6
+}
+
+// The process listening on a port: the socket's inode from /proc/net/tcp{,6}, then
+// the process holding it. Other users' processes aren't readable and come back None.
+#[cfg(target_os = "linux")]
+fn process_name(port: u16) -> Option<String> {
+    let inodes: Vec<String> = ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .flat_map(|table| listening_inodes(&table, port))
+        .collect();
+    if inodes.is_empty() {
+        return None;
+    }
+    for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+        let pid = entry.file_name();
+        if !pid.to_string_lossy().chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        let holds_socket = fds.flatten().any(|fd| {
+            std::fs::read_link(fd.path()).is_ok_and(|link| {
+                let link = link.to_string_lossy();
+                inodes
+                    .iter()
+                    .any(|inode| link == format!("socket:[{}]", inode))
+            })
+        });
+        if holds_socket {
+            let comm = std::fs::read_to_string(entry.path().join("comm")).ok()?;
+            return Some(comm.trim().to_string());
+        }
+    }
+    None
+}
+
+// Inodes of listening sockets on a port, from a /proc/net/tcp-style table:
+// "sl local_address rem_address st ... inode" with hex addr:port and st 0A = LISTEN
+#[cfg(target_os = "linux")]
+fn listening_inodes(table: &str, port: u16) -> Vec<String> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let local_port = fields.get(1)?.rsplit(':').next()?;
+            if *fields.get(3)? != "0A" || u16::from_str_radix(local_port, 16).ok()? != port {
+                return None;
+            }
+            fields.get(9).map(|inode| inode.to_string())
+        })
+        .collect()
+}
+
+// lsof prints "p<pid>" then "c<command>" per process with -F pc
+#[cfg(target_os = "macos")]
+fn process_name(port: u16) -> Option<String> {
+    let output = std::process::Command::new("lsof")
+        .args(["-nP", "-sTCP:LISTEN", "-F", "pc"])
+        .arg(format!("-iTCP:{}", port))
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix('c'))
+        .map(String::from)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn process_name(_port: u16) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ports_accepts_lists_and_ranges() {
+        assert_eq!(
+            parse_ports("8080, 3000-3002,5173,3001").unwrap(),
+            [3000, 3001, 3002, 5173, 8080]
+        );
+        assert!(parse_ports("3005-3000").is_err());
+        assert!(parse_ports("0").is_err());
+        assert!(parse_ports("http").is_err());
+        assert!(parse_ports(" , ").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scan_finds_listening_ports() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let open = listener.local_addr().unwrap().port();
+        let closed = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let found = scan(&[closed, open]).await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].port, open);
+        // Our own socket is always readable
+        #[cfg(target_os = "linux")]
+        assert!(found[0].process.is_some());
+    }
+}
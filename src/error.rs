@@ -0,0 +1,116 @@
+use std::fmt;
+
+use crate::cloudflare::{AuthError, UnreachableError};
+
+// Failure categories that scripts can branch on via the process exit code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    // ytunnel hasn't been initialized (`ytunnel init`)
+    NotConfigured,
+    // A tunnel, account, zone, or DNS record doesn't exist
+    NotFound,
+    // Cloudflare rejected the credentials
+    Auth,
+    // Cloudflare API or network failure, usually worth retrying
+    Api,
+    // A required external tool (cloudflared) is missing
+    MissingDependency,
+    // An existing resource (e.g. a DNS record) would be overwritten
+    Conflict,
+}
+
+impl ErrorKind {
+    // Exit code for this category (1 is any other error, 2 is a usage error from clap)
+    pub fn exit_code(self) -> u8 {
+        match self {
+            ErrorKind::NotConfigured => 3,
+            ErrorKind::NotFound => 4,
+            ErrorKind::Auth => 5,
+            ErrorKind::Api => 6,
+            ErrorKind::MissingDependency => 7,
+            ErrorKind::Conflict => 8,
+        }
+    }
+}
+
+// An error tagged with its category
+#[derive(Debug)]
+pub struct CategorizedError {
+    pub kind: ErrorKind,
+    message: String,
+}
+
+impl fmt::Display for CategorizedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CategorizedError {}
+
+pub fn categorized(kind: ErrorKind, message: impl Into<String>) -> anyhow::Error {
+    CategorizedError {
+        kind,
+        message: message.into(),
+    }
+    .into()
+}
+
+pub fn not_configured(message: impl Into<String>) -> anyhow::Error {
+    categorized(ErrorKind::NotConfigured, message)
+}
+
+pub fn not_found(message: impl Into<String>) -> anyhow::Error {
+    categorized(ErrorKind::NotFound, message)
+}
+
+pub fn api(message: impl Into<String>) -> anyhow::Error {
+    categorized(ErrorKind::Api, message)
+}
+
+pub fn missing_dependency(message: impl Into<String>) -> anyhow::Error {
+    categorized(ErrorKind::MissingDependency, message)
+}
+
+pub fn conflict(message: impl Into<String>) -> anyhow::Error {
+    categorized(ErrorKind::Conflict, message)
+}
+
+// Find the category of an error by walking its cause chain
+pub fn kind_of(err: &anyhow::Error) -> Option<ErrorKind> {
+    err.chain().find_map(|cause| {
+        if let Some(e) = cause.downcast_ref::<CategorizedError>() {
+            Some(e.kind)
+        } else if cause.is::<AuthError>() {
+            Some(ErrorKind::Auth)
+        } else if cause.is::<UnreachableError>() || cause.is::<reqwest::Error>() {
+            Some(ErrorKind::Api)
+        } else {
+            None
+        }
+    })
+}
+
+// Process exit code for an error
+pub fn exit_code(err: &anyhow::Error) -> u8 {
+    kind_of(err).map(ErrorKind::exit_code).unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_exit_code_survives_context() {
+        let err = Err::<(), _>(not_found("Tunnel 'x' not found"))
+            .context("Failed to stop tunnel")
+            .unwrap_err();
+        assert_eq!(exit_code(&err), 4);
+
+        let err: anyhow::Error = AuthError::new("Invalid API Token").into();
+        assert_eq!(exit_code(&err), 5);
+
+        assert_eq!(exit_code(&anyhow::anyhow!("something else")), 1);
+    }
+}
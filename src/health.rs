@@ -0,0 +1,71 @@
+use std::time::{Duration, Instant};
+
+// Check whether a tunnel hostname is reachable over HTTPS.
+// 2xx/3xx/4xx count as healthy (the origin answered); 5xx and errors do not.
+pub async fn check_hostname(hostname: &str, timeout: Duration) -> bool {
+    let url = format!("https://{}", hostname);
+
+    // Simple HTTP HEAD request with short timeout
+    let client = match reqwest::Client::builder()
+        .timeout(timeout)
+        .danger_accept_invalid_certs(true) // In case of self-signed certs
+        .build()
+    {
+        Ok(c) => c,
+        Err(_) => return false,
+    };
+
+    match client.head(&url).send().await {
+        Ok(resp) => !resp.status().is_server_error(),
+        Err(_) => false,
+    }
+}
+
+// Check whether an HTTP server answers on a unix domain socket (for unix: targets,
+// to tell a dead origin apart from a broken tunnel). Same status rule as above.
+#[cfg(unix)]
+pub async fn check_unix_socket(path: &str, timeout: Duration) -> bool {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let probe = async {
+        let mut stream = tokio::net::UnixStream::connect(path).await.ok()?;
+        stream
+            .write_all(b"HEAD / HTTP/1.0\r\nHost: localhost\r\n\r\n")
+            .await
+            .ok()?;
+        let mut buf = [0u8; 64];
+        let n = stream.read(&mut buf).await.ok()?;
+        // "HTTP/1.1 200 OK": the status code is the second word
+        let line = String::from_utf8_lossy(&buf[..n]).into_owned();
+        line.split_whitespace().nth(1)?.parse::<u16>().ok()
+    };
+    matches!(tokio::time::timeout(timeout, probe).await, Ok(Some(status)) if status < 500)
+}
+
+#[cfg(not(unix))]
+pub async fn check_unix_socket(_path: &str, _timeout: Duration) -> bool {
+    false
+}
+
+// Poll a hostname until it becomes healthy or the deadline passes.
+// Calls `on_attempt` with the elapsed time before each check for progress output.
+pub async fn wait_until_healthy(
+    hostname: &str,
+    check_timeout: Duration,
+    deadline: Duration,
+    mut on_attempt: impl FnMut(Duration),
+) -> bool {
+    let poll_interval = Duration::from_secs(2);
+    let start = Instant::now();
+
+    loop {
+        on_attempt(start.elapsed());
+        if check_hostname(hostname, check_timeout).await {
+            return true;
+        }
+        if start.elapsed() + poll_interval >= deadline {
+            return false;
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::{bail, Result};
+
+// Internationalized domain names: Cloudflare stores zones and records in their
+// ASCII (punycode) form, so names are compared and sent in that form and only
+// turned back into Unicode for display.
+
+// ASCII form of a zone, hostname, or single label (münchen-dev.de ->
+// xn--mnchen-dev-9db.de). Plain ASCII names are returned as given.
+pub fn to_ascii(name: &str) -> Result<String> {
+    if name.is_ascii() {
+        return Ok(name.to_string());
+    }
+    match idna::domain_to_ascii(name) {
+        Ok(ascii) => Ok(ascii),
+        Err(_) => bail!("'{}' is not a valid internationalized domain name", name),
+    }
+}
+
+// Like to_ascii, but keeps the name as given when it can't be converted (for
+// lookups, where an invalid name simply won't match)
+pub fn normalize(name: &str) -> String {
+    to_ascii(name).unwrap_or_else(|_| name.to_string())
+}
+
+// Unicode form for display (xn--mnchen-dev-9db.de -> münchen-dev.de)
+pub fn to_unicode(name: &str) -> String {
+    if !name.contains("xn--") {
+        return name.to_string();
+    }
+    match idna::domain_to_unicode(name) {
+        (unicode, Ok(())) => unicode,
+        _ => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode_and_punycode_round_trip() {
+        assert_eq!(to_ascii("münchen-dev.de").unwrap(), "xn--mnchen-dev-9db.de");
+        assert_eq!(to_ascii("bücher").unwrap(), "xn--bcher-kva");
+        assert_eq!(to_unicode("xn--mnchen-dev-9db.de"), "münchen-dev.de");
+        assert_eq!(
+            to_unicode("api.xn--mnchen-dev-9db.de"),
+            "api.münchen-dev.de"
+        );
+        // ASCII input is left alone (not even lowercased)
+        assert_eq!(to_ascii("MyApp.example.com").unwrap(), "MyApp.example.com");
+        assert_eq!(to_unicode("api.example.com"), "api.example.com");
+        assert_eq!(normalize("xn--mnchen-dev-9db.de"), "xn--mnchen-dev-9db.de");
+    }
+}
@@ -0,0 +1,12 @@
+// Reusable pieces of ytunnel: the Cloudflare API client, config and tunnel
+// state files, and cloudflared metrics parsing and transfer totals. The `ytunnel` binary builds
+// its CLI and TUI on top of these.
+
+pub mod backup;
+pub mod cloudflare;
+pub mod config;
+pub mod error;
+pub mod idn;
+pub mod metrics;
+pub mod state;
+pub mod transfer;
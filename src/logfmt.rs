@@ -0,0 +1,134 @@
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+
+// Severity of a cloudflared log line, for coloring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    // Debug lines, and anything without a recognizable level
+    Other,
+}
+
+impl Level {
+    // cloudflared's JSON level names (zerolog's)
+    fn from_json(level: &str) -> Self {
+        match level {
+            "error" | "fatal" | "panic" => Level::Error,
+            "warn" | "warning" => Level::Warn,
+            "info" => Level::Info,
+            _ => Level::Other,
+        }
+    }
+
+    // Plain-text lines carry the level as "INF", "WRN", "ERR", or "FTL"
+    fn from_plain(line: &str) -> Self {
+        if line.contains("ERR") || line.contains("FTL") {
+            Level::Error
+        } else if line.contains("WRN") {
+            Level::Warn
+        } else if line.contains("INF") {
+            Level::Info
+        } else {
+            Level::Other
+        }
+    }
+}
+
+// The level of a log line and the text to show for it. With --logformat json,
+// cloudflared writes one object per line; when `formatted`, those come back in
+// the layout of its plain output, `time LVL message key=value...`. Plain lines,
+// and every line when not `formatted`, come back unchanged.
+pub fn render(line: &str, formatted: bool) -> (Level, Cow<'_, str>) {
+    let Some(fields) = parse_json(line) else {
+        return (Level::from_plain(line), Cow::Borrowed(line));
+    };
+    let level_name = fields.get("level").and_then(Value::as_str).unwrap_or("");
+    let severity = Level::from_json(level_name);
+    if !formatted {
+        return (severity, Cow::Borrowed(line));
+    }
+
+    let message_key = if fields.contains_key("message") {
+        "message"
+    } else {
+        "msg"
+    };
+    let mut parts = Vec::new();
+    if let Some(time) = fields.get("time") {
+        parts.push(field_value(time));
+    }
+    if !level_name.is_empty() {
+        parts.push(abbreviate(level_name));
+    }
+    match fields.get(message_key) {
+        Some(Value::String(message)) => parts.push(message.clone()),
+        Some(message) => parts.push(message.to_string()),
+        None => {}
+    }
+    for (key, value) in &fields {
+        if !matches!(key.as_str(), "level" | "time") && key != message_key {
+            parts.push(format!("{}={}", key, field_value(value)));
+        }
+    }
+    (severity, Cow::Owned(parts.join(" ")))
+}
+
+fn parse_json(line: &str) -> Option<Map<String, Value>> {
+    if !line.trim_start().starts_with('{') {
+        return None;
+    }
+    match serde_json::from_str(line) {
+        Ok(Value::Object(fields)) => Some(fields),
+        _ => None,
+    }
+}
+
+// The three-letter level cloudflared prints in plain-text logs
+fn abbreviate(level: &str) -> String {
+    match level {
+        "debug" => "DBG".to_string(),
+        "info" => "INF".to_string(),
+        "warn" | "warning" => "WRN".to_string(),
+        "error" => "ERR".to_string(),
+        "fatal" | "panic" => "FTL".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+// Strings bare unless they contain spaces (then JSON-quoted, so key=value pairs
+// stay readable); numbers, booleans, and nested values as JSON
+fn field_value(value: &Value) -> String {
+    match value {
+        Value::String(s) if s.is_empty() || s.contains(char::is_whitespace) => value.to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_formats_json_lines_and_keeps_plain_ones() {
+        let json = r#"{"level":"error","connIndex":0,"error":"dial tcp: i/o timeout","time":"2025-01-01T00:00:01Z","message":"Failed to serve"}"#;
+        let (level, text) = render(json, true);
+        assert_eq!(level, Level::Error);
+        assert_eq!(
+            text,
+            r#"2025-01-01T00:00:01Z ERR Failed to serve connIndex=0 error="dial tcp: i/o timeout""#
+        );
+        // Raw view: same color, original text
+        assert_eq!(render(json, false), (Level::Error, Cow::Borrowed(json)));
+
+        let (level, text) = render(r#"{"level":"warn","msg":"retrying"}"#, true);
+        assert_eq!((level, text.as_ref()), (Level::Warn, "WRN retrying"));
+
+        let plain = "2025-01-01T00:00:02Z INF Registered tunnel connection";
+        assert_eq!(render(plain, true), (Level::Info, Cow::Borrowed(plain)));
+        assert_eq!(render("{not json", true).0, Level::Other);
+        assert_eq!(render("panic: FTL", true).0, Level::Error);
+    }
+}
@@ -1,107 +1,395 @@
+mod balancer;
+mod browser;
 mod cli;
-mod cloudflare;
-mod config;
+mod clipboard;
 mod daemon;
-mod metrics;
-mod state;
+mod detect;
+mod health;
+mod logfmt;
+mod output;
+mod parallel;
+mod pattern;
+mod remote;
+mod table;
+mod teardown;
 mod tui;
 mod tunnel;
 mod update;
+mod watchdog;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
-use cli::{AccountCommands, Cli, Commands, ZonesCommands};
+use cli::{
+    AccountCommands, BackupCommands, Cli, Commands, DnsCommands, MetricsCommands, ZonesCommands,
+};
 use config::Account;
-use state::{write_tunnel_config, PersistentTunnel, TunnelState};
+use state::{write_tunnel_config, HostnameRoute, PersistentTunnel, TunnelState};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+use ytunnel::metrics::{self, TunnelMetrics};
+use ytunnel::{backup, cloudflare, config, error, idn, state, transfer};
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     let cli = Cli::parse();
+
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::from(error::exit_code(&e))
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    if let Some(dir) = cli.config_dir.clone() {
+        config::set_config_dir(dir)?;
+    }
     let account = cli.account.as_deref();
+    let no_color = cli.no_color;
+    let output = output::Format::parse(&cli.output);
+
+    let update_checks = cli.version_check != "never" && update::update_checks_enabled();
 
     // Show update hints after CLI commands, but not TUI, demo, or update itself
-    let show_update_hint = matches!(
+    let show_update_hint = update_checks
+        && matches!(
+            cli.command,
+            Some(Commands::Init { .. })
+                | Some(Commands::Run { .. })
+                | Some(Commands::Add { .. })
+                | Some(Commands::Clone { .. })
+                | Some(Commands::Start { .. })
+                | Some(Commands::Stop { .. })
+                | Some(Commands::Restart { .. })
+                | Some(Commands::Set { .. })
+                | Some(Commands::Maintenance { .. })
+                | Some(Commands::Status { .. })
+                | Some(Commands::Open { .. })
+                | Some(Commands::Logs { .. })
+                | Some(Commands::Zones { .. })
+                | Some(Commands::List { .. })
+                | Some(Commands::Metrics { .. })
+                | Some(Commands::Stats { .. })
+                | Some(Commands::Dns { .. })
+                | Some(Commands::Doctor)
+                | Some(Commands::Delete { .. })
+                | Some(Commands::CleanEphemeral { .. })
+                | Some(Commands::Reset { .. })
+                | Some(Commands::Account { .. })
+                | Some(Commands::Backup { .. })
+        );
+
+    // Move config/log files from the pre-account layout (best-effort)
+    if !matches!(
         cli.command,
-        Some(Commands::Init)
-            | Some(Commands::Run { .. })
-            | Some(Commands::Add { .. })
-            | Some(Commands::Start { .. })
-            | Some(Commands::Stop { .. })
-            | Some(Commands::Restart { .. })
-            | Some(Commands::Logs { .. })
-            | Some(Commands::Zones { .. })
-            | Some(Commands::List)
-            | Some(Commands::Delete { .. })
-            | Some(Commands::Reset { .. })
-            | Some(Commands::Account { .. })
-    );
+        Some(Commands::Init { .. }) | Some(Commands::Demo) | Some(Commands::Update { .. })
+    ) {
+        if let Err(e) = daemon::migrate_legacy_paths().await {
+            eprintln!("Warning: failed to migrate tunnel files: {:#}", e);
+        }
+        match state::migrate_credentials() {
+            Ok(0) => {}
+            Ok(moved) => eprintln!(
+                "Moved {} credentials file(s) to {}",
+                moved,
+                config::credentials_dir()?.display()
+            ),
+            Err(e) => eprintln!("Warning: failed to move credentials files: {:#}", e),
+        }
+    }
 
     match cli.command {
         None => {
             // Default: open TUI
-            tui::run_tui(account).await?;
+            tui::run_tui(account, cli.ascii, cli.theme.as_deref()).await?;
         }
-        Some(Commands::Init) => {
-            cmd_init().await?;
+        Some(Commands::Init { api_key, email }) => {
+            cmd_init(api_key, email).await?;
         }
-        Some(Commands::Run { args, zone }) => {
-            // Parse args: if 1 arg it's target, if 2 args it's name + target
-            let (name, target) = if args.len() == 2 {
+        Some(Commands::Run {
+            args,
+            detect,
+            detect_range,
+            zone,
+            fallback,
+            keep,
+            delete,
+            timeout,
+        }) => {
+            // Parse args: if 1 arg it's target, if 2 args it's name + target.
+            // --detect finds the target, so a single arg is the name.
+            let (name, target) = if detect {
+                if args.len() > 1 {
+                    anyhow::bail!("With --detect, pass at most a name (the target is detected).");
+                }
+                let target = detect_target(detect_range.as_deref()).await?;
+                (args.first().cloned(), target)
+            } else if args.len() == 2 {
                 (Some(args[0].clone()), args[1].clone())
             } else {
                 (None, args[0].clone())
             };
-            cmd_run(name, target, zone, account).await?;
+            // --keep/--delete answer the exit prompt up front
+            let keep = match (keep, delete) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            };
+            let timeout = timeout.map(Duration::from_secs);
+            cmd_run(name, target, zone, fallback, keep, timeout, account).await?;
         }
         Some(Commands::Add {
             name,
             target,
             zone,
             start,
+            protocol,
+            log_level,
+            metrics_port,
+            fallback,
+            wait_healthy,
+            hostnames,
+            no_rollback,
+            force,
+            no_dns,
+            description,
+            preset,
+        }) => {
+            let opts = AddOptions {
+                zone,
+                start,
+                protocol,
+                log_level,
+                metrics_port,
+                fallback,
+                wait_healthy,
+                hostnames,
+                no_rollback,
+                force,
+                no_dns,
+                description,
+                auto_start: false,
+                preset,
+            };
+            cmd_add(name, target, opts, account).await?;
+        }
+        Some(Commands::Clone {
+            source,
+            name,
+            target,
+            zone,
+            start,
+        }) => {
+            cmd_clone(source, name, target, zone, start, account).await?;
+        }
+        Some(Commands::Start {
+            name,
+            no_dns,
+            regex,
+            offline,
+            verify_dns,
+            force_recreate,
+        }) => {
+            let dns = DnsCheck::new(offline, verify_dns, force_recreate);
+            match matching_tunnels(&name, regex, account)? {
+                Some((acct, names)) => {
+                    for_each_tunnel("Started", names, |n| cmd_start(n, no_dns, dns, Some(&acct)))
+                        .await?
+                }
+                None => cmd_start(name, no_dns, dns, account).await?,
+            }
+        }
+        Some(Commands::Stop { name, regex, force }) => {
+            match matching_tunnels(&name, regex, account)? {
+                Some((acct, names)) => cmd_stop_many(names, force, &acct).await?,
+                None => cmd_stop(name, force, account).await?,
+            }
+        }
+        Some(Commands::Restart {
+            name,
+            regex,
+            offline,
+            verify_dns,
+            force_recreate,
+        }) => {
+            let dns = DnsCheck::new(offline, verify_dns, force_recreate);
+            match matching_tunnels(&name, regex, account)? {
+                Some((acct, names)) => {
+                    for_each_tunnel("Restarted", names, |n| cmd_restart(n, dns, Some(&acct)))
+                        .await?
+                }
+                None => cmd_restart(name, dns, account).await?,
+            }
+        }
+        Some(Commands::Set {
+            name,
+            target,
+            auto_start,
+            metrics_port,
+            protocol,
+            log_level,
+            fallback,
+            manage_dns,
+            description,
+            unset,
+            restart,
         }) => {
-            cmd_add(name, target, zone, start, account).await?;
+            let opts = SetOptions {
+                target,
+                auto_start,
+                metrics_port,
+                protocol,
+                log_level,
+                fallback,
+                manage_dns,
+                description,
+                unset,
+                restart,
+            };
+            cmd_set(name, opts, account).await?;
         }
-        Some(Commands::Start { name }) => {
-            cmd_start(name, account).await?;
+        Some(Commands::Maintenance {
+            name,
+            enabled,
+            origin,
+        }) => {
+            cmd_maintenance(name, enabled, origin, account).await?;
         }
-        Some(Commands::Stop { name }) => {
-            cmd_stop(name, account).await?;
+        Some(Commands::Open { name, print }) => {
+            cmd_open(name, print, account)?;
         }
-        Some(Commands::Restart { name }) => {
-            cmd_restart(name, account).await?;
+        Some(Commands::Status { name }) => {
+            cmd_status(name, output, account).await?;
         }
         Some(Commands::Logs {
             name,
+            all,
             follow,
             lines,
+            raw,
+        }) => {
+            let printer = LogPrinter::new(raw, no_color);
+            match name {
+                Some(name) if !all => cmd_logs(name, follow, lines, printer, account).await?,
+                _ => cmd_logs_all(follow, lines, printer, account).await?,
+            }
+        }
+        Some(Commands::Zones {
+            command,
+            verify,
+            json,
+        }) => match command {
+            None => {
+                let output = if json { output::Format::Json } else { output };
+                cmd_zones_list(verify, output, no_color, account).await?
+            }
+            Some(ZonesCommands::Default { domain, yes }) => {
+                cmd_zones_default(domain, yes, account).await?
+            }
+        },
+        Some(Commands::List {
+            columns,
+            wide,
+            auto_start,
+            enabled,
+            json,
+            remote,
         }) => {
-            cmd_logs(name, follow, lines, account).await?;
+            let filter = ListFilter {
+                auto_start,
+                enabled,
+            };
+            let output = if json { output::Format::Json } else { output };
+            if remote {
+                cmd_list_remote(filter, output, no_color, account).await?;
+            } else if output.is_structured() {
+                cmd_list_structured(filter, output, account).await?;
+            } else {
+                cmd_list(columns, wide, filter, output, no_color, account).await?;
+            }
         }
-        Some(Commands::Zones { command }) => match command {
-            None => cmd_zones_list(account).await?,
-            Some(ZonesCommands::Default { domain }) => cmd_zones_default(domain, account).await?,
+        Some(Commands::Metrics { command }) => match command {
+            MetricsCommands::Push { url, interval } => {
+                cmd_metrics_push(url, interval, account).await?
+            }
         },
-        Some(Commands::List) => {
-            cmd_list(account).await?;
+        Some(Commands::Stats { json }) => {
+            let output = if json { output::Format::Json } else { output };
+            cmd_stats(output, no_color, account).await?
         }
-        Some(Commands::Delete { name }) => {
-            cmd_delete(name, account).await?;
+        Some(Commands::Dns { command }) => match command {
+            DnsCommands::List { zone } => cmd_dns_list(zone, account).await?,
+            DnsCommands::Delete { hostname, yes } => cmd_dns_delete(hostname, yes, account).await?,
+        },
+        Some(Commands::Doctor) => cmd_doctor(account).await?,
+        Some(Commands::Watch { interval, grace }) => {
+            let cfg = config::load_config()?.watchdog;
+            watchdog::run(
+                account,
+                Duration::from_secs(interval.unwrap_or(cfg.interval_secs)),
+                Duration::from_secs(grace.unwrap_or(cfg.grace_secs)),
+            )
+            .await?
         }
-        Some(Commands::Reset { yes }) => {
-            cmd_reset(yes).await?;
+        Some(Commands::Delete {
+            name,
+            yes,
+            regex,
+            retry,
+            keep_dns,
+        }) => match matching_tunnels(&name, regex, account)? {
+            Some((acct, names)) => {
+                if names.len() > 1 && !yes && !confirm_bulk("Delete", &names)? {
+                    println!("Cancelled.");
+                    return Ok(());
+                }
+                backup_before(&format!("delete {}", name))?;
+                for_each_tunnel("Deleted", names, |n| {
+                    cmd_delete(n, yes, retry, keep_dns, Some(&acct))
+                })
+                .await?
+            }
+            None => cmd_delete(name, yes, retry, keep_dns, account).await?,
+        },
+        Some(Commands::CleanEphemeral { older_than, yes }) => {
+            cmd_clean_ephemeral(older_than, yes, account).await?;
         }
+        Some(Commands::Reset { yes, retry }) => match account {
+            Some(name) => cmd_reset_account(name, yes, retry).await?,
+            None => cmd_reset(yes, retry).await?,
+        },
         Some(Commands::Demo) => {
-            tui::run_demo_tui().await?;
+            tui::run_demo_tui(cli.ascii, cli.theme.as_deref()).await?;
         }
+        Some(Commands::Backup { command }) => match command {
+            BackupCommands::List => cmd_backup_list(no_color)?,
+            BackupCommands::Restore {
+                timestamp,
+                yes,
+                force,
+            } => cmd_backup_restore(timestamp, yes, force).await?,
+        },
         Some(Commands::Account { command }) => match command {
-            None => cmd_account_list().await?,
-            Some(AccountCommands::List) => cmd_account_list().await?,
+            None => cmd_account_list(output).await?,
+            Some(AccountCommands::List) => cmd_account_list(output).await?,
             Some(AccountCommands::Select { name }) => cmd_account_select(name).await?,
             Some(AccountCommands::Default { name }) => cmd_account_select(name).await?,
-            Some(AccountCommands::Remove { name, yes }) => cmd_account_remove(name, yes).await?,
+            Some(AccountCommands::Switch { name }) => cmd_account_switch(name).await?,
+            Some(AccountCommands::Token { name, token, force }) => {
+                cmd_account_token(name, token, force).await?
+            }
+            Some(AccountCommands::Remove { name, yes, retry }) => {
+                cmd_account_remove(name, yes, retry).await?
+            }
         },
         Some(Commands::Update { check }) => {
-            update::cmd_update(check).await?;
+            update::cmd_update(check, update_checks).await?;
+        }
+        Some(Commands::Balance { name }) => {
+            cmd_balance(name, account).await?;
         }
     }
 
@@ -112,13 +400,23 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-async fn cmd_init() -> Result<()> {
+// Print a prompt and read one trimmed line from stdin
+fn prompt_line(question: &str) -> Result<String> {
+    println!("{}", question);
+    print!("> ");
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+async fn cmd_init(use_api_key: bool, email: Option<String>) -> Result<()> {
     // Check if cloudflared is installed (do this first for better UX)
     if !tunnel::is_cloudflared_installed().await {
-        anyhow::bail!(
-            "cloudflared is not installed. Please install it first:\n  \
-             brew install cloudflare/cloudflare/cloudflared"
-        );
+        return Err(error::missing_dependency(format!(
+            "cloudflared is not installed; {}",
+            tunnel::CLOUDFLARED_INSTALL_HINT
+        )));
     }
 
     // Check if already configured
@@ -184,7 +482,7 @@ async fn cmd_init() -> Result<()> {
                     return Ok(());
                 }
                 // Reset and continue to init flow
-                cmd_reset(true).await?;
+                cmd_reset(true, false).await?;
                 println!();
 
                 // Prompt for account name after reset
@@ -225,52 +523,88 @@ async fn cmd_init() -> Result<()> {
         name
     };
 
-    // Get API token
-    println!("\nEnter your Cloudflare API token:");
-    println!(
-        "  Required permissions: Zone→Zone→Edit, Zone→DNS→Edit, Account→Cloudflare Tunnel→Edit"
-    );
-    print!("> ");
-    std::io::Write::flush(&mut std::io::stdout())?;
-    let mut token = String::new();
-    std::io::stdin().read_line(&mut token)?;
-    let token = token.trim().to_string();
-
-    if token.is_empty() {
-        anyhow::bail!("API token cannot be empty");
-    }
+    // Get API token (or a Global API Key and its email)
+    let (token, api_key, email) = if use_api_key {
+        println!(
+            "\nWarning: a Global API Key has full access to everything in your Cloudflare login."
+        );
+        println!(
+            "  Prefer a scoped API token when you can (run `ytunnel init` without --api-key)."
+        );
+        let email = match email {
+            Some(email) => email,
+            None => prompt_line("\nEnter the email address of your Cloudflare login:")?,
+        };
+        if email.is_empty() {
+            anyhow::bail!("Email cannot be empty");
+        }
+        let key = prompt_line("\nEnter your Cloudflare Global API Key:")?;
+        if key.is_empty() {
+            anyhow::bail!("API key cannot be empty");
+        }
+        (String::new(), Some(key), Some(email))
+    } else {
+        let token = prompt_line(
+            "\nEnter your Cloudflare API token:\n  \
+             Required permissions: Zone→Zone→Edit, Zone→DNS→Edit, Account→Cloudflare Tunnel→Edit",
+        )?;
+        if token.is_empty() {
+            anyhow::bail!("API token cannot be empty");
+        }
+        (token, None, None)
+    };
 
-    // Verify token and fetch zones
-    println!("\nVerifying token and fetching zones...");
-    let client = cloudflare::Client::new(&token);
+    // Verify credentials and fetch zones
+    println!("\nVerifying credentials and fetching zones...");
+    let client = match (&api_key, &email) {
+        (Some(key), Some(email)) => {
+            cloudflare::Client::with_credentials(cloudflare::Credentials::ApiKey {
+                key: key.clone(),
+                email: email.clone(),
+            })
+        }
+        _ => cloudflare::Client::new(&token),
+    };
     let zones = client.list_zones().await?;
 
-    if zones.is_empty() {
-        anyhow::bail!("No zones found for this API token");
-    }
-
-    println!("✓ Found {} zone(s):", zones.len());
-    for (i, zone) in zones.iter().enumerate() {
-        println!("  {}. {} ({})", i + 1, zone.name, zone.id);
-    }
-
-    // Get account ID from first zone
-    let cf_account_id = zones[0].account_id.clone();
+    let (cf_account_id, default_zone_id, default_zone_name) = if zones.is_empty() {
+        // Account-scoped tokens can manage tunnels without seeing any zones
+        println!("No zones visible to this token. Looking up its account instead...");
+        let cf_account_id = choose_cloudflare_account(&client.list_accounts().await?)?;
+        println!(
+            "\nContinuing without zones. Tunnels for this account need a hostname (or --zone) \
+             in a zone from another configured account, e.g.:"
+        );
+        println!("  ytunnel run myapp.example.com localhost:3000");
+        (cf_account_id, String::new(), String::new())
+    } else {
+        println!("✓ Found {} zone(s):", zones.len());
+        for (i, zone) in zones.iter().enumerate() {
+            println!("  {}. {} ({})", i + 1, zone.name, zone.id);
+        }
 
-    // Set default zone
-    let default_zone = &zones[0];
-    println!(
-        "\nSetting default zone to: {} (change with `ytunnel zones default <domain>`)",
-        default_zone.name
-    );
+        // Set default zone (the account ID comes from the first zone too)
+        let default_zone = &zones[0];
+        println!(
+            "\nSetting default zone to: {} (change with `ytunnel zones default <domain>`)",
+            default_zone.name
+        );
+        (
+            default_zone.account_id.clone(),
+            default_zone.id.clone(),
+            default_zone.name.clone(),
+        )
+    };
 
     // Create the account
     let new_account = Account {
         name: account_name.clone(),
         api_token: token,
+        api_key,
+        email,
         account_id: cf_account_id,
-        default_zone_id: default_zone.id.clone(),
-        default_zone_name: default_zone.name.clone(),
+        default_zone_id,
+        default_zone_name,
         zones: zones
             .into_iter()
             .map(|z| config::ZoneConfig {
@@ -280,33 +614,39 @@ async fn cmd_init() -> Result<()> {
             .collect(),
     };
 
-    // Load existing config or create new one
-    let mut cfg = if config::config_path()?.exists() {
-        config::load_config()?
-    } else {
-        config::Config {
-            selected_account: account_name.clone(),
-            accounts: Vec::new(),
-        }
-    };
-
-    // Add the new account
-    cfg.add_account(new_account)?;
-
-    // Ask if this should be the default (if there are multiple accounts)
-    if cfg.accounts.len() > 1 && cfg.selected_account != account_name {
+    // Ask if this should be the default (if there are other accounts), before
+    // taking the config lock so the prompt doesn't hold up other ytunnel commands
+    let has_other_accounts = config::config_path()?.exists()
+        && config::load_config()?
+            .accounts
+            .iter()
+            .any(|a| a.name != account_name);
+    let make_default = has_other_accounts && {
         println!("\nSet '{}' as the default account? [y/N]", account_name);
         print!("> ");
         std::io::Write::flush(&mut std::io::stdout())?;
         let mut set_default = String::new();
         std::io::stdin().read_line(&mut set_default)?;
-        if set_default.trim().to_lowercase() == "y" {
+        set_default.trim().to_lowercase() == "y"
+    };
+
+    // Load existing config or create new one, and add the new account
+    {
+        let _lock = config::lock()?;
+        let mut cfg = if config::config_path()?.exists() {
+            config::load_config()?
+        } else {
+            config::Config::new(account_name.clone())
+        };
+        cfg.add_account(new_account)?;
+        if make_default {
             cfg.select_account(&account_name)?;
-            println!("Default account set to '{}'", account_name);
         }
+        config::save_config(&cfg)?;
+    }
+    if make_default {
+        println!("Default account set to '{}'", account_name);
     }
-
-    config::save_config(&cfg)?;
 
     println!(
         "\n✓ Account '{}' added to {}",
@@ -321,112 +661,225 @@ async fn cmd_init() -> Result<()> {
     Ok(())
 }
 
+// Pick the Cloudflare account for a token without zone access, asking when it has several
+fn choose_cloudflare_account(accounts: &[cloudflare::Account]) -> Result<String> {
+    match accounts {
+        [] => Err(error::categorized(
+            error::ErrorKind::Auth,
+            "No zones or accounts found for this API token. \
+             It needs at least Account→Cloudflare Tunnel→Edit.",
+        )),
+        [only] => {
+            println!("✓ Found account: {} ({})", only.name, only.id);
+            Ok(only.id.clone())
+        }
+        _ => {
+            println!("This token can access {} accounts:", accounts.len());
+            for (i, a) in accounts.iter().enumerate() {
+                println!("  {}. {} ({})", i + 1, a.name, a.id);
+            }
+            println!(
+                "Which one should tunnels be created in? [1-{}]",
+                accounts.len()
+            );
+            print!("> ");
+            std::io::Write::flush(&mut std::io::stdout())?;
+
+            let mut input = String::new();
+            std::io::stdin().read_line(&mut input)?;
+            let index = input
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|i| (1..=accounts.len()).contains(i))
+                .context("Invalid selection")?;
+            Ok(accounts[index - 1].id.clone())
+        }
+    }
+}
+
+// Scan for a local dev server and return it as a localhost:<port> target, asking
+// which one to use when several are listening
+async fn detect_target(range: Option<&str>) -> Result<String> {
+    use std::io::IsTerminal;
+
+    let ports = match range {
+        Some(spec) => detect::parse_ports(spec)?,
+        None => config::load_config()?.run.detect_ports,
+    };
+    let found = detect::scan(&ports).await;
+    let label = |server: &detect::DevServer| match &server.process {
+        Some(process) => format!("localhost:{} ({})", server.port, process),
+        None => format!("localhost:{}", server.port),
+    };
+    let chosen = match found.as_slice() {
+        [] => anyhow::bail!(
+            "Nothing is listening on localhost ports {}. Start your dev server, pass \
+             --detect-range, or give the target explicitly.",
+            ports
+                .iter()
+                .map(u16::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        [only] => {
+            println!("Detected {}", label(only));
+            only
+        }
+        several => {
+            if !std::io::stdin().is_terminal() {
+                anyhow::bail!(
+                    "Several ports are open ({}); pass the target or narrow --detect-range.",
+                    several.iter().map(label).collect::<Vec<_>>().join(", ")
+                );
+            }
+            println!("Found several local servers:");
+            for (i, server) in several.iter().enumerate() {
+                println!("  {}. {}", i + 1, label(server));
+            }
+            let answer = prompt_line(&format!("Which one? [1-{}]", several.len()))?;
+            match answer.parse::<usize>() {
+                Ok(n) if (1..=several.len()).contains(&n) => &several[n - 1],
+                _ => anyhow::bail!("No server selected."),
+            }
+        }
+    };
+    Ok(format!("localhost:{}", chosen.port))
+}
+
 // Run an ephemeral tunnel (foreground, stops on Ctrl+C)
 async fn cmd_run(
     name: Option<String>,
     target: String,
     zone: Option<String>,
+    fallback: Option<String>,
+    keep: Option<bool>,
+    timeout: Option<Duration>,
     account: Option<&str>,
 ) -> Result<()> {
     let cfg = config::load_config()?;
     let acct = cfg.get_account(account)?;
-    let client = cloudflare::Client::new(&acct.api_token);
-
-    // Determine zone
-    let (zone_id, zone_name) = if let Some(z) = zone {
-        // Find zone by name
-        let found = acct.zones.iter().find(|zc| zc.name == z);
-        match found {
-            Some(zc) => (zc.id.clone(), zc.name.clone()),
-            None => anyhow::bail!(
-                "Zone '{}' not found. Run `ytunnel zones` to see available zones.",
-                z
-            ),
-        }
-    } else {
-        (acct.default_zone_id.clone(), acct.default_zone_name.clone())
-    };
-
-    // Determine subdomain name
-    let subdomain = match name {
-        Some(n) => {
-            // Check if it's a full domain or just a subdomain part
-            if n.contains('.') {
-                // Full domain like "license.tunnel.rhuk.net" - extract the subdomain part
-                n.strip_suffix(&format!(".{}", zone_name))
-                    .map(|s| s.to_string())
-                    .unwrap_or(n)
-            } else {
-                n
-            }
-        }
-        None => {
-            // Generate random name
-            use rand::Rng;
-            let mut rng = rand::rng();
-            let suffix: String = (0..6)
-                .map(|_| {
-                    let chars: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
-                    chars[rng.random_range(0..chars.len())] as char
-                })
-                .collect();
-            format!("ytunnel-{}", suffix)
-        }
-    };
-
+    let client = cfg.client(acct);
+
+    // Use the given name, or generate a random one (the Cloudflare tunnel gets the
+    // tunnel_prefix from cloudflare_tunnel_name, like any other)
+    let name = name.unwrap_or_else(|| {
+        use rand::Rng;
+        let mut rng = rand::rng();
+        (0..6)
+            .map(|_| {
+                let chars: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+                chars[rng.random_range(0..chars.len())] as char
+            })
+            .collect()
+    });
+
+    // Determine zone and subdomain (full hostnames select their zone automatically)
+    let (zc, subdomain) = cfg.resolve_hostname(acct, &name, zone.as_deref())?;
+    let (zone_id, zone_name) = (zc.id, zc.name);
+    let dns_acct = cfg.dns_account(acct, &zone_id);
+    let dns_client = cfg.client(dns_acct);
+
+    validate_target(&target)?;
+    if let Some(fallback) = &fallback {
+        state::validate_service(fallback)?;
+    }
     let full_hostname = format!("{}.{}", subdomain, zone_name);
-    println!("Setting up tunnel: {} -> {}", full_hostname, target);
+    println!(
+        "Setting up tunnel: {} -> {}",
+        idn::to_unicode(&full_hostname),
+        target
+    );
+    if cfg.accounts.len() > 1 {
+        // <tunnel_prefix><name> can exist in several accounts; make the one in use obvious
+        println!("Using account: {}", acct.name);
+    }
+    if dns_acct.name != acct.name {
+        println!("Using DNS from account: {}", dns_acct.name);
+    }
 
     // Check if tunnel exists, create if not
-    let tunnel_name = format!("ytunnel-{}", subdomain);
-    let (tunnel, credentials_path) = match client
-        .get_tunnel_by_name(&acct.account_id, &tunnel_name)
-        .await?
-    {
-        Some(t) => {
-            println!("✓ Using existing tunnel: {}", t.name);
-            let creds_path = t.credentials_path()?;
-            if !creds_path.exists() {
-                anyhow::bail!(
-                    "Credentials file not found: {}\n\
-                     This tunnel may have been created outside ytunnel.\n\
-                     Delete it with `ytunnel delete {}` and try again.",
-                    creds_path.display(),
-                    subdomain
-                );
-            }
-            (t, creds_path)
-        }
-        None => {
-            println!("Creating tunnel: {}", tunnel_name);
-            let result = client.create_tunnel(&acct.account_id, &tunnel_name).await?;
-            (result.tunnel, result.credentials_path)
-        }
-    };
+    let tunnel_name = config::cloudflare_tunnel_name(&subdomain);
+    offer_leaked_run_cleanup(&cfg, acct, &tunnel_name).await?;
+    let cloudflare::TunnelWithCredentials {
+        tunnel,
+        credentials_path,
+        created,
+    } = client
+        .create_or_get_tunnel(&acct.account_id, &tunnel_name)
+        .await?;
+    if created {
+        println!("✓ Created tunnel: {}", tunnel.name);
+    } else {
+        println!("✓ Using existing tunnel: {}", tunnel.name);
+    }
 
     // Ensure DNS record exists
-    println!("Configuring DNS record...");
-    client
+    resolve_dns_conflicts(&dns_client, &zone_id, &full_hostname).await?;
+    let change = dns_client
         .ensure_dns_record(&zone_id, &full_hostname, &tunnel.id)
         .await?;
-    println!("✓ DNS configured: {}", full_hostname);
+    println!("✓ {}", change.describe(&full_hostname));
+
+    // Warn about other connectors for the same tunnel competing for traffic
+    if !tunnel::other_live_ephemeral_configs(&tunnel.id).is_empty()
+        || tunnel::is_cloudflared_running_for(&tunnel.id).await
+    {
+        println!(
+            "Warning: cloudflared is already running for this tunnel. \
+             Requests will be split between both connectors."
+        );
+    }
 
     // Run the tunnel
-    println!("\nStarting tunnel (Ctrl+C to stop)...\n");
-    tunnel::run_tunnel(&tunnel.id, &credentials_path, &full_hostname, &target).await?;
+    match timeout {
+        Some(timeout) => println!(
+            "\nStarting tunnel (Ctrl+C to stop, stops by itself after {})...\n",
+            format_uptime(timeout)
+        ),
+        None => println!("\nStarting tunnel (Ctrl+C to stop)...\n"),
+    }
+    let timed_out = tunnel::run_tunnel(
+        &tunnel.id,
+        &credentials_path,
+        &full_hostname,
+        &target,
+        fallback.as_deref(),
+        timeout,
+    )
+    .await?;
+
+    // A timed-out run is usually unattended: delete (unless --keep) rather than wait
+    // for an answer
+    let keep = keep.or(timed_out.then_some(false));
 
     // Check if tunnel was imported as a managed tunnel (skip cleanup if so)
     let state = TunnelState::load()?;
     let was_imported = state.tunnels.iter().any(|t| t.tunnel_id == tunnel.id);
 
+    // Other runs still using this tunnel own the shared DNS record and credentials
+    let other_runs = tunnel::other_live_ephemeral_configs(&tunnel.id);
+
     if was_imported {
         println!("\nTunnel was imported as managed - keeping resources.");
+    } else if !other_runs.is_empty() {
+        println!(
+            "\nTunnel is still in use by {} other `ytunnel run` process(es) - keeping resources.",
+            other_runs.len()
+        );
+    } else if keep.unwrap_or_else(|| ask_keep_run(&cfg.run)) {
+        tunnel::mark_kept(&tunnel.id, &full_hostname, &target)?;
+        println!(
+            "\nKept tunnel {} and its DNS record; `ytunnel run {} {}` reuses them.",
+            tunnel_name, subdomain, target
+        );
     } else {
         // Clean up after tunnel stops
         println!("\nCleaning up...");
+        tunnel::unmark_kept(&tunnel.id);
 
         // Delete DNS record
-        if let Err(e) = client.delete_dns_record(&zone_id, &full_hostname).await {
+        if let Err(e) = dns_client.delete_dns_record(&zone_id, &full_hostname).await {
             eprintln!("Warning: Failed to delete DNS record: {}", e);
         } else {
             println!("✓ Removed DNS record: {}", full_hostname);
@@ -450,453 +903,3286 @@ async fn cmd_run(
     Ok(())
 }
 
-// Add a persistent tunnel (non-interactive CLI command)
-async fn cmd_add(
-    name: String,
-    target: String,
-    zone: Option<String>,
-    start: bool,
+// Ask whether to keep the run's tunnel for the next run (true) or delete it. Asked
+// once per terminal session; without a terminal the tunnel is deleted as before.
+fn ask_keep_run(run: &config::RunConfig) -> bool {
+    use std::io::{IsTerminal, Write};
+
+    if !std::io::stdin().is_terminal() {
+        return false;
+    }
+    if let Some(keep) = tunnel::session_exit_choice() {
+        return keep;
+    }
+
+    let default_keep = run.keeps_by_default();
+    println!(
+        "\nDelete this tunnel or keep it for reuse? {}",
+        if default_keep { "[d/K]" } else { "[D/k]" }
+    );
+    print!("> ");
+    std::io::stdout().flush().ok();
+    let mut input = String::new();
+    if std::io::stdin().read_line(&mut input).is_err() {
+        return default_keep;
+    }
+    let keep = match input.trim().to_lowercase().as_str() {
+        "d" | "delete" => false,
+        "k" | "keep" => true,
+        _ => default_keep,
+    };
+    if let Err(e) = tunnel::remember_session_exit_choice(keep) {
+        eprintln!("Warning: couldn't remember the choice: {:#}", e);
+    }
+    keep
+}
+
+// Delete the account's unused <tunnel_prefix>* tunnels (only those older than
+// `older_than` seconds, if given) with the DNS records pointing at them
+async fn cmd_clean_ephemeral(
+    older_than: Option<u64>,
+    skip_confirm: bool,
     account: Option<&str>,
 ) -> Result<()> {
+    use std::io::IsTerminal;
+
     let cfg = config::load_config()?;
     let acct = cfg.get_account(account)?;
-    let client = cloudflare::Client::new(&acct.api_token);
-    let account_name = acct.name.clone();
-
-    // Check if tunnel already exists in state for this account
     let state = TunnelState::load()?;
-    if state.find_for_account(&name, &account_name).is_some() {
-        anyhow::bail!(
-            "Tunnel '{}' already exists for account '{}'. Use `ytunnel delete {}` first.",
-            name,
-            account_name,
-            name
-        );
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let mut idle = tunnel::find_idle_ephemeral(&cfg.client(acct), acct, &state).await?;
+    // Without a creation time a tunnel can't be shown to be old enough
+    if let Some(min_age) = older_than {
+        idle.retain(|t| {
+            t.created_secs()
+                .is_some_and(|created| now.saturating_sub(created) >= min_age)
+        });
     }
-
-    // Determine zone
-    let (zone_id, zone_name) = if let Some(z) = zone {
-        let found = acct.zones.iter().find(|zc| zc.name == z);
-        match found {
-            Some(zc) => (zc.id.clone(), zc.name.clone()),
-            None => anyhow::bail!(
-                "Zone '{}' not found. Run `ytunnel zones` to see available zones.",
-                z
-            ),
-        }
-    } else {
-        (acct.default_zone_id.clone(), acct.default_zone_name.clone())
+    if idle.is_empty() {
+        println!("No unused ephemeral tunnels in account '{}'.", acct.name);
+        return Ok(());
+    }
+    idle.sort_by_key(|t| t.created_secs());
+    let records = tunnel::tunnel_dns_records(&cfg, acct).await?;
+    let records_for = |tunnel_id: &str| -> Vec<cloudflare::DnsRecord> {
+        records
+            .iter()
+            .filter(|r| r.tunnel_id() == Some(tunnel_id))
+            .cloned()
+            .collect()
     };
 
-    let tunnel_name = format!("ytunnel-{}", name);
-    let hostname = format!("{}.{}", name, zone_name);
+    println!(
+        "Found {} unused ephemeral tunnel(s) in account '{}':",
+        idle.len(),
+        acct.name
+    );
+    for t in &idle {
+        let age = t
+            .created_secs()
+            .map(|created| {
+                let age = std::time::Duration::from_secs(now.saturating_sub(created));
+                format!(", created {} ago", format_uptime(age))
+            })
+            .unwrap_or_default();
+        let kept = if tunnel::is_kept(&t.id) {
+            ", kept for reuse"
+        } else {
+            ""
+        };
+        println!("  {} ({}{}{})", t.name, t.id, age, kept);
+        for record in records_for(&t.id) {
+            println!("    DNS {} -> {}", record.name, record.content);
+        }
+    }
 
-    println!("Adding tunnel: {} -> {}", hostname, target);
+    if !skip_confirm {
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("Pass --yes to delete them without a prompt.");
+        }
+        let answer = prompt_line("Delete these tunnels and their DNS records? [y/N]")?;
+        if !matches!(answer.to_lowercase().as_str(), "y" | "yes") {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
 
-    // Check if tunnel exists in Cloudflare, create if not
-    let (cf_tunnel, _credentials_path) = match client
-        .get_tunnel_by_name(&acct.account_id, &tunnel_name)
-        .await?
-    {
-        Some(t) => {
-            let creds_path = t.credentials_path()?;
-            if !creds_path.exists() {
-                anyhow::bail!(
-                    "Credentials file not found: {}\n\
-                     This tunnel may have been created outside ytunnel.\n\
-                     Delete it with `ytunnel delete {}` and try again.",
-                    creds_path.display(),
-                    name
-                );
+    let mut failed = 0;
+    for t in &idle {
+        match tunnel::remove_ephemeral(&cfg, acct, &t.id, &records_for(&t.id)).await {
+            Ok(()) => println!("✓ Removed {}", t.name),
+            Err(e) => {
+                failed += 1;
+                eprintln!("✗ Failed to remove {}: {:#}", t.name, e);
             }
-            println!("✓ Using existing Cloudflare tunnel: {}", t.name);
-            (t, creds_path)
-        }
-        None => {
-            println!("Creating Cloudflare tunnel: {}", tunnel_name);
-            let result = client.create_tunnel(&acct.account_id, &tunnel_name).await?;
-            (result.tunnel, result.credentials_path)
         }
-    };
+    }
+    if failed > 0 {
+        anyhow::bail!(
+            "{} of {} tunnel(s) could not be removed",
+            failed,
+            idle.len()
+        );
+    }
+    Ok(())
+}
 
-    // Ensure DNS record exists
-    println!("Configuring DNS record...");
-    client
-        .ensure_dns_record(&zone_id, &hostname, &cf_tunnel.id)
-        .await?;
-    println!("✓ DNS configured: {}", hostname);
+// Earlier runs that were killed or crashed leave their tunnel and DNS record
+// behind; list them and offer to remove them. The tunnel about to run is skipped
+// since it gets reused.
+async fn offer_leaked_run_cleanup(
+    cfg: &config::Config,
+    acct: &Account,
+    tunnel_name: &str,
+) -> Result<()> {
+    use std::io::IsTerminal;
 
-    // Create persistent tunnel
-    let persistent = PersistentTunnel {
-        name: name.clone(),
-        account_name: account_name.clone(),
-        target,
-        zone_id,
-        zone_name,
-        hostname: hostname.clone(),
-        tunnel_id: cf_tunnel.id,
-        enabled: start,
-        auto_start: false,
-        metrics_port: None,
+    let state = TunnelState::load()?;
+    let leaks: Vec<_> = match tunnel::find_leaked_runs(&cfg.client(acct), acct, &state).await {
+        Ok(leaks) => leaks
+            .into_iter()
+            .filter(|l| l.tunnel_name != tunnel_name)
+            .collect(),
+        // Not worth failing the run over
+        Err(_) => return Ok(()),
     };
+    if leaks.is_empty() {
+        return Ok(());
+    }
 
-    // Write tunnel config
-    write_tunnel_config(&persistent)?;
-
-    // Install daemon
-    daemon::install_daemon(&persistent).await?;
-    println!("✓ Daemon installed");
-
-    // Save to state
-    let mut state = TunnelState::load()?;
-    state.add(persistent);
-    state.save()?;
-    println!("✓ Tunnel saved to state");
-
-    if start {
-        daemon::start_daemon(&name, &account_name).await?;
-        println!("✓ Tunnel started");
-        println!("\nTunnel running: https://{}", hostname);
-    } else {
-        println!("\nTunnel added. Start with: ytunnel start {}", name);
+    println!(
+        "Found {} tunnel(s) left behind by a `ytunnel run` that didn't exit cleanly:",
+        leaks.len()
+    );
+    for leak in &leaks {
+        match &leak.hostname {
+            Some(hostname) => println!("  {} ({})", leak.tunnel_name, hostname),
+            None => println!("  {}", leak.tunnel_name),
+        }
+    }
+    if !std::io::stdin().is_terminal() {
+        println!("Remove them with `ytunnel delete <name>` or from the TUI.");
+        return Ok(());
+    }
+    println!("Clean them up now? [y/N]");
+    print!("> ");
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+    if input != "y" && input != "yes" {
+        println!("Skipped.");
+        return Ok(());
     }
 
+    for leak in &leaks {
+        match tunnel::clean_up_leaked_run(cfg, acct, leak).await {
+            Ok(()) => println!("✓ Removed {}", leak.tunnel_name),
+            Err(e) => eprintln!("Warning: Failed to remove {}: {:#}", leak.tunnel_name, e),
+        }
+    }
+    println!();
     Ok(())
 }
 
-// Start a stopped tunnel
-async fn cmd_start(name: String, account: Option<&str>) -> Result<()> {
+// Resolve a name pattern (glob, or regex with --regex) to the matching tunnels in
+// the current account. None means the name is a plain tunnel name.
+fn matching_tunnels(
+    name: &str,
+    regex: bool,
+    account: Option<&str>,
+) -> Result<Option<(String, Vec<String>)>> {
+    let Some(pattern) = pattern::compile(name, regex)? else {
+        return Ok(None);
+    };
     let cfg = config::load_config()?;
-    let acct = cfg.get_account(account)?;
-    let account_name = acct.name.clone();
-    let client = cloudflare::Client::new(&acct.api_token);
-    let mut state = TunnelState::load()?;
+    let account_name = cfg.get_account(account)?.name.clone();
+    let state = TunnelState::load()?;
 
-    // Get tunnel info and hostname before mutable borrow
-    let (hostname, tunnel_clone) = {
-        let tunnel = state.find_for_account(&name, &account_name).ok_or_else(|| {
-            anyhow::anyhow!(
-                "Tunnel '{}' not found for account '{}'. Run `ytunnel list` to see available tunnels.",
-                name,
-                account_name
-            )
-        })?;
-        (tunnel.hostname.clone(), tunnel.clone())
-    };
+    let mut names: Vec<String> = state
+        .tunnels_for_account(&account_name)
+        .into_iter()
+        .filter(|t| pattern.is_match(&t.name))
+        .map(|t| t.name.clone())
+        .collect();
+    if names.is_empty() {
+        return Err(error::not_found(format!(
+            "No tunnels in account '{}' match '{}'.",
+            account_name, name
+        )));
+    }
+    names.sort();
+    Ok(Some((account_name, names)))
+}
 
-    // Use the tunnel's own account_name for daemon operations (handles legacy tunnels)
-    let tunnel_account = &tunnel_clone.account_name;
+// Ask before acting on several tunnels at once
+fn confirm_bulk(action: &str, names: &[String]) -> Result<bool> {
+    println!("{} {} tunnels?", action, names.len());
+    for name in names {
+        println!("  {}", name);
+    }
+    println!("Are you sure? [y/N]");
+    print!("> ");
+    std::io::Write::flush(&mut std::io::stdout())?;
 
-    // Ensure DNS record exists (recreates if manually deleted)
-    client
-        .ensure_dns_record(&tunnel_clone.zone_id, &hostname, &tunnel_clone.tunnel_id)
-        .await?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+    Ok(input == "y" || input == "yes")
+}
 
-    // Ensure config file exists
-    write_tunnel_config(&tunnel_clone)?;
+// Run a single-tunnel command for each matched tunnel, continuing past failures.
+// One at a time, unlike cmd_stop_many and teardown::Report::teardown_tunnels:
+// start, restart, and delete print as they go and each loads and saves
+// tunnels.toml around its own awaits, so concurrent runs would interleave their
+// output and overwrite each other's state changes.
+async fn for_each_tunnel<F, Fut>(done: &str, names: Vec<String>, mut op: F) -> Result<()>
+where
+    F: FnMut(String) -> Fut,
+    Fut: std::future::Future<Output = Result<()>>,
+{
+    let mut failed = Vec::new();
+    for name in &names {
+        println!("── {}", name);
+        if let Err(e) = op(name.clone()).await {
+            eprintln!("✗ {}: {:#}", name, e);
+            failed.push(name.clone());
+        }
+    }
+    bulk_summary(done, names.len(), &failed)
+}
 
-    // Ensure daemon is installed
-    daemon::install_daemon(&tunnel_clone).await?;
+// The closing line of a multi-tunnel command, and an error naming the failures
+fn bulk_summary(done: &str, total: usize, failed: &[String]) -> Result<()> {
+    println!("\n{} {} of {} tunnels.", done, total - failed.len(), total);
+    if !failed.is_empty() {
+        anyhow::bail!("Failed for: {}", failed.join(", "));
+    }
+    Ok(())
+}
 
-    // Start the daemon
-    daemon::start_daemon(&name, tunnel_account).await?;
+// Options for `ytunnel add` beyond the tunnel name and target
+struct AddOptions {
+    zone: Option<String>,
+    start: bool,
+    protocol: Option<String>,
+    log_level: Option<String>,
+    metrics_port: Option<u16>,
+    // Catch-all ingress service (default http_status:404)
+    fallback: Option<String>,
+    wait_healthy: Option<u64>,
+    // Extra hostnames as `zone=subdomain` or full hostnames
+    hostnames: Vec<String>,
+    // Keep partially created resources when a step fails
+    no_rollback: bool,
+    // Replace existing non-tunnel DNS records at the hostnames
+    force: bool,
+    // Leave DNS records to someone else (split-horizon, Terraform)
+    no_dns: bool,
+    description: Option<String>,
+    // Start on login (only settable through a preset)
+    auto_start: bool,
+    // [presets.<name>] to take defaults from
+    preset: Option<String>,
+}
 
-    // Update state
-    if let Some(t) = state.find_for_account_mut(&name, &account_name) {
-        t.enabled = true;
+impl AddOptions {
+    // Fill in what the command line left unset from a preset
+    fn with_preset(self, preset: &config::Preset) -> Self {
+        Self {
+            zone: self.zone.or_else(|| preset.zone.clone()),
+            start: self.start || preset.start,
+            protocol: self.protocol.or_else(|| preset.protocol.clone()),
+            wait_healthy: self.wait_healthy.or(preset.wait_healthy_secs),
+            no_dns: self.no_dns || !preset.manage_dns,
+            description: self.description.or_else(|| preset.description.clone()),
+            auto_start: self.auto_start || preset.auto_start,
+            ..self
+        }
     }
-    state.save()?;
+}
 
-    println!("✓ Started tunnel: {}", name);
-    println!("  https://{}", hostname);
+// Parse an extra hostname given as `zone=subdomain` or a full hostname
+fn parse_hostname_route(acct: &Account, spec: &str) -> Result<HostnameRoute> {
+    let (zone, subdomain) = match spec.split_once('=') {
+        Some((zone, subdomain)) => acct.resolve_hostname(subdomain.trim(), Some(zone.trim()))?,
+        None => acct.resolve_hostname(spec.trim(), None)?,
+    };
+    Ok(HostnameRoute {
+        hostname: format!("{}.{}", subdomain, zone.name),
+        zone_id: zone.id,
+        zone_name: zone.name,
+    })
+}
+
+// Point every (zone_id, hostname) at the tunnel, recording the records that
+// didn't exist before so they can be rolled back
+async fn ensure_dns_records(
+    client: &cloudflare::Client,
+    tunnel_id: &str,
+    routes: &[(&str, &str)],
+    created: &mut Vec<(String, String)>,
+) -> Result<()> {
+    for &(zone_id, hostname) in routes {
+        let change = client
+            .ensure_dns_record(zone_id, hostname, tunnel_id)
+            .await
+            .with_context(|| format!("Failed to configure DNS for {}", hostname))?;
+        if change == cloudflare::DnsChange::Created {
+            created.push((zone_id.to_string(), hostname.to_string()));
+        }
+        println!("✓ {}", change.describe(hostname));
+    }
 
     Ok(())
 }
 
-// Stop a running tunnel
-async fn cmd_stop(name: String, account: Option<&str>) -> Result<()> {
-    let cfg = config::load_config()?;
-    let account_name = cfg.get_account(account)?.name.clone();
-    let mut state = TunnelState::load()?;
+// Records that would shadow a tunnel CNAME (e.g. a stale A record left from
+// dashboard edits) are listed and, once confirmed at a terminal, removed. Without
+// confirmation this fails, explaining which records are in the way.
+async fn resolve_dns_conflicts(
+    client: &cloudflare::Client,
+    zone_id: &str,
+    hostname: &str,
+) -> Result<()> {
+    use std::io::IsTerminal;
 
-    // Get tunnel info before mutable borrow
-    let (hostname, tunnel_account) = {
-        let tunnel = state.find_for_account(&name, &account_name).ok_or_else(|| {
-            anyhow::anyhow!(
-                "Tunnel '{}' not found for account '{}'. Run `ytunnel list` to see available tunnels.",
-                name,
-                account_name
-            )
-        })?;
-        (tunnel.hostname.clone(), tunnel.account_name.clone())
-    };
+    let conflicts = client.find_dns_conflicts(zone_id, hostname).await?;
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+    let explanation = cloudflare::describe_dns_conflicts(hostname, &conflicts);
+    if !std::io::stdin().is_terminal() {
+        return Err(error::conflict(format!(
+            "{}\nRemove them in the Cloudflare dashboard, or run this command in a terminal to remove them.",
+            explanation
+        )));
+    }
 
-    // Use the tunnel's own account_name for daemon operations (handles legacy tunnels)
-    daemon::stop_daemon(&name, &tunnel_account).await?;
+    println!("{}", explanation);
+    println!("Remove them so {} routes to the tunnel? [y/N]", hostname);
+    print!("> ");
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+    if input != "y" && input != "yes" {
+        return Err(error::conflict(format!(
+            "{} still has conflicting DNS records; nothing was changed",
+            hostname
+        )));
+    }
 
-    // Update state
-    if let Some(t) = state.find_for_account_mut(&name, &account_name) {
-        t.enabled = false;
+    for record in &conflicts {
+        client
+            .delete_dns_record_by_id(zone_id, &record.id)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to remove {} record for {}",
+                    record.record_type, hostname
+                )
+            })?;
+        println!(
+            "✓ Removed {} record for {} ({})",
+            record.record_type, hostname, record.content
+        );
     }
-    state.save()?;
+    Ok(())
+}
 
-    println!("✓ Stopped tunnel: {}", name);
-    println!("  {}", hostname);
+// Resources created by `ytunnel add`, undone if a later step fails
+#[derive(Default)]
+struct AddRollback {
+    // (account_id, tunnel_id, credentials_path) when the Cloudflare tunnel was created by this add
+    tunnel: Option<(String, String, PathBuf)>,
+    // (zone_id, hostname) of DNS records that didn't exist before
+    dns_records: Vec<(String, String)>,
+    config_path: Option<PathBuf>,
+    // (name, account_name) once the daemon was installed
+    daemon: Option<(String, String)>,
+    // Records deleted by --force; these can't be restored automatically
+    replaced_records: Vec<String>,
+}
 
-    Ok(())
+impl AddRollback {
+    // Undo everything in reverse order of creation, reporting each step
+    async fn undo(self, client: &cloudflare::Client, dns_client: &cloudflare::Client) {
+        println!("Rolling back...");
+
+        if let Some((name, account_name)) = self.daemon {
+            match daemon::uninstall_daemon(&name, &account_name).await {
+                Ok(()) => println!("  Removed daemon"),
+                Err(e) => eprintln!("  Warning: Failed to remove daemon: {}", e),
+            }
+        }
+        if let Some(path) = self.config_path {
+            std::fs::remove_file(&path).ok();
+        }
+        for (zone_id, hostname) in self.dns_records.iter().rev() {
+            match dns_client.delete_dns_record(zone_id, hostname).await {
+                Ok(()) => println!("  Removed DNS record: {}", hostname),
+                Err(e) => eprintln!("  Warning: Failed to remove DNS record {}: {}", hostname, e),
+            }
+        }
+        if let Some((account_id, tunnel_id, credentials_path)) = self.tunnel {
+            match client.delete_tunnel(&account_id, &tunnel_id).await {
+                Ok(()) => println!("  Removed Cloudflare tunnel"),
+                Err(e) => eprintln!("  Warning: Failed to remove Cloudflare tunnel: {}", e),
+            }
+            std::fs::remove_file(&credentials_path).ok();
+        }
+        for record in &self.replaced_records {
+            eprintln!("  Warning: Replaced record was not restored: {}", record);
+        }
+    }
+
+    // List what was left behind when rollback is disabled
+    fn report(&self) {
+        println!("Leaving partially created resources in place (--no-rollback):");
+        if let Some((_, tunnel_id, credentials_path)) = &self.tunnel {
+            println!("  Cloudflare tunnel: {}", tunnel_id);
+            println!("  Credentials: {}", credentials_path.display());
+        }
+        for (_, hostname) in &self.dns_records {
+            println!("  DNS record: {}", hostname);
+        }
+        if let Some(path) = &self.config_path {
+            println!("  Config: {}", path.display());
+        }
+        if self.daemon.is_some() {
+            println!("  Daemon: installed");
+        }
+    }
 }
 
-// Restart a running tunnel (stop, reinstall daemon config, start)
-async fn cmd_restart(name: String, account: Option<&str>) -> Result<()> {
+// Add a persistent tunnel (non-interactive CLI command)
+async fn cmd_add(
+    name: String,
+    target: String,
+    opts: AddOptions,
+    account: Option<&str>,
+) -> Result<()> {
     let cfg = config::load_config()?;
+    let AddOptions {
+        zone,
+        start,
+        protocol,
+        log_level,
+        metrics_port,
+        fallback,
+        wait_healthy,
+        hostnames,
+        no_rollback,
+        force,
+        no_dns,
+        description,
+        auto_start,
+        preset: _,
+    } = match opts.preset.clone() {
+        Some(name) => opts.with_preset(cfg.preset(&name)?),
+        None => opts,
+    };
     let acct = cfg.get_account(account)?;
+    let client = cfg.client(acct);
     let account_name = acct.name.clone();
-    let client = cloudflare::Client::new(&acct.api_token);
-    let state = TunnelState::load()?;
 
-    let tunnel = state
-        .find_for_account(&name, &account_name)
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-                "Tunnel '{}' not found for account '{}'. Run `ytunnel list` to see available tunnels.",
-                name,
-                account_name
-            )
-        })?
-        .clone();
+    // Accept either a subdomain or a full hostname in one of the account's zones
+    // (or, for accounts without zones, another configured account's zones)
+    let (zc, name) = cfg.resolve_hostname(acct, &name, zone.as_deref())?;
+    let (zone_id, zone_name) = (zc.id, zc.name);
+    let dns_acct = cfg.dns_account(acct, &zone_id);
+    let dns_client = cfg.client(dns_acct);
+
+    // Resolve extra hostnames up front so typos fail before anything is created
+    let mut extra_hostnames: Vec<HostnameRoute> = Vec::new();
+    for spec in &hostnames {
+        let route = parse_hostname_route(dns_acct, spec)?;
+        let primary = format!("{}.{}", name, zone_name);
+        if route.hostname != primary && !extra_hostnames.contains(&route) {
+            extra_hostnames.push(route);
+        }
+    }
 
-    // Use the tunnel's own account_name for daemon operations (handles legacy tunnels)
-    let tunnel_account = &tunnel.account_name;
+    validate_targets(&target)?;
+    if let Some(fallback) = &fallback {
+        state::validate_service(fallback)?;
+    }
 
-    println!("Restarting tunnel: {}", name);
+    // Check if tunnel already exists in state for this account
+    let state = TunnelState::load()?;
+    if state.find_for_account(&name, &account_name).is_some() {
+        anyhow::bail!(
+            "Tunnel '{}' already exists for account '{}'. Use `ytunnel delete {}` first.",
+            name,
+            account_name,
+            name
+        );
+    }
+    if let Some(port) = metrics_port {
+        check_metrics_port(&state, port, &name, &account_name)?;
+    }
 
-    // Stop the daemon
-    daemon::stop_daemon(&name, tunnel_account).await.ok();
+    let tunnel_name = config::cloudflare_tunnel_name(&name);
+    let hostname = format!("{}.{}", name, zone_name);
 
-    // Ensure DNS record exists (recreates if manually deleted)
-    client
-        .ensure_dns_record(&tunnel.zone_id, &tunnel.hostname, &tunnel.tunnel_id)
-        .await?;
+    // Refuse to clobber existing records (e.g. an A record for a real server)
+    // before anything is created
+    let mut conflicts = Vec::new();
+    let routes = std::iter::once((zone_id.as_str(), hostname.as_str())).chain(
+        extra_hostnames
+            .iter()
+            .map(|r| (r.zone_id.as_str(), r.hostname.as_str())),
+    );
+    for (route_zone, route_hostname) in routes.filter(|_| !no_dns) {
+        for record in dns_client
+            .find_dns_conflicts(route_zone, route_hostname)
+            .await?
+        {
+            conflicts.push((route_zone.to_string(), record));
+        }
+    }
+    if !conflicts.is_empty() && !force {
+        let list: Vec<String> = conflicts
+            .iter()
+            .map(|(_, r)| format!("  {} {} -> {}", r.name, r.record_type, r.content))
+            .collect();
+        return Err(error::conflict(format!(
+            "These DNS records already exist and don't point at a Cloudflare tunnel:\n{}\n\
+             Adding the tunnel would overwrite them. Pass --force to replace them.",
+            list.join("\n")
+        )));
+    }
 
-    // Reinstall daemon (regenerates plist with latest config)
-    write_tunnel_config(&tunnel)?;
-    daemon::install_daemon(&tunnel).await?;
+    println!(
+        "Adding tunnel: {} -> {}",
+        idn::to_unicode(&hostname),
+        target
+    );
+    if dns_acct.name != account_name && !no_dns {
+        println!("Using DNS from account: {}", dns_acct.name);
+    }
 
-    // Start the daemon
-    daemon::start_daemon(&name, tunnel_account).await?;
+    // Everything created from here on is undone if a later step fails
+    let mut rollback = AddRollback::default();
 
-    // Update state
-    let mut state = TunnelState::load()?;
-    if let Some(t) = state.find_for_account_mut(&name, &account_name) {
-        t.enabled = true;
+    // Check if tunnel exists in Cloudflare, create if not
+    let result = client
+        .create_or_get_tunnel(&acct.account_id, &tunnel_name)
+        .await?;
+    if result.created {
+        println!("✓ Created Cloudflare tunnel: {}", tunnel_name);
+        rollback.tunnel = Some((
+            acct.account_id.clone(),
+            result.tunnel.id.clone(),
+            result.credentials_path,
+        ));
+    } else {
+        println!("✓ Using existing Cloudflare tunnel: {}", tunnel_name);
     }
-    state.save()?;
+    let cf_tunnel = result.tunnel;
 
-    println!("✓ Restarted tunnel: {}", name);
-    println!("  https://{}", tunnel.hostname);
+    // Create persistent tunnel
+    let mut persistent = PersistentTunnel {
+        name: name.clone(),
+        account_name: account_name.clone(),
+        target: String::new(),
+        zone_id,
+        zone_name,
+        hostname: hostname.clone(),
+        tunnel_id: cf_tunnel.id,
+        enabled: start,
+        auto_start,
+        metrics_port,
+        protocol,
+        log_level,
+        extra_hostnames,
+        manage_dns: !no_dns,
+        description: description
+            .as_deref()
+            .and_then(state::normalize_description),
+        connect_time_ms: None,
+        maintenance: None,
+        fallback,
+        backends: Vec::new(),
+        dns_verified: None,
+    };
+    persistent.set_target(&target)?;
 
-    Ok(())
-}
+    let setup = async {
+        // Remove conflicting records the user asked to replace (--force)
+        for (zone, record) in &conflicts {
+            dns_client
+                .delete_dns_record_by_id(zone, &record.id)
+                .await
+                .with_context(|| format!("Failed to remove existing record for {}", record.name))?;
+            rollback.replaced_records.push(format!(
+                "{} {} -> {}",
+                record.name, record.record_type, record.content
+            ));
+            println!(
+                "✓ Removed existing {} record for {} (--force)",
+                record.record_type, record.name
+            );
+        }
 
-// View logs for a tunnel
-async fn cmd_logs(name: String, follow: bool, lines: usize, account: Option<&str>) -> Result<()> {
-    let cfg = config::load_config()?;
-    let account_name = cfg.get_account(account)?.name.clone();
-    let state = TunnelState::load()?;
+        // Ensure DNS records exist for every hostname
+        if persistent.manage_dns {
+            ensure_dns_records(
+                &dns_client,
+                &persistent.tunnel_id,
+                &persistent.dns_routes(),
+                &mut rollback.dns_records,
+            )
+            .await?;
+        }
 
-    let tunnel = state
-        .find_for_account(&name, &account_name)
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-            "Tunnel '{}' not found for account '{}'. Run `ytunnel list` to see available tunnels.",
-            name,
-            account_name
-        )
+        // Write tunnel config
+        rollback.config_path = Some(write_tunnel_config(&persistent)?);
+
+        // Install daemon
+        daemon::install_daemon(&persistent).await?;
+        rollback.daemon = Some((name.clone(), account_name.clone()));
+        println!("✓ Daemon installed");
+
+        // Save to state (unless another ytunnel added the name meanwhile)
+        TunnelState::update(|state| {
+            if state.find_for_account(&name, &account_name).is_some() {
+                return Err(error::conflict(format!(
+                    "Tunnel '{}' was added for account '{}' by another ytunnel meanwhile",
+                    name, account_name
+                )));
+            }
+            state.add(persistent.clone());
+            Ok(())
         })?;
+        println!("✓ Tunnel saved to state");
 
-    let log_path = tunnel.log_path()?;
+        Ok::<(), anyhow::Error>(())
+    }
+    .await;
 
-    if !log_path.exists() {
-        println!("No logs yet for tunnel '{}'", name);
-        return Ok(());
+    if let Err(e) = setup {
+        if no_rollback {
+            rollback.report();
+        } else {
+            rollback.undo(&client, &dns_client).await;
+        }
+        return Err(e);
     }
 
-    if follow {
-        // Use tail -f for following
-        use std::process::Command;
-        let status = Command::new("tail")
-            .args(["-f", "-n", &lines.to_string()])
-            .arg(&log_path)
-            .status()?;
+    if no_dns {
+        print_dns_reminder(&persistent);
+    }
 
-        if !status.success() {
-            anyhow::bail!("Failed to tail log file");
+    if start {
+        daemon::start_daemon(&name, &account_name).await?;
+        let connect_time = daemon::verify_daemon_started(&persistent).await?;
+        println!("✓ Tunnel started");
+        print_connect_time(connect_time);
+
+        if let Some(secs) = wait_healthy {
+            let healthy = health::wait_until_healthy(
+                &hostname,
+                cfg.tui.health_timeout(),
+                Duration::from_secs(secs),
+                |elapsed| {
+                    println!(
+                        "Waiting for https://{} to become healthy... ({}s)",
+                        hostname,
+                        elapsed.as_secs()
+                    )
+                },
+            )
+            .await;
+            if !healthy {
+                // For socket targets, say whether the app itself is answering
+                let origin = match state::unix_socket_path(&persistent.target) {
+                    Some(path)
+                        if !health::check_unix_socket(path, cfg.tui.health_timeout()).await =>
+                    {
+                        format!(" Nothing answered HTTP on {}.", path)
+                    }
+                    _ => String::new(),
+                };
+                anyhow::bail!(
+                    "Tunnel did not become healthy within {}s.{} Check logs with `ytunnel logs {}`.",
+                    secs,
+                    origin,
+                    name
+                );
+            }
+            println!("✓ Tunnel is healthy");
         }
+
+        println!("\nTunnel running: https://{}", idn::to_unicode(&hostname));
     } else {
-        // Just read and print the last N lines
-        let log_lines = daemon::read_log_tail(tunnel, lines)?;
-        for line in log_lines {
-            println!("{}", line);
-        }
+        println!("\nTunnel added. Start with: ytunnel start {}", name);
     }
 
     Ok(())
 }
 
-async fn cmd_zones_list(account: Option<&str>) -> Result<()> {
-    let cfg = config::load_config()?;
-    let acct = cfg.get_account(account)?;
-
-    println!("Available zones for account '{}':", acct.name);
-    for zone in &acct.zones {
-        let marker = if zone.id == acct.default_zone_id {
-            " (default)"
-        } else {
-            ""
-        };
-        println!("  {}{}", zone.name, marker);
+// List the CNAMEs someone else has to create when ytunnel doesn't manage DNS
+fn print_dns_reminder(tunnel: &PersistentTunnel) {
+    println!(
+        "\nDNS is not managed by ytunnel. The tunnel only works once these CNAME records exist:"
+    );
+    for (_, hostname) in tunnel.dns_routes() {
+        println!("  {} -> {} (proxied)", hostname, tunnel.cname_target());
     }
+}
 
-    Ok(())
+// Client for the account that manages DNS in this zone (see Config::dns_account)
+fn dns_client(cfg: &config::Config, acct: &Account, zone_id: &str) -> cloudflare::Client {
+    cfg.client(cfg.dns_account(acct, zone_id))
 }
 
-async fn cmd_zones_default(domain: String, account: Option<&str>) -> Result<()> {
-    let mut cfg = config::load_config()?;
-    let acct = cfg.get_account_mut(account)?;
+// Accounts that have a managed tunnel with this name
+fn accounts_with_tunnel(cfg: &config::Config, state: &TunnelState, name: &str) -> Vec<String> {
+    cfg.accounts
+        .iter()
+        .filter(|a| state.find_for_account(name, &a.name).is_some())
+        .map(|a| a.name.clone())
+        .collect()
+}
 
-    let zone = acct.zones.iter().find(|z| z.name == domain).cloned();
-    match zone {
-        Some(z) => {
-            acct.default_zone_id = z.id.clone();
-            acct.default_zone_name = z.name.clone();
-            config::save_config(&cfg)?;
-            println!("Default zone set to: {}", domain);
+// Account a tunnel-name command acts on. Without --account, a name that exists
+// in more than one account is ambiguous and has to be picked explicitly.
+fn account_for_tunnel<'a>(
+    cfg: &'a config::Config,
+    state: &TunnelState,
+    name: &str,
+    account: Option<&str>,
+) -> Result<&'a Account> {
+    if account.is_none() {
+        let owners = accounts_with_tunnel(cfg, state, name);
+        if owners.len() > 1 {
+            anyhow::bail!(
+                "Tunnel '{}' exists in more than one account ({}). Pass --account <name> to choose one.",
+                name,
+                owners.join(", ")
+            );
         }
-        None => {
+    }
+    cfg.get_account(account)
+}
+
+// "Not found" error for a tunnel that names any other account that has it
+fn tunnel_not_found(
+    cfg: &config::Config,
+    state: &TunnelState,
+    name: &str,
+    account_name: &str,
+) -> anyhow::Error {
+    let others: Vec<String> = accounts_with_tunnel(cfg, state, name)
+        .into_iter()
+        .filter(|a| a != account_name)
+        .collect();
+    match others.first() {
+        Some(other) => error::not_found(format!(
+            "Tunnel '{}' not found for account '{}', but it exists in account '{}'. Use --account {} to act on it.",
+            name, account_name, other, other
+        )),
+        None => error::not_found(format!(
+            "Tunnel '{}' not found for account '{}'. Run `ytunnel list` to see available tunnels.",
+            name, account_name
+        )),
+    }
+}
+
+// How start and restart treat the Cloudflare checks before launching cloudflared
+#[derive(Clone, Copy, Default)]
+struct DnsCheck {
+    // Skip them entirely (--offline)
+    offline: bool,
+    // Check the DNS records even if they were verified recently (--verify-dns)
+    force: bool,
+    // Replace a Cloudflare tunnel that was deleted outside ytunnel (--force-recreate)
+    recreate: bool,
+}
+
+impl DnsCheck {
+    fn new(offline: bool, verify_dns: bool, force_recreate: bool) -> Self {
+        Self {
+            offline,
+            force: verify_dns,
+            recreate: force_recreate,
+        }
+    }
+
+    // Whether the tunnel's DNS records need checking, saying why not when they don't.
+    // A recreated tunnel's records always do: they point at the old id.
+    fn needed(self, tunnel: &PersistentTunnel, now: u64) -> bool {
+        if !tunnel.manage_dns {
+            return false;
+        }
+        if self.recreate {
+            return true;
+        }
+        match &tunnel.dns_verified {
+            Some(v) if !self.force && tunnel.dns_recently_verified(now) => {
+                println!(
+                    "✓ DNS records verified {} ago (--verify-dns checks them again)",
+                    format_uptime(Duration::from_secs(now.saturating_sub(v.at)))
+                );
+                false
+            }
+            _ => true,
+        }
+    }
+
+    // Whether to look up the Cloudflare tunnel before launching cloudflared: with
+    // --force-recreate, or when the DNS check talks to the API anyway. Otherwise a
+    // recently verified tunnel starts without an API call, and the lookup only
+    // happens if cloudflared fails to come up (missing_tunnel_or).
+    fn looks_up_tunnel(self, dns_needed: bool) -> bool {
+        !self.offline && (self.recreate || dns_needed)
+    }
+}
+
+// cloudflared didn't come up and the Cloudflare tunnel wasn't looked up first: a
+// tunnel deleted outside ytunnel explains that better than cloudflared's logs
+async fn missing_tunnel_or(
+    client: &cloudflare::Client,
+    acct: &Account,
+    tunnel: &PersistentTunnel,
+    dns: DnsCheck,
+    err: anyhow::Error,
+) -> anyhow::Error {
+    let mut probe = tunnel.clone();
+    match ensure_cloudflare_tunnel(client, acct, &mut probe, dns).await {
+        Err(missing) if error::kind_of(&missing) == Some(error::ErrorKind::NotFound) => missing,
+        _ => err,
+    }
+}
+
+// Make sure the tunnel's Cloudflare tunnel still exists; cloudflared can't run one
+// that was deleted outside ytunnel. With --force-recreate a new one replaces it (new
+// id and credentials, saved right away) and its DNS records are checked next, which
+// repoints them. Skipped offline; an unreachable API is left to the DNS check to report.
+// Returns whether the tunnel was recreated. Start and restart only call this when
+// the lookup is wanted anyway (see DnsCheck::looks_up_tunnel).
+async fn ensure_cloudflare_tunnel(
+    client: &cloudflare::Client,
+    acct: &Account,
+    tunnel: &mut PersistentTunnel,
+    dns: DnsCheck,
+) -> Result<bool> {
+    if dns.offline {
+        return Ok(false);
+    }
+    let existing = match client.get_tunnel(&acct.account_id, &tunnel.tunnel_id).await {
+        Err(e) if cloudflare::is_unreachable(&e) => return Ok(false),
+        result => result?,
+    };
+    if existing.is_some() {
+        return Ok(false);
+    }
+    if !dns.recreate {
+        return Err(error::not_found(format!(
+            "Tunnel '{}' no longer exists in Cloudflare (id {}).\n\
+             Rerun with --force-recreate to create a new one and repoint its DNS records at it.",
+            tunnel.name, tunnel.tunnel_id
+        )));
+    }
+
+    let old_credentials = tunnel.credentials_path()?;
+    let created = client
+        .create_or_get_tunnel(
+            &acct.account_id,
+            &config::cloudflare_tunnel_name(&tunnel.name),
+        )
+        .await?;
+    println!(
+        "✓ Recreated Cloudflare tunnel: {} (was {})",
+        created.tunnel.id, tunnel.tunnel_id
+    );
+    tunnel.tunnel_id = created.tunnel.id;
+    // The records still point at the old id
+    tunnel.dns_verified = None;
+    TunnelState::update(|state| {
+        if let Some(t) = state.find_for_account_mut(&tunnel.name, &tunnel.account_name) {
+            t.tunnel_id = tunnel.tunnel_id.clone();
+            t.dns_verified = None;
+        }
+        Ok(())
+    })?;
+    teardown::remove_file_if_exists(&old_credentials).ok();
+    Ok(true)
+}
+
+// start and restart only mention DNS records they had to fix
+fn print_dns_repair(hostname: &str, change: &cloudflare::DnsChange) {
+    match change {
+        cloudflare::DnsChange::Created => {
+            println!("✓ Recreated missing DNS record: {}", hostname)
+        }
+        cloudflare::DnsChange::Updated { .. } => println!("✓ {}", change.describe(hostname)),
+        cloudflare::DnsChange::Unchanged => {}
+    }
+}
+
+// Seconds since the epoch
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+// Run a check that needs the Cloudflare API, or skip it with a warning when
+// offline: with --offline, or when the API turns out to be unreachable. Auth
+// and API errors still fail. Returns whether the check ran.
+async fn unless_offline(
+    offline: bool,
+    what: &str,
+    check: impl std::future::Future<Output = Result<()>>,
+) -> Result<bool> {
+    if offline {
+        println!(
+            "⚠ Offline: skipped {}; existing records are used as they are",
+            what
+        );
+        return Ok(false);
+    }
+    match check.await {
+        Err(e) if cloudflare::is_unreachable(&e) => {
+            println!(
+                "⚠ Cloudflare unreachable, skipped {}; existing records are used as they are ({:#})",
+                what, e
+            );
+            Ok(false)
+        }
+        result => result.map(|()| true),
+    }
+}
+
+// Start a stopped tunnel
+async fn cmd_start(name: String, no_dns: bool, dns: DnsCheck, account: Option<&str>) -> Result<()> {
+    let cfg = config::load_config()?;
+    let state = TunnelState::load()?;
+    let acct = account_for_tunnel(&cfg, &state, &name, account)?;
+    let account_name = acct.name.clone();
+
+    // Get tunnel info and hostname before mutable borrow
+    let (hostname, mut tunnel_clone) = {
+        let tunnel = state
+            .find_for_account(&name, &account_name)
+            .ok_or_else(|| tunnel_not_found(&cfg, &state, &name, &account_name))?;
+        (tunnel.hostname.clone(), tunnel.clone())
+    };
+    if no_dns {
+        tunnel_clone.manage_dns = false;
+    }
+
+    // Use the tunnel's own account_name for daemon operations (handles legacy tunnels)
+    let tunnel_account = tunnel_clone.account_name.clone();
+
+    // Ensure DNS records exist (recreates if manually deleted), unless they
+    // were verified recently
+    let now = now_secs();
+    let dns_needed = dns.needed(&tunnel_clone, now);
+    let client = cfg.client(acct);
+    let looked_up = dns.looks_up_tunnel(dns_needed);
+    if looked_up {
+        ensure_cloudflare_tunnel(&client, acct, &mut tunnel_clone, dns).await?;
+    }
+
+    if dns_needed {
+        let checked = unless_offline(dns.offline, "the DNS check", async {
+            for (zone_id, route_hostname) in tunnel_clone.managed_dns_routes() {
+                let client = dns_client(&cfg, acct, zone_id);
+                resolve_dns_conflicts(&client, zone_id, route_hostname).await?;
+                let change = client
+                    .ensure_dns_record(zone_id, route_hostname, &tunnel_clone.tunnel_id)
+                    .await?;
+                print_dns_repair(route_hostname, &change);
+            }
+            Ok(())
+        })
+        .await?;
+        if checked {
+            tunnel_clone.mark_dns_verified(now);
+        }
+    }
+
+    // Ensure config file exists
+    write_tunnel_config(&tunnel_clone)?;
+
+    // Ensure daemon is installed
+    daemon::install_daemon(&tunnel_clone).await?;
+
+    // Start the daemon
+    daemon::start_daemon(&name, &tunnel_account).await?;
+
+    // Update state
+    TunnelState::update(|state| {
+        if let Some(t) = state.find_for_account_mut(&name, &account_name) {
+            t.enabled = true;
+            t.manage_dns = tunnel_clone.manage_dns;
+            t.tunnel_id = tunnel_clone.tunnel_id.clone();
+            t.dns_verified = tunnel_clone.dns_verified.clone();
+        }
+        Ok(())
+    })?;
+
+    let connect_time = match daemon::verify_daemon_started(&tunnel_clone).await {
+        Err(e) if !looked_up => {
+            return Err(missing_tunnel_or(&client, acct, &tunnel_clone, dns, e).await)
+        }
+        result => result?,
+    };
+
+    println!("✓ Started tunnel: {}", name);
+    print_connect_time(connect_time);
+    println!("  https://{}", idn::to_unicode(&hostname));
+    if let Some(url) = tunnel_clone.metrics_url() {
+        println!("  Metrics: {}", url);
+    }
+    if !tunnel_clone.manage_dns {
+        print_dns_reminder(&tunnel_clone);
+    }
+
+    Ok(())
+}
+
+fn print_connect_time(connect_time: Option<std::time::Duration>) {
+    match connect_time {
+        Some(time) => println!(
+            "✓ Connected to the Cloudflare edge in {}",
+            format_connect_time(time.as_millis() as u64)
+        ),
+        None => println!("  Not connected to the Cloudflare edge yet (see `ytunnel logs`)"),
+    }
+}
+
+// Add a tunnel with another tunnel's settings. The metrics port and extra
+// hostnames would collide with the source's, so those are left out.
+async fn cmd_clone(
+    source: String,
+    name: String,
+    target: Option<String>,
+    zone: Option<String>,
+    start: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    let cfg = config::load_config()?;
+    let state = TunnelState::load()?;
+    let account_name = account_for_tunnel(&cfg, &state, &source, account)?
+        .name
+        .clone();
+    let src = state
+        .find_for_account(&source, &account_name)
+        .ok_or_else(|| tunnel_not_found(&cfg, &state, &source, &account_name))?
+        .clone();
+
+    // A full hostname picks its own zone; a bare name lands next to the source
+    let zone = zone.or_else(|| (!name.contains('.')).then(|| src.zone_name.clone()));
+    let opts = AddOptions {
+        zone,
+        start,
+        protocol: src.protocol.clone(),
+        log_level: src.log_level.clone(),
+        metrics_port: None,
+        fallback: src.fallback.clone(),
+        wait_healthy: None,
+        hostnames: Vec::new(),
+        no_rollback: false,
+        force: false,
+        no_dns: !src.manage_dns,
+        description: src.description.clone(),
+        auto_start: src.auto_start,
+        preset: None,
+    };
+    let target = target.unwrap_or_else(|| src.target_spec());
+    cmd_add(name, target, opts, Some(&account_name)).await
+}
+
+// Stop a running tunnel
+async fn cmd_stop(name: String, force: bool, account: Option<&str>) -> Result<()> {
+    let cfg = config::load_config()?;
+    let state = TunnelState::load()?;
+    let account_name = account_for_tunnel(&cfg, &state, &name, account)?
+        .name
+        .clone();
+
+    let tunnel = state
+        .find_for_account(&name, &account_name)
+        .ok_or_else(|| tunnel_not_found(&cfg, &state, &name, &account_name))?
+        .clone();
+
+    // Also kills cloudflared processes the service manager lost track of
+    let strays = daemon::stop_daemon_fully(&tunnel, force).await?;
+    for line in daemon::describe_strays(&strays) {
+        println!("⚠ {}", line);
+    }
+
+    // Update state
+    TunnelState::update(|state| {
+        if let Some(t) = state.find_for_account_mut(&name, &account_name) {
+            t.enabled = false;
+        }
+        Ok(())
+    })?;
+
+    println!("✓ Stopped tunnel: {}", name);
+    println!("  {}", tunnel.hostname);
+
+    Ok(())
+}
+
+// Stop the matched tunnels parallel::LIMIT at a time. Each unit only talks to the
+// service manager, so tunnels.toml is updated once at the end; a tunnel's lines
+// print together when it finishes.
+async fn cmd_stop_many(names: Vec<String>, force: bool, account_name: &str) -> Result<()> {
+    let state = TunnelState::load()?;
+    let tunnels: Vec<PersistentTunnel> = names
+        .iter()
+        .filter_map(|name| state.find_for_account(name, account_name).cloned())
+        .collect();
+
+    let results = parallel::run_bounded(
+        tunnels,
+        parallel::LIMIT,
+        |tunnel| async move {
+            let result = daemon::stop_daemon_fully(&tunnel, force).await;
+            (tunnel, result)
+        },
+        |(tunnel, result)| {
+            println!("── {}", tunnel.name);
+            match result {
+                Ok(strays) => {
+                    for line in daemon::describe_strays(strays) {
+                        println!("⚠ {}", line);
+                    }
+                    println!("✓ Stopped tunnel: {}", tunnel.name);
+                    println!("  {}", tunnel.hostname);
+                }
+                Err(e) => eprintln!("✗ {}: {:#}", tunnel.name, e),
+            }
+        },
+    )
+    .await;
+
+    let mut failed = Vec::new();
+    TunnelState::update(|state| {
+        for (tunnel, result) in &results {
+            match result {
+                Ok(_) => {
+                    if let Some(t) = state.find_for_account_mut(&tunnel.name, account_name) {
+                        t.enabled = false;
+                    }
+                }
+                Err(_) => failed.push(tunnel.name.clone()),
+            }
+        }
+        Ok(())
+    })?;
+    bulk_summary("Stopped", results.len(), &failed)
+}
+
+// Settings for `ytunnel set`; None leaves a setting unchanged
+struct SetOptions {
+    target: Option<String>,
+    auto_start: Option<bool>,
+    metrics_port: Option<u16>,
+    protocol: Option<String>,
+    log_level: Option<String>,
+    fallback: Option<String>,
+    manage_dns: Option<bool>,
+    description: Option<String>,
+    // Settings to reset to their defaults
+    unset: Vec<String>,
+    // Restart a running tunnel afterwards so the changes take effect
+    restart: bool,
+}
+
+impl SetOptions {
+    fn is_empty(&self) -> bool {
+        self.target.is_none()
+            && self.auto_start.is_none()
+            && self.metrics_port.is_none()
+            && self.protocol.is_none()
+            && self.log_level.is_none()
+            && self.fallback.is_none()
+            && self.manage_dns.is_none()
+            && self.description.is_none()
+            && self.unset.is_empty()
+    }
+}
+
+// Metrics endpoints all listen on localhost, so a port must be unique across
+// accounts and not already bound by some other process
+fn check_metrics_port(
+    state: &TunnelState,
+    port: u16,
+    name: &str,
+    account_name: &str,
+) -> Result<()> {
+    if let Some(other) = state.metrics_port_owner(port, name, account_name) {
+        return Err(error::conflict(format!(
+            "Metrics port {} is already used by tunnel '{}' (account '{}').",
+            port, other.name, other.account_name
+        )));
+    }
+    if std::net::TcpListener::bind(("127.0.0.1", port)).is_err() {
+        return Err(error::conflict(format!(
+            "Metrics port {} is already in use by another process.",
+            port
+        )));
+    }
+    Ok(())
+}
+
+// Reject targets cloudflared couldn't proxy to (same http:// default as the config).
+// A unix socket that doesn't exist yet only warns: the app may create it later, and
+// so does a host other than this machine, which the tunnel publishes to the internet.
+fn validate_target(target: &str) -> Result<()> {
+    if let Some(path) = state::unix_socket_path(target) {
+        if !path.starts_with('/') {
             anyhow::bail!(
-                "Zone '{}' not found. Run `ytunnel zones` to see available zones.",
-                domain
+                "Invalid target '{}'. Unix socket targets need an absolute path (e.g., unix:/tmp/app.sock).",
+                target
+            );
+        }
+        if !std::path::Path::new(path).exists() {
+            eprintln!(
+                "Warning: socket {} doesn't exist yet; requests fail until something listens on it.",
+                path
             );
         }
+        return Ok(());
+    }
+    let parsed = state::parse_target(target).with_context(|| {
+        format!(
+            "Invalid target '{}'. Use host:port or a URL (e.g., localhost:3000).",
+            target
+        )
+    })?;
+    if !parsed.is_loopback() {
+        eprintln!(
+            "Warning: {} is not this machine; anyone with the URL can reach it through the tunnel.",
+            parsed.host
+        );
+    }
+    Ok(())
+}
+
+// Validate a target, or each of several comma-separated (balanced) ones
+fn validate_targets(spec: &str) -> Result<()> {
+    let targets = state::split_targets(spec);
+    if targets.is_empty() {
+        anyhow::bail!("Missing target (e.g., localhost:3000).");
+    }
+    for target in &targets {
+        validate_target(target)?;
+    }
+    Ok(())
+}
+
+// (setting, current value) pairs shown by `ytunnel set <name>`
+fn tunnel_settings(tunnel: &PersistentTunnel) -> Vec<(&'static str, String)> {
+    let on_off = |b: bool| if b { "on" } else { "off" }.to_string();
+    vec![
+        ("target", tunnel.target_spec()),
+        ("auto_start", on_off(tunnel.auto_start)),
+        (
+            "metrics_port",
+            match tunnel.metrics_port {
+                Some(port) => port.to_string(),
+                None => format!("{} (default)", tunnel.get_metrics_port()),
+            },
+        ),
+        (
+            "protocol",
+            tunnel
+                .protocol
+                .clone()
+                .unwrap_or_else(|| "default (cloudflared decides)".to_string()),
+        ),
+        (
+            "log_level",
+            tunnel
+                .log_level
+                .clone()
+                .unwrap_or_else(|| "default (info)".to_string()),
+        ),
+        (
+            "fallback",
+            match &tunnel.fallback {
+                Some(fallback) => fallback.clone(),
+                None => format!("default ({})", state::DEFAULT_FALLBACK),
+            },
+        ),
+        ("manage_dns", on_off(tunnel.manage_dns)),
+        (
+            "maintenance",
+            match &tunnel.maintenance {
+                Some(service) => format!("on ({})", service),
+                None => "off".to_string(),
+            },
+        ),
+        (
+            "description",
+            tunnel
+                .description
+                .clone()
+                .unwrap_or_else(|| "(none)".to_string()),
+        ),
+    ]
+}
+
+// Change per-tunnel settings, then rewrite the cloudflared config and daemon unit
+async fn cmd_set(name: String, opts: SetOptions, account: Option<&str>) -> Result<()> {
+    // Held until the state is saved, not through the reinstall and restart
+    let lock = config::lock()?;
+    let cfg = config::load_config()?;
+    let mut state = TunnelState::load()?;
+    let account_name = account_for_tunnel(&cfg, &state, &name, account)?
+        .name
+        .clone();
+    let tunnel = state
+        .find_for_account(&name, &account_name)
+        .ok_or_else(|| tunnel_not_found(&cfg, &state, &name, &account_name))?
+        .clone();
+
+    if opts.is_empty() {
+        println!(
+            "Settings for tunnel '{}' (account '{}'):",
+            name, tunnel.account_name
+        );
+        for (setting, value) in tunnel_settings(&tunnel) {
+            println!("  {:<14}{}", setting, value);
+        }
+        println!("  {:<14}{}", "metrics_url", tunnel.metrics_display());
+        return Ok(());
+    }
+
+    let unsets = |setting: &str| opts.unset.iter().any(|s| s == setting);
+    if unsets("metrics_port") && opts.metrics_port.is_some() {
+        anyhow::bail!("--metrics-port and --unset metrics_port can't be combined");
+    }
+    if unsets("protocol") && opts.protocol.is_some() {
+        anyhow::bail!("--protocol and --unset protocol can't be combined");
+    }
+    if unsets("log_level") && opts.log_level.is_some() {
+        anyhow::bail!("--log-level and --unset log_level can't be combined");
+    }
+    if unsets("fallback") && opts.fallback.is_some() {
+        anyhow::bail!("--fallback and --unset fallback can't be combined");
+    }
+    if unsets("description") && opts.description.is_some() {
+        anyhow::bail!("--description and --unset description can't be combined");
+    }
+
+    let mut updated = tunnel.clone();
+    if unsets("metrics_port") {
+        updated.metrics_port = None;
+    }
+    if unsets("protocol") {
+        updated.protocol = None;
+    }
+    if unsets("log_level") {
+        updated.log_level = None;
+    }
+    if unsets("fallback") {
+        updated.fallback = None;
+    }
+    if unsets("description") {
+        updated.description = None;
+    }
+    if let Some(target) = opts.target {
+        validate_targets(&target)?;
+        updated.set_target(&target)?;
+    }
+    if let Some(auto_start) = opts.auto_start {
+        updated.auto_start = auto_start;
+    }
+    if let Some(port) = opts.metrics_port {
+        updated.metrics_port = Some(port);
+    }
+    if let Some(protocol) = opts.protocol {
+        updated.protocol = Some(protocol);
+    }
+    if let Some(level) = opts.log_level {
+        updated.log_level = Some(level);
+    }
+    if let Some(fallback) = opts.fallback {
+        state::validate_service(&fallback)?;
+        updated.fallback = Some(fallback);
+    }
+    if let Some(manage_dns) = opts.manage_dns {
+        updated.manage_dns = manage_dns;
+    }
+    if let Some(description) = &opts.description {
+        updated.description = state::normalize_description(description);
+    }
+    let port = updated.get_metrics_port();
+    if port != tunnel.get_metrics_port() {
+        check_metrics_port(&state, port, &name, &updated.account_name)?;
+    }
+
+    let changes: Vec<_> = tunnel_settings(&tunnel)
+        .into_iter()
+        .zip(tunnel_settings(&updated))
+        .filter(|((_, before), (_, after))| before != after)
+        .map(|((setting, before), (_, after))| (setting, before, after))
+        .collect();
+    if changes.is_empty() {
+        println!("No changes for tunnel '{}'.", name);
+        return Ok(());
+    }
+
+    if let Some(t) = state.find_for_account_mut(&name, &account_name) {
+        *t = updated.clone();
+    }
+    state.save()?;
+    drop(lock);
+    for (setting, before, after) in &changes {
+        println!("✓ {}: {} -> {}", setting, before, after);
+    }
+
+    // The target and fallback live in the cloudflared config, and the backends in the
+    // balancer's plist/unit; everything else is in the cloudflared plist/unit
+    let config_changed = updated.target != tunnel.target
+        || updated.fallback != tunnel.fallback
+        || updated.backends != tunnel.backends;
+    let unit_changed = updated.auto_start != tunnel.auto_start
+        || daemon::cloudflared_args(&updated) != daemon::cloudflared_args(&tunnel);
+    if daemon::is_daemon_installed(&updated) {
+        // Rewrites the cloudflared config as well
+        daemon::install_daemon(&updated).await?;
+    } else if config_changed && updated.config_path()?.exists() {
+        write_tunnel_config(&updated)?;
+    }
+
+    let running = daemon::is_daemon_running(&name, &updated.account_name).await;
+    if !running {
+        if opts.restart {
+            println!("Tunnel isn't running; changes apply the next time it starts.");
+        }
+    } else if opts.restart {
+        cmd_restart(name, DnsCheck::default(), Some(&account_name)).await?;
+    } else if config_changed || unit_changed {
+        println!(
+            "Run `ytunnel restart {}` (or pass --restart) to apply the changes.",
+            name
+        );
+    }
+
+    Ok(())
+}
+
+// Switch a tunnel's ingress to the maintenance service (or back to its target) and
+// restart it if it is running
+async fn cmd_maintenance(
+    name: String,
+    enabled: bool,
+    origin: Option<String>,
+    account: Option<&str>,
+) -> Result<()> {
+    // Held until the state is saved, not through the restart
+    let lock = config::lock()?;
+    let cfg = config::load_config()?;
+    let mut state = TunnelState::load()?;
+    let account_name = account_for_tunnel(&cfg, &state, &name, account)?
+        .name
+        .clone();
+    let tunnel = state
+        .find_for_account(&name, &account_name)
+        .ok_or_else(|| tunnel_not_found(&cfg, &state, &name, &account_name))?
+        .clone();
+
+    let maintenance = match (enabled, origin) {
+        (false, Some(_)) => anyhow::bail!("--origin only applies when turning maintenance on"),
+        (false, None) => None,
+        (true, Some(origin)) => {
+            validate_target(&origin)?;
+            Some(state::service_url(&origin))
+        }
+        (true, None) => Some(state::MAINTENANCE_SERVICE.to_string()),
+    };
+    if maintenance == tunnel.maintenance {
+        println!(
+            "Tunnel '{}' is already {}.",
+            name,
+            if enabled {
+                "in maintenance mode"
+            } else {
+                "out of maintenance mode"
+            }
+        );
+        return Ok(());
+    }
+
+    let mut updated = tunnel.clone();
+    updated.maintenance = maintenance;
+    if let Some(t) = state.find_for_account_mut(&name, &account_name) {
+        *t = updated.clone();
+    }
+    state.save()?;
+    drop(lock);
+    match &updated.maintenance {
+        Some(service) => println!("✓ Maintenance mode on: {} -> {}", name, service),
+        None => println!(
+            "✓ Maintenance mode off: {} -> {}",
+            name,
+            updated.display_target()
+        ),
+    }
+
+    if daemon::is_daemon_installed(&updated) {
+        daemon::install_daemon(&updated).await?;
+    } else if updated.config_path()?.exists() {
+        write_tunnel_config(&updated)?;
+    }
+    if daemon::is_daemon_running(&name, &updated.account_name).await {
+        cmd_restart(name, DnsCheck::default(), Some(&account_name)).await?;
+    } else {
+        println!("Tunnel isn't running; the change applies the next time it starts.");
+    }
+    Ok(())
+}
+
+// Restart a running tunnel (stop, reinstall daemon config, start)
+async fn cmd_restart(name: String, dns: DnsCheck, account: Option<&str>) -> Result<()> {
+    let cfg = config::load_config()?;
+    let state = TunnelState::load()?;
+    let acct = account_for_tunnel(&cfg, &state, &name, account)?;
+    let account_name = acct.name.clone();
+    let client = cfg.client(acct);
+
+    let mut tunnel = state
+        .find_for_account(&name, &account_name)
+        .ok_or_else(|| tunnel_not_found(&cfg, &state, &name, &account_name))?
+        .clone();
+
+    // Use the tunnel's own account_name for daemon operations (handles legacy tunnels)
+    let tunnel_account = tunnel.account_name.clone();
+
+    // Make sure the tunnel's zone still belongs to the account (or the account
+    // its DNS lives in), refreshing the cached zone list from Cloudflare before giving up
+    let zone_known = cfg
+        .accounts
+        .iter()
+        .any(|a| a.zones.iter().any(|z| z.id == tunnel.zone_id));
+    if !zone_known {
+        unless_offline(dns.offline, "the zone check", async {
+            let zones = client.list_zones().await?;
+            if !zones.iter().any(|z| z.id == tunnel.zone_id) {
+                return Err(error::not_found(format!(
+                    "Zone '{}' used by tunnel '{}' is no longer available to account '{}'.\n\
+                     Move the tunnel to another zone (press [e] in the TUI) or delete it with `ytunnel delete {}`.",
+                    tunnel.zone_name, name, account_name, name
+                )));
+            }
+            let zones: Vec<_> = zones
+                .into_iter()
+                .map(|z| config::ZoneConfig {
+                    id: z.id,
+                    name: z.name,
+                })
+                .collect();
+            config::update_config(|latest| {
+                latest.get_account_mut(Some(&account_name))?.zones = zones;
+                Ok(())
+            })?;
+            println!("✓ Refreshed zone list for account '{}'", account_name);
+            Ok(())
+        })
+        .await?;
+    }
+
+    let now = now_secs();
+    let dns_needed = dns.needed(&tunnel, now);
+    let looked_up = dns.looks_up_tunnel(dns_needed);
+    let recreated = looked_up
+        && ensure_cloudflare_tunnel(
+            &client,
+            cfg.get_account(Some(&account_name))?,
+            &mut tunnel,
+            dns,
+        )
+        .await?;
+
+    println!("Restarting tunnel: {}", name);
+
+    // Stop the daemon (and any cloudflared it lost track of, which would keep
+    // serving alongside the restarted one)
+    if let Ok(strays) = daemon::stop_daemon_fully(&tunnel, false).await {
+        for line in daemon::describe_strays(&strays) {
+            println!("⚠ {}", line);
+        }
+    }
+
+    // Ensure DNS record exists and points at this tunnel (recreates if manually
+    // deleted), unless it was verified recently
+    if dns_needed {
+        let checked = unless_offline(dns.offline, "the DNS check", async {
+            let acct = cfg.get_account(Some(&account_name))?;
+            for (zone_id, hostname) in tunnel.dns_routes() {
+                let client = dns_client(&cfg, acct, zone_id);
+                resolve_dns_conflicts(&client, zone_id, hostname).await?;
+                let change = client
+                    .ensure_dns_record(zone_id, hostname, &tunnel.tunnel_id)
+                    .await?;
+                print_dns_repair(hostname, &change);
+            }
+            Ok(())
+        })
+        .await?;
+        if checked {
+            tunnel.mark_dns_verified(now);
+        }
+    }
+
+    // Reinstall daemon (regenerates plist with latest config)
+    let previous_args = daemon::installed_cloudflared_args(&tunnel);
+    write_tunnel_config(&tunnel)?;
+    daemon::install_daemon(&tunnel).await?;
+    let current_args = daemon::cloudflared_args(&tunnel);
+    if let Some(previous_args) = previous_args {
+        if previous_args != current_args {
+            println!("✓ Updated cloudflared arguments:");
+            println!("    before: {}", previous_args.join(" "));
+            println!("    after:  {}", current_args.join(" "));
+        }
+    }
+
+    // Start the daemon
+    daemon::start_daemon(&name, &tunnel_account).await?;
+
+    // Update state
+    TunnelState::update(|state| {
+        if let Some(t) = state.find_for_account_mut(&name, &account_name) {
+            t.enabled = true;
+            t.dns_verified = tunnel.dns_verified.clone();
+        }
+        Ok(())
+    })?;
+
+    let connect_time = match daemon::verify_daemon_started(&tunnel).await {
+        Err(e) if !looked_up => {
+            let acct = cfg.get_account(Some(&account_name))?;
+            return Err(missing_tunnel_or(&client, acct, &tunnel, dns, e).await);
+        }
+        result => result?,
+    };
+
+    println!("✓ Restarted tunnel: {}", name);
+    print_connect_time(connect_time);
+    println!("  https://{}", idn::to_unicode(&tunnel.hostname));
+    if recreated && !tunnel.manage_dns {
+        print_dns_reminder(&tunnel);
+    }
+
+    Ok(())
+}
+
+// `ytunnel status` as printed with --output json/yaml
+#[derive(serde::Serialize)]
+struct StatusReport<'a> {
+    name: &'a str,
+    account: &'a str,
+    description: Option<&'a str>,
+    status: &'static str,
+    uptime_secs: Option<u64>,
+    pid: Option<u32>,
+    memory_bytes: Option<u64>,
+    connect_time_ms: Option<u64>,
+    // Bytes sent and received across every cloudflared run ytunnel has sampled
+    lifetime_bytes: Option<u64>,
+    target: &'a str,
+    // What the hostnames serve instead of the target while in maintenance mode
+    maintenance: Option<&'a str>,
+    // Balanced targets behind the local balancer at `target`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    backends: Vec<BackendReport<'a>>,
+    tunnel_id: &'a str,
+    // None when [daemon] metrics_bind is off
+    metrics: Option<String>,
+    hostnames: Vec<HostnameReport>,
+}
+
+#[derive(serde::Serialize)]
+struct BackendReport<'a> {
+    target: &'a str,
+    // Whether it accepts connections right now
+    healthy: bool,
+}
+
+#[derive(serde::Serialize)]
+struct HostnameReport {
+    hostname: String,
+    // routed, missing, or unmanaged (routed to the tunnel but not in ytunnel's state)
+    dns: &'static str,
+}
+
+// Show a tunnel's status, comparing the hostnames in state with the DNS records
+// that actually point at the tunnel (in every configured zone)
+async fn cmd_status(name: String, output: output::Format, account: Option<&str>) -> Result<()> {
+    let cfg = config::load_config()?;
+    let state = TunnelState::load()?;
+    let account_name = account_for_tunnel(&cfg, &state, &name, account)?
+        .name
+        .clone();
+
+    let tunnel = state
+        .find_for_account(&name, &account_name)
+        .ok_or_else(|| tunnel_not_found(&cfg, &state, &name, &account_name))?;
+
+    let status = daemon::get_daemon_status(tunnel).await;
+    let info = if status == state::TunnelStatus::Running {
+        daemon::get_daemon_info(tunnel).await
+    } else {
+        daemon::DaemonInfo::default()
+    };
+    let lifetime_bytes = lifetime_transfer(tunnel, status, cfg.metrics.timeout()).await;
+    let mut backends = Vec::new();
+    for target in &tunnel.backends {
+        backends.push(BackendReport {
+            target,
+            healthy: balancer::check_backend(target).await,
+        });
+    }
+
+    if output == output::Format::Table {
+        let status_text = match (status, info.uptime) {
+            (state::TunnelStatus::Running, Some(uptime)) => {
+                format!("running (up {})", format_uptime(uptime))
+            }
+            _ => status_name(status).to_string(),
+        };
+        println!("{} (account: {})", tunnel.name, tunnel.account_name);
+        if let Some(description) = &tunnel.description {
+            println!("  {}", description);
+        }
+        println!("  status:    {}", status_text);
+        if let Some(pid) = info.pid {
+            println!("  pid:       {}", pid);
+        }
+        if let Some(bytes) = info.memory_bytes {
+            println!("  memory:    {}", format_memory(bytes));
+        }
+        if let Some(ms) = tunnel.connect_time_ms {
+            println!(
+                "  connect:   {} to the first edge connection (last start)",
+                format_connect_time(ms)
+            );
+        }
+        if let Some(bytes) = lifetime_bytes {
+            println!("  transfer:  {} lifetime", transfer::format_bytes(bytes));
+        }
+        if backends.is_empty() {
+            println!("  target:    {}", tunnel.target);
+        } else {
+            println!("  target:    {} (balancer)", tunnel.target);
+            println!("  backends:");
+            for backend in &backends {
+                let health = if backend.healthy { "up" } else { "down" };
+                println!("    {} ({})", backend.target, health);
+            }
+        }
+        if let Some(service) = &tunnel.maintenance {
+            println!("  maintenance: on (serving {})", service);
+        }
+        println!("  tunnel id: {}", tunnel.tunnel_id);
+        println!("  metrics:   {}", tunnel.metrics_display());
+    }
+
+    // Each account can only see its own zones, so search them with their own credentials
+    let mut routed = Vec::new();
+    for acct in &cfg.accounts {
+        let zone_ids: Vec<&str> = acct.zones.iter().map(|z| z.id.as_str()).collect();
+        if zone_ids.is_empty() {
+            continue;
+        }
+        match cfg
+            .client(acct)
+            .find_records_pointing_at(&zone_ids, &tunnel.tunnel_id)
+            .await
+        {
+            Ok(records) => routed.extend(records.into_iter().map(|r| r.name)),
+            Err(e) => eprintln!(
+                "Warning: couldn't check DNS for account '{}': {:#}",
+                acct.name, e
+            ),
+        }
+    }
+    routed.sort();
+    routed.dedup();
+
+    let expected: Vec<&str> = tunnel.dns_routes().into_iter().map(|(_, h)| h).collect();
+    let mut hostnames: Vec<HostnameReport> = expected
+        .iter()
+        .map(|hostname| HostnameReport {
+            hostname: hostname.to_string(),
+            dns: if routed.iter().any(|r| r == hostname) {
+                "routed"
+            } else {
+                "missing"
+            },
+        })
+        .collect();
+    hostnames.extend(
+        routed
+            .iter()
+            .filter(|r| !expected.contains(&r.as_str()))
+            .map(|hostname| HostnameReport {
+                hostname: hostname.clone(),
+                dns: "unmanaged",
+            }),
+    );
+
+    let report = StatusReport {
+        name: &tunnel.name,
+        account: &tunnel.account_name,
+        description: tunnel.description.as_deref(),
+        status: status_name(status),
+        uptime_secs: info.uptime.map(|u| u.as_secs()),
+        pid: info.pid,
+        memory_bytes: info.memory_bytes,
+        connect_time_ms: tunnel.connect_time_ms,
+        lifetime_bytes,
+        target: &tunnel.target,
+        maintenance: tunnel.maintenance.as_deref(),
+        backends,
+        tunnel_id: &tunnel.tunnel_id,
+        metrics: tunnel.metrics_url(),
+        hostnames,
+    };
+    match output {
+        output::Format::Json | output::Format::Yaml => {
+            return output::print_structured(output, &report)
+        }
+        output::Format::Plain => {
+            print_status_plain(&report);
+            return Ok(());
+        }
+        output::Format::Table => {}
+    }
+
+    println!("\nHostnames:");
+    for host in &report.hostnames {
+        let note = match host.dns {
+            "routed" => "routed",
+            "unmanaged" => "routed, but not in ytunnel's state",
+            _ if tunnel.manage_dns => "no DNS record (run `ytunnel restart` to recreate it)",
+            _ => "no DNS record (DNS is managed elsewhere)",
+        };
+        println!("  {}: {}", idn::to_unicode(&host.hostname), note);
+    }
+
+    Ok(())
+}
+
+// A tunnel's lifetime bytes, folding in cloudflared's current counters first when
+// it's running. None if it has never been sampled.
+async fn lifetime_transfer(
+    tunnel: &PersistentTunnel,
+    status: state::TunnelStatus,
+    timeout: Duration,
+) -> Option<u64> {
+    let totals = if status == state::TunnelStatus::Running {
+        let scraped = TunnelMetrics::fetch(tunnel.metrics_url().as_deref(), timeout).await;
+        if scraped.available {
+            transfer::record(&[(&tunnel.tunnel_id, scraped.bytes_total())])
+        } else {
+            transfer::TransferTotals::load()
+        }
+    } else {
+        transfer::TransferTotals::load()
+    };
+    match totals {
+        Ok(totals) => totals.lifetime(&tunnel.tunnel_id),
+        Err(e) => {
+            eprintln!("Warning: couldn't read transfer totals: {:#}", e);
+            None
+        }
+    }
+}
+
+// key<TAB>value lines, then backend<TAB><target><TAB><up|down> per balanced backend
+// and hostname<TAB><hostname><TAB><dns> per hostname
+fn print_status_plain(report: &StatusReport) {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    let mut table = table::Table::new(["KEY", "VALUE"]);
+    for (key, value) in [
+        ("name", report.name.to_string()),
+        ("account", report.account.to_string()),
+        ("status", report.status.to_string()),
+        (
+            "uptime_secs",
+            optional(report.uptime_secs.map(|v| v.to_string())),
+        ),
+        ("pid", optional(report.pid.map(|v| v.to_string()))),
+        (
+            "memory_bytes",
+            optional(report.memory_bytes.map(|v| v.to_string())),
+        ),
+        (
+            "connect_time_ms",
+            optional(report.connect_time_ms.map(|v| v.to_string())),
+        ),
+        (
+            "lifetime_bytes",
+            optional(report.lifetime_bytes.map(|v| v.to_string())),
+        ),
+        ("target", report.target.to_string()),
+        (
+            "maintenance",
+            optional(report.maintenance.map(String::from)),
+        ),
+        ("tunnel_id", report.tunnel_id.to_string()),
+        ("metrics", optional(report.metrics.clone())),
+        (
+            "description",
+            optional(report.description.map(String::from)),
+        ),
+    ] {
+        table.add_row(vec![key.into(), value.into()]);
+    }
+    for backend in &report.backends {
+        table.add_row(vec![
+            "backend".into(),
+            backend.target.into(),
+            if backend.healthy { "up" } else { "down" }.into(),
+        ]);
+    }
+    for host in &report.hostnames {
+        table.add_row(vec![
+            "hostname".into(),
+            host.hostname.as_str().into(),
+            host.dns.into(),
+        ]);
+    }
+    output::print_table(output::Format::Plain, &table, table::Style::plain());
+}
+
+// Serve a balanced tunnel's local balancer until stopped (run by its daemon)
+async fn cmd_balance(name: String, account: Option<&str>) -> Result<()> {
+    let cfg = config::load_config()?;
+    let state = TunnelState::load()?;
+    let account_name = account_for_tunnel(&cfg, &state, &name, account)?
+        .name
+        .clone();
+    let tunnel = state
+        .find_for_account(&name, &account_name)
+        .ok_or_else(|| tunnel_not_found(&cfg, &state, &name, &account_name))?;
+    let port = tunnel
+        .balancer_port()
+        .with_context(|| format!("Tunnel '{}' has a single target; nothing to balance.", name))?;
+    balancer::run(port, &tunnel.backends).await
+}
+
+// Open a tunnel's public URL in the browser (or print it with --print)
+fn cmd_open(name: String, print: bool, account: Option<&str>) -> Result<()> {
+    let cfg = config::load_config()?;
+    let state = TunnelState::load()?;
+    let account_name = account_for_tunnel(&cfg, &state, &name, account)?
+        .name
+        .clone();
+    let tunnel = state
+        .find_for_account(&name, &account_name)
+        .ok_or_else(|| tunnel_not_found(&cfg, &state, &name, &account_name))?;
+
+    let url = format!("https://{}", tunnel.hostname);
+    if print {
+        println!("{}", url);
+        return Ok(());
+    }
+    browser::open_url(&url).with_context(|| {
+        format!(
+            "Failed to open {} in a browser (use --print to just show the URL)",
+            url
+        )
+    })?;
+    println!("Opened {}", url);
+    Ok(())
+}
+
+// How `ytunnel logs` prints lines: colored by level, with cloudflared's JSON logs
+// formatted, on a terminal; untouched when piped (e.g. into jq) or with --raw
+#[derive(Clone, Copy)]
+struct LogPrinter {
+    formatted: bool,
+    color: bool,
+}
+
+impl LogPrinter {
+    fn new(raw: bool, no_color: bool) -> Self {
+        use std::io::IsTerminal;
+        Self {
+            formatted: !raw && std::io::stdout().is_terminal(),
+            color: table::Style::detect(no_color).color,
+        }
+    }
+
+    fn render(self, line: &str) -> String {
+        let (level, text) = logfmt::render(line, self.formatted);
+        let color = match level {
+            logfmt::Level::Error => table::Color::Red,
+            logfmt::Level::Warn => table::Color::Yellow,
+            logfmt::Level::Info => table::Color::Green,
+            logfmt::Level::Other => return text.into_owned(),
+        };
+        if self.color {
+            color.paint(&text)
+        } else {
+            text.into_owned()
+        }
+    }
+}
+
+// View logs for a tunnel
+async fn cmd_logs(
+    name: String,
+    follow: bool,
+    lines: usize,
+    printer: LogPrinter,
+    account: Option<&str>,
+) -> Result<()> {
+    let cfg = config::load_config()?;
+    let state = TunnelState::load()?;
+    let account_name = account_for_tunnel(&cfg, &state, &name, account)?
+        .name
+        .clone();
+
+    let tunnel = state
+        .find_for_account(&name, &account_name)
+        .ok_or_else(|| tunnel_not_found(&cfg, &state, &name, &account_name))?;
+
+    let log_path = tunnel.existing_log_path()?;
+
+    if !log_path.exists() {
+        println!("No logs yet for tunnel '{}'", name);
+        return Ok(());
+    }
+
+    if follow {
+        // Use tail -f for following, passing each line through the printer
+        use tokio::io::AsyncBufReadExt;
+        let mut child = tokio::process::Command::new("tail")
+            .args(["-f", "-n", &lines.to_string()])
+            .arg(&log_path)
+            .stdout(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to tail log file")?;
+        let stdout = child.stdout.take().context("Failed to tail log file")?;
+        let mut reader = tokio::io::BufReader::new(stdout).lines();
+        while let Some(line) = reader.next_line().await? {
+            println!("{}", printer.render(&line));
+        }
+
+        if !child.wait().await?.success() {
+            anyhow::bail!("Failed to tail log file");
+        }
+    } else {
+        // Just read and print the last N lines
+        let log_lines = daemon::read_log_tail(tunnel, lines)?;
+        for line in log_lines {
+            println!("{}", printer.render(&line));
+        }
+    }
+
+    Ok(())
+}
+
+// Merged, chronological log tail of every tunnel in the account, each line
+// prefixed with its tunnel's name. Stopped tunnels count too: the last lines of
+// one that crashed are often the interesting ones.
+async fn cmd_logs_all(
+    follow: bool,
+    lines: usize,
+    printer: LogPrinter,
+    account: Option<&str>,
+) -> Result<()> {
+    let cfg = config::load_config()?;
+    let acct = cfg.get_account(account)?;
+    let state = TunnelState::load()?;
+
+    let mut logs = Vec::new();
+    for tunnel in state.tunnels_for_account(&acct.name) {
+        let path = tunnel.existing_log_path()?;
+        if path.exists() {
+            logs.push((tunnel, path));
+        }
+    }
+    if logs.is_empty() {
+        println!("No logs yet for any tunnel in account '{}'", acct.name);
+        return Ok(());
+    }
+    let width = logs.iter().map(|(t, _)| t.name.len()).max().unwrap_or(0);
+
+    let tails = logs
+        .iter()
+        .map(|(tunnel, _)| Ok((tunnel.name.as_str(), daemon::read_log_tail(tunnel, lines)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let merged = merge_logs(&tails);
+    for (name, line) in &merged[merged.len().saturating_sub(lines)..] {
+        println!("{:<width$} | {}", name, printer.render(line), width = width);
+    }
+    if !follow {
+        return Ok(());
+    }
+
+    // One `tail -f` per log, printing lines as they arrive from any of them
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut tails = Vec::new();
+    for (tunnel, path) in logs {
+        let mut child = tokio::process::Command::new("tail")
+            .args(["-f", "-n", "0"])
+            .arg(&path)
+            .stdout(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to tail log file")?;
+        let stdout = child.stdout.take().context("Failed to tail log file")?;
+        let (name, tx) = (tunnel.name.clone(), tx.clone());
+        tokio::spawn(async move {
+            use tokio::io::AsyncBufReadExt;
+            let mut reader = tokio::io::BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = reader.next_line().await {
+                if tx.send((name.clone(), line)).is_err() {
+                    break;
+                }
+            }
+        });
+        tails.push(child);
+    }
+    drop(tx);
+    while let Some((name, line)) = rx.recv().await {
+        println!(
+            "{:<width$} | {}",
+            name,
+            printer.render(&line),
+            width = width
+        );
+    }
+    Ok(())
+}
+
+// Interleave several tunnels' log lines by their leading cloudflared timestamp.
+// Lines without one (e.g. a wrapped panic) stay behind the line before them, and
+// the sort is stable, so each log keeps its own order.
+fn merge_logs<'a>(logs: &'a [(&'a str, Vec<String>)]) -> Vec<(&'a str, &'a str)> {
+    let mut merged = Vec::new();
+    for (name, lines) in logs {
+        let mut at = 0;
+        for line in lines {
+            at = cloudflare::parse_timestamp(line).unwrap_or(at);
+            merged.push((at, *name, line.as_str()));
+        }
+    }
+    merged.sort_by_key(|(at, _, _)| *at);
+    merged
+        .into_iter()
+        .map(|(_, name, line)| (name, line))
+        .collect()
+}
+
+// A zone and the managed tunnels routed through it, for `ytunnel zones`
+#[derive(serde::Serialize)]
+struct ZoneUsage {
+    name: String,
+    id: String,
+    default: bool,
+    tunnels: Vec<String>,
+    // Only set with --verify: whether the API still returns this zone
+    #[serde(skip_serializing_if = "Option::is_none")]
+    available: Option<bool>,
+}
+
+async fn cmd_zones_list(
+    verify: bool,
+    output: output::Format,
+    no_color: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    let cfg = config::load_config()?;
+    let acct = cfg.get_account(account)?;
+    let state = TunnelState::load()?;
+    let tunnels = state.tunnels_for_account(&acct.name);
+
+    let live_zone_ids: Option<Vec<String>> = if verify {
+        let client = cfg.client(acct);
+        let zones = client.list_zones().await?;
+        Some(zones.into_iter().map(|z| z.id).collect())
+    } else {
+        None
+    };
+
+    let usage: Vec<ZoneUsage> = acct
+        .zones
+        .iter()
+        .map(|zone| ZoneUsage {
+            name: zone.name.clone(),
+            id: zone.id.clone(),
+            default: zone.id == acct.default_zone_id,
+            tunnels: tunnels
+                .iter()
+                .filter(|t| t.dns_routes().iter().any(|(id, _)| *id == zone.id))
+                .map(|t| t.name.clone())
+                .collect(),
+            available: live_zone_ids.as_ref().map(|ids| ids.contains(&zone.id)),
+        })
+        .collect();
+
+    if output.is_structured() {
+        return output::print_structured(output, &usage);
+    }
+
+    let mut headers = vec!["ZONE", "DEFAULT", "TUNNELS"];
+    if verify {
+        headers.push("AVAILABLE");
+    }
+    let mut zones = table::Table::new(headers);
+    for zone in &usage {
+        let mut row = vec![
+            idn::to_unicode(&zone.name).into(),
+            if zone.default {
+                table::Cell::colored("yes", table::Color::Cyan)
+            } else {
+                "".into()
+            },
+            if zone.tunnels.is_empty() {
+                table::Cell::colored("-", table::Color::Dim)
+            } else {
+                zone.tunnels.join(", ").into()
+            },
+        ];
+        match zone.available {
+            Some(true) => row.push(table::Cell::colored("yes", table::Color::Green)),
+            Some(false) => row.push(table::Cell::colored(
+                "no longer returned by Cloudflare",
+                table::Color::Red,
+            )),
+            None => {}
+        }
+        zones.add_row(row);
+    }
+
+    if output == output::Format::Table {
+        println!("Available zones for account '{}':", acct.name);
+    }
+    output::print_table(output, &zones, output.style(no_color));
+
+    Ok(())
+}
+
+async fn cmd_zones_default(
+    domain: String,
+    skip_confirm: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    let cfg = config::load_config()?;
+    let acct = cfg.get_account(account)?;
+
+    let zone = acct.zone_by_name(&domain).cloned().ok_or_else(|| {
+        error::not_found(format!(
+            "Zone '{}' not found. Run `ytunnel zones` to see available zones.",
+            domain
+        ))
+    })?;
+
+    if zone.id == acct.default_zone_id {
+        println!("{} is already the default zone.", domain);
+        return Ok(());
+    }
+
+    // Show which tunnels live under the old and new defaults for context
+    let state = TunnelState::load()?;
+    let tunnels_in_zone = |zone_id: &str| -> Vec<String> {
+        state
+            .tunnels_for_account(&acct.name)
+            .into_iter()
+            .filter(|t| t.dns_routes().iter().any(|(id, _)| *id == zone_id))
+            .map(|t| t.name.clone())
+            .collect()
+    };
+    let previous_tunnels = tunnels_in_zone(&acct.default_zone_id);
+    let new_tunnels = tunnels_in_zone(&zone.id);
+
+    if !previous_tunnels.is_empty() {
+        println!(
+            "Tunnels under the current default ({}): {}",
+            acct.default_zone_name,
+            previous_tunnels.join(", ")
+        );
+    }
+    if !new_tunnels.is_empty() {
+        println!("Tunnels under {}: {}", zone.name, new_tunnels.join(", "));
+    }
+
+    if !skip_confirm && !previous_tunnels.is_empty() && !new_tunnels.is_empty() {
+        println!(
+            "New tunnels will be created under {} instead of {}. Continue? [y/N]",
+            zone.name, acct.default_zone_name
+        );
+        print!("> ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+
+        if input != "y" && input != "yes" {
+            println!("Cancelled.");
+            return Ok(());
+        }
     }
 
+    config::update_config(|latest| {
+        let acct = latest.get_account_mut(Some(&acct.name))?;
+        acct.default_zone_id = zone.id.clone();
+        acct.default_zone_name = zone.name.clone();
+        Ok(())
+    })?;
+    println!("Default zone set to: {}", domain);
+
     Ok(())
 }
 
-async fn cmd_list(account: Option<&str>) -> Result<()> {
+// Columns shown by `ytunnel list` unless --columns or --wide is given
+const DEFAULT_LIST_COLUMNS: &[&str] = &["status", "name", "hostname", "target", "auto_start"];
+const ALL_LIST_COLUMNS: &[&str] = &[
+    "status",
+    "name",
+    "account",
+    "hostname",
+    "target",
+    "auto_start",
+    "enabled",
+    "uptime",
+    "pid",
+    "memory",
+    "metrics",
+    "description",
+];
+
+// Which tunnels `ytunnel list` shows
+struct ListFilter {
+    auto_start: bool,
+    enabled: bool,
+}
+
+impl ListFilter {
+    fn matches(&self, tunnel: &state::PersistentTunnel) -> bool {
+        (!self.auto_start || tunnel.auto_start) && (!self.enabled || tunnel.enabled)
+    }
+}
+
+async fn cmd_list(
+    columns: Vec<String>,
+    wide: bool,
+    filter: ListFilter,
+    output: output::Format,
+    no_color: bool,
+    account: Option<&str>,
+) -> Result<()> {
     let cfg = config::load_config()?;
-    let account_name = cfg.get_account(account)?.name.clone();
+    let acct = cfg.get_account(account)?;
+    let account_name = acct.name.clone();
     let state = TunnelState::load()?;
 
     let tunnels: Vec<_> = state.tunnels_for_account(&account_name);
+    let default_zone = if acct.has_zones() {
+        acct.default_zone_name.as_str()
+    } else {
+        "none"
+    };
 
+    // Plain output is for scripts, so an empty list prints nothing
+    if tunnels.is_empty() && output == output::Format::Plain {
+        return Ok(());
+    }
     if tunnels.is_empty() {
         println!("No tunnels configured for account '{}'.", account_name);
-        println!("Add one with: ytunnel add <name> <target>");
+        println!(
+            "Add one with: ytunnel add <name> <target>  (default zone: {})",
+            default_zone
+        );
+        return Ok(());
+    }
+
+    let tunnels: Vec<_> = tunnels.into_iter().filter(|t| filter.matches(t)).collect();
+    if tunnels.is_empty() && output == output::Format::Plain {
+        return Ok(());
+    }
+    if tunnels.is_empty() {
+        println!(
+            "No tunnels for account '{}' match the filter.",
+            account_name
+        );
+        return Ok(());
+    }
+
+    let columns: Vec<&str> = if wide {
+        ALL_LIST_COLUMNS.to_vec()
+    } else if columns.is_empty() {
+        DEFAULT_LIST_COLUMNS.to_vec()
+    } else {
+        columns.iter().map(String::as_str).collect()
+    };
+
+    let style = output.style(no_color);
+    let mut list = table::Table::new(columns.iter().map(|c| c.to_uppercase()));
+    for tunnel in tunnels {
+        let status = daemon::get_daemon_status(tunnel).await;
+        // Process details cost extra commands, so only look them up when shown
+        let wants_info = ["uptime", "pid", "memory"]
+            .iter()
+            .any(|c| columns.contains(c));
+        let info = if wants_info && status == state::TunnelStatus::Running {
+            daemon::get_daemon_info(tunnel).await
+        } else {
+            daemon::DaemonInfo::default()
+        };
+
+        let row = columns
+            .iter()
+            .map(|column| match *column {
+                "status" => {
+                    let (text, color) = match status {
+                        state::TunnelStatus::Running => ("running", table::Color::Green),
+                        state::TunnelStatus::Stopped => ("stopped", table::Color::Yellow),
+                        state::TunnelStatus::Error => ("error", table::Color::Red),
+                    };
+                    let text = if style.color {
+                        format!("{} {}", status.symbol(), text)
+                    } else {
+                        text.to_string()
+                    };
+                    table::Cell::colored(text, color)
+                }
+                "name" => idn::to_unicode(&tunnel.name).into(),
+                "account" => table::Cell::colored(tunnel.account_name.as_str(), table::Color::Cyan),
+                "hostname" => idn::to_unicode(&tunnel.hostname).into(),
+                "target" => tunnel.target_spec().into(),
+                // Same ⟳ marker as the TUI; plain output spells it out
+                "auto_start" => match (tunnel.auto_start, style.color) {
+                    (true, true) => table::Cell::colored("⟳", table::Color::Cyan),
+                    (true, false) => "yes".into(),
+                    (false, true) => "".into(),
+                    (false, false) => "no".into(),
+                },
+                "enabled" => if tunnel.enabled { "yes" } else { "no" }.into(),
+                "metrics" => tunnel.metrics_display().into(),
+                "description" => tunnel.description.as_deref().unwrap_or("").into(),
+                "uptime" => match info.uptime {
+                    Some(uptime) => format_uptime(uptime).into(),
+                    None => table::Cell::colored("-", table::Color::Dim),
+                },
+                "pid" => match info.pid {
+                    Some(pid) => pid.to_string().into(),
+                    None => table::Cell::colored("-", table::Color::Dim),
+                },
+                "memory" => match info.memory_bytes {
+                    Some(bytes) => format_memory(bytes).into(),
+                    None => table::Cell::colored("-", table::Color::Dim),
+                },
+                _ => "".into(),
+            })
+            .collect();
+        list.add_row(row);
+    }
+
+    if output == output::Format::Table {
+        println!(
+            "Tunnels for account '{}' (default zone: {}):",
+            account_name, default_zone
+        );
+    }
+    output::print_table(output, &list, style);
+
+    Ok(())
+}
+
+// A tunnel as printed by `ytunnel list --output json` (or yaml)
+#[derive(serde::Serialize)]
+struct TunnelListing<'a> {
+    name: &'a str,
+    account: &'a str,
+    hostname: &'a str,
+    target: &'a str,
+    status: &'static str,
+    auto_start: bool,
+    enabled: bool,
+    // Seconds since the daemon started (running tunnels only)
+    uptime_secs: Option<u64>,
+    pid: Option<u32>,
+    // Resident memory of the cloudflared process
+    memory_bytes: Option<u64>,
+    // Time to the first edge connection at the last start
+    connect_time_ms: Option<u64>,
+    metrics: Option<String>,
+    description: Option<&'a str>,
+}
+
+async fn cmd_list_structured(
+    filter: ListFilter,
+    output: output::Format,
+    account: Option<&str>,
+) -> Result<()> {
+    let cfg = config::load_config()?;
+    let acct = cfg.get_account(account)?;
+    let state = TunnelState::load()?;
+
+    let mut listings = Vec::new();
+    for tunnel in state
+        .tunnels_for_account(&acct.name)
+        .into_iter()
+        .filter(|t| filter.matches(t))
+    {
+        let status = daemon::get_daemon_status(tunnel).await;
+        let info = if status == state::TunnelStatus::Running {
+            daemon::get_daemon_info(tunnel).await
+        } else {
+            daemon::DaemonInfo::default()
+        };
+        listings.push(TunnelListing {
+            name: &tunnel.name,
+            account: &tunnel.account_name,
+            hostname: &tunnel.hostname,
+            target: &tunnel.target,
+            status: status_name(status),
+            auto_start: tunnel.auto_start,
+            enabled: tunnel.enabled,
+            uptime_secs: info.uptime.map(|u| u.as_secs()),
+            pid: info.pid,
+            memory_bytes: info.memory_bytes,
+            connect_time_ms: tunnel.connect_time_ms,
+            metrics: tunnel.metrics_url(),
+            description: tunnel.description.as_deref(),
+        });
+    }
+
+    output::print_structured(output, &listings)
+}
+
+// Managed tunnels' local state next to Cloudflare's (`ytunnel list --remote`)
+async fn cmd_list_remote(
+    filter: ListFilter,
+    output: output::Format,
+    no_color: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    let cfg = config::load_config()?;
+    let acct = cfg.get_account(account)?;
+    let state = TunnelState::load()?;
+    let tunnels: Vec<_> = state
+        .tunnels_for_account(&acct.name)
+        .into_iter()
+        .filter(|t| filter.matches(t))
+        .collect();
+    let views = remote::compare(&cfg, acct, &tunnels).await?;
+    if output.is_structured() {
+        return output::print_structured(output, &views);
+    }
+    if views.is_empty() {
+        if output == output::Format::Table {
+            println!("No tunnels for account '{}' to compare.", acct.name);
+        }
+        return Ok(());
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let style = output.style(no_color);
+    let mut list = table::Table::new([
+        "NAME",
+        "LOCAL",
+        "REMOTE",
+        "CONNECTIONS",
+        "LAST SEEN",
+        "PROBLEM",
+    ]);
+    for view in &views {
+        let local_color = match view.local {
+            "running" => table::Color::Green,
+            "stopped" => table::Color::Yellow,
+            _ => table::Color::Red,
+        };
+        let remote = match view.remote.as_deref() {
+            Some(status @ ("healthy" | "degraded")) => {
+                table::Cell::colored(status, table::Color::Green)
+            }
+            Some(status) => table::Cell::colored(status, table::Color::Yellow),
+            None => table::Cell::colored("missing", table::Color::Red),
+        };
+        let connections = if view.connections == 0 {
+            table::Cell::colored("0", table::Color::Dim)
+        } else {
+            format!("{} ({})", view.connections, view.colos.join(", ")).into()
+        };
+        let last_seen = match (&view.last_seen, view.connections) {
+            (_, 1..) => "now".into(),
+            (Some(at), 0) => match cloudflare::parse_timestamp(at) {
+                Some(secs) => {
+                    let ago = std::time::Duration::from_secs(now.saturating_sub(secs));
+                    format!("{} ago", format_uptime(ago)).into()
+                }
+                None => at.as_str().into(),
+            },
+            (None, 0) => table::Cell::colored("-", table::Color::Dim),
+        };
+        let problem = match &view.problem {
+            Some(problem) => table::Cell::colored(problem.as_str(), table::Color::Red),
+            None => table::Cell::colored("-", table::Color::Dim),
+        };
+        list.add_row(vec![
+            idn::to_unicode(&view.name).into(),
+            table::Cell::colored(view.local, local_color),
+            remote,
+            connections,
+            last_seen,
+            problem,
+        ]);
+    }
+
+    if output == output::Format::Table {
+        println!(
+            "Tunnels for account '{}', locally and on Cloudflare:",
+            acct.name
+        );
+    }
+    output::print_table(output, &list, style);
+    Ok(())
+}
+
+// Time between the two scrapes `stats` measures request rates over
+const STATS_SAMPLE_WINDOW: Duration = Duration::from_secs(1);
+
+// One row of `ytunnel stats`. Metrics are None for stopped tunnels and ones whose
+// endpoint couldn't be scraped; health is None unless running.
+#[derive(serde::Serialize)]
+struct TunnelStats {
+    name: String,
+    status: &'static str,
+    requests: Option<u64>,
+    errors: Option<u64>,
+    requests_per_sec: Option<f64>,
+    ha_connections: Option<u64>,
+    edge_locations: Vec<String>,
+    healthy: Option<bool>,
+}
+
+// Live metrics for every tunnel in the account (`ytunnel stats`)
+async fn cmd_stats(output: output::Format, no_color: bool, account: Option<&str>) -> Result<()> {
+    let cfg = config::load_config()?;
+    let acct = cfg.get_account(account)?;
+    let state = TunnelState::load()?;
+    let tunnels: Vec<PersistentTunnel> = state
+        .tunnels_for_account(&acct.name)
+        .into_iter()
+        .cloned()
+        .collect();
+    let metrics_timeout = cfg.metrics.timeout();
+    let health_timeout = cfg.tui.health_timeout();
+
+    // Status and a first scrape of each tunnel, then a second one (with a health
+    // check) a sample window later for the request rate
+    let first = parallel::run_bounded(
+        tunnels,
+        parallel::LIMIT,
+        |tunnel| async move {
+            let status = daemon::get_daemon_status(&tunnel).await;
+            let before = if status == state::TunnelStatus::Running {
+                TunnelMetrics::fetch(tunnel.metrics_url().as_deref(), metrics_timeout).await
+            } else {
+                TunnelMetrics::default()
+            };
+            (tunnel, status, before, std::time::Instant::now())
+        },
+        |_| {},
+    )
+    .await;
+    if first.iter().any(|(_, _, before, _)| before.available) {
+        tokio::time::sleep(STATS_SAMPLE_WINDOW).await;
+    }
+    let stats = parallel::run_bounded(
+        first,
+        parallel::LIMIT,
+        |(tunnel, status, before, at)| async move {
+            let mut stats = TunnelStats {
+                name: tunnel.name.clone(),
+                status: status_name(status),
+                requests: None,
+                errors: None,
+                requests_per_sec: None,
+                ha_connections: None,
+                edge_locations: Vec::new(),
+                healthy: None,
+            };
+            if status != state::TunnelStatus::Running {
+                return stats;
+            }
+            let metrics_url = tunnel.metrics_url();
+            // Timed as soon as the metrics arrive, not when the slower health
+            // check finishes
+            let ((after, elapsed), healthy) = tokio::join!(
+                async {
+                    let after = TunnelMetrics::fetch(metrics_url.as_deref(), metrics_timeout).await;
+                    (after, at.elapsed())
+                },
+                health::check_hostname(&tunnel.hostname, health_timeout),
+            );
+            stats.healthy = Some(healthy);
+            if after.available {
+                stats.requests = Some(after.total_requests);
+                stats.errors = Some(after.request_errors);
+                stats.ha_connections = Some(after.ha_connections);
+                stats.edge_locations = after
+                    .edge_locations
+                    .iter()
+                    .map(|l| l.name.clone())
+                    .collect();
+                // A restart in between resets the counter; no rate then
+                if before.available && after.total_requests >= before.total_requests {
+                    let requests = after.total_requests - before.total_requests;
+                    stats.requests_per_sec = Some(requests as f64 / elapsed.as_secs_f64());
+                }
+            }
+            stats
+        },
+        |_| {},
+    )
+    .await;
+
+    if output.is_structured() {
+        return output::print_structured(output, &stats);
+    }
+    if stats.is_empty() {
+        if output == output::Format::Table {
+            println!("No tunnels configured for account '{}'.", acct.name);
+        }
+        return Ok(());
+    }
+
+    let style = output.style(no_color);
+    let dash = || table::Cell::colored("-", table::Color::Dim);
+    let mut list = table::Table::new([
+        "NAME", "STATUS", "REQUESTS", "ERRORS", "REQ/S", "HA", "EDGE", "HEALTH",
+    ]);
+    for row in &stats {
+        let status_color = match row.status {
+            "running" => table::Color::Green,
+            "stopped" => table::Color::Yellow,
+            _ => table::Color::Red,
+        };
+        let errors = match row.errors {
+            Some(0) => "0".into(),
+            Some(errors) => table::Cell::colored(errors.to_string(), table::Color::Red),
+            None => dash(),
+        };
+        let edge = if row.edge_locations.is_empty() {
+            dash()
+        } else {
+            row.edge_locations.join(", ").into()
+        };
+        let health = match row.healthy {
+            Some(true) => table::Cell::colored("healthy", table::Color::Green),
+            Some(false) => table::Cell::colored("unreachable", table::Color::Red),
+            None => dash(),
+        };
+        list.add_row(vec![
+            idn::to_unicode(&row.name).into(),
+            table::Cell::colored(row.status, status_color),
+            row.requests.map_or_else(dash, |n| n.to_string().into()),
+            errors,
+            row.requests_per_sec
+                .map_or_else(dash, |rate| format!("{:.1}", rate).into()),
+            row.ha_connections
+                .map_or_else(dash, |n| n.to_string().into()),
+            edge,
+            health,
+        ]);
+    }
+    output::print_table(output, &list, style);
+    Ok(())
+}
+
+// Status as spelled in structured output
+fn status_name(status: state::TunnelStatus) -> &'static str {
+    match status {
+        state::TunnelStatus::Running => "running",
+        state::TunnelStatus::Stopped => "stopped",
+        state::TunnelStatus::Error => "error",
+    }
+}
+
+// Compact uptime: the two most significant units (e.g. "3d 4h", "2h 5m", "5m 3s")
+fn format_uptime(uptime: std::time::Duration) -> String {
+    let secs = uptime.as_secs();
+    let (days, hours, mins, secs) = (
+        secs / 86_400,
+        secs % 86_400 / 3_600,
+        secs % 3_600 / 60,
+        secs % 60,
+    );
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else if mins > 0 {
+        format!("{}m {}s", mins, secs)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+// Time to first edge connection, e.g. "1.8s"
+fn format_connect_time(ms: u64) -> String {
+    format!("{:.1}s", ms as f64 / 1000.0)
+}
+
+// Resident memory in binary units (e.g. "31.2 MB")
+fn format_memory(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    let mb = bytes as f64 / MB;
+    if mb >= 1024.0 {
+        format!("{:.1} GB", mb / 1024.0)
+    } else {
+        format!("{:.1} MB", mb)
+    }
+}
+
+// Push running tunnels' metrics to a Pushgateway, once or every `interval` seconds.
+// A tunnel that can't be scraped or pushed is reported and the others still go out.
+async fn cmd_metrics_push(url: String, interval: Option<u64>, account: Option<&str>) -> Result<()> {
+    let cfg = config::load_config()?;
+    if let Some(name) = account {
+        cfg.get_account(Some(name))?;
+    }
+    // Reject a malformed URL up front rather than once per tunnel
+    metrics::pushgateway_url(&url, "", "")?;
+    if cfg.daemon.metrics_bind().is_none() {
+        anyhow::bail!(
+            "cloudflared's metrics are off ([daemon] metrics_bind = \"{}\"), so there's nothing to push",
+            config::METRICS_OFF
+        );
+    }
+
+    loop {
+        let state = TunnelState::load()?;
+        let mut pushed = 0;
+        let mut failed = 0;
+        for tunnel in state
+            .tunnels
+            .iter()
+            .filter(|t| account.is_none_or(|a| t.account_name == a))
+        {
+            if !daemon::is_daemon_running(&tunnel.name, &tunnel.account_name).await {
+                continue;
+            }
+            let Some(metrics_url) = tunnel.metrics_url() else {
+                continue;
+            };
+            let scraped = TunnelMetrics::fetch(Some(&metrics_url), cfg.metrics.timeout()).await;
+            let result = if scraped.available {
+                metrics::push(
+                    &url,
+                    &tunnel.name,
+                    &tunnel.account_name,
+                    &scraped,
+                    cfg.api.timeout(),
+                )
+                .await
+            } else {
+                Err(anyhow::anyhow!(
+                    "no metrics at {} (is cloudflared up?)",
+                    metrics_url
+                ))
+            };
+            match result {
+                Ok(()) => pushed += 1,
+                Err(e) => {
+                    failed += 1;
+                    eprintln!(
+                        "Warning: couldn't push metrics for '{}' (account: {}): {:#}",
+                        tunnel.name, tunnel.account_name, e
+                    );
+                }
+            }
+        }
+        println!(
+            "Pushed metrics for {} running tunnel(s) to {}{}",
+            pushed,
+            url,
+            if failed > 0 {
+                format!(" ({} failed)", failed)
+            } else {
+                String::new()
+            }
+        );
+
+        match interval {
+            Some(secs) => tokio::time::sleep(Duration::from_secs(secs)).await,
+            None if failed > 0 => {
+                anyhow::bail!("Failed to push metrics for {} tunnel(s)", failed)
+            }
+            None => return Ok(()),
+        }
+    }
+}
+
+// List DNS records that route to Cloudflare tunnels
+async fn cmd_dns_list(zone: Option<String>, account: Option<&str>) -> Result<()> {
+    let cfg = config::load_config()?;
+    let acct = cfg.get_account(account)?;
+    let client = cfg.client(acct);
+    let state = TunnelState::load()?;
+
+    let zones: Vec<_> = match zone {
+        Some(z) => {
+            let found = acct.zone_by_name(&z);
+            match found {
+                Some(zc) => vec![zc],
+                None => {
+                    return Err(error::not_found(format!(
+                        "Zone '{}' not found. Run `ytunnel zones` to see available zones.",
+                        z
+                    )))
+                }
+            }
+        }
+        None => acct.zones.iter().collect(),
+    };
+
+    for zone in zones {
+        let records: Vec<_> = client
+            .list_dns_records(&zone.id, &cloudflare::DnsRecordFilter::cnames())
+            .await?
+            .into_iter()
+            .filter(|r| r.content.ends_with(".cfargotunnel.com"))
+            .collect();
+
+        println!("{} ({} tunnel record(s)):", zone.name, records.len());
+        for record in records {
+            let tunnel_id = record.content.trim_end_matches(".cfargotunnel.com");
+            let owner = match state.tunnels.iter().find(|t| t.tunnel_id == tunnel_id) {
+                Some(t) => format!("managed: {}", t.name),
+                None => "unmanaged".to_string(),
+            };
+            println!("  {} -> {} ({})", record.name, record.content, owner);
+        }
+    }
+
+    Ok(())
+}
+
+// Look for records that would shadow the tunnel CNAME of every managed hostname
+// (all accounts unless --account is given)
+async fn cmd_doctor(account: Option<&str>) -> Result<()> {
+    let cfg = config::load_config()?;
+    let state = TunnelState::load()?;
+    if let Some(name) = account {
+        cfg.get_account(Some(name))?;
+    }
+
+    let mut problems = 0;
+    let mut unrouted = 0;
+    for tunnel in state
+        .tunnels
+        .iter()
+        .filter(|t| account.is_none_or(|a| t.account_name == a))
+    {
+        let acct = cfg.get_account(Some(&tunnel.account_name))?;
+        let expected = tunnel.cname_target();
+        for (zone_id, hostname) in tunnel.managed_dns_routes() {
+            let client = dns_client(&cfg, acct, zone_id);
+            let conflicts = client.find_dns_conflicts(zone_id, hostname).await?;
+            if !conflicts.is_empty() {
+                problems += 1;
+                println!(
+                    "✗ {}",
+                    cloudflare::describe_dns_conflicts(hostname, &conflicts)
+                );
+                continue;
+            }
+            // start and restart only check this when it wasn't verified recently
+            match client.get_dns_record(zone_id, hostname).await? {
+                Some(record) if record.content == expected => {
+                    println!("✓ {} ({})", hostname, tunnel.name)
+                }
+                Some(record) => {
+                    unrouted += 1;
+                    println!(
+                        "✗ {} points at {}, not tunnel '{}'",
+                        hostname, record.content, tunnel.name
+                    );
+                }
+                None => {
+                    unrouted += 1;
+                    println!("✗ {} has no DNS record ({})", hostname, tunnel.name);
+                }
+            }
+        }
+    }
+
+    // Local state against Cloudflare's, one tunnel list per account
+    let mut disagreements = 0;
+    let mut elsewhere = 0;
+    for acct in cfg
+        .accounts
+        .iter()
+        .filter(|a| account.is_none_or(|name| a.name == name))
+    {
+        let tunnels = state.tunnels_for_account(&acct.name);
+        if tunnels.is_empty() {
+            continue;
+        }
+        for view in remote::compare(&cfg, acct, &tunnels).await? {
+            match &view.problem {
+                Some(problem) if view.served_elsewhere => {
+                    elsewhere += 1;
+                    println!("⚠ {}: {}", view.name, problem);
+                }
+                Some(problem) => {
+                    disagreements += 1;
+                    println!("✗ {}: {}", view.name, problem);
+                }
+                None => println!(
+                    "✓ {} (local {}, remote {})",
+                    view.name,
+                    view.local,
+                    view.remote.as_deref().unwrap_or("missing")
+                ),
+            }
+        }
+    }
+
+    if problems > 0 {
+        return Err(error::conflict(format!(
+            "{} hostname(s) have conflicting DNS records. Remove them in the Cloudflare \
+             dashboard, or run `ytunnel restart <name>` to remove them after confirming.",
+            problems
+        )));
+    }
+    if unrouted > 0 {
+        anyhow::bail!(
+            "{} hostname(s) don't point at their tunnel. Run `ytunnel restart --verify-dns <name>` \
+             to repair them.",
+            unrouted
+        );
+    }
+    if disagreements > 0 {
+        anyhow::bail!(
+            "{} tunnel(s) disagree with Cloudflare's view. Run `ytunnel list --remote` for details.",
+            disagreements
+        );
+    }
+    if elsewhere > 0 {
+        println!(
+            "No DNS conflicts found; {} tunnel(s) stopped here are served from another machine",
+            elsewhere
+        );
         return Ok(());
     }
+    println!("No DNS conflicts found, and Cloudflare agrees with local state");
+    Ok(())
+}
+
+// Delete a DNS record that points at a Cloudflare tunnel
+async fn cmd_dns_delete(hostname: String, skip_confirm: bool, account: Option<&str>) -> Result<()> {
+    let hostname = idn::to_ascii(&hostname)?;
+    let cfg = config::load_config()?;
+    let acct = cfg.get_account(account)?;
+    let client = cfg.client(acct);
+
+    let zone = acct.zone_for_hostname(&hostname).ok_or_else(|| {
+        error::not_found(format!(
+            "'{}' is not in any zone for account '{}'. Run `ytunnel zones` to see available zones.",
+            hostname, acct.name
+        ))
+    })?;
+
+    let record = client
+        .list_dns_records(&zone.id, &cloudflare::DnsRecordFilter::cnames())
+        .await?
+        .into_iter()
+        .find(|r| r.name == hostname)
+        .ok_or_else(|| error::not_found(format!("No CNAME record found for '{}'", hostname)))?;
+
+    if !record.content.ends_with(".cfargotunnel.com") {
+        anyhow::bail!(
+            "'{}' points at {}, not a Cloudflare tunnel. Refusing to delete it.",
+            hostname,
+            record.content
+        );
+    }
+
+    // Warn when a managed tunnel still relies on this record
+    let state = TunnelState::load()?;
+    if let Some(t) = state
+        .tunnels
+        .iter()
+        .find(|t| t.dns_routes().iter().any(|(_, h)| *h == hostname))
+    {
+        println!(
+            "Warning: managed tunnel '{}' uses this hostname and will stop resolving.",
+            t.name
+        );
+    }
+
+    if !skip_confirm {
+        println!(
+            "Delete DNS record {} -> {}? [y/N]",
+            record.name, record.content
+        );
+        print!("> ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+
+        if input != "y" && input != "yes" {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
 
-    println!("Tunnels for account '{}':", account_name);
-    for tunnel in tunnels {
-        let status = daemon::get_daemon_status(tunnel).await;
-        let status_symbol = status.symbol();
-        let status_text = match status {
-            state::TunnelStatus::Running => "running",
-            state::TunnelStatus::Stopped => "stopped",
-            state::TunnelStatus::Error => "error",
-        };
-        println!(
-            "  {} {:<12} {} -> {} ({})",
-            status_symbol, tunnel.name, tunnel.hostname, tunnel.target, status_text
-        );
-    }
+    client.delete_dns_record_by_id(&zone.id, &record.id).await?;
+    println!("✓ Deleted DNS record: {}", hostname);
+
+    // The next start of a tunnel using the hostname must recreate the record
+    // instead of trusting its last check
+    TunnelState::update(|state| {
+        for t in &mut state.tunnels {
+            if t.dns_routes().iter().any(|(_, h)| *h == hostname) {
+                t.dns_verified = None;
+            }
+        }
+        Ok(())
+    })?;
 
     Ok(())
 }
 
-async fn cmd_delete(name: String, account: Option<&str>) -> Result<()> {
+async fn cmd_delete(
+    name: String,
+    skip_confirm: bool,
+    retry: bool,
+    keep_dns: bool,
+    account: Option<&str>,
+) -> Result<()> {
     let cfg = config::load_config()?;
-    let acct = cfg.get_account(account)?;
-    let account_name = acct.name.clone();
-    let client = cloudflare::Client::new(&acct.api_token);
 
-    // Handle both "name" and "ytunnel-name" formats
-    let name = name.strip_prefix("ytunnel-").unwrap_or(&name).to_string();
+    // Handle both "name" and "<tunnel_prefix>name" formats
+    let given = name;
+    let name = config::short_tunnel_name(&given)
+        .unwrap_or(&given)
+        .to_string();
 
-    // Get the tunnel's own account_name for daemon operations (handles legacy tunnels)
+    let mut report = teardown::Report::default();
     let state = TunnelState::load()?;
-    let tunnel_account = state
-        .find_for_account(&name, &account_name)
-        .map(|t| t.account_name.clone())
-        .unwrap_or_default();
-
-    // Stop and uninstall daemon
-    daemon::stop_daemon(&name, &tunnel_account).await.ok();
-    daemon::uninstall_daemon(&name, &tunnel_account).await.ok();
-
-    // Remove from state
-    let mut state = TunnelState::load()?;
-    if let Some(tunnel) = state.remove_for_account(&name, &account_name) {
-        // Delete the DNS CNAME record
-        client
-            .delete_dns_record(&tunnel.zone_id, &tunnel.hostname)
-            .await
-            .ok();
-        println!("✓ Deleted DNS record");
-
-        // Delete from Cloudflare
-        client
-            .delete_tunnel(&acct.account_id, &tunnel.tunnel_id)
-            .await
-            .ok();
-        println!("✓ Deleted Cloudflare tunnel");
-
-        // Remove credentials file
-        if let Ok(creds_path) = tunnel.credentials_path() {
-            std::fs::remove_file(&creds_path).ok();
-        }
-
-        // Remove config file
-        if let Ok(config_path) = tunnel.config_path() {
-            std::fs::remove_file(&config_path).ok();
+    let acct = account_for_tunnel(&cfg, &state, &name, account)?;
+    let account_name = acct.name.clone();
+    let client = cfg.client(acct);
+    let mut kept_hostnames = Vec::new();
+
+    if let Some(tunnel) = state.find_for_account(&name, &account_name).cloned() {
+        backup_before(&format!("delete {}", name))?;
+        if keep_dns {
+            kept_hostnames = tunnel
+                .dns_routes()
+                .into_iter()
+                .map(|(_, hostname)| hostname.to_string())
+                .collect();
         }
-
-        // Remove log file
-        if let Ok(log_path) = tunnel.log_path() {
-            std::fs::remove_file(&log_path).ok();
+        // Use the tunnel's own account_name for daemon operations (handles legacy tunnels)
+        let tunnel_account = tunnel.account_name.clone();
+        report
+            .teardown_tunnel(&tunnel, &tunnel_account, Some(acct), keep_dns)
+            .await;
+        TunnelState::update(|state| {
+            state.remove_for_account(&name, &account_name);
+            Ok(())
+        })?;
+    } else {
+        // Try deleting from Cloudflare directly (might be a tunnel created with `run`,
+        // or one named with an earlier tunnel_prefix and given in full)
+        let mut candidates = vec![config::cloudflare_tunnel_name(&name), given.clone()];
+        candidates.dedup();
+        let mut found = None;
+        for tunnel_name in candidates {
+            if let Some(t) = client
+                .get_tunnel_by_name(&acct.account_id, &tunnel_name)
+                .await?
+            {
+                found = Some((tunnel_name, t));
+                break;
+            }
         }
+        match found {
+            Some((tunnel_name, t)) => {
+                // Tunnel names are only unique per account, so say which one this is
+                println!(
+                    "'{}' isn't managed by ytunnel; found Cloudflare tunnel {} ({}) in account '{}' ({}).",
+                    name, tunnel_name, t.id, account_name, acct.account_id
+                );
+                if !skip_confirm {
+                    println!("Delete it from this account? [y/N]");
+                    print!("> ");
+                    std::io::Write::flush(&mut std::io::stdout())?;
+
+                    let mut input = String::new();
+                    std::io::stdin().read_line(&mut input)?;
+                    let input = input.trim().to_lowercase();
+
+                    if input != "y" && input != "yes" {
+                        println!("Cancelled.");
+                        return Ok(());
+                    }
+                }
 
-        state.save()?;
-        println!("✓ Deleted tunnel: {}", name);
-    } else {
-        // Try deleting from Cloudflare directly (might be a tunnel created with `run`)
-        let tunnel_name = format!("ytunnel-{}", name);
-        match client
-            .get_tunnel_by_name(&acct.account_id, &tunnel_name)
-            .await?
-        {
-            Some(t) => {
+                report
+                    .run(teardown::Step::DeleteTunnel {
+                        creds: Some((acct.credentials(), acct.account_id.clone())),
+                        tunnel_id: t.id.clone(),
+                    })
+                    .await;
                 // Delete credentials file if it exists
                 if let Ok(creds_path) = t.credentials_path() {
-                    std::fs::remove_file(&creds_path).ok();
+                    report.run(teardown::Step::RemoveFile(creds_path)).await;
                 }
-                client.delete_tunnel(&acct.account_id, &t.id).await?;
-                println!("✓ Deleted Cloudflare tunnel: {}", tunnel_name);
-            }
-            None => {
-                println!(
-                    "Tunnel '{}' not found for account '{}'.",
-                    name, account_name
-                );
             }
+            None => return Err(tunnel_not_found(&cfg, &state, &name, &account_name)),
         }
     }
 
+    if retry {
+        report.retry_failures().await;
+    }
+    report.print_table();
+
+    let remote_failed = report.has_remote_failures();
+    report.into_result()?;
+    if !remote_failed {
+        println!("\n✓ Deleted tunnel: {}", name);
+    }
+    if !kept_hostnames.is_empty() {
+        println!(
+            "\nWarning: kept DNS records for {}. They point at the deleted tunnel and will return \
+             530 errors until repointed (or removed with `ytunnel dns delete`).",
+            kept_hostnames.join(", ")
+        );
+    }
+
     Ok(())
 }
 
 // Reset ytunnel configuration (allows re-initialization)
-async fn cmd_reset(skip_confirm: bool) -> Result<()> {
+async fn cmd_reset(skip_confirm: bool, retry: bool) -> Result<()> {
     // Check if ytunnel is even configured
     if !config::config_path()?.exists() {
         println!("ytunnel is not configured. Nothing to reset.");
@@ -906,11 +4192,12 @@ async fn cmd_reset(skip_confirm: bool) -> Result<()> {
     // Confirmation prompt unless -y flag
     if !skip_confirm {
         println!("This will:");
-        println!("  - Stop all running tunnels");
+        println!("  - Stop all running tunnels for ALL accounts");
         println!("  - Remove all tunnel configurations");
         println!("  - Delete tunnels from Cloudflare");
         println!("  - Remove ytunnel configuration");
         println!();
+        println!("To reset a single account instead, use `ytunnel reset --account <name>`.");
         println!("Are you sure? [y/N] ");
 
         let mut input = String::new();
@@ -923,6 +4210,7 @@ async fn cmd_reset(skip_confirm: bool) -> Result<()> {
         }
     }
 
+    backup_before("reset")?;
     println!("Resetting ytunnel...\n");
 
     // Load config for Cloudflare API access
@@ -931,71 +4219,57 @@ async fn cmd_reset(skip_confirm: bool) -> Result<()> {
     // Load state to get all tunnels
     let state = TunnelState::load().unwrap_or_default();
 
-    // Stop and clean up all tunnels
-    for tunnel in &state.tunnels {
-        print!("Removing tunnel '{}'... ", tunnel.name);
-
-        // Stop daemon (use tunnel's account_name, fallback to default for migrated tunnels)
-        let acct_name = if tunnel.account_name.is_empty() {
-            cfg.as_ref()
-                .map(|c| c.selected_account.clone())
-                .unwrap_or_default()
-        } else {
-            tunnel.account_name.clone()
-        };
-        daemon::stop_daemon(&tunnel.name, &acct_name).await.ok();
-
-        // Uninstall daemon
-        daemon::uninstall_daemon(&tunnel.name, &acct_name)
-            .await
-            .ok();
-
-        // Delete from Cloudflare - find the right account
-        if let Some(cfg) = &cfg {
-            let acct = if tunnel.account_name.is_empty() {
-                cfg.get_account(None).ok()
+    // Stop and clean up all tunnels, a few at a time
+    let mut report = teardown::Report::default();
+    let tunnels = state
+        .tunnels
+        .iter()
+        .map(|tunnel| {
+            // Use tunnel's account_name, fallback to default for migrated tunnels
+            let acct = cfg.as_ref().and_then(|cfg| {
+                if tunnel.account_name.is_empty() {
+                    cfg.get_account(None).ok()
+                } else {
+                    cfg.accounts.iter().find(|a| a.name == tunnel.account_name)
+                }
+            });
+            let acct_name = if tunnel.account_name.is_empty() {
+                cfg.as_ref()
+                    .map(|c| c.selected_account.clone())
+                    .unwrap_or_default()
             } else {
-                cfg.accounts.iter().find(|a| a.name == tunnel.account_name)
+                tunnel.account_name.clone()
             };
-            if let Some(acct) = acct {
-                let client = cloudflare::Client::new(&acct.api_token);
-                client
-                    .delete_tunnel(&acct.account_id, &tunnel.tunnel_id)
-                    .await
-                    .ok();
+            teardown::Teardown {
+                tunnel: tunnel.clone(),
+                daemon_account: acct_name,
+                account: acct.cloned(),
             }
-        }
-
-        // Remove credentials file
-        if let Ok(creds_path) = tunnel.credentials_path() {
-            std::fs::remove_file(&creds_path).ok();
-        }
-
-        // Remove config file
-        if let Ok(config_path) = tunnel.config_path() {
-            std::fs::remove_file(&config_path).ok();
-        }
-
-        // Remove log file
-        if let Ok(log_path) = tunnel.log_path() {
-            std::fs::remove_file(&log_path).ok();
-        }
+        })
+        .collect();
+    report.teardown_tunnels(tunnels).await;
 
-        println!("done");
+    if retry {
+        report.retry_failures().await;
     }
 
     // Remove tunnels.toml
+    let _lock = config::lock()?;
     if let Ok(tunnels_path) = state::tunnels_path() {
-        std::fs::remove_file(&tunnels_path).ok();
+        report.run(teardown::Step::RemoveFile(tunnels_path)).await;
     }
 
     // Remove config.toml
     if let Ok(config_path) = config::config_path() {
-        std::fs::remove_file(&config_path).ok();
+        report.run(teardown::Step::RemoveFile(config_path)).await;
     }
 
     // Clean up empty directories
     if let Ok(config_dir) = config::config_dir() {
+        for dir in ["tunnel-configs", "logs"] {
+            remove_empty_account_dirs(&config_dir.join(dir));
+        }
+
         // Remove tunnel-configs directory if empty
         let tunnel_configs_dir = config_dir.join("tunnel-configs");
         std::fs::remove_dir(&tunnel_configs_dir).ok();
@@ -1009,16 +4283,199 @@ async fn cmd_reset(skip_confirm: bool) -> Result<()> {
         std::fs::remove_dir(&logs_dir).ok();
     }
 
+    report.print_table();
+
     println!("\n✓ ytunnel has been reset.");
     println!("Run `ytunnel init` to set up with new credentials.");
 
-    Ok(())
+    report.into_result()
+}
+
+// Reset a single account: tear down its tunnels, clear its cached files, and
+// remove it from config, leaving other accounts untouched
+async fn cmd_reset_account(name: &str, skip_confirm: bool, retry: bool) -> Result<()> {
+    let cfg = config::load_config()?;
+    let acct = cfg
+        .accounts
+        .iter()
+        .find(|a| a.name == name)
+        .cloned()
+        .ok_or_else(|| {
+            error::not_found(format!(
+                "Account '{}' not found. Run `ytunnel account list` to see available accounts.",
+                name
+            ))
+        })?;
+
+    let state = TunnelState::load()?;
+    let tunnels: Vec<PersistentTunnel> = state
+        .tunnels_for_account(name)
+        .into_iter()
+        .cloned()
+        .collect();
+
+    // Confirmation prompt unless -y flag
+    if !skip_confirm {
+        println!("This will, for account '{}' only:", name);
+        println!(
+            "  - Stop and delete its {} tunnel(s) (locally and on Cloudflare)",
+            tunnels.len()
+        );
+        println!("  - Remove its cached tunnel configs and logs");
+        println!("  - Remove the account from ytunnel configuration");
+        println!();
+        println!("Are you sure? [y/N]");
+        print!("> ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+
+        if input != "y" && input != "yes" {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    backup_before(&format!("reset --account {}", name))?;
+    println!("Resetting account '{}'...\n", name);
+
+    let mut report = teardown::Report::default();
+    let names: Vec<String> = tunnels.iter().map(|t| t.name.clone()).collect();
+    report
+        .teardown_tunnels(
+            tunnels
+                .into_iter()
+                .map(|tunnel| teardown::Teardown {
+                    daemon_account: tunnel.account_name.clone(),
+                    tunnel,
+                    account: Some(acct.clone()),
+                })
+                .collect(),
+        )
+        .await;
+    TunnelState::update(|state| {
+        for tunnel_name in &names {
+            state.remove_for_account(tunnel_name, name);
+        }
+        Ok(())
+    })?;
+
+    if retry {
+        report.retry_failures().await;
+    }
+
+    // Clear anything left in the account's cached directories
+    if let Ok(config_dir) = config::config_dir() {
+        for dir in ["tunnel-configs", "logs"] {
+            let path = config_dir.join(dir).join(name);
+            if path.exists() {
+                report.run(teardown::Step::RemoveDir(path)).await;
+            }
+        }
+    }
+
+    // Remove the account (the whole config if it was the only one)
+    {
+        let _lock = config::lock()?;
+        let mut cfg = config::load_config()?;
+        cfg.remove_account(name)?;
+        if cfg.accounts.is_empty() {
+            teardown::remove_file_if_exists(&config::config_path()?)?;
+        } else {
+            config::save_config(&cfg)?;
+        }
+    }
+
+    report.print_table();
+
+    println!("\n✓ Account '{}' has been reset.", name);
+    if cfg.accounts.is_empty() {
+        println!("No accounts left. Run `ytunnel init` to set up with new credentials.");
+    } else {
+        println!("Default account is now: {}", cfg.selected_account);
+    }
+
+    report.into_result()
+}
+
+// Remove any empty per-account subdirectories under dir
+fn remove_empty_account_dirs(dir: &std::path::Path) {
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                std::fs::remove_dir(entry.path()).ok();
+            }
+        }
+    }
+}
+
+// An account as printed by `ytunnel account list --output json` (never the credentials)
+#[derive(serde::Serialize)]
+struct AccountListing<'a> {
+    name: &'a str,
+    default: bool,
+    // "token" or "api_key"
+    auth: &'static str,
+    zones: Vec<AccountZone<'a>>,
+}
+
+#[derive(serde::Serialize)]
+struct AccountZone<'a> {
+    name: &'a str,
+    id: &'a str,
+    default: bool,
 }
 
 // List all configured accounts
-async fn cmd_account_list() -> Result<()> {
+async fn cmd_account_list(output: output::Format) -> Result<()> {
     let cfg = config::load_config()?;
 
+    let listings: Vec<AccountListing> = cfg
+        .accounts
+        .iter()
+        .map(|acct| AccountListing {
+            name: &acct.name,
+            default: acct.name == cfg.selected_account,
+            auth: if acct.uses_api_key() {
+                "api_key"
+            } else {
+                "token"
+            },
+            zones: acct
+                .zones
+                .iter()
+                .map(|zone| AccountZone {
+                    name: &zone.name,
+                    id: &zone.id,
+                    default: zone.id == acct.default_zone_id,
+                })
+                .collect(),
+        })
+        .collect();
+    match output {
+        output::Format::Json | output::Format::Yaml => {
+            return output::print_structured(output, &listings)
+        }
+        output::Format::Plain => {
+            // name, default, auth, zones (comma-separated)
+            let mut table = table::Table::new(["NAME", "DEFAULT", "AUTH", "ZONES"]);
+            for listing in &listings {
+                let zones: Vec<&str> = listing.zones.iter().map(|z| z.name).collect();
+                table.add_row(vec![
+                    listing.name.into(),
+                    if listing.default { "yes" } else { "no" }.into(),
+                    listing.auth.into(),
+                    zones.join(",").into(),
+                ]);
+            }
+            output::print_table(output, &table, table::Style::plain());
+            return Ok(());
+        }
+        output::Format::Table => {}
+    }
+
     if cfg.accounts.is_empty() {
         println!("No accounts configured.");
         println!("Run `ytunnel init` to add an account.");
@@ -1032,14 +4489,25 @@ async fn cmd_account_list() -> Result<()> {
         } else {
             ""
         };
-        println!("  {} - {} zones{}", acct.name, acct.zones.len(), marker);
+        let auth = if acct.uses_api_key() {
+            " [Global API Key]"
+        } else {
+            ""
+        };
+        println!(
+            "  {} - {} zones{}{}",
+            acct.name,
+            acct.zones.len(),
+            marker,
+            auth
+        );
         for zone in &acct.zones {
             let zone_marker = if zone.id == acct.default_zone_id {
                 " (default)"
             } else {
                 ""
             };
-            println!("      - {}{}", zone.name, zone_marker);
+            println!("      - {}{}", idn::to_unicode(&zone.name), zone_marker);
         }
     }
 
@@ -1048,23 +4516,199 @@ async fn cmd_account_list() -> Result<()> {
 
 // Set the default account
 async fn cmd_account_select(name: String) -> Result<()> {
-    let mut cfg = config::load_config()?;
-    cfg.select_account(&name)?;
-    config::save_config(&cfg)?;
+    config::update_config(|cfg| cfg.select_account(&name))?;
     println!("Default account set to: {}", name);
     Ok(())
 }
 
-// Remove an account
-async fn cmd_account_remove(name: String, skip_confirm: bool) -> Result<()> {
+// Choose the default account from a numbered menu, or by name
+async fn cmd_account_switch(name: Option<String>) -> Result<()> {
+    use std::io::IsTerminal;
+
+    if let Some(name) = name {
+        return cmd_account_select(name).await;
+    }
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "No terminal to show the account menu. Pass the account name: `ytunnel account switch <name>`"
+        );
+    }
+
+    let cfg = config::load_config()?;
+    if cfg.accounts.is_empty() {
+        anyhow::bail!("No accounts configured. Run `ytunnel init` first.");
+    }
+    println!("Accounts:");
+    for (i, acct) in cfg.accounts.iter().enumerate() {
+        let marker = if acct.name == cfg.selected_account {
+            "*"
+        } else {
+            " "
+        };
+        println!("{} {}. {}", marker, i + 1, acct.name);
+    }
+    let input = prompt_line(&format!(
+        "Switch to which account? [1-{}, or name; Enter keeps '{}']",
+        cfg.accounts.len(),
+        cfg.selected_account
+    ))?;
+    if input.is_empty() {
+        println!("Default account unchanged: {}", cfg.selected_account);
+        return Ok(());
+    }
+
+    // A name wins over a menu number, for accounts named like one
+    let name = match input.parse::<usize>() {
+        Ok(_) if cfg.accounts.iter().any(|a| a.name == input) => input,
+        Ok(i) => cfg
+            .accounts
+            .get(i.wrapping_sub(1))
+            .map(|a| a.name.clone())
+            .context("Invalid selection")?,
+        Err(_) => input,
+    };
+    if name == cfg.selected_account {
+        println!("Default account unchanged: {}", name);
+        return Ok(());
+    }
+    cmd_account_select(name).await
+}
+
+// Environment variables `ytunnel account token` reads the new credentials from
+const TOKEN_ENV: &str = "CLOUDFLARE_API_TOKEN";
+const API_KEY_ENV: &str = "CLOUDFLARE_API_KEY";
+
+// Replace an account's credentials after verifying them with Cloudflare
+async fn cmd_account_token(name: String, token: Option<String>, force: bool) -> Result<()> {
     let mut cfg = config::load_config()?;
+    let acct = cfg.get_account(Some(&name))?;
+
+    // --token, then the environment, then a prompt
+    let supplied = |env: &str| {
+        token
+            .clone()
+            .or_else(|| std::env::var(env).ok())
+            .map(|s| s.trim().to_string())
+    };
+    let credentials = match (acct.uses_api_key(), acct.email.clone()) {
+        (true, Some(email)) => {
+            let key = match supplied(API_KEY_ENV) {
+                Some(key) => key,
+                None => read_secret(&format!(
+                    "Enter the new Global API Key for account '{}' ({}):",
+                    name, email
+                ))?,
+            };
+            if key.is_empty() {
+                anyhow::bail!("API key cannot be empty");
+            }
+            cloudflare::Credentials::ApiKey { key, email }
+        }
+        _ => {
+            let token = match supplied(TOKEN_ENV) {
+                Some(token) => token,
+                None => read_secret(&format!("Enter the new API token for account '{}':", name))?,
+            };
+            if token.is_empty() {
+                anyhow::bail!("API token cannot be empty");
+            }
+            cloudflare::Credentials::Token(token)
+        }
+    };
+
+    println!("Verifying...");
+    let update = cfg.update_credentials(&name, credentials, force).await?;
+    // Only this account changed; write it into a fresh copy of the config
+    let updated = cfg.get_account(Some(&name))?.clone();
+    config::update_config(|latest| {
+        *latest.get_account_mut(Some(&name))? = updated;
+        Ok(())
+    })?;
+
+    if let Some(previous) = &update.previous_account {
+        println!(
+            "Warning: account '{}' now uses Cloudflare account {} (was {}); \
+             tunnels created under {} can no longer be managed from it.",
+            name,
+            cfg.get_account(Some(&name))?.account_id,
+            previous,
+            previous
+        );
+    }
+    println!("✓ Updated credentials for account '{}'", name);
+    for zone in &update.zones_added {
+        println!("  + zone {}", zone);
+    }
+    for zone in &update.zones_removed {
+        println!(
+            "  - zone {} (no longer visible to the new credentials)",
+            zone
+        );
+    }
+    Ok(())
+}
+
+// Read a secret without echoing it: masked when stdin is a terminal, otherwise one line
+// from stdin so scripts can pipe it in
+fn read_secret(prompt: &str) -> Result<String> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use std::io::{IsTerminal, Write};
+
+    if !std::io::stdin().is_terminal() {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        return Ok(line.trim().to_string());
+    }
+
+    println!("{}", prompt);
+    print!("> ");
+    std::io::stdout().flush()?;
+
+    crossterm::terminal::enable_raw_mode()?;
+    let mut secret = String::new();
+    let result = loop {
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => key,
+            Ok(Event::Paste(text)) => {
+                secret.push_str(&text);
+                print!("{}", "*".repeat(text.chars().count()));
+                std::io::stdout().flush().ok();
+                continue;
+            }
+            Ok(_) => continue,
+            Err(e) => break Err(e.into()),
+        };
+        match key.code {
+            KeyCode::Enter => break Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                break Err(anyhow::anyhow!("Cancelled"))
+            }
+            KeyCode::Esc => break Err(anyhow::anyhow!("Cancelled")),
+            KeyCode::Backspace if secret.pop().is_some() => print!("\u{8} \u{8}"),
+            KeyCode::Char(c) => {
+                secret.push(c);
+                print!("*");
+            }
+            _ => {}
+        }
+        std::io::stdout().flush().ok();
+    };
+    crossterm::terminal::disable_raw_mode()?;
+    println!();
+
+    result.map(|()| secret.trim().to_string())
+}
+
+// Remove an account
+async fn cmd_account_remove(name: String, skip_confirm: bool, retry: bool) -> Result<()> {
+    let cfg = config::load_config()?;
 
     // Check if account exists
     if !cfg.accounts.iter().any(|a| a.name == name) {
-        anyhow::bail!(
+        return Err(error::not_found(format!(
             "Account '{}' not found. Run `ytunnel account list` to see available accounts.",
             name
-        );
+        )));
     }
 
     // Check if this is the last account
@@ -1100,11 +4744,12 @@ async fn cmd_account_remove(name: String, skip_confirm: bool) -> Result<()> {
         }
     }
 
+    backup_before(&format!("account remove {}", name))?;
+
     // Remove tunnels for this account
+    let mut report = teardown::Report::default();
     if tunnel_count > 0 {
         let acct = cfg.accounts.iter().find(|a| a.name == name).unwrap();
-        let client = cloudflare::Client::new(&acct.api_token);
-        let mut state = TunnelState::load()?;
 
         // Collect tunnels to remove
         let tunnels_to_remove: Vec<_> = state
@@ -1113,50 +4758,140 @@ async fn cmd_account_remove(name: String, skip_confirm: bool) -> Result<()> {
             .filter(|t| t.account_name == name)
             .cloned()
             .collect();
+        let tunnel_names: Vec<String> = tunnels_to_remove.iter().map(|t| t.name.clone()).collect();
+
+        report
+            .teardown_tunnels(
+                tunnels_to_remove
+                    .into_iter()
+                    .map(|tunnel| teardown::Teardown {
+                        tunnel,
+                        daemon_account: name.clone(),
+                        account: Some(acct.clone()),
+                    })
+                    .collect(),
+            )
+            .await;
 
-        for tunnel in tunnels_to_remove {
-            print!("Removing tunnel '{}'... ", tunnel.name);
+        TunnelState::update(|state| {
+            for tunnel_name in &tunnel_names {
+                state.remove_for_account(tunnel_name, &name);
+            }
+            Ok(())
+        })?;
+
+        if retry {
+            report.retry_failures().await;
+        }
+        report.print_table();
+    }
 
-            // Stop and uninstall daemon
-            daemon::stop_daemon(&tunnel.name, &name).await.ok();
-            daemon::uninstall_daemon(&tunnel.name, &name).await.ok();
+    // Remove the account
+    let selected = config::update_config(|cfg| {
+        cfg.remove_account(&name)?;
+        Ok(cfg.selected_account.clone())
+    })?;
 
-            // Delete from Cloudflare
-            client
-                .delete_tunnel(&acct.account_id, &tunnel.tunnel_id)
-                .await
-                .ok();
+    println!("✓ Removed account: {}", name);
+    println!("Default account is now: {}", selected);
 
-            // Remove credentials file
-            if let Ok(creds_path) = tunnel.credentials_path() {
-                std::fs::remove_file(&creds_path).ok();
-            }
+    report.into_result()
+}
 
-            // Remove config file
-            if let Ok(config_path) = tunnel.config_path() {
-                std::fs::remove_file(&config_path).ok();
-            }
+// Back up config.toml and tunnels.toml before a destructive command changes them
+// (once per invocation)
+fn backup_before(reason: &str) -> Result<()> {
+    if let Some(backup) = backup::snapshot(reason)? {
+        println!(
+            "Backed up configuration to {} (undo with `ytunnel backup restore {}`)",
+            backup.path.display(),
+            backup.id
+        );
+    }
+    Ok(())
+}
 
-            // Remove log file
-            if let Ok(log_path) = tunnel.log_path() {
-                std::fs::remove_file(&log_path).ok();
-            }
+fn cmd_backup_list(no_color: bool) -> Result<()> {
+    let backups = backup::list()?;
+    if backups.is_empty() {
+        println!(
+            "No backups yet. They are taken before reset, account removal, delete, and migrations."
+        );
+        return Ok(());
+    }
 
-            // Remove from state
-            state.remove_for_account(&tunnel.name, &name);
+    let style = table::Style::detect(no_color);
+    let mut list = table::Table::new(["TIMESTAMP", "REASON", "FILES"]);
+    for b in &backups {
+        list.add_row(vec![
+            b.id.as_str().into(),
+            b.reason.as_str().into(),
+            b.files.join(", ").into(),
+        ]);
+    }
+    println!(
+        "Backups in {} (newest first, last {} kept):",
+        backup::backups_dir()?.display(),
+        backup::KEEP_BACKUPS
+    );
+    list.print(style);
+    Ok(())
+}
 
-            println!("done");
-        }
+async fn cmd_backup_restore(id: String, skip_confirm: bool, force: bool) -> Result<()> {
+    let target = backup::find(&id)?;
 
-        state.save()?;
+    // Daemons keep running with the old settings, so make the user stop them first
+    let state = TunnelState::load().unwrap_or_default();
+    let mut running = Vec::new();
+    for t in &state.tunnels {
+        if daemon::is_daemon_running(&t.name, &t.account_name).await {
+            running.push(t.name.clone());
+        }
+    }
+    if !running.is_empty() && !force {
+        return Err(error::conflict(format!(
+            "{} tunnel(s) are running ({}). Stop them first, or pass --force to restore anyway.",
+            running.len(),
+            running.join(", ")
+        )));
     }
 
-    // Remove the account
-    cfg.remove_account(&name)?;
-    config::save_config(&cfg)?;
+    if !skip_confirm {
+        println!(
+            "Replace the current configuration with backup {} ({})?",
+            target.id, target.reason
+        );
+        println!("  Restores: {}", target.files.join(", "));
+        println!("The current files are backed up first.");
+        println!("Are you sure? [y/N]");
+        print!("> ");
+        std::io::Write::flush(&mut std::io::stdout())?;
 
-    println!("✓ Removed account: {}", name);
-    println!("Default account is now: {}", cfg.selected_account);
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+
+        if input != "y" && input != "yes" {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
 
+    let previous = backup::restore(&target)?;
+    println!(
+        "✓ Restored {} from backup {}",
+        target.files.join(", "),
+        target.id
+    );
+    if let Some(previous) = previous {
+        println!(
+            "  Previous files saved as backup {} (`ytunnel backup restore {}` to undo)",
+            previous.id, previous.id
+        );
+    }
+    if !running.is_empty() {
+        println!("Restart running tunnels so they pick up the restored settings.");
+    }
     Ok(())
 }
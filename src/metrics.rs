@@ -1,5 +1,5 @@
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 // Metrics collected from cloudflared's Prometheus endpoint
@@ -15,34 +15,158 @@ pub struct TunnelMetrics {
     pub concurrent_requests: u64,
     // Response counts by status code
     pub response_codes: HashMap<u16, u64>,
-    // Connected edge locations (e.g., "dfw08", "den01")
-    pub edge_locations: Vec<String>,
+    // Bytes sent to and received from the edge since cloudflared started, summed
+    // over its QUIC connections
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    // Connected edge locations with their connection counts, busiest first
+    pub edge_locations: Vec<EdgeLocation>,
     // Whether metrics were successfully fetched
     pub available: bool,
 }
 
+// A Cloudflare edge location (colo) and how many tunnel connections go to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeLocation {
+    // Colo code, normalized to lowercase (e.g., "dfw08")
+    pub name: String,
+    pub connections: u64,
+}
+
 impl TunnelMetrics {
-    // Fetch metrics from a cloudflared metrics endpoint
-    pub async fn fetch(metrics_url: &str) -> Self {
-        fetch_metrics_internal(metrics_url)
+    // Fetch metrics from a cloudflared metrics endpoint (unavailable without one,
+    // when [daemon] metrics_bind is off)
+    pub async fn fetch(metrics_url: Option<&str>, timeout: Duration) -> Self {
+        let Some(metrics_url) = metrics_url else {
+            return Self::default();
+        };
+        fetch_metrics_internal(metrics_url, timeout)
             .await
             .unwrap_or_default()
     }
 
-    // Get the list of edge locations as a string
+    // Parse metrics from Prometheus text already scraped from cloudflared
+    pub fn parse(text: &str) -> Self {
+        parse_prometheus_metrics(text)
+    }
+
+    // Bytes moved either way since cloudflared started
+    pub fn bytes_total(&self) -> u64 {
+        self.bytes_sent.saturating_add(self.bytes_received)
+    }
+
+    // Get the list of edge locations as a string, e.g. "dfw08 (2), den01 (1)"
     pub fn locations_string(&self) -> String {
         if self.edge_locations.is_empty() {
             "None".to_string()
         } else {
-            self.edge_locations.join(", ")
+            self.edge_locations
+                .iter()
+                .map(|loc| format!("{} ({})", loc.name, loc.connections))
+                .collect::<Vec<_>>()
+                .join(", ")
         }
     }
+
+    // Prometheus text exposition of the parsed metrics, as pushed to a Pushgateway.
+    // Names match cloudflared's own; edge locations become a connection count each.
+    pub fn to_exposition(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, samples: Vec<(String, u64)>| {
+            out.push_str(&format!("# TYPE {} {}\n", name, kind));
+            for (labels, value) in samples {
+                out.push_str(&format!("{}{} {}\n", name, labels, value));
+            }
+        };
+        metric(
+            "cloudflared_tunnel_total_requests",
+            "counter",
+            vec![(String::new(), self.total_requests)],
+        );
+        metric(
+            "cloudflared_tunnel_request_errors",
+            "counter",
+            vec![(String::new(), self.request_errors)],
+        );
+        metric(
+            "cloudflared_tunnel_ha_connections",
+            "gauge",
+            vec![(String::new(), self.ha_connections)],
+        );
+        metric(
+            "cloudflared_tunnel_concurrent_requests_per_tunnel",
+            "gauge",
+            vec![(String::new(), self.concurrent_requests)],
+        );
+        let mut codes: Vec<_> = self.response_codes.iter().collect();
+        codes.sort();
+        metric(
+            "cloudflared_tunnel_response_by_code",
+            "counter",
+            codes
+                .into_iter()
+                .map(|(code, count)| (format!("{{status_code=\"{}\"}}", code), *count))
+                .collect(),
+        );
+        metric(
+            "ytunnel_edge_location_connections",
+            "gauge",
+            self.edge_locations
+                .iter()
+                .map(|loc| {
+                    (
+                        format!("{{edge_location=\"{}\"}}", loc.name),
+                        loc.connections,
+                    )
+                })
+                .collect(),
+        );
+        out
+    }
+}
+
+// Group the pushed metrics are filed under in a Pushgateway
+pub const PUSH_JOB: &str = "ytunnel";
+
+// Pushgateway URL for one tunnel's group: <gateway>/metrics/job/ytunnel/tunnel/<name>/account/<account>
+pub fn pushgateway_url(gateway: &str, tunnel: &str, account: &str) -> Result<reqwest::Url> {
+    let mut url = reqwest::Url::parse(gateway)
+        .map_err(|e| anyhow::anyhow!("Invalid Pushgateway URL '{}': {}", gateway, e))?;
+    url.path_segments_mut()
+        .map_err(|_| anyhow::anyhow!("Invalid Pushgateway URL '{}'", gateway))?
+        .pop_if_empty()
+        .extend([
+            "metrics", "job", PUSH_JOB, "tunnel", tunnel, "account", account,
+        ]);
+    Ok(url)
+}
+
+// Push a tunnel's metrics to a Pushgateway, replacing what was pushed for it before
+pub async fn push(
+    gateway: &str,
+    tunnel: &str,
+    account: &str,
+    metrics: &TunnelMetrics,
+    timeout: Duration,
+) -> Result<()> {
+    let url = pushgateway_url(gateway, tunnel, account)?;
+    let client = reqwest::Client::builder().timeout(timeout).build()?;
+    let response = client
+        .post(url)
+        .header("Content-Type", "text/plain; version=0.0.4")
+        .body(metrics.to_exposition())
+        .send()
+        .await?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Pushgateway answered {}: {}", status, body.trim());
+    }
+    Ok(())
 }
 
-async fn fetch_metrics_internal(metrics_url: &str) -> Result<TunnelMetrics> {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(2))
-        .build()?;
+async fn fetch_metrics_internal(metrics_url: &str, timeout: Duration) -> Result<TunnelMetrics> {
+    let client = reqwest::Client::builder().timeout(timeout).build()?;
 
     let response = client.get(metrics_url).send().await?;
     let text = response.text().await?;
@@ -56,6 +180,8 @@ fn parse_prometheus_metrics(text: &str) -> TunnelMetrics {
         available: true,
         ..Default::default()
     };
+    // Connection ids seen per edge location, so repeated series don't double count
+    let mut locations: HashMap<String, HashSet<String>> = HashMap::new();
 
     for line in text.lines() {
         // Skip comments and empty lines
@@ -93,18 +219,42 @@ fn parse_prometheus_metrics(text: &str) -> TunnelMetrics {
                 metrics.response_codes.insert(code, count as u64);
             }
         }
+        // Parse quic_client_sent_bytes{conn_index="0"} 1234 (one series per connection)
+        else if line.starts_with("quic_client_sent_bytes") {
+            if let Some(value) = extract_value(line) {
+                metrics.bytes_sent += value as u64;
+            }
+        } else if line.starts_with("quic_client_receive_bytes") {
+            if let Some(value) = extract_value(line) {
+                metrics.bytes_received += value as u64;
+            }
+        }
         // Parse cloudflared_tunnel_server_locations{connection_id="0",edge_location="dfw08"} 1
         else if line.starts_with("cloudflared_tunnel_server_locations{") {
-            if let Some(location) = extract_edge_location(line) {
-                if !metrics.edge_locations.contains(&location) {
-                    metrics.edge_locations.push(location);
+            if let Some(location) = extract_label(line, "edge_location") {
+                let location = location.trim().to_lowercase();
+                if location.is_empty() {
+                    continue;
                 }
+                let connection_id = extract_label(line, "connection_id").unwrap_or_default();
+                locations.entry(location).or_default().insert(connection_id);
             }
         }
     }
 
-    // Sort edge locations for consistent display
-    metrics.edge_locations.sort();
+    metrics.edge_locations = locations
+        .into_iter()
+        .map(|(name, connections)| EdgeLocation {
+            name,
+            connections: connections.len() as u64,
+        })
+        .collect();
+    // Busiest locations first, then by name for consistent display
+    metrics.edge_locations.sort_by(|a, b| {
+        b.connections
+            .cmp(&a.connections)
+            .then_with(|| a.name.cmp(&b.name))
+    });
 
     metrics
 }
@@ -123,12 +273,14 @@ fn extract_status_code(line: &str) -> Option<u16> {
     line[start..end].parse().ok()
 }
 
-// Extract edge location from server_locations metric
-fn extract_edge_location(line: &str) -> Option<String> {
+// Extract a label value from a metric line
+fn extract_label(line: &str, label: &str) -> Option<String> {
     // Format: cloudflared_tunnel_server_locations{connection_id="0",edge_location="dfw08"} 1
-    let start = line.find("edge_location=\"")? + 15;
-    let end = line[start..].find('"')? + start;
-    Some(line[start..end].to_string())
+    let labels = &line[line.find('{')? + 1..line.find('}')?];
+    labels.split(',').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key.trim() == label).then(|| value.trim().trim_matches('"').to_string())
+    })
 }
 
 #[cfg(test)]
@@ -148,16 +300,56 @@ cloudflared_tunnel_response_by_code{status_code="200"} 35
 cloudflared_tunnel_response_by_code{status_code="404"} 5
 cloudflared_tunnel_server_locations{connection_id="0",edge_location="dfw08"} 1
 cloudflared_tunnel_server_locations{connection_id="1",edge_location="den01"} 1
+quic_client_sent_bytes{conn_index="0"} 1000
+quic_client_sent_bytes{conn_index="1"} 500
+quic_client_receive_bytes{conn_index="0"} 2.5e+03
 "#;
 
         let metrics = parse_prometheus_metrics(text);
         assert!(metrics.available);
+        assert_eq!((metrics.bytes_sent, metrics.bytes_received), (1500, 2500));
+        assert_eq!(metrics.bytes_total(), 4000);
         assert_eq!(metrics.total_requests, 42);
         assert_eq!(metrics.request_errors, 2);
         assert_eq!(metrics.ha_connections, 4);
         assert_eq!(metrics.concurrent_requests, 1);
         assert_eq!(metrics.response_codes.get(&200), Some(&35));
         assert_eq!(metrics.response_codes.get(&404), Some(&5));
-        assert_eq!(metrics.edge_locations, vec!["den01", "dfw08"]);
+        assert_eq!(
+            metrics.edge_locations,
+            vec![
+                EdgeLocation {
+                    name: "den01".to_string(),
+                    connections: 1
+                },
+                EdgeLocation {
+                    name: "dfw08".to_string(),
+                    connections: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_edge_location_connection_counts() {
+        let text = r#"
+cloudflared_tunnel_server_locations{connection_id="0",edge_location="dfw08"} 1
+cloudflared_tunnel_server_locations{connection_id="1",edge_location="den01"} 1
+cloudflared_tunnel_server_locations{connection_id="2",edge_location="DFW08"} 1
+cloudflared_tunnel_server_locations{connection_id="2",edge_location="dfw08"} 1
+cloudflared_tunnel_server_locations{connection_id="3",edge_location="iad02"} 1
+"#;
+
+        let metrics = parse_prometheus_metrics(text);
+        let counts: Vec<_> = metrics
+            .edge_locations
+            .iter()
+            .map(|loc| (loc.name.as_str(), loc.connections))
+            .collect();
+        assert_eq!(counts, vec![("dfw08", 2), ("den01", 1), ("iad02", 1)]);
+        assert_eq!(
+            metrics.locations_string(),
+            "dfw08 (2), den01 (1), iad02 (1)"
+        );
     }
 }
@@ -0,0 +1,189 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::table;
+
+// Values accepted by --output
+pub const FORMATS: [&str; 4] = ["table", "json", "yaml", "plain"];
+
+// How list-style commands print their results (--output)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    // Aligned columns for people (the default)
+    Table,
+    Json,
+    Yaml,
+    // Tab-separated rows without headers, for awk and cut
+    Plain,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "json" => Format::Json,
+            "yaml" => Format::Yaml,
+            "plain" => Format::Plain,
+            _ => Format::Table,
+        }
+    }
+
+    // JSON and YAML print the serialized value instead of a table
+    pub fn is_structured(self) -> bool {
+        matches!(self, Format::Json | Format::Yaml)
+    }
+
+    // Plain output never has colors or symbols
+    pub fn style(self, no_color: bool) -> table::Style {
+        match self {
+            Format::Plain => table::Style::plain(),
+            _ => table::Style::detect(no_color),
+        }
+    }
+}
+
+// Print a value as JSON or YAML
+pub fn print_structured<T: Serialize>(format: Format, value: &T) -> Result<()> {
+    match format {
+        Format::Yaml => print!("{}", to_yaml(&serde_json::to_value(value)?)),
+        _ => println!("{}", serde_json::to_string_pretty(value)?),
+    }
+    Ok(())
+}
+
+// Print a table as aligned columns, or as tab-separated rows for --output plain
+pub fn print_table(format: Format, table: &table::Table, style: table::Style) {
+    match format {
+        Format::Plain => print!("{}", table.render_tsv()),
+        _ => table.print(style),
+    }
+}
+
+// Block-style YAML for the JSON data model (what every command serializes)
+pub fn to_yaml(value: &Value) -> String {
+    let mut out = String::new();
+    write_yaml(&mut out, value, 0);
+    out
+}
+
+fn write_yaml(out: &mut String, value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                out.push_str(&format!("{}{}:", pad, scalar(&Value::String(key.clone()))));
+                write_child(out, child, indent);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for item in items {
+                if is_block(item) {
+                    // Put the item's first line after the dash
+                    let mut nested = String::new();
+                    write_yaml(&mut nested, item, indent + 2);
+                    out.push_str(&format!("{}- {}", pad, &nested[indent + 2..]));
+                } else {
+                    out.push_str(&format!("{}- {}\n", pad, scalar(item)));
+                }
+            }
+        }
+        _ => out.push_str(&format!("{}{}\n", pad, scalar(value))),
+    }
+}
+
+fn write_child(out: &mut String, value: &Value, indent: usize) {
+    if is_block(value) {
+        out.push('\n');
+        write_yaml(out, value, indent + 2);
+    } else {
+        out.push_str(&format!(" {}\n", scalar(value)));
+    }
+}
+
+// Non-empty maps and lists take their own lines; everything else fits inline
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) if needs_quotes(s) => {
+            // A JSON string is a valid double-quoted YAML scalar
+            serde_json::to_string(s).unwrap_or_default()
+        }
+        Value::String(s) => s.clone(),
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+    }
+}
+
+// Only plain identifiers, hostnames, paths, and URLs go out bare: they start with a
+// letter and use no YAML syntax. Everything else is quoted, since YAML 1.1 parsers
+// read many unquoted strings as other types (0x1F, .inf, 2024-01-15, 1:30, on).
+fn needs_quotes(s: &str) -> bool {
+    const KEYWORDS: [&str; 10] = [
+        "true", "false", "yes", "no", "on", "off", "null", "~", "y", "n",
+    ];
+    let plain = s.starts_with(|c: char| c.is_ascii_alphabetic())
+        && !s.ends_with(':')
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:@+=?&%".contains(c));
+    !plain || KEYWORDS.contains(&s.to_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_nests_and_quotes_ambiguous_strings() {
+        let value = serde_json::json!([
+            {
+                "name": "api",
+                "enabled": true,
+                "pid": null,
+                "target": "http://localhost:3000",
+                "port": "8080",
+                "note": "on",
+                "ambiguous": ["0x1F", ".inf", ".nan", "2024-01-15", "1:30", "-1", "a: b", "ok:"],
+                "hostnames": ["api.example.com", "api.example.org"],
+                "tags": [],
+            },
+            {"name": "web", "zones": {"example.com": {"default": true}}},
+        ]);
+        assert_eq!(
+            to_yaml(&value),
+            "- name: api\n  \
+               enabled: true\n  \
+               pid: null\n  \
+               target: http://localhost:3000\n  \
+               port: \"8080\"\n  \
+               note: \"on\"\n  \
+               ambiguous:\n    \
+                 - \"0x1F\"\n    \
+                 - \".inf\"\n    \
+                 - \".nan\"\n    \
+                 - \"2024-01-15\"\n    \
+                 - \"1:30\"\n    \
+                 - \"-1\"\n    \
+                 - \"a: b\"\n    \
+                 - \"ok:\"\n  \
+               hostnames:\n    \
+                 - api.example.com\n    \
+                 - api.example.org\n  \
+               tags: []\n\
+             - name: web\n  \
+               zones:\n    \
+                 example.com:\n      \
+                   default: true\n"
+        );
+        assert_eq!(to_yaml(&serde_json::json!([])), "[]\n");
+    }
+}
@@ -0,0 +1,89 @@
+use std::future::Future;
+use tokio::task::JoinSet;
+
+// How many tunnels multi-tunnel commands (reset, account remove, pattern stop, stats) work on at once
+pub const LIMIT: usize = 4;
+
+// Run `op` on every item, at most `limit` at a time. `on_done` sees each result as
+// soon as its unit finishes (for progress output); the results are returned in the
+// items' order.
+pub async fn run_bounded<T, R, F, Fut>(
+    items: Vec<T>,
+    limit: usize,
+    op: F,
+    mut on_done: impl FnMut(&R),
+) -> Vec<R>
+where
+    T: Send + 'static,
+    R: Send + 'static,
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = R> + Send + 'static,
+{
+    let mut results: Vec<Option<R>> = items.iter().map(|_| None).collect();
+    let mut running = JoinSet::new();
+    for (index, item) in items.into_iter().enumerate() {
+        if running.len() >= limit.max(1) {
+            finish_one(&mut running, &mut results, &mut on_done).await;
+        }
+        let unit = op(item);
+        running.spawn(async move { (index, unit.await) });
+    }
+    while !running.is_empty() {
+        finish_one(&mut running, &mut results, &mut on_done).await;
+    }
+    results.into_iter().flatten().collect()
+}
+
+async fn finish_one<R: Send + 'static>(
+    running: &mut JoinSet<(usize, R)>,
+    results: &mut [Option<R>],
+    on_done: &mut impl FnMut(&R),
+) {
+    let (index, result) = match running.join_next().await {
+        Some(Ok(done)) => done,
+        // A panicking unit takes the command down, as it would have sequentially
+        Some(Err(e)) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Some(Err(e)) => panic!("tunnel task failed: {}", e),
+        None => return,
+    };
+    on_done(&result);
+    results[index] = Some(result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_run_bounded_limits_concurrency_and_keeps_order() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let mut finished = Vec::new();
+
+        // Later items finish first, so completion order differs from input order
+        let results = run_bounded(
+            (0..10u64).collect(),
+            3,
+            |i| {
+                let (active, peak) = (active.clone(), peak.clone());
+                async move {
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(40 - i * 3)).await;
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    i * 10
+                }
+            },
+            |r| finished.push(*r),
+        )
+        .await;
+
+        assert_eq!(results, (0..10).map(|i| i * 10).collect::<Vec<_>>());
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        assert_eq!(finished.len(), 10);
+        assert_ne!(finished, results);
+    }
+}
@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+
+// Glob wildcards that turn a tunnel name argument into a pattern
+const GLOB_CHARS: [char; 3] = ['*', '?', '['];
+
+// Compile a tunnel name argument into a matcher. Plain names (no wildcards, no
+// --regex) return None so callers keep their exact-name lookup.
+pub fn compile(name: &str, regex: bool) -> Result<Option<Regex>> {
+    let source = if regex {
+        format!("^(?:{})$", name)
+    } else if name.contains(GLOB_CHARS) {
+        glob_to_regex(name)
+    } else {
+        return Ok(None);
+    };
+    Regex::new(&source)
+        .map(Some)
+        .with_context(|| format!("Invalid tunnel name pattern '{}'", name))
+}
+
+// Translate a shell-style glob (*, ?, [abc], [!abc]) into an anchored regex
+fn glob_to_regex(glob: &str) -> String {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut out = String::from("^");
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' => out.push_str(".*"),
+            '?' => out.push('.'),
+            // A [ without a closing ] is just a character
+            '[' if chars[i + 1..].contains(&']') => {
+                let close = i + 1 + chars[i + 1..].iter().position(|&c| c == ']').unwrap();
+                let mut members = &chars[i + 1..close];
+                out.push('[');
+                if let Some(('!', rest)) = members.split_first() {
+                    out.push('^');
+                    members = rest;
+                }
+                for &m in members {
+                    // Keep ranges (a-z) but nothing that means more inside a regex class
+                    if matches!(m, '\\' | '[' | '^' | '&' | '~') {
+                        out.push('\\');
+                    }
+                    out.push(m);
+                }
+                out.push(']');
+                i = close;
+            }
+            c => out.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+        i += 1;
+    }
+    out.push('$');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, regex: bool, name: &str) -> bool {
+        compile(pattern, regex).unwrap().unwrap().is_match(name)
+    }
+
+    #[test]
+    fn test_plain_names_are_not_patterns() {
+        assert!(compile("staging-api", false).unwrap().is_none());
+        assert!(compile("staging-api", true).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_globs_match_whole_names() {
+        assert!(matches("staging-*", false, "staging-api"));
+        assert!(!matches("staging-*", false, "old-staging-api"));
+        assert!(matches("api-?", false, "api-1"));
+        assert!(!matches("api-?", false, "api-10"));
+        assert!(matches("api-[12]", false, "api-2"));
+        assert!(!matches("api-[!12]", false, "api-2"));
+        // Regex metacharacters in a glob are literal
+        assert!(!matches("a.b*", false, "axb"));
+        assert!(matches("a[b*", false, "a[bc"));
+    }
+
+    #[test]
+    fn test_regex_is_anchored() {
+        assert!(matches("staging-(api|web)", true, "staging-web"));
+        assert!(!matches("api", true, "staging-api"));
+        assert!(compile("(", true).is_err());
+    }
+}
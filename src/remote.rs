@@ -0,0 +1,152 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::daemon;
+use ytunnel::cloudflare::Tunnel;
+use ytunnel::config::{Account, Config};
+use ytunnel::state::{PersistentTunnel, TunnelStatus};
+
+// A managed tunnel as this machine sees it next to Cloudflare's view of it
+// (`ytunnel list --remote`, `ytunnel doctor`)
+#[derive(Debug, Serialize)]
+pub struct RemoteView {
+    pub name: String,
+    pub account: String,
+    pub enabled: bool,
+    // Daemon status here: running, stopped, or error
+    pub local: &'static str,
+    // inactive, down, degraded, or healthy; None when Cloudflare has no such tunnel
+    pub remote: Option<String>,
+    pub connections: usize,
+    pub colos: Vec<String>,
+    // When the last connection dropped (RFC 3339), for tunnels without any now
+    pub last_seen: Option<String>,
+    // What the disagreement between the two usually means
+    pub problem: Option<String>,
+    // Stopped here while another machine's cloudflared serves it: expected on a
+    // standby host, so `doctor` only warns about it
+    #[serde(skip)]
+    pub served_elsewhere: bool,
+}
+
+// Join the account's managed tunnels with Cloudflare's tunnel list. The list
+// already carries each tunnel's connections, so one request covers them all.
+pub async fn compare(
+    cfg: &Config,
+    acct: &Account,
+    tunnels: &[&PersistentTunnel],
+) -> Result<Vec<RemoteView>> {
+    let remote: HashMap<String, Tunnel> = cfg
+        .client(acct)
+        .list_tunnels(&acct.account_id)
+        .await?
+        .into_iter()
+        .filter(|t| t.deleted_at.is_none())
+        .map(|t| (t.id.clone(), t))
+        .collect();
+
+    let mut views = Vec::new();
+    for tunnel in tunnels {
+        let local = daemon::get_daemon_status(tunnel).await;
+        views.push(view(tunnel, local, remote.get(&tunnel.tunnel_id)));
+    }
+    Ok(views)
+}
+
+fn view(tunnel: &PersistentTunnel, local: TunnelStatus, remote: Option<&Tunnel>) -> RemoteView {
+    RemoteView {
+        name: tunnel.name.clone(),
+        account: tunnel.account_name.clone(),
+        enabled: tunnel.enabled,
+        local: crate::status_name(local),
+        remote: remote.map(|r| r.status.clone().unwrap_or_else(|| "unknown".to_string())),
+        connections: remote.map_or(0, |r| {
+            r.connections
+                .iter()
+                .filter(|c| !c.is_pending_reconnect)
+                .count()
+        }),
+        colos: remote.map_or_else(Vec::new, |r| {
+            r.colos().into_iter().map(String::from).collect()
+        }),
+        last_seen: remote
+            .filter(|r| !r.is_connected())
+            .and_then(|r| r.conns_inactive_at.clone()),
+        problem: disagreement(local, remote),
+        served_elsewhere: served_elsewhere(local, remote),
+    }
+}
+
+// Local and remote state that don't fit together, explained
+fn disagreement(local: TunnelStatus, remote: Option<&Tunnel>) -> Option<String> {
+    let Some(remote) = remote else {
+        return Some("missing in Cloudflare (deleted outside ytunnel?)".to_string());
+    };
+    let remote_status = remote.status.as_deref().unwrap_or("unknown");
+    match (local, remote.is_connected()) {
+        (TunnelStatus::Running, false) => Some(format!(
+            "local: running / remote: {} (cloudflared is up but can't reach the edge)",
+            remote_status
+        )),
+        (TunnelStatus::Stopped | TunnelStatus::Error, true) => Some(format!(
+            "local: {} / remote: {} (another cloudflared is serving it)",
+            crate::status_name(local),
+            remote_status
+        )),
+        _ => None,
+    }
+}
+
+fn served_elsewhere(local: TunnelStatus, remote: Option<&Tunnel>) -> bool {
+    local == TunnelStatus::Stopped && remote.is_some_and(Tunnel::is_connected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(status: &str, colos: &[&str]) -> Tunnel {
+        let connections: Vec<String> = colos
+            .iter()
+            .map(|colo| format!(r#"{{"colo_name":"{}","is_pending_reconnect":false}}"#, colo))
+            .collect();
+        serde_json::from_str(&format!(
+            r#"{{"id":"t1","name":"ytunnel-api","deleted_at":null,"status":"{}","connections":[{}]}}"#,
+            status,
+            connections.join(",")
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_disagreement_flags_mismatched_states() {
+        let healthy = remote("healthy", &["ams01", "fra06"]);
+        let down = remote("down", &[]);
+
+        assert_eq!(disagreement(TunnelStatus::Running, Some(&healthy)), None);
+        assert_eq!(disagreement(TunnelStatus::Stopped, Some(&down)), None);
+        assert!(disagreement(TunnelStatus::Running, Some(&down))
+            .unwrap()
+            .starts_with("local: running / remote: down"));
+        assert!(disagreement(TunnelStatus::Stopped, Some(&healthy))
+            .unwrap()
+            .starts_with("local: stopped / remote: healthy"));
+        assert!(disagreement(TunnelStatus::Running, None)
+            .unwrap()
+            .starts_with("missing in Cloudflare"));
+    }
+
+    #[test]
+    fn test_served_elsewhere_only_when_stopped_here() {
+        let healthy = remote("healthy", &["ams01"]);
+        assert!(served_elsewhere(TunnelStatus::Stopped, Some(&healthy)));
+        assert!(!served_elsewhere(TunnelStatus::Running, Some(&healthy)));
+        assert!(!served_elsewhere(TunnelStatus::Error, Some(&healthy)));
+        assert!(!served_elsewhere(
+            TunnelStatus::Stopped,
+            Some(&remote("down", &[]))
+        ));
+        assert!(!served_elsewhere(TunnelStatus::Stopped, None));
+    }
+}
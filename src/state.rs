@@ -1,9 +1,10 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::path::PathBuf;
 
-use crate::config;
+use crate::{config, idn};
 
 // Represents the current runtime status of a tunnel
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,6 +22,15 @@ impl TunnelStatus {
             TunnelStatus::Error => "✗",
         }
     }
+
+    // For terminals without Unicode
+    pub fn ascii_symbol(&self) -> &'static str {
+        match self {
+            TunnelStatus::Running => "R",
+            TunnelStatus::Stopped => "S",
+            TunnelStatus::Error => "X",
+        }
+    }
 }
 
 // A persistent tunnel configuration stored in tunnels.toml
@@ -42,29 +52,224 @@ pub struct PersistentTunnel {
     // Port for cloudflared metrics endpoint (optional, calculated if not set)
     #[serde(default)]
     pub metrics_port: Option<u16>,
+    // cloudflared transport protocol (quic, http2, auto); unset lets cloudflared decide
+    #[serde(default)]
+    pub protocol: Option<String>,
+    // cloudflared --loglevel (debug, info, warn, error, fatal); unset keeps its default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<String>,
+    // Additional hostnames (possibly in other zones) served by the same tunnel
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_hostnames: Vec<HostnameRoute>,
+    // Whether ytunnel creates, reconciles, and deletes the DNS records (false when
+    // they are managed elsewhere, e.g. Terraform)
+    #[serde(default = "default_manage_dns")]
+    pub manage_dns: bool,
+    // Free-form note about what the tunnel is for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    // How long cloudflared took to make its first edge connection the last time
+    // ytunnel started it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_time_ms: Option<u64>,
+    // While in maintenance mode, the ingress service every hostname is routed to
+    // instead of the target (which is kept, so turning maintenance off restores it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maintenance: Option<String>,
+    // Catch-all ingress service for requests matching none of the hostnames
+    // (unset serves DEFAULT_FALLBACK)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
+    // Targets a local balancer spreads connections over; the target is then the
+    // balancer's own 127.0.0.1:<port>. Empty for the usual single target.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub backends: Vec<String>,
+    // When start/restart last confirmed that every managed hostname's CNAME points
+    // at the tunnel, so the next start can skip asking Cloudflare again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dns_verified: Option<DnsVerification>,
+}
+
+fn default_manage_dns() -> bool {
+    true
+}
+
+// How long a DNS verification is trusted before start checks the records again
+pub const DNS_VERIFY_TTL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+// The DNS setup a tunnel's records were last found to match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DnsVerification {
+    // Seconds since the epoch
+    pub at: u64,
+    // CNAME content the records pointed at
+    pub content: String,
+    // Hostnames that were checked, primary first
+    pub hostnames: Vec<String>,
+}
+
+// Trim a user-supplied description; a blank one means "no description"
+pub fn normalize_description(description: &str) -> Option<String> {
+    let description = description.trim();
+    (!description.is_empty()).then(|| description.to_string())
+}
+
+// A public hostname and the zone its DNS record lives in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostnameRoute {
+    pub zone_id: String,
+    pub zone_name: String,
+    pub hostname: String,
 }
 
 impl PersistentTunnel {
+    // All (zone_id, hostname) pairs that need a DNS record, primary first
+    pub fn dns_routes(&self) -> Vec<(&str, &str)> {
+        std::iter::once((self.zone_id.as_str(), self.hostname.as_str()))
+            .chain(
+                self.extra_hostnames
+                    .iter()
+                    .map(|r| (r.zone_id.as_str(), r.hostname.as_str())),
+            )
+            .collect()
+    }
+
+    // DNS routes ytunnel is responsible for (none when DNS is managed elsewhere)
+    pub fn managed_dns_routes(&self) -> Vec<(&str, &str)> {
+        if self.manage_dns {
+            self.dns_routes()
+        } else {
+            Vec::new()
+        }
+    }
+
+    // CNAME content every hostname needs to route to this tunnel
+    pub fn cname_target(&self) -> String {
+        format!("{}.cfargotunnel.com", self.tunnel_id)
+    }
+
+    // Remember that the managed records were just found to be correct
+    pub fn mark_dns_verified(&mut self, now: u64) {
+        self.dns_verified = Some(DnsVerification {
+            at: now,
+            content: self.cname_target(),
+            hostnames: self
+                .managed_dns_routes()
+                .into_iter()
+                .map(|(_, hostname)| hostname.to_string())
+                .collect(),
+        });
+    }
+
+    // Whether the managed records were verified within DNS_VERIFY_TTL for the
+    // tunnel's current hostnames; adding a hostname or replacing the tunnel
+    // means checking again
+    pub fn dns_recently_verified(&self, now: u64) -> bool {
+        self.dns_verified.as_ref().is_some_and(|v| {
+            now.saturating_sub(v.at) < DNS_VERIFY_TTL.as_secs()
+                && v.content == self.cname_target()
+                && v.hostnames
+                    .iter()
+                    .map(String::as_str)
+                    .eq(self.managed_dns_routes().into_iter().map(|(_, h)| h))
+        })
+    }
+
     // Get the path to the credentials file for this tunnel
     pub fn credentials_path(&self) -> Result<PathBuf> {
-        let config_dir = config::config_dir()?;
-        Ok(config_dir.join(format!("{}.json", self.tunnel_id)))
+        Ok(config::credentials_dir()?.join(format!("{}.json", self.tunnel_id)))
     }
 
-    // Get the path to the tunnel config file
+    // Get the path to the tunnel config file (tunnel-configs/<account>/<name>.yml)
     pub fn config_path(&self) -> Result<PathBuf> {
         let config_dir = config::config_dir()?;
-        let configs_dir = config_dir.join("tunnel-configs");
+        let configs_dir = config_dir.join("tunnel-configs").join(&self.account_name);
         Ok(configs_dir.join(format!("{}.yml", self.name)))
     }
 
-    // Get the path to the log file for this tunnel
+    // Get the path to the log file for this tunnel (logs/<account>/<name>.log)
     pub fn log_path(&self) -> Result<PathBuf> {
         let config_dir = config::config_dir()?;
-        let logs_dir = config_dir.join("logs");
+        let logs_dir = config_dir.join("logs").join(&self.account_name);
         Ok(logs_dir.join(format!("{}.log", self.name)))
     }
 
+    // Config path used before paths were namespaced by account
+    fn legacy_config_path(&self) -> Result<PathBuf> {
+        let config_dir = config::config_dir()?;
+        Ok(config_dir
+            .join("tunnel-configs")
+            .join(format!("{}.yml", self.name)))
+    }
+
+    // Log path used before paths were namespaced by account
+    fn legacy_log_path(&self) -> Result<PathBuf> {
+        let config_dir = config::config_dir()?;
+        Ok(config_dir.join("logs").join(format!("{}.log", self.name)))
+    }
+
+    // Log file to read from: the namespaced path, or the legacy one if it hasn't been migrated yet
+    pub fn existing_log_path(&self) -> Result<PathBuf> {
+        let path = self.log_path()?;
+        if !path.exists() {
+            let legacy = self.legacy_log_path()?;
+            if legacy.exists() {
+                return Ok(legacy);
+            }
+        }
+        Ok(path)
+    }
+
+    // Move legacy flat config/log files into the per-account directories.
+    // Returns true if anything was moved (the daemon unit then needs regenerating).
+    pub fn migrate_legacy_files(&self) -> Result<bool> {
+        if self.account_name.is_empty() {
+            return Ok(false);
+        }
+
+        let mut moved = false;
+        for (legacy, new) in [
+            (self.legacy_config_path()?, self.config_path()?),
+            (self.legacy_log_path()?, self.log_path()?),
+        ] {
+            if legacy.exists() && !new.exists() {
+                ensure_parent_dir(&new)?;
+                fs::rename(&legacy, &new).with_context(|| {
+                    format!("Failed to move {} to {}", legacy.display(), new.display())
+                })?;
+                moved = true;
+            }
+        }
+
+        Ok(moved)
+    }
+
+    // This tunnel's credentials, config, and log files
+    pub fn local_files(&self) -> Vec<PathBuf> {
+        [self.credentials_path(), self.config_path(), self.log_path()]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    // Remove this tunnel's credentials, config, and log files (and its transfer total)
+    pub fn remove_local_files(&self) {
+        for path in self.local_files() {
+            fs::remove_file(&path).ok();
+        }
+        self.remove_empty_account_dirs();
+        crate::transfer::forget(&self.tunnel_id);
+    }
+
+    // Drop the per-account config/log directories once they're empty
+    pub fn remove_empty_account_dirs(&self) {
+        for path in [self.config_path(), self.log_path()].into_iter().flatten() {
+            if let Some(parent) = path.parent() {
+                fs::remove_dir(parent).ok();
+            }
+        }
+    }
+
     // Get the metrics port for this tunnel (calculates from name hash if not set)
     pub fn get_metrics_port(&self) -> u16 {
         self.metrics_port.unwrap_or_else(|| {
@@ -78,19 +283,166 @@ impl PersistentTunnel {
         })
     }
 
-    // Get the metrics URL for this tunnel
-    pub fn metrics_url(&self) -> String {
-        format!("http://localhost:{}/metrics", self.get_metrics_port())
+    // The service cloudflared routes the hostnames to: the maintenance service while
+    // in maintenance mode, otherwise the target
+    pub fn ingress_service(&self) -> String {
+        match &self.maintenance {
+            Some(service) => service.clone(),
+            None => service_url(&self.target),
+        }
+    }
+
+    // The target as shown to people: the backends of a balanced tunnel rather than
+    // the local balancer address
+    pub fn display_target(&self) -> String {
+        if self.backends.is_empty() {
+            display_target(&self.target)
+        } else {
+            format!("{} (balanced)", self.backends.join(", "))
+        }
+    }
+
+    // The target as typed: the comma-separated backends of a balanced tunnel
+    pub fn target_spec(&self) -> String {
+        if self.backends.is_empty() {
+            self.target.clone()
+        } else {
+            self.backends.join(",")
+        }
+    }
+
+    // Port of the local balancer in front of the backends, if the tunnel has one
+    pub fn balancer_port(&self) -> Option<u16> {
+        if self.backends.is_empty() {
+            return None;
+        }
+        parse_target(&self.target).ok()?.port
+    }
+
+    // Point the tunnel at a target, or at several comma-separated ones through a
+    // local balancer. A balanced tunnel keeps its balancer port.
+    pub fn set_target(&mut self, spec: &str) -> Result<()> {
+        let targets = split_targets(spec);
+        if targets.len() < 2 {
+            self.target = targets.into_iter().next().unwrap_or_default();
+            self.backends.clear();
+            return Ok(());
+        }
+        for target in &targets {
+            backend_addr(target)?;
+        }
+        let port = match self.balancer_port() {
+            Some(port) => port,
+            None => free_local_port()?,
+        };
+        self.target = format!("127.0.0.1:{}", port);
+        self.backends = targets;
+        Ok(())
+    }
+
+    // The last, catch-all ingress rule's service
+    pub fn fallback_service(&self) -> &str {
+        self.fallback.as_deref().unwrap_or(DEFAULT_FALLBACK)
+    }
+
+    // Where cloudflared serves this tunnel's metrics ([daemon] metrics_bind), None
+    // when metrics are off
+    pub fn metrics_addr(&self) -> Option<SocketAddr> {
+        let ip = config::daemon_config().metrics_bind()?;
+        Some(SocketAddr::new(ip, self.get_metrics_port()))
+    }
+
+    // Get the metrics URL for this tunnel, None when metrics are off
+    pub fn metrics_url(&self) -> Option<String> {
+        self.metrics_addr().map(scrape_url)
+    }
+
+    // The metrics URL as shown to people: "off" when there isn't one
+    pub fn metrics_display(&self) -> String {
+        self.metrics_url()
+            .unwrap_or_else(|| config::METRICS_OFF.to_string())
     }
 }
 
+// URL to scrape a metrics server listening on `addr`; one bound to every interface
+// is reached over loopback
+fn scrape_url(mut addr: SocketAddr) -> String {
+    if addr.ip().is_unspecified() {
+        addr.set_ip(match addr.ip() {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        });
+    }
+    format!("http://{}/metrics", addr)
+}
+
+// Schema version written to tunnels.toml. Bump it (with a step in migrate_state)
+// whenever the format changes, including new fields, so older builds refuse to
+// save the file instead of dropping them.
+pub const STATE_VERSION: u32 = 7;
+
 // The collection of all persistent tunnels
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TunnelState {
+    // Schema version (0 = written before versioning)
+    #[serde(default)]
+    pub version: u32,
     #[serde(default)]
     pub tunnels: Vec<PersistentTunnel>,
 }
 
+impl Default for TunnelState {
+    fn default() -> Self {
+        Self {
+            version: STATE_VERSION,
+            tunnels: Vec::new(),
+        }
+    }
+}
+
+// Bring a parsed tunnels.toml up to STATE_VERSION (newer files are left untouched)
+fn migrate_state(doc: &mut toml::Table) -> Result<()> {
+    if config::schema_version(doc, "tunnels.toml")? == 0 {
+        // v0 -> v1: only the version marker; tunnels from before multiple accounts
+        // (no account_name) are assigned by assign_unowned_tunnels once the
+        // account is known
+        doc.insert("version".to_string(), toml::Value::Integer(1));
+    }
+    // v1 -> v2 added connect_time_ms; nothing to convert
+    if config::schema_version(doc, "tunnels.toml")? < 2 {
+        doc.insert("version".to_string(), toml::Value::Integer(2));
+    }
+    // v2 -> v3 added log_level; nothing to convert
+    if config::schema_version(doc, "tunnels.toml")? < 3 {
+        doc.insert("version".to_string(), toml::Value::Integer(3));
+    }
+    // v3 -> v4 added maintenance; nothing to convert
+    if config::schema_version(doc, "tunnels.toml")? < 4 {
+        doc.insert("version".to_string(), toml::Value::Integer(4));
+    }
+    // v4 -> v5 added fallback; nothing to convert
+    if config::schema_version(doc, "tunnels.toml")? < 5 {
+        doc.insert("version".to_string(), toml::Value::Integer(5));
+    }
+    // v5 -> v6 added backends; nothing to convert
+    if config::schema_version(doc, "tunnels.toml")? < 6 {
+        doc.insert("version".to_string(), toml::Value::Integer(6));
+    }
+    // v6 -> v7 added dns_verified; nothing to convert
+    if config::schema_version(doc, "tunnels.toml")? < 7 {
+        doc.insert("version".to_string(), toml::Value::Integer(7));
+    }
+    Ok(())
+}
+
+fn parse_state(contents: &str) -> Result<TunnelState> {
+    let mut doc: toml::Table = toml::from_str(contents).context("Failed to parse tunnels.toml")?;
+    migrate_state(&mut doc)?;
+    toml::Value::Table(doc)
+        .try_into()
+        .context("Failed to parse tunnels.toml")
+}
+
 impl TunnelState {
     // Load the tunnel state from disk
     pub fn load() -> Result<Self> {
@@ -102,59 +454,70 @@ impl TunnelState {
         let contents = fs::read_to_string(&path)
             .with_context(|| format!("Failed to read tunnels from {}", path.display()))?;
 
-        let state: TunnelState =
-            toml::from_str(&contents).with_context(|| "Failed to parse tunnels.toml")?;
+        parse_state(&contents)
+    }
 
-        Ok(state)
+    // Load, change, and save the tunnel state under the config lock, so another
+    // ytunnel can't save in between. Keep prompts and network waits out of `change`.
+    pub fn update<T>(change: impl FnOnce(&mut TunnelState) -> Result<T>) -> Result<T> {
+        let _lock = config::lock()?;
+        let mut state = Self::load()?;
+        let result = change(&mut state)?;
+        state.save()?;
+        Ok(result)
     }
 
     // Load tunnel state and migrate any tunnels with empty account_name
     // to the specified default account
     pub fn load_and_migrate(default_account: &str) -> Result<Self> {
         let mut state = Self::load()?;
-
-        // Check if any tunnels need migration
-        let needs_migration = state.tunnels.iter().any(|t| t.account_name.is_empty());
-
-        if needs_migration {
-            for tunnel in &mut state.tunnels {
-                if tunnel.account_name.is_empty() {
-                    tunnel.account_name = default_account.to_string();
-                }
-            }
+        if state.tunnels.iter().any(|t| t.account_name.is_empty()) {
+            // Reload under the lock in case another ytunnel saved meanwhile
+            let _lock = config::lock()?;
+            state = Self::load()?;
+            crate::backup::snapshot("tunnel state migration")?;
+            state.assign_unowned_tunnels(default_account);
             // Save the migrated state
             state.save()?;
         }
-
         Ok(state)
     }
 
+    // Give tunnels from before multiple accounts (empty account_name) to an account.
+    // Returns true if any changed.
+    pub fn assign_unowned_tunnels(&mut self, account: &str) -> bool {
+        let mut changed = false;
+        for tunnel in self
+            .tunnels
+            .iter_mut()
+            .filter(|t| t.account_name.is_empty())
+        {
+            tunnel.account_name = account.to_string();
+            changed = true;
+        }
+        changed
+    }
+
     // Save the tunnel state to disk
     pub fn save(&self) -> Result<()> {
         let dir = config::config_dir()?;
         fs::create_dir_all(&dir)
             .with_context(|| format!("Failed to create config directory: {}", dir.display()))?;
 
+        config::ensure_writable(self.version, STATE_VERSION, "tunnels.toml")?;
+        let _lock = config::lock()?;
         let path = tunnels_path()?;
         let contents = toml::to_string_pretty(self).context("Failed to serialize tunnels")?;
-        fs::write(&path, contents)
+        config::write_atomic(&path, &contents)
             .with_context(|| format!("Failed to write tunnels to {}", path.display()))?;
 
         Ok(())
     }
 
-    // Find a tunnel by name (searches all accounts)
-    pub fn find(&self, name: &str) -> Option<&PersistentTunnel> {
-        self.tunnels.iter().find(|t| t.name == name)
-    }
-
-    // Find a tunnel by name (mutable, searches all accounts)
-    pub fn find_mut(&mut self, name: &str) -> Option<&mut PersistentTunnel> {
-        self.tunnels.iter_mut().find(|t| t.name == name)
-    }
-
-    // Find a tunnel by name for a specific account
+    // Find a tunnel by name for a specific account (a Unicode name finds the
+    // punycode one it was stored as)
     pub fn find_for_account(&self, name: &str, account: &str) -> Option<&PersistentTunnel> {
+        let name = idn::normalize(name);
         self.tunnels
             .iter()
             .find(|t| t.name == name && t.account_name == account)
@@ -166,6 +529,7 @@ impl TunnelState {
         name: &str,
         account: &str,
     ) -> Option<&mut PersistentTunnel> {
+        let name = idn::normalize(name);
         self.tunnels
             .iter_mut()
             .find(|t| t.name == name && t.account_name == account)
@@ -179,22 +543,26 @@ impl TunnelState {
             .collect()
     }
 
+    // A tunnel other than name/account (in any account) whose metrics endpoint uses this port
+    pub fn metrics_port_owner(
+        &self,
+        port: u16,
+        name: &str,
+        account: &str,
+    ) -> Option<&PersistentTunnel> {
+        self.tunnels
+            .iter()
+            .find(|t| (t.name != name || t.account_name != account) && t.get_metrics_port() == port)
+    }
+
     // Add a new tunnel
     pub fn add(&mut self, tunnel: PersistentTunnel) {
         self.tunnels.push(tunnel);
     }
 
-    // Remove a tunnel by name (from any account)
-    pub fn remove(&mut self, name: &str) -> Option<PersistentTunnel> {
-        if let Some(pos) = self.tunnels.iter().position(|t| t.name == name) {
-            Some(self.tunnels.remove(pos))
-        } else {
-            None
-        }
-    }
-
     // Remove a tunnel by name for a specific account
     pub fn remove_for_account(&mut self, name: &str, account: &str) -> Option<PersistentTunnel> {
+        let name = idn::normalize(name);
         if let Some(pos) = self
             .tunnels
             .iter()
@@ -212,52 +580,240 @@ pub fn tunnels_path() -> Result<PathBuf> {
     Ok(config::config_dir()?.join("tunnels.toml"))
 }
 
-// Ensure the tunnel-configs directory exists
-pub fn ensure_configs_dir() -> Result<PathBuf> {
-    let config_dir = config::config_dir()?;
-    let configs_dir = config_dir.join("tunnel-configs");
-    fs::create_dir_all(&configs_dir).with_context(|| {
-        format!(
-            "Failed to create configs directory: {}",
-            configs_dir.display()
-        )
-    })?;
-    Ok(configs_dir)
+// Ensure the directory containing a file exists
+pub fn ensure_parent_dir(path: &std::path::Path) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+    Ok(())
+}
+
+// A network target (host:port or URL) split into its parts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    // http when the target has no scheme
+    pub scheme: String,
+    // IPv6 addresses without their brackets
+    pub host: String,
+    pub port: Option<u16>,
 }
 
-// Ensure the logs directory exists
-pub fn ensure_logs_dir() -> Result<PathBuf> {
-    let config_dir = config::config_dir()?;
-    let logs_dir = config_dir.join("logs");
-    fs::create_dir_all(&logs_dir)
-        .with_context(|| format!("Failed to create logs directory: {}", logs_dir.display()))?;
-    Ok(logs_dir)
+impl Target {
+    // Whether the target is this machine (anything else gets published to the internet)
+    pub fn is_loopback(&self) -> bool {
+        let host = self.host.to_lowercase();
+        if host == "localhost" || host.ends_with(".localhost") {
+            return true;
+        }
+        match host.parse::<std::net::IpAddr>() {
+            Ok(ip) => ip.is_loopback() || ip.is_unspecified(),
+            Err(_) => false,
+        }
+    }
+}
+
+// Parse a host:port or URL target. IPv6 hosts must be bracketed ([::1]:3000),
+// otherwise the port can't be told apart from the address.
+pub fn parse_target(target: &str) -> Result<Target> {
+    let (scheme, rest) = match target.split_once("://") {
+        Some((scheme, rest)) => (scheme.to_lowercase(), rest),
+        None => ("http".to_string(), target),
+    };
+    if scheme.is_empty()
+        || !scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    {
+        bail!("Invalid scheme in target '{}'", target);
+    }
+    // Only the authority matters; a path is left to cloudflared
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let authority = authority.rsplit('@').next().unwrap_or_default();
+
+    let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+        let (host, after) = bracketed
+            .split_once(']')
+            .with_context(|| format!("Missing ']' in target '{}'", target))?;
+        if host.parse::<std::net::Ipv6Addr>().is_err() {
+            bail!("Invalid IPv6 address '{}' in target '{}'", host, target);
+        }
+        let port =
+            match after {
+                "" => None,
+                _ => Some(after.strip_prefix(':').with_context(|| {
+                    format!("Expected ':<port>' after ']' in target '{}'", target)
+                })?),
+            };
+        (host, port)
+    } else if authority.matches(':').count() > 1 {
+        bail!(
+            "IPv6 addresses need brackets in targets, e.g. [::1]:3000 (got '{}')",
+            target
+        );
+    } else {
+        match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    if host.is_empty() {
+        bail!("Missing host in target '{}'", target);
+    }
+    let port = match port {
+        Some(port) => Some(
+            port.parse::<u16>()
+                .ok()
+                .filter(|p| *p != 0)
+                .with_context(|| format!("Invalid port '{}' in target '{}'", port, target))?,
+        ),
+        None => None,
+    };
+    Ok(Target {
+        scheme,
+        host: host.to_string(),
+        port,
+    })
+}
+
+// The targets in a comma-separated list (a single target is a list of one)
+pub fn split_targets(spec: &str) -> Vec<String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+// host:port of a balanced backend, which must be plain HTTP (port 80 when omitted)
+pub fn backend_addr(target: &str) -> Result<String> {
+    let parsed = parse_target(target)?;
+    if parsed.scheme != "http" {
+        bail!(
+            "Balanced targets must be plain HTTP (host:port or http://...), got '{}'",
+            target
+        );
+    }
+    let port = parsed.port.unwrap_or(80);
+    Ok(if parsed.host.contains(':') {
+        format!("[{}]:{}", parsed.host, port)
+    } else {
+        format!("{}:{}", parsed.host, port)
+    })
+}
+
+// A port on 127.0.0.1 nothing is listening on right now
+fn free_local_port() -> Result<u16> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")
+        .context("Failed to find a free local port for the balancer")?;
+    Ok(listener.local_addr()?.port())
+}
+
+// What maintenance mode serves when no maintenance origin is given
+pub const MAINTENANCE_SERVICE: &str = "http_status:503";
+
+// What requests for hostnames a tunnel doesn't serve get, unless it sets a fallback
+pub const DEFAULT_FALLBACK: &str = "http_status:404";
+
+// Schemes cloudflared accepts for an ingress service URL
+const SERVICE_SCHEMES: [&str; 8] = ["http", "https", "ws", "wss", "tcp", "ssh", "rdp", "smb"];
+
+// Check an ingress service the way cloudflared will: a URL with a supported
+// scheme, a unix socket, http_status:<code>, hello_world, or bastion
+pub fn validate_service(service: &str) -> Result<()> {
+    if let Some(code) = service.strip_prefix("http_status:") {
+        return match code.parse::<u16>() {
+            Ok(100..=599) => Ok(()),
+            _ => bail!(
+                "Invalid status in '{}'; expected http_status:<100-599>",
+                service
+            ),
+        };
+    }
+    if matches!(service, "hello_world" | "bastion") {
+        return Ok(());
+    }
+    if let Some(path) = unix_socket_path(service) {
+        if !path.starts_with('/') {
+            bail!(
+                "Unix socket services need an absolute path (got '{}')",
+                service
+            );
+        }
+        return Ok(());
+    }
+    match service.split_once("://") {
+        Some((scheme, _)) if SERVICE_SCHEMES.contains(&scheme.to_lowercase().as_str()) => {
+            parse_target(service).map(|_| ())
+        }
+        _ => bail!(
+            "Invalid service '{}'. Use http_status:<code>, a URL (e.g., https://example.com), \
+             unix:<path>, hello_world, or bastion.",
+            service
+        ),
+    }
+}
+
+// Socket path of a unix domain socket target (unix:/path or unix+tls:/path)
+pub fn unix_socket_path(target: &str) -> Option<&str> {
+    target
+        .strip_prefix("unix:")
+        .or_else(|| target.strip_prefix("unix+tls:"))
+}
+
+// The cloudflared ingress service for a target: URLs of any scheme (tcp://, ssh://,
+// ...) and unix sockets as given, with the scheme lowercased; host:port as
+// http://host:port (never re-formatted, so [::1] keeps its brackets)
+pub fn service_url(target: &str) -> String {
+    if unix_socket_path(target).is_some() {
+        return target.to_string();
+    }
+    match target.split_once("://") {
+        Some((scheme, rest)) if is_scheme(scheme) => {
+            format!("{}://{}", scheme.to_ascii_lowercase(), rest)
+        }
+        _ => format!("http://{}", target),
+    }
+}
+
+// A URL scheme per RFC 3986: a letter, then letters, digits, '+', '-', or '.'
+fn is_scheme(scheme: &str) -> bool {
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+}
+
+// A target for display: the URL, or the socket path for unix targets
+pub fn display_target(target: &str) -> String {
+    match unix_socket_path(target) {
+        Some(path) => format!("{} (unix socket)", path),
+        None => service_url(target),
+    }
 }
 
 // Generate the cloudflared config YAML content for a tunnel
 pub fn generate_tunnel_config(tunnel: &PersistentTunnel) -> Result<String> {
     let credentials_path = tunnel.credentials_path()?;
+    let target_url = tunnel.ingress_service();
 
-    // Normalize target URL
-    let target_url =
-        if tunnel.target.starts_with("http://") || tunnel.target.starts_with("https://") {
-            tunnel.target.clone()
-        } else {
-            format!("http://{}", tunnel.target)
-        };
+    // One ingress rule per hostname, all pointing at the same target
+    let rules: String = tunnel
+        .dns_routes()
+        .iter()
+        .map(|(_, hostname)| format!("  - hostname: {}\n    service: {}\n", hostname, target_url))
+        .collect();
 
     let config = format!(
         r#"tunnel: {tunnel_id}
 credentials-file: {credentials_path}
 ingress:
-  - hostname: {hostname}
-    service: {target_url}
-  - service: http_status:404
+{rules}  - service: {fallback}
 "#,
         tunnel_id = tunnel.tunnel_id,
         credentials_path = credentials_path.display(),
-        hostname = tunnel.hostname,
-        target_url = target_url
+        rules = rules,
+        fallback = tunnel.fallback_service()
     );
 
     Ok(config)
@@ -265,10 +821,536 @@ ingress:
 
 // Write the cloudflared config file for a tunnel
 pub fn write_tunnel_config(tunnel: &PersistentTunnel) -> Result<PathBuf> {
-    ensure_configs_dir()?;
     let config_path = tunnel.config_path()?;
+    ensure_parent_dir(&config_path)?;
     let config_content = generate_tunnel_config(tunnel)?;
-    fs::write(&config_path, &config_content)
+    config::write_atomic(&config_path, &config_content)
         .with_context(|| format!("Failed to write tunnel config to {}", config_path.display()))?;
     Ok(config_path)
 }
+
+// Move credentials files into credentials_dir from where they were before: the
+// config directory, or the directory the tunnels' cloudflared configs still name
+// (an earlier credentials_dir). Then point those configs at the new paths.
+// Returns how many files moved.
+pub fn migrate_credentials() -> Result<usize> {
+    let current = config::credentials_dir()?;
+    let tunnels = TunnelState::load()?.tunnels;
+    let mut previous = vec![config::config_dir()?];
+    for tunnel in &tunnels {
+        let Ok(contents) = fs::read_to_string(tunnel.config_path()?) else {
+            continue;
+        };
+        if let Some(dir) = configured_credentials_path(&contents)
+            .as_deref()
+            .and_then(std::path::Path::parent)
+        {
+            if !previous.iter().any(|p| p == dir) {
+                previous.push(dir.to_path_buf());
+            }
+        }
+    }
+
+    let mut moved = 0;
+    for dir in &previous {
+        moved += move_credentials(dir, &current)?;
+    }
+    if moved > 0 {
+        for tunnel in &tunnels {
+            if tunnel.config_path()?.exists() {
+                write_tunnel_config(tunnel)?;
+            }
+        }
+    }
+    Ok(moved)
+}
+
+// The credentials-file a cloudflared config points at
+fn configured_credentials_path(config: &str) -> Option<PathBuf> {
+    config.lines().find_map(|line| {
+        let path = line.strip_prefix("credentials-file:")?.trim();
+        let path = path.trim_matches(|c| c == '"' || c == '\'');
+        (!path.is_empty()).then(|| PathBuf::from(path))
+    })
+}
+
+// Move every <tunnel-id>.json from one directory to another. Copies, then removes
+// the original, since credentials_dir is often on another volume.
+fn move_credentials(from: &std::path::Path, to: &std::path::Path) -> Result<usize> {
+    if from == to || !from.is_dir() {
+        return Ok(0);
+    }
+    let mut moved = 0;
+    for entry in fs::read_dir(from).with_context(|| format!("Failed to read {}", from.display()))? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().filter(|_| is_credentials_file(&path)) else {
+            continue;
+        };
+        fs::create_dir_all(to)
+            .with_context(|| format!("Failed to create credentials directory {}", to.display()))?;
+        let dest = to.join(name);
+        // A copy already there is the same tunnel's secret
+        if !dest.exists() {
+            fs::copy(&path, &dest).with_context(|| {
+                format!("Failed to move {} to {}", path.display(), dest.display())
+            })?;
+        }
+        fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+// Credentials files are named after the tunnel's UUID
+fn is_credentials_file(path: &std::path::Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+        && path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| {
+                stem.len() == 36 && stem.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+            })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tunnel(name: &str, account: &str, tunnel_id: &str) -> PersistentTunnel {
+        PersistentTunnel {
+            name: name.to_string(),
+            account_name: account.to_string(),
+            target: "localhost:3000".to_string(),
+            zone_id: "zone".to_string(),
+            zone_name: "example.com".to_string(),
+            hostname: format!("{}.example.com", name),
+            tunnel_id: tunnel_id.to_string(),
+            enabled: false,
+            auto_start: false,
+            metrics_port: None,
+            protocol: None,
+            log_level: None,
+            extra_hostnames: Vec::new(),
+            manage_dns: true,
+            description: None,
+            connect_time_ms: None,
+            maintenance: None,
+            fallback: None,
+            backends: Vec::new(),
+            dns_verified: None,
+        }
+    }
+
+    #[test]
+    fn test_service_url_keeps_unix_socket_targets() {
+        assert_eq!(service_url("localhost:3000"), "http://localhost:3000");
+        assert_eq!(service_url("https://app.local"), "https://app.local");
+        assert_eq!(service_url("unix:/run/app.sock"), "unix:/run/app.sock");
+        // Other schemes pass through instead of becoming http://tcp://...
+        assert_eq!(service_url("tcp://localhost:5432"), "tcp://localhost:5432");
+        assert_eq!(service_url("ssh://localhost:22"), "ssh://localhost:22");
+        assert_eq!(service_url("HTTPS://App.local/x"), "https://App.local/x");
+        assert_eq!(
+            unix_socket_path("unix+tls:/run/app.sock"),
+            Some("/run/app.sock")
+        );
+        assert_eq!(unix_socket_path("localhost:3000"), None);
+        assert_eq!(
+            display_target("unix:/run/app.sock"),
+            "/run/app.sock (unix socket)"
+        );
+
+        let mut t = tunnel("api", "default", "t1");
+        t.target = "[::1]:3000".to_string();
+        let config = generate_tunnel_config(&t).unwrap();
+        assert!(
+            config.contains("service: http://[::1]:3000\n"),
+            "{}",
+            config
+        );
+
+        t.target = "unix:/run/app.sock".to_string();
+        let config = generate_tunnel_config(&t).unwrap();
+        assert!(config.contains("service: unix:/run/app.sock"), "{}", config);
+
+        // Maintenance mode routes to the maintenance service but keeps the target
+        t.maintenance = Some(MAINTENANCE_SERVICE.to_string());
+        let config = generate_tunnel_config(&t).unwrap();
+        assert!(config.contains("service: http_status:503\n"), "{}", config);
+        assert!(!config.contains("unix:"), "{}", config);
+        assert_eq!(t.target, "unix:/run/app.sock");
+    }
+
+    #[test]
+    fn test_parse_target_handles_ipv6_ipv4_hostnames_and_schemes() {
+        let target = |scheme: &str, host: &str, port: Option<u16>| Target {
+            scheme: scheme.to_string(),
+            host: host.to_string(),
+            port,
+        };
+        assert_eq!(
+            parse_target("[::1]:3000").unwrap(),
+            target("http", "::1", Some(3000))
+        );
+        assert_eq!(
+            parse_target("https://[fe80::1]/api").unwrap(),
+            target("https", "fe80::1", None)
+        );
+        assert_eq!(
+            parse_target("192.168.1.40:8080").unwrap(),
+            target("http", "192.168.1.40", Some(8080))
+        );
+        assert_eq!(
+            parse_target("localhost:3000").unwrap(),
+            target("http", "localhost", Some(3000))
+        );
+        assert_eq!(
+            parse_target("HTTPS://app.internal:8443/x?y").unwrap(),
+            target("https", "app.internal", Some(8443))
+        );
+        assert_eq!(
+            parse_target("tcp://db.internal:5432").unwrap(),
+            target("tcp", "db.internal", Some(5432))
+        );
+
+        assert!(parse_target("::1:3000").is_err());
+        assert!(parse_target("[::1").is_err());
+        assert!(parse_target("[nope]:80").is_err());
+        assert!(parse_target("localhost:http").is_err());
+        assert!(parse_target("localhost:70000").is_err());
+        assert!(parse_target(":3000").is_err());
+
+        assert!(parse_target("[::1]:3000").unwrap().is_loopback());
+        assert!(parse_target("127.0.0.2:80").unwrap().is_loopback());
+        assert!(parse_target("app.localhost:80").unwrap().is_loopback());
+        assert!(!parse_target("192.168.1.40:8080").unwrap().is_loopback());
+        assert!(!parse_target("[2001:db8::1]:80").unwrap().is_loopback());
+        assert!(!parse_target("nas.lan:5000").unwrap().is_loopback());
+    }
+
+    #[test]
+    fn test_fallback_is_validated_and_ends_the_ingress() {
+        for service in [
+            "http_status:403",
+            "https://example.com",
+            "http://[::1]:8080",
+            "unix:/run/app.sock",
+            "hello_world",
+            "tcp://localhost:22",
+        ] {
+            assert!(validate_service(service).is_ok(), "{}", service);
+        }
+        for service in [
+            "http_status:42",
+            "http_status:nope",
+            "example.com",
+            "ftp://example.com",
+            "unix:relative.sock",
+            "https://",
+        ] {
+            assert!(validate_service(service).is_err(), "{}", service);
+        }
+
+        let mut t = tunnel("api", "default", "t1");
+        let config = generate_tunnel_config(&t).unwrap();
+        assert!(
+            config.ends_with("  - service: http_status:404\n"),
+            "{}",
+            config
+        );
+        t.fallback = Some("https://example.com".to_string());
+        let config = generate_tunnel_config(&t).unwrap();
+        assert!(
+            config.ends_with("  - service: https://example.com\n"),
+            "{}",
+            config
+        );
+    }
+
+    #[test]
+    fn test_same_name_in_two_accounts_is_not_aliased() {
+        let mut state = TunnelState::default();
+        state.add(tunnel("api", "work", "id-work"));
+        state.add(tunnel("api", "personal", "id-personal"));
+
+        state
+            .find_for_account_mut("api", "personal")
+            .unwrap()
+            .enabled = true;
+        assert!(!state.find_for_account("api", "work").unwrap().enabled);
+        assert!(state.find_for_account("api", "personal").unwrap().enabled);
+
+        let removed = state.remove_for_account("api", "personal").unwrap();
+        assert_eq!(removed.tunnel_id, "id-personal");
+        assert_eq!(state.tunnels.len(), 1);
+        assert_eq!(state.tunnels[0].tunnel_id, "id-work");
+    }
+
+    #[test]
+    fn test_extra_hostnames_get_ingress_rules() {
+        let mut t = tunnel("app", "work", "id-1");
+        t.extra_hostnames.push(HostnameRoute {
+            zone_id: "zone-2".to_string(),
+            zone_name: "example.org".to_string(),
+            hostname: "app.example.org".to_string(),
+        });
+
+        let config = generate_tunnel_config(&t).unwrap();
+        let hostnames: Vec<&str> = config
+            .lines()
+            .filter_map(|l| l.trim().strip_prefix("- hostname: "))
+            .collect();
+        assert_eq!(hostnames, vec!["app.example.com", "app.example.org"]);
+        assert!(config.trim_end().ends_with("- service: http_status:404"));
+
+        // Round-trips through tunnels.toml
+        let state = TunnelState {
+            tunnels: vec![t],
+            ..Default::default()
+        };
+        let parsed: TunnelState = toml::from_str(&toml::to_string_pretty(&state).unwrap()).unwrap();
+        assert_eq!(parsed.tunnels[0].extra_hostnames.len(), 1);
+    }
+
+    #[test]
+    fn test_config_and_log_paths_are_namespaced_by_account() {
+        let work = tunnel("api", "work", "id-work");
+        let personal = tunnel("api", "personal", "id-personal");
+
+        assert_ne!(work.config_path().unwrap(), personal.config_path().unwrap());
+        assert_ne!(work.log_path().unwrap(), personal.log_path().unwrap());
+        assert!(work
+            .config_path()
+            .unwrap()
+            .ends_with("tunnel-configs/work/api.yml"));
+        assert!(personal
+            .log_path()
+            .unwrap()
+            .ends_with("logs/personal/api.log"));
+    }
+
+    #[test]
+    fn test_scrape_url_reaches_wildcard_binds_over_loopback() {
+        let url = |addr: &str| scrape_url(addr.parse().unwrap());
+        assert_eq!(url("127.0.0.1:21000"), "http://127.0.0.1:21000/metrics");
+        assert_eq!(url("0.0.0.0:21000"), "http://127.0.0.1:21000/metrics");
+        assert_eq!(url("[::]:21000"), "http://[::1]:21000/metrics");
+        assert_eq!(url("10.0.0.5:21000"), "http://10.0.0.5:21000/metrics");
+    }
+
+    #[test]
+    fn test_metrics_port_owner_ignores_the_tunnel_itself() {
+        let mut api = tunnel("api", "work", "id-1");
+        api.metrics_port = Some(21500);
+        let mut web = tunnel("web", "personal", "id-2");
+        web.metrics_port = Some(21600);
+        let state = TunnelState {
+            tunnels: vec![api, web],
+            ..Default::default()
+        };
+
+        assert!(state.metrics_port_owner(21500, "api", "work").is_none());
+        assert_eq!(
+            state
+                .metrics_port_owner(21500, "web", "personal")
+                .map(|t| t.name.as_str()),
+            Some("api")
+        );
+        assert!(state.metrics_port_owner(21501, "web", "personal").is_none());
+    }
+
+    #[test]
+    fn test_manage_dns_defaults_on_and_gates_dns_routes() {
+        let mut t = tunnel("api", "work", "id-1");
+        let mut toml_text = toml::to_string_pretty(&TunnelState {
+            tunnels: vec![t.clone()],
+            ..Default::default()
+        })
+        .unwrap();
+        // State written before the flag existed still manages DNS
+        assert!(toml_text.contains("manage_dns = true\n"));
+        toml_text = toml_text.replace("manage_dns = true\n", "");
+        let parsed: TunnelState = toml::from_str(&toml_text).unwrap();
+        assert!(parsed.tunnels[0].manage_dns);
+
+        t.manage_dns = false;
+        assert!(t.managed_dns_routes().is_empty());
+        assert_eq!(t.dns_routes(), vec![("zone", "api.example.com")]);
+    }
+
+    #[test]
+    fn test_dns_verification_expires_and_tracks_routes() {
+        let mut t = tunnel("api", "work", "id-1");
+        let now = 1_700_000_000;
+        assert!(!t.dns_recently_verified(now));
+
+        t.mark_dns_verified(now);
+        assert!(t.dns_recently_verified(now + 60));
+        assert!(!t.dns_recently_verified(now + DNS_VERIFY_TTL.as_secs()));
+
+        // A new hostname hasn't been checked yet
+        let mut routed = t.clone();
+        routed.extra_hostnames.push(HostnameRoute {
+            zone_id: "zone".to_string(),
+            zone_name: "example.com".to_string(),
+            hostname: "www.example.com".to_string(),
+        });
+        assert!(!routed.dns_recently_verified(now + 60));
+
+        // Nor has a replacement Cloudflare tunnel
+        let mut replaced = t.clone();
+        replaced.tunnel_id = "id-2".to_string();
+        assert!(!replaced.dns_recently_verified(now + 60));
+    }
+
+    #[test]
+    fn test_set_target_balances_lists_and_keeps_the_balancer_port() {
+        let mut t = tunnel("api", "work", "id-1");
+        t.set_target("localhost:3001, localhost:3002,[::1]:3003")
+            .unwrap();
+        assert_eq!(
+            t.backends,
+            ["localhost:3001", "localhost:3002", "[::1]:3003"]
+        );
+        let port = t.balancer_port().unwrap();
+        assert_eq!(t.target, format!("127.0.0.1:{}", port));
+        assert_eq!(t.target_spec(), "localhost:3001,localhost:3002,[::1]:3003");
+
+        t.set_target("localhost:4001,localhost:4002").unwrap();
+        assert_eq!(t.balancer_port(), Some(port));
+        assert!(t
+            .set_target("localhost:4001,https://localhost:4002")
+            .is_err());
+
+        t.set_target("localhost:5000").unwrap();
+        assert_eq!(t.target, "localhost:5000");
+        assert!(t.backends.is_empty());
+        assert_eq!(t.balancer_port(), None);
+    }
+
+    #[test]
+    fn test_backend_addr_defaults_port_and_keeps_ipv6_brackets() {
+        assert_eq!(backend_addr("localhost:3001").unwrap(), "localhost:3001");
+        assert_eq!(backend_addr("http://app.local").unwrap(), "app.local:80");
+        assert_eq!(backend_addr("[::1]:3002").unwrap(), "[::1]:3002");
+        assert!(backend_addr("https://localhost:3003").is_err());
+    }
+
+    #[test]
+    fn test_description_is_optional_and_normalized() {
+        let mut t = tunnel("api", "work", "id-1");
+        let toml_text = toml::to_string_pretty(&TunnelState {
+            tunnels: vec![t.clone()],
+            ..Default::default()
+        })
+        .unwrap();
+        // No key written for tunnels without one, and older state still parses
+        assert!(!toml_text.contains("description"));
+        let parsed: TunnelState = toml::from_str(&toml_text).unwrap();
+        assert_eq!(parsed.tunnels[0].description, None);
+
+        t.description = normalize_description("  Webhook receiver  ");
+        let toml_text = toml::to_string_pretty(&TunnelState {
+            tunnels: vec![t],
+            ..Default::default()
+        })
+        .unwrap();
+        let parsed: TunnelState = toml::from_str(&toml_text).unwrap();
+        assert_eq!(
+            parsed.tunnels[0].description.as_deref(),
+            Some("Webhook receiver")
+        );
+        assert_eq!(normalize_description("   "), None);
+    }
+
+    #[test]
+    fn test_pre_account_tunnels_migrate_to_current() {
+        // tunnels.toml as written before versioning and multiple accounts
+        let mut state = parse_state(
+            r#"
+[[tunnels]]
+name = "api"
+target = "localhost:3000"
+zone_id = "zone"
+zone_name = "example.com"
+hostname = "api.example.com"
+tunnel_id = "id-1"
+enabled = true
+"#,
+        )
+        .unwrap();
+        assert_eq!(state.version, STATE_VERSION);
+        assert!(state.tunnels[0].manage_dns);
+        assert!(!state.tunnels[0].auto_start);
+        assert!(state.tunnels[0].log_level.is_none());
+        assert!(state.tunnels[0].maintenance.is_none());
+        assert!(state.tunnels[0].fallback.is_none());
+        assert!(state.tunnels[0].backends.is_empty());
+
+        assert!(state.assign_unowned_tunnels("default"));
+        assert_eq!(state.tunnels[0].account_name, "default");
+        assert!(!state.assign_unowned_tunnels("other"));
+    }
+
+    #[test]
+    fn test_newer_state_loads_but_refuses_to_save() {
+        // A later ytunnel's file, with a field this build doesn't know about
+        let state = parse_state(
+            r#"
+version = 99
+
+[[tunnels]]
+name = "api"
+account_name = "work"
+target = "localhost:3000"
+zone_id = "zone"
+zone_name = "example.com"
+hostname = "api.example.com"
+tunnel_id = "id-1"
+enabled = true
+future_field = 1
+"#,
+        )
+        .unwrap();
+        assert_eq!(state.version, 99);
+        assert_eq!(state.tunnels.len(), 1);
+        assert!(config::ensure_writable(state.version, STATE_VERSION, "tunnels.toml").is_err());
+        assert!(config::ensure_writable(STATE_VERSION, STATE_VERSION, "tunnels.toml").is_ok());
+    }
+
+    #[test]
+    fn test_move_credentials_takes_only_tunnel_credentials() {
+        let base = std::env::temp_dir().join(format!("ytunnel-creds-{}", std::process::id()));
+        let (from, to) = (base.join("config"), base.join("secrets"));
+        fs::create_dir_all(&from).unwrap();
+        let id = "6f1d2c3b-4a5e-4f60-8a7b-9c0d1e2f3a4b";
+        fs::write(from.join(format!("{}.json", id)), "{}").unwrap();
+        fs::write(from.join("update-check.json"), "{}").unwrap();
+        fs::write(from.join("config.toml"), "").unwrap();
+
+        assert_eq!(move_credentials(&from, &from).unwrap(), 0);
+        assert_eq!(move_credentials(&from, &to).unwrap(), 1);
+        assert!(to.join(format!("{}.json", id)).exists());
+        assert!(!from.join(format!("{}.json", id)).exists());
+        assert!(from.join("update-check.json").exists());
+        // Nothing left to move on the next run
+        assert_eq!(move_credentials(&from, &to).unwrap(), 0);
+
+        fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn test_configured_credentials_path_reads_the_cloudflared_config() {
+        let t = tunnel("api", "default", "6f1d2c3b-4a5e-4f60-8a7b-9c0d1e2f3a4b");
+        let config = generate_tunnel_config(&t).unwrap();
+        assert_eq!(
+            configured_credentials_path(&config),
+            Some(t.credentials_path().unwrap())
+        );
+        assert_eq!(
+            configured_credentials_path("credentials-file: \"/Volumes/Old Disk/x.json\"\n"),
+            Some(PathBuf::from("/Volumes/Old Disk/x.json"))
+        );
+        assert_eq!(configured_credentials_path("tunnel: abc\n"), None);
+    }
+}
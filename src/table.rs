@@ -0,0 +1,254 @@
+use std::io::IsTerminal;
+
+// Columns never shrink below this when fitting the terminal width
+const MIN_COLUMN_WIDTH: usize = 6;
+// Spaces between columns
+const COLUMN_GAP: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Green,
+    Yellow,
+    Red,
+    Cyan,
+    Dim,
+}
+
+impl Color {
+    fn ansi(self) -> &'static str {
+        match self {
+            Color::Green => "\x1b[32m",
+            Color::Yellow => "\x1b[33m",
+            Color::Red => "\x1b[31m",
+            Color::Cyan => "\x1b[36m",
+            Color::Dim => "\x1b[2m",
+        }
+    }
+
+    // Text wrapped in this color's escape codes
+    pub fn paint(self, text: &str) -> String {
+        format!("{}{}\x1b[0m", self.ansi(), text)
+    }
+}
+
+// How tables are drawn: colors and unicode only on a terminal, plain ASCII otherwise
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    pub color: bool,
+    // Maximum line width (terminal width), if any
+    pub max_width: Option<usize>,
+}
+
+impl Style {
+    // Plain aligned ASCII with no width limit (pipes, files, --no-color)
+    pub fn plain() -> Self {
+        Self {
+            color: false,
+            max_width: None,
+        }
+    }
+
+    // Pick a style for stdout, honoring --no-color and the NO_COLOR convention
+    pub fn detect(no_color: bool) -> Self {
+        if !std::io::stdout().is_terminal() {
+            return Self::plain();
+        }
+        let no_color = no_color || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Self {
+            color: !no_color,
+            max_width: crossterm::terminal::size()
+                .ok()
+                .map(|(cols, _)| cols as usize),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cell {
+    text: String,
+    color: Option<Color>,
+}
+
+impl Cell {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            color: None,
+        }
+    }
+
+    pub fn colored(text: impl Into<String>, color: Color) -> Self {
+        Self {
+            text: text.into(),
+            color: Some(color),
+        }
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell::new(text)
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::new(text)
+    }
+}
+
+// A simple column-aligned table for CLI output
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    pub fn new<S: Into<String>>(headers: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            headers: headers.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn add_row(&mut self, row: Vec<Cell>) {
+        self.rows.push(row);
+    }
+
+    // Column widths, shrinking the widest columns until the table fits max_width
+    fn widths(&self, max_width: Option<usize>) -> Vec<usize> {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate().take(widths.len()) {
+                widths[i] = widths[i].max(cell.text.chars().count());
+            }
+        }
+
+        if let Some(max) = max_width {
+            let gaps = COLUMN_GAP * widths.len().saturating_sub(1);
+            while widths.iter().sum::<usize>() + gaps > max {
+                let (widest, width) = match widths.iter().enumerate().max_by_key(|(_, w)| **w) {
+                    Some((i, w)) => (i, *w),
+                    None => break,
+                };
+                if width <= MIN_COLUMN_WIDTH {
+                    break;
+                }
+                widths[widest] -= 1;
+            }
+        }
+
+        widths
+    }
+
+    pub fn render(&self, style: Style) -> String {
+        let widths = self.widths(style.max_width);
+        let mut out = String::new();
+
+        let header: Vec<Cell> = self.headers.iter().map(|h| Cell::new(h.as_str())).collect();
+        render_line(&mut out, &header, &widths, style);
+        for row in &self.rows {
+            render_line(&mut out, row, &widths, style);
+        }
+
+        out
+    }
+
+    pub fn print(&self, style: Style) {
+        print!("{}", self.render(style));
+    }
+
+    // Rows as tab-separated values without the header (--output plain)
+    pub fn render_tsv(&self) -> String {
+        let mut out = String::new();
+        for row in &self.rows {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|c| c.text.replace(['\t', '\n'], " "))
+                .collect();
+            out.push_str(&cells.join("\t"));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn render_line(out: &mut String, cells: &[Cell], widths: &[usize], style: Style) {
+    let mut line = String::new();
+    for (i, width) in widths.iter().enumerate() {
+        let cell = cells.get(i);
+        let text = truncate(
+            cell.map(|c| c.text.as_str()).unwrap_or(""),
+            *width,
+            style.color,
+        );
+        let is_last = i + 1 == widths.len();
+        let padding = if is_last {
+            0
+        } else {
+            width - text.chars().count() + COLUMN_GAP
+        };
+
+        match cell.and_then(|c| c.color) {
+            Some(color) if style.color => {
+                line.push_str(color.ansi());
+                line.push_str(&text);
+                line.push_str("\x1b[0m");
+            }
+            _ => line.push_str(&text),
+        }
+        line.push_str(&" ".repeat(padding));
+    }
+    out.push_str(line.trim_end());
+    out.push('\n');
+}
+
+// Cut text to width characters, marking the cut with an ellipsis
+fn truncate(text: &str, width: usize, unicode: bool) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let ellipsis = if unicode { "…" } else { "..." };
+    let keep = width.saturating_sub(ellipsis.chars().count());
+    let mut out: String = text.chars().take(keep).collect();
+    out.push_str(ellipsis);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_table_is_aligned_and_truncated_to_width() {
+        let mut table = Table::new(["NAME", "HOSTNAME"]);
+        table.add_row(vec!["api".into(), "api.example.com".into()]);
+        table.add_row(vec![
+            Cell::colored("a-much-longer-name", Color::Green),
+            "x.example.com".into(),
+        ]);
+
+        assert_eq!(
+            table.render(Style::plain()),
+            "NAME                HOSTNAME\n\
+             api                 api.example.com\n\
+             a-much-longer-name  x.example.com\n"
+        );
+
+        let narrow = Style {
+            color: false,
+            max_width: Some(27),
+        };
+        assert_eq!(
+            table.render(narrow),
+            "NAME           HOSTNAME\n\
+             api            api.examp...\n\
+             a-much-lon...  x.example...\n"
+        );
+
+        assert_eq!(
+            table.render_tsv(),
+            "api\tapi.example.com\na-much-longer-name\tx.example.com\n"
+        );
+    }
+}
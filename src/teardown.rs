@@ -0,0 +1,356 @@
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::daemon;
+use crate::parallel;
+use ytunnel::cloudflare;
+use ytunnel::config::{self, Account};
+use ytunnel::state::PersistentTunnel;
+use ytunnel::transfer;
+
+// How long to wait before re-attempting failed operations with --retry
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+// Cloudflare API access for remote operations: (credentials, account_id)
+pub type Credentials = Option<(cloudflare::Credentials, String)>;
+
+// A single cleanup operation, kept so failed ones can be retried
+#[derive(Debug, Clone)]
+pub enum Step {
+    StopDaemon {
+        name: String,
+        account: String,
+    },
+    UninstallDaemon {
+        name: String,
+        account: String,
+    },
+    DeleteDnsRecord {
+        creds: Credentials,
+        zone_id: String,
+        hostname: String,
+    },
+    DeleteTunnel {
+        creds: Credentials,
+        tunnel_id: String,
+    },
+    RemoveFile(PathBuf),
+    RemoveDir(PathBuf),
+}
+
+impl Step {
+    fn describe(&self) -> String {
+        match self {
+            Step::StopDaemon { name, .. } => format!("stop daemon {}", name),
+            Step::UninstallDaemon { name, .. } => format!("uninstall daemon {}", name),
+            Step::DeleteDnsRecord { hostname, .. } => format!("delete DNS record {}", hostname),
+            Step::DeleteTunnel { tunnel_id, .. } => {
+                format!("delete Cloudflare tunnel {}", tunnel_id)
+            }
+            Step::RemoveFile(path) | Step::RemoveDir(path) => format!("remove {}", path.display()),
+        }
+    }
+
+    // Remote failures leave billable or routable resources behind, so they fail the command
+    fn is_remote(&self) -> bool {
+        matches!(
+            self,
+            Step::DeleteDnsRecord { .. } | Step::DeleteTunnel { .. }
+        )
+    }
+
+    async fn run(&self) -> Result<()> {
+        match self {
+            Step::StopDaemon { name, account } => daemon::stop_daemon(name, account).await,
+            Step::UninstallDaemon { name, account } => {
+                daemon::uninstall_daemon(name, account).await
+            }
+            Step::DeleteDnsRecord {
+                creds,
+                zone_id,
+                hostname,
+            } => {
+                let (credentials, _) = require_creds(creds)?;
+                cloudflare::Client::with_credentials(credentials.clone())
+                    .delete_dns_record(zone_id, hostname)
+                    .await
+            }
+            Step::DeleteTunnel { creds, tunnel_id } => {
+                let (credentials, account_id) = require_creds(creds)?;
+                cloudflare::Client::with_credentials(credentials.clone())
+                    .delete_tunnel(account_id, tunnel_id)
+                    .await
+            }
+            Step::RemoveFile(path) => remove_file_if_exists(path)
+                .with_context(|| format!("Failed to remove {}", path.display())),
+            Step::RemoveDir(path) => remove_dir_all_if_exists(path)
+                .with_context(|| format!("Failed to remove {}", path.display())),
+        }
+    }
+}
+
+fn require_creds(creds: &Credentials) -> Result<(&cloudflare::Credentials, &String)> {
+    creds
+        .as_ref()
+        .map(|(credentials, account_id)| (credentials, account_id))
+        .context("No configured account to authenticate with")
+}
+
+// Remove a file, treating "already gone" as success
+pub fn remove_file_if_exists(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+fn remove_dir_all_if_exists(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_dir_all(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+// A tunnel for Report::teardown_tunnels, with everything its teardown needs
+pub struct Teardown {
+    pub tunnel: PersistentTunnel,
+    // Account the tunnel's daemon is registered under
+    pub daemon_account: String,
+    // Account for the Cloudflare API calls, if it's still configured
+    pub account: Option<Account>,
+}
+
+struct Operation {
+    step: Step,
+    error: Option<anyhow::Error>,
+}
+
+// Results of every cleanup operation, so failures aren't silently lost
+#[derive(Default)]
+pub struct Report {
+    ops: Vec<Operation>,
+}
+
+impl Report {
+    // Run a step and record its outcome, returning whether it succeeded
+    pub async fn run(&mut self, step: Step) -> bool {
+        let error = step.run().await.err();
+        let ok = error.is_none();
+        self.ops.push(Operation { step, error });
+        ok
+    }
+
+    // Stop, uninstall, and delete a tunnel (DNS records unless keep_dns, and the
+    // Cloudflare tunnel), then remove its local files
+    pub async fn teardown_tunnel(
+        &mut self,
+        tunnel: &PersistentTunnel,
+        daemon_account: &str,
+        acct: Option<&Account>,
+        keep_dns: bool,
+    ) {
+        print!("Removing tunnel '{}'... ", tunnel.name);
+        std::io::stdout().flush().ok();
+        let ok = self.remove(tunnel, daemon_account, acct, keep_dns).await;
+        println!("{}", if ok { "done" } else { "failed" });
+    }
+
+    // Tear down several tunnels, parallel::LIMIT at a time. Each prints one line
+    // when it finishes; the operations are recorded in the tunnels' order.
+    pub async fn teardown_tunnels(&mut self, tunnels: Vec<Teardown>) {
+        let reports = parallel::run_bounded(
+            tunnels,
+            parallel::LIMIT,
+            |t| async move {
+                let mut report = Report::default();
+                let ok = report
+                    .remove(&t.tunnel, &t.daemon_account, t.account.as_ref(), false)
+                    .await;
+                (t.tunnel.name, ok, report)
+            },
+            |(name, ok, _)| {
+                if *ok {
+                    println!("Removed tunnel '{}'", name);
+                } else {
+                    println!("Removing tunnel '{}' failed", name);
+                }
+            },
+        )
+        .await;
+        for (_, _, report) in reports {
+            self.ops.extend(report.ops);
+        }
+    }
+
+    // The steps of teardown_tunnel, returning whether all of them succeeded
+    async fn remove(
+        &mut self,
+        tunnel: &PersistentTunnel,
+        daemon_account: &str,
+        acct: Option<&Account>,
+        keep_dns: bool,
+    ) -> bool {
+        let creds: Credentials = acct.map(|a| (a.credentials(), a.account_id.clone()));
+        let mut steps = vec![
+            Step::StopDaemon {
+                name: tunnel.name.clone(),
+                account: daemon_account.to_string(),
+            },
+            Step::UninstallDaemon {
+                name: tunnel.name.clone(),
+                account: daemon_account.to_string(),
+            },
+        ];
+        let dns_routes = if keep_dns {
+            Vec::new()
+        } else {
+            tunnel.managed_dns_routes()
+        };
+        // DNS may live in a zone owned by another configured account
+        let cfg = config::load_config().ok();
+        for (zone_id, hostname) in dns_routes {
+            let dns_creds = match (&cfg, acct) {
+                (Some(cfg), Some(acct)) => {
+                    let dns_acct = cfg.dns_account(acct, zone_id);
+                    Some((dns_acct.credentials(), dns_acct.account_id.clone()))
+                }
+                _ => creds.clone(),
+            };
+            steps.push(Step::DeleteDnsRecord {
+                creds: dns_creds,
+                zone_id: zone_id.to_string(),
+                hostname: hostname.to_string(),
+            });
+        }
+        steps.push(Step::DeleteTunnel {
+            creds,
+            tunnel_id: tunnel.tunnel_id.clone(),
+        });
+        steps.extend(tunnel.local_files().into_iter().map(Step::RemoveFile));
+
+        let mut ok = true;
+        for step in steps {
+            ok &= self.run(step).await;
+        }
+        tunnel.remove_empty_account_dirs();
+        if ok {
+            transfer::forget(&tunnel.tunnel_id);
+        }
+        ok
+    }
+
+    fn failed_count(&self) -> usize {
+        self.ops.iter().filter(|op| op.error.is_some()).count()
+    }
+
+    // Re-attempt just the failed operations once
+    pub async fn retry_failures(&mut self) {
+        let failed = self.failed_count();
+        if failed == 0 {
+            return;
+        }
+
+        println!(
+            "\nRetrying {} failed operation(s) in {}s...",
+            failed,
+            RETRY_DELAY.as_secs()
+        );
+        tokio::time::sleep(RETRY_DELAY).await;
+
+        for op in self.ops.iter_mut().filter(|op| op.error.is_some()) {
+            op.error = op.step.run().await.err();
+        }
+    }
+
+    // Print every operation with its outcome
+    pub fn print_table(&self) {
+        if self.ops.is_empty() {
+            return;
+        }
+
+        let width = self
+            .ops
+            .iter()
+            .map(|op| op.step.describe().len())
+            .max()
+            .unwrap_or(0);
+
+        println!();
+        println!("{:<width$}  RESULT", "OPERATION", width = width);
+        for op in &self.ops {
+            let result = match &op.error {
+                None => "ok".to_string(),
+                Some(e) => format!("FAILED: {:#}", e),
+            };
+            println!("{:<width$}  {}", op.step.describe(), result, width = width);
+        }
+
+        let failed = self.failed_count();
+        println!();
+        println!("{} succeeded, {} failed", self.ops.len() - failed, failed);
+    }
+
+    // Whether any Cloudflare resource may have been left behind
+    pub fn has_remote_failures(&self) -> bool {
+        self.ops
+            .iter()
+            .any(|op| op.error.is_some() && op.step.is_remote())
+    }
+
+    // Fail (keeping the underlying error's category for the exit code) if any
+    // remote operation failed; local failures are only reported
+    pub fn into_result(self) -> Result<()> {
+        let mut remote_failures = self
+            .ops
+            .into_iter()
+            .filter(|op| op.step.is_remote())
+            .filter_map(|op| op.error);
+
+        match remote_failures.next() {
+            Some(first) => {
+                let count = 1 + remote_failures.count();
+                Err(first.context(format!(
+                    "{} Cloudflare operation(s) failed; the resources listed above may still exist. \
+                     Re-run with --retry or remove them in the Cloudflare dashboard.",
+                    count
+                )))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_only_remote_failures_fail_the_command() {
+        let dir = std::env::temp_dir().join(format!("ytunnel-teardown-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let blocker = dir.join("not-a-file");
+        std::fs::create_dir_all(&blocker).unwrap();
+
+        let mut report = Report::default();
+        // Already gone counts as removed; a directory can't be removed as a file
+        assert!(report.run(Step::RemoveFile(dir.join("missing"))).await);
+        assert!(!report.run(Step::RemoveFile(blocker.clone())).await);
+        assert!(!report.has_remote_failures());
+
+        assert!(
+            !report
+                .run(Step::DeleteTunnel {
+                    creds: None,
+                    tunnel_id: "id".to_string(),
+                })
+                .await
+        );
+        assert!(report.has_remote_failures());
+        assert_eq!(report.failed_count(), 2);
+        assert!(report.into_result().is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
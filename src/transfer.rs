@@ -0,0 +1,164 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config;
+
+// Bytes each tunnel has moved over its lifetime, kept in transfer.toml. cloudflared's
+// byte counters start over whenever it restarts, so every sample's increase is added
+// to a running total instead. Keyed by tunnel id, which stays put across renames and
+// accounts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TransferTotals {
+    #[serde(default)]
+    pub tunnels: BTreeMap<String, TransferTotal>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferTotal {
+    // Sent plus received, across every cloudflared run seen
+    pub bytes: u64,
+    // The counter value last added, to take the next delta from
+    pub last_sample: u64,
+}
+
+impl TransferTotal {
+    // Add a fresh reading of cloudflared's counter. A reading below the last one
+    // means cloudflared restarted, so all of it is new traffic. (Traffic between
+    // the last sample and the restart isn't seen.)
+    pub fn record(&mut self, current: u64) {
+        let delta = if current < self.last_sample {
+            current
+        } else {
+            current - self.last_sample
+        };
+        self.bytes = self.bytes.saturating_add(delta);
+        self.last_sample = current;
+    }
+}
+
+pub fn transfer_path() -> Result<PathBuf> {
+    Ok(config::config_dir()?.join("transfer.toml"))
+}
+
+impl TransferTotals {
+    pub fn load() -> Result<Self> {
+        let path = transfer_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = transfer_path()?;
+        crate::state::ensure_parent_dir(&path)?;
+        let contents =
+            toml::to_string_pretty(self).context("Failed to serialize transfer totals")?;
+        config::write_atomic(&path, &contents)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    // Lifetime bytes for a tunnel, if it has ever been sampled
+    pub fn lifetime(&self, tunnel_id: &str) -> Option<u64> {
+        self.tunnels.get(tunnel_id).map(|t| t.bytes)
+    }
+}
+
+// Add counter readings (tunnel id, bytes sent + received) to the saved totals and
+// return the updated totals. Load, update, and save happen under the config lock,
+// so the TUI, `status`, and `watch` sampling the same tunnel don't count it twice.
+// Nothing is written when no counter moved.
+pub fn record(samples: &[(&str, u64)]) -> Result<TransferTotals> {
+    let _lock = config::lock()?;
+    let mut totals = TransferTotals::load()?;
+    let mut changed = false;
+    for (tunnel_id, current) in samples {
+        let total = totals.tunnels.entry(tunnel_id.to_string()).or_default();
+        let before = *total;
+        total.record(*current);
+        changed |= *total != before;
+    }
+    if changed {
+        totals.save()?;
+    }
+    Ok(totals)
+}
+
+// Like record, but for the TUI: when another ytunnel process holds the config
+// lock, skip this sample (the next one catches up) and return the saved totals
+pub fn try_record(samples: &[(&str, u64)]) -> Option<TransferTotals> {
+    match config::try_lock() {
+        Ok(_lock) => record(samples).ok(),
+        Err(_) => TransferTotals::load().ok(),
+    }
+}
+
+// Drop a deleted tunnel's total (best-effort)
+pub fn forget(tunnel_id: &str) {
+    let Ok(_lock) = config::lock() else {
+        return;
+    };
+    if let Ok(mut totals) = TransferTotals::load() {
+        if totals.tunnels.remove(tunnel_id).is_some() {
+            totals.save().ok();
+        }
+    }
+}
+
+// Decimal units, e.g. "12.3 GB"
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KB", "MB", "GB", "TB", "PB"];
+    if bytes < 1000 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64;
+    let mut unit = "B";
+    for next in UNITS {
+        if value < 1000.0 {
+            break;
+        }
+        value /= 1000.0;
+        unit = next;
+    }
+    format!("{:.1} {}", value, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_survives_counter_resets() {
+        let mut total = TransferTotal::default();
+        total.record(500);
+        total.record(1_500);
+        assert_eq!(total.bytes, 1_500);
+        // Same reading again adds nothing
+        total.record(1_500);
+        assert_eq!(total.bytes, 1_500);
+        // cloudflared restarted: its counter began again at 0
+        total.record(200);
+        assert_eq!(total.bytes, 1_700);
+        total.record(700);
+        assert_eq!(
+            total,
+            TransferTotal {
+                bytes: 2_200,
+                last_sample: 700
+            }
+        );
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(999), "999 B");
+        assert_eq!(format_bytes(1_000), "1.0 KB");
+        assert_eq!(format_bytes(12_345_678_901), "12.3 GB");
+    }
+}
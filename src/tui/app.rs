@@ -1,8 +1,9 @@
 use anyhow::Result;
 use crossterm::{
+    cursor,
     event::{
-        self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEventKind,
-        KeyModifiers,
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, Event, KeyCode,
+        KeyEventKind, KeyModifiers,
     },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -14,13 +15,21 @@ use std::time::Duration;
 use rand::Rng;
 use std::collections::HashMap;
 
-use crate::cloudflare;
-use crate::config;
-use crate::config::Account;
 use crate::daemon;
-use crate::metrics::TunnelMetrics;
-use crate::state::{write_tunnel_config, PersistentTunnel, TunnelState, TunnelStatus};
+use crate::health;
+use crate::tunnel;
+use ytunnel::cloudflare;
+use ytunnel::config;
+use ytunnel::config::Account;
+use ytunnel::error;
+use ytunnel::idn;
+use ytunnel::metrics::{EdgeLocation, TunnelMetrics};
+use ytunnel::state::{
+    normalize_description, write_tunnel_config, PersistentTunnel, TunnelState, TunnelStatus,
+};
+use ytunnel::transfer;
 
+use super::theme::Theme;
 use super::ui;
 
 // Check if a key event is a cancel key (Esc or Ctrl+C)
@@ -32,45 +41,82 @@ fn is_cancel_key(key: &crossterm::event::KeyEvent) -> bool {
         || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
 }
 
+// TunnelState::update for the TUI: fails at once when another ytunnel holds the
+// lock, instead of waiting (and printing over the screen)
+fn update_state<T>(change: impl FnOnce(&mut TunnelState) -> Result<T>) -> Result<T> {
+    let _lock = config::try_lock()?;
+    TunnelState::update(change)
+}
+
+// The log level after `current` for the TUI's `v`: cloudflared's default (None),
+// then each of LOG_LEVELS, then the default again
+fn next_log_level(current: Option<&str>) -> Option<String> {
+    let next = match current {
+        None => 0,
+        Some(level) => config::LOG_LEVELS.iter().position(|&l| l == level)? + 1,
+    };
+    config::LOG_LEVELS.get(next).map(|l| l.to_string())
+}
+
 // Standalone async operation: start a tunnel (doesn't borrow App)
 async fn start_tunnel_op(
     name: String,
     account_name: String,
     tunnel: PersistentTunnel,
 ) -> Result<String> {
-    // Ensure DNS record exists (recreates if manually deleted)
+    // Ensure DNS record exists (recreates if manually deleted), unless it was
+    // verified recently
     let cfg = config::load_config()?;
+    let now = crate::now_secs();
+    let mut dns_checked = false;
     if let Some(acct) = cfg.accounts.iter().find(|a| a.name == account_name) {
-        let client = cloudflare::Client::new(&acct.api_token);
-        client
-            .ensure_dns_record(&tunnel.zone_id, &tunnel.hostname, &tunnel.tunnel_id)
-            .await?;
+        if !tunnel.dns_recently_verified(now) {
+            for (zone_id, hostname) in tunnel.managed_dns_routes() {
+                // The zone may belong to another configured account
+                cfg.client(cfg.dns_account(acct, zone_id))
+                    .ensure_dns_record(zone_id, hostname, &tunnel.tunnel_id)
+                    .await?;
+            }
+            dns_checked = true;
+        }
     }
 
     write_tunnel_config(&tunnel)?;
     daemon::install_daemon(&tunnel).await?;
     daemon::start_daemon(&name, &account_name).await?;
 
-    let mut state = TunnelState::load()?;
-    if let Some(t) = state.find_mut(&name) {
-        t.enabled = true;
-    }
-    state.save()?;
+    update_state(|state| {
+        if let Some(t) = state.find_for_account_mut(&name, &account_name) {
+            t.enabled = true;
+            if dns_checked {
+                t.mark_dns_verified(now);
+            }
+        }
+        Ok(())
+    })?;
+
+    daemon::verify_daemon_started(&tunnel).await?;
 
     Ok(name)
 }
 
 // Standalone async operation: stop a tunnel
-async fn stop_tunnel_op(name: String, account_name: String) -> Result<String> {
-    daemon::stop_daemon(&name, &account_name).await?;
+async fn stop_tunnel_op(tunnel: PersistentTunnel) -> Result<String> {
+    let strays = daemon::stop_daemon_fully(&tunnel, false).await?;
 
-    let mut state = TunnelState::load()?;
-    if let Some(t) = state.find_mut(&name) {
-        t.enabled = false;
-    }
-    state.save()?;
+    update_state(|state| {
+        if let Some(t) = state.find_for_account_mut(&tunnel.name, &tunnel.account_name) {
+            t.enabled = false;
+        }
+        Ok(())
+    })?;
 
-    Ok(name)
+    // Mention leftover cloudflared processes in the status bar
+    let mut message = format!("Stopped {}", tunnel.name);
+    for line in daemon::describe_strays(&strays) {
+        message.push_str(&format!(" ({})", line));
+    }
+    Ok(message)
 }
 
 // Standalone async operation: restart a tunnel
@@ -79,71 +125,83 @@ async fn restart_tunnel_op(
     account_name: String,
     tunnel: PersistentTunnel,
 ) -> Result<String> {
-    daemon::stop_daemon(&name, &account_name).await.ok();
-
-    // Ensure DNS record exists (recreates if manually deleted)
+    daemon::stop_daemon_fully(&tunnel, false).await.ok();
+    // Ensure DNS record exists (recreates if manually deleted), unless it was
+    // verified recently
     let cfg = config::load_config()?;
+    let now = crate::now_secs();
+    let mut dns_checked = false;
     if let Some(acct) = cfg.accounts.iter().find(|a| a.name == account_name) {
-        let client = cloudflare::Client::new(&acct.api_token);
-        client
-            .ensure_dns_record(&tunnel.zone_id, &tunnel.hostname, &tunnel.tunnel_id)
-            .await?;
+        if !tunnel.dns_recently_verified(now) {
+            for (zone_id, hostname) in tunnel.managed_dns_routes() {
+                // The zone may belong to another configured account
+                cfg.client(cfg.dns_account(acct, zone_id))
+                    .ensure_dns_record(zone_id, hostname, &tunnel.tunnel_id)
+                    .await?;
+            }
+            dns_checked = true;
+        }
     }
 
     daemon::install_daemon(&tunnel).await?;
     daemon::start_daemon(&name, &account_name).await?;
 
-    let mut state = TunnelState::load()?;
-    if let Some(t) = state.find_mut(&name) {
-        t.enabled = true;
-    }
-    state.save()?;
+    update_state(|state| {
+        if let Some(t) = state.find_for_account_mut(&name, &account_name) {
+            t.enabled = true;
+            if dns_checked {
+                t.mark_dns_verified(now);
+            }
+        }
+        Ok(())
+    })?;
+
+    daemon::verify_daemon_started(&tunnel).await?;
 
     Ok(name)
 }
 
-// Standalone async operation: create a new tunnel
+// Cloudflare client honoring the configured API timeout (default if config can't be read)
+fn api_client(account: &Account) -> cloudflare::Client {
+    match config::load_config() {
+        Ok(cfg) => cfg.client(account),
+        Err(_) => cloudflare::Client::for_account(account),
+    }
+}
+
+// Standalone async operation: create a new tunnel, copying settings from
+// `template` when cloning
 async fn create_tunnel_op(
     name: String,
     target: String,
     zone: config::ZoneConfig,
     account: Account,
+    template: Option<PersistentTunnel>,
 ) -> Result<(String, PersistentTunnel)> {
-    let client = cloudflare::Client::new(&account.api_token);
+    let client = api_client(&account);
 
-    let tunnel_name = format!("ytunnel-{}", name);
+    let tunnel_name = config::cloudflare_tunnel_name(&name);
     let hostname = format!("{}.{}", name, zone.name);
 
     // Check if tunnel exists, create if not
-    let (tunnel, _credentials_path) = match client
-        .get_tunnel_by_name(&account.account_id, &tunnel_name)
+    let tunnel = client
+        .create_or_get_tunnel(&account.account_id, &tunnel_name)
         .await?
-    {
-        Some(t) => {
-            let creds_path = t.credentials_path()?;
-            if !creds_path.exists() {
-                anyhow::bail!("Credentials missing for existing tunnel");
-            }
-            (t, creds_path)
-        }
-        None => {
-            let result = client
-                .create_tunnel(&account.account_id, &tunnel_name)
-                .await?;
-            (result.tunnel, result.credentials_path)
-        }
-    };
+        .tunnel;
 
-    // Ensure DNS record exists
-    client
-        .ensure_dns_record(&zone.id, &hostname, &tunnel.id)
-        .await?;
+    // Ensure DNS record exists (unless the source leaves DNS to someone else)
+    let manage_dns = template.as_ref().is_none_or(|t| t.manage_dns);
+    if manage_dns {
+        client
+            .ensure_dns_record(&zone.id, &hostname, &tunnel.id)
+            .await?;
+    }
 
     // Create persistent tunnel
-    let persistent = PersistentTunnel {
+    let mut persistent = PersistentTunnel {
         name: name.clone(),
         account_name: account.name.clone(),
-        target,
+        target: String::new(),
         zone_id: zone.id,
         zone_name: zone.name,
         hostname,
@@ -151,7 +209,26 @@ async fn create_tunnel_op(
         enabled: true,
         auto_start: false,
         metrics_port: None,
+        protocol: None,
+        log_level: None,
+        extra_hostnames: Vec::new(),
+        manage_dns,
+        description: None,
+        connect_time_ms: None,
+        maintenance: None,
+        fallback: None,
+        backends: Vec::new(),
+        dns_verified: None,
     };
+    persistent.set_target(&target)?;
+    // The metrics port and extra hostnames would collide with the source's
+    if let Some(src) = template {
+        persistent.auto_start = src.auto_start;
+        persistent.protocol = src.protocol;
+        persistent.log_level = src.log_level;
+        persistent.description = src.description;
+        persistent.fallback = src.fallback;
+    }
 
     // Write tunnel config
     write_tunnel_config(&persistent)?;
@@ -160,12 +237,14 @@ async fn create_tunnel_op(
     daemon::install_daemon(&persistent).await?;
 
     // Save to state
-    let mut state = TunnelState::load()?;
-    state.add(persistent.clone());
-    state.save()?;
+    update_state(|state| {
+        state.add(persistent.clone());
+        Ok(())
+    })?;
 
     // Start the daemon
     daemon::start_daemon(&name, &account.name).await?;
+    daemon::verify_daemon_started(&persistent).await?;
 
     Ok((name, persistent))
 }
@@ -178,7 +257,7 @@ async fn import_tunnel_op(
     tunnel_id: String,
     account: Account,
 ) -> Result<String> {
-    let client = cloudflare::Client::new(&account.api_token);
+    let client = api_client(&account);
     let hostname = format!("{}.{}", name, zone.name);
 
     // Ensure DNS record exists
@@ -198,6 +277,16 @@ async fn import_tunnel_op(
         enabled: true,
         auto_start: false,
         metrics_port: None,
+        protocol: None,
+        log_level: None,
+        extra_hostnames: Vec::new(),
+        manage_dns: true,
+        description: None,
+        connect_time_ms: None,
+        maintenance: None,
+        fallback: None,
+        backends: Vec::new(),
+        dns_verified: None,
     };
 
     // Write tunnel config
@@ -207,12 +296,14 @@ async fn import_tunnel_op(
     daemon::install_daemon(&persistent).await?;
 
     // Save to state
-    let mut state = TunnelState::load()?;
-    state.add(persistent);
-    state.save()?;
+    update_state(|state| {
+        state.add(persistent.clone());
+        Ok(())
+    })?;
 
     // Start the daemon
     daemon::start_daemon(&name, &account.name).await?;
+    daemon::verify_daemon_started(&persistent).await?;
 
     Ok(name)
 }
@@ -222,6 +313,7 @@ async fn import_tunnel_op(
 async fn edit_tunnel_op(
     name: String,
     new_target: String,
+    new_description: Option<String>,
     new_zone: config::ZoneConfig,
     original_zone_id: String,
     original_hostname: String,
@@ -229,14 +321,17 @@ async fn edit_tunnel_op(
     was_running: bool,
     account: Account,
 ) -> Result<String> {
-    let client = cloudflare::Client::new(&account.api_token);
+    let client = api_client(&account);
 
     // Compute new hostname
     let new_hostname = format!("{}.{}", name, new_zone.name);
     let zone_changed = new_zone.id != original_zone_id;
 
-    // If zone changed, handle DNS records
-    if zone_changed {
+    // If zone changed, move the DNS record (unless it is managed elsewhere)
+    let manage_dns = TunnelState::load()?
+        .find_for_account(&name, &account.name)
+        .is_none_or(|t| t.manage_dns);
+    if zone_changed && manage_dns {
         // Delete old DNS record
         client
             .delete_dns_record(&original_zone_id, &original_hostname)
@@ -250,17 +345,20 @@ async fn edit_tunnel_op(
     }
 
     // Update state
-    let mut state = TunnelState::load()?;
-    if let Some(tunnel) = state.find_mut(&name) {
-        tunnel.target = new_target;
+    let updated = update_state(|state| {
+        let Some(tunnel) = state.find_for_account_mut(&name, &account.name) else {
+            return Ok(None);
+        };
+        tunnel.set_target(&new_target)?;
+        tunnel.description = new_description;
         tunnel.zone_id = new_zone.id;
         tunnel.zone_name = new_zone.name;
         tunnel.hostname = new_hostname;
-    }
-    state.save()?;
+        Ok(Some(tunnel.clone()))
+    })?;
 
     // Regenerate config YAML
-    if let Some(tunnel) = state.find(&name) {
+    if let Some(tunnel) = &updated {
         write_tunnel_config(tunnel)?;
 
         // Reinstall daemon with updated config
@@ -269,12 +367,99 @@ async fn edit_tunnel_op(
         // Restart daemon if it was running
         if was_running {
             daemon::start_daemon(&name, &account.name).await?;
+            daemon::verify_daemon_started(tunnel).await?;
         }
     }
 
     Ok(name)
 }
 
+// Standalone async operation: confirm the managed DNS records of running tunnels
+// still point at them. Clean results are recorded so the next start skips the
+// check; a tunnel with a bad record loses its verification, so restarting it
+// repairs the record. Returns the problems to show, or None if Cloudflare
+// couldn't be asked.
+async fn check_dns_op(tunnels: Vec<PersistentTunnel>) -> Result<Option<Vec<String>>> {
+    let cfg = config::load_config()?;
+    let now = crate::now_secs();
+    let mut problems = Vec::new();
+    let mut results = Vec::new();
+    for t in &tunnels {
+        let Some(acct) = cfg.accounts.iter().find(|a| a.name == t.account_name) else {
+            continue;
+        };
+        let expected = t.cname_target();
+        let mut verified = true;
+        for (zone_id, hostname) in t.managed_dns_routes() {
+            let record = cfg
+                .client(cfg.dns_account(acct, zone_id))
+                .get_dns_record(zone_id, hostname)
+                .await;
+            let problem = match record {
+                Ok(Some(record)) if record.content == expected => continue,
+                Ok(Some(record)) => format!("points at {}", record.content),
+                Ok(None) => "has no DNS record".to_string(),
+                Err(_) => return Ok(None),
+            };
+            verified = false;
+            problems.push(format!(
+                "{} {} — select '{}' and press r to repair",
+                idn::to_unicode(hostname),
+                problem,
+                t.name
+            ));
+        }
+        results.push((t.account_name.clone(), t.name.clone(), verified));
+    }
+
+    update_state(|state| {
+        for (account_name, name, verified) in &results {
+            if let Some(t) = state.find_for_account_mut(name, account_name) {
+                if *verified {
+                    t.mark_dns_verified(now);
+                } else {
+                    t.dns_verified = None;
+                }
+            }
+        }
+        Ok(())
+    })?;
+    Ok(Some(problems))
+}
+
+// Standalone async operation: delete the given ephemeral tunnels with their DNS
+// records (as `ytunnel clean-ephemeral`), skipping any that came back into use since
+// they were listed; returns how many went
+async fn clean_ephemeral_op(
+    accounts: Vec<Account>,
+    tunnel_ids: std::collections::HashSet<String>,
+) -> Result<usize> {
+    let cfg = config::load_config()?;
+    let state = TunnelState::load()?;
+    let mut removed = 0;
+    for acct in &accounts {
+        let idle: Vec<_> = tunnel::find_idle_ephemeral(&api_client(acct), acct, &state)
+            .await?
+            .into_iter()
+            .filter(|t| tunnel_ids.contains(&t.id))
+            .collect();
+        if idle.is_empty() {
+            continue;
+        }
+        let records = tunnel::tunnel_dns_records(&cfg, acct).await?;
+        for t in idle {
+            let own: Vec<_> = records
+                .iter()
+                .filter(|r| r.tunnel_id() == Some(t.id.as_str()))
+                .cloned()
+                .collect();
+            tunnel::remove_ephemeral(&cfg, acct, &t.id, &own).await?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
 // Standalone async operation: delete a tunnel
 async fn delete_tunnel_op(
     name: String,
@@ -286,12 +471,25 @@ async fn delete_tunnel_op(
     if is_ephemeral {
         // Ephemeral tunnel: just delete from Cloudflare
         if let (Some(acct), Some(tid)) = (account, tunnel_id) {
-            let client = cloudflare::Client::new(&acct.api_token);
+            let live = tunnel::ephemeral_configs(&tid).iter().any(|c| c.is_live());
+            if let (false, Ok(cfg)) = (live, config::load_config()) {
+                // Kept for reuse, or left behind by a run that didn't exit cleanly:
+                // also remove its DNS
+                // records, as the run would have
+                let leak = tunnel::LeakedRun {
+                    tunnel_id: tid,
+                    tunnel_name: config::cloudflare_tunnel_name(&name),
+                    hostname: None,
+                };
+                tunnel::clean_up_leaked_run(&cfg, &acct, &leak).await.ok();
+                return Ok(name);
+            }
+            let client = api_client(&acct);
             client.delete_tunnel(&acct.account_id, &tid).await.ok();
 
             // Remove credentials file if it exists
-            let config_dir = crate::config::config_dir()?;
-            let creds_path = config_dir.join(format!("{}.json", tid));
+            let credentials_dir = ytunnel::config::credentials_dir()?;
+            let creds_path = credentials_dir.join(format!("{}.json", tid));
             std::fs::remove_file(&creds_path).ok();
         }
     } else {
@@ -299,18 +497,21 @@ async fn delete_tunnel_op(
         daemon::stop_daemon(&name, &account_name).await?;
         daemon::uninstall_daemon(&name, &account_name).await?;
 
-        // Remove from state and get tunnel info
-        let mut state = TunnelState::load()?;
-        if let Some(tunnel) = state.remove(&name) {
+        // Get tunnel info, and remove it from state once it's cleaned up
+        let state = TunnelState::load()?;
+        if let Some(tunnel) = state.find_for_account(&name, &account_name) {
             // Delete from Cloudflare
             if let Some(acct) = account {
-                let client = cloudflare::Client::new(&acct.api_token);
-
-                // Delete the DNS CNAME record
-                client
-                    .delete_dns_record(&tunnel.zone_id, &tunnel.hostname)
-                    .await
-                    .ok();
+                // Delete the DNS CNAME records (possibly in another account's zones)
+                let cfg = config::load_config().ok();
+                let client = api_client(&acct);
+                for (zone_id, hostname) in tunnel.managed_dns_routes() {
+                    let dns_client = match &cfg {
+                        Some(cfg) => cfg.client(cfg.dns_account(&acct, zone_id)),
+                        None => cloudflare::Client::for_account(&acct),
+                    };
+                    dns_client.delete_dns_record(zone_id, hostname).await.ok();
+                }
 
                 client
                     .delete_tunnel(&acct.account_id, &tunnel.tunnel_id)
@@ -318,81 +519,123 @@ async fn delete_tunnel_op(
                     .ok();
             }
 
-            // Remove credentials file
-            if let Ok(creds_path) = tunnel.credentials_path() {
-                std::fs::remove_file(&creds_path).ok();
-            }
-
-            // Remove config file
-            if let Ok(config_path) = tunnel.config_path() {
-                std::fs::remove_file(&config_path).ok();
-            }
-
-            // Remove log file
-            if let Ok(log_path) = tunnel.log_path() {
-                std::fs::remove_file(&log_path).ok();
-            }
+            // Remove credentials, config, and log files
+            tunnel.remove_local_files();
         }
-        state.save()?;
+        update_state(|state| {
+            state.remove_for_account(&name, &account_name);
+            Ok(())
+        })?;
     }
 
     Ok(name)
 }
 
-// Parse an ephemeral tunnel's config file to extract hostname and target
-fn parse_ephemeral_config(tunnel_id: &str) -> Option<(String, String)> {
-    let config_dir = crate::config::config_dir().ok()?;
-    let config_path = config_dir.join(format!("tunnel-{}.yml", tunnel_id));
-
-    if !config_path.exists() {
-        return None;
-    }
-
-    let content = std::fs::read_to_string(&config_path).ok()?;
-
-    // Parse simple YAML - look for hostname and service lines
-    // Format is:
-    //   ingress:
-    //     - hostname: example.com
-    //       service: http://localhost:8080
-    //     - service: http_status:404
-    let mut hostname = None;
-    let mut service = None;
-
-    for line in content.lines() {
-        // Strip leading whitespace and list marker
-        let line = line.trim().trim_start_matches('-').trim();
-
-        if line.starts_with("hostname:") {
-            hostname = line.strip_prefix("hostname:").map(|s| s.trim().to_string());
-        } else if line.starts_with("service:") {
-            let svc = line.strip_prefix("service:").map(|s| s.trim().to_string());
-            // Skip the fallback http_status:404 service, take first real service
-            if let Some(ref s) = svc {
-                if !s.contains("http_status") && service.is_none() {
-                    service = svc;
-                }
-            }
-        }
-    }
-
-    match (hostname, service) {
-        (Some(h), Some(s)) => Some((h, s)),
-        _ => None,
-    }
-}
-
-// Input mode for the TUI
-#[derive(Debug, Clone, PartialEq, Eq)]
+// Input mode for the TUI. The add/import/edit dialogs carry what their earlier
+// steps entered, so a step can't be reached without the values it needs.
+#[derive(Debug, Clone)]
 pub enum InputMode {
     Normal,
-    AddName,
-    AddTarget,
-    AddZone,
-    EditTarget,
-    EditZone,
+    Add(AddFlow),
+    Import(ImportFlow),
+    Edit(EditFlow),
     Confirm,
     Help,
+    // Entering a replacement API token (masked) for token_account
+    TokenEntry,
+    // Typing the `/` filter (applied as you type)
+    Filter,
+    // Paths and ids of the selected tunnel (`i`), one of them selected for copying
+    Info,
+    // `c` pressed: waiting for u/t/h/i to pick what to copy
+    Copy,
+}
+
+// Add (or `C` clone) dialog
+#[derive(Debug, Clone)]
+pub struct AddFlow {
+    pub step: AddStep,
+    // Tunnel whose settings the new one copies (boxed: most adds aren't clones)
+    pub clone_source: Option<Box<PersistentTunnel>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddStep {
+    Name,
+    Target {
+        name: String,
+    },
+    Zone {
+        name: String,
+        target: String,
+        selected: usize,
+    },
+}
+
+// Import dialog for an ephemeral tunnel whose target or zone isn't known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportFlow {
+    pub name: String,
+    pub tunnel_id: String,
+    pub step: ImportStep,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportStep {
+    Target,
+    Zone { target: String, selected: usize },
+}
+
+// Edit dialog (target -> description -> zone)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditFlow {
+    pub name: String,
+    // Where the tunnel's DNS record is now (cleaned up if the zone changes)
+    pub original_zone_id: String,
+    pub original_hostname: String,
+    pub step: EditStep,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditStep {
+    Target,
+    Description {
+        target: String,
+    },
+    // description: None clears it
+    Zone {
+        target: String,
+        description: Option<String>,
+        selected: usize,
+    },
+}
+
+// What a dialog hands over once its zone is picked
+#[derive(Debug)]
+pub enum FlowRequest {
+    Create {
+        name: String,
+        target: String,
+        zone: config::ZoneConfig,
+        account: Account,
+        template: Option<Box<PersistentTunnel>>,
+    },
+    Import {
+        name: String,
+        target: String,
+        zone: config::ZoneConfig,
+        tunnel_id: String,
+        account: Account,
+    },
+    Edit {
+        name: String,
+        target: String,
+        description: Option<String>,
+        zone: config::ZoneConfig,
+        original_zone_id: String,
+        original_hostname: String,
+        account: Account,
+    },
 }
 
 // Whether a tunnel is managed (persistent) or ephemeral
@@ -435,6 +678,13 @@ impl Spinner {
         }
     }
 
+    pub fn ascii() -> Self {
+        Self {
+            frames: vec!['|', '/', '-', '\\'],
+            ..Self::new()
+        }
+    }
+
     // Start the spinner with a message
     pub fn start(&mut self, message: &str) {
         self.message = Some(message.to_string());
@@ -466,6 +716,15 @@ impl Spinner {
     }
 }
 
+// Lines loaded when a tunnel's logs are first shown
+const LOG_INITIAL_LINES: usize = 500;
+// Maximum log lines retained while following
+const LOG_MAX_LINES: usize = 5000;
+// Lines moved per PageUp/PageDown in the log panel
+const LOG_PAGE_LINES: usize = 10;
+// Percent of the width `<`/`>` move the list/details split by
+const SPLIT_STEP: u16 = 5;
+
 // Historical metrics for sparkline display
 #[derive(Debug, Clone, Default)]
 pub struct MetricsHistory {
@@ -498,6 +757,29 @@ impl MetricsHistory {
         self.last_total = total_requests;
     }
 
+    // ASCII stand-in for the sparkline: the latest sample against the window's
+    // peak, e.g. "[#####---] 42/67"
+    pub fn gauge(&self) -> String {
+        let Some(&last) = self.request_samples.last() else {
+            return String::new();
+        };
+        let max = self
+            .request_samples
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let filled = ((last as f64 / max as f64) * 8.0).round() as usize;
+        format!(
+            "[{}{}] {}/{}",
+            "#".repeat(filled),
+            "-".repeat(8 - filled),
+            last,
+            max
+        )
+    }
+
     // Generate sparkline string using Unicode blocks
     pub fn sparkline(&self) -> String {
         if self.request_samples.is_empty() {
@@ -521,6 +803,132 @@ impl MetricsHistory {
     }
 }
 
+// Recent health check results (oldest first), to draw a timeline and to notice an
+// origin that keeps going up and down
+#[derive(Debug, Clone, Default)]
+pub struct HealthHistory {
+    // When each check ran and whether the tunnel was reachable
+    pub samples: Vec<(std::time::Instant, bool)>,
+    // Flapping was announced and hasn't settled yet
+    pub flapping: bool,
+}
+
+// What a new health result means for notifications
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthChange {
+    // Same as before (or the first result)
+    Steady,
+    Down,
+    Up,
+    // Too many changes within the window: one notice, then quiet
+    StartedFlapping,
+    // A change while flapping, not announced
+    Suppressed,
+    // A whole window without changes after flapping; carries whether it's up
+    Settled(bool),
+}
+
+impl HealthHistory {
+    const MAX_SAMPLES: usize = 30;
+    // More than this many up/down changes within FLAP_WINDOW counts as flapping
+    const FLAP_TRANSITIONS: usize = 3;
+    const FLAP_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+    pub fn record(&mut self, healthy: bool, now: std::time::Instant) -> HealthChange {
+        let previous = self.samples.last().map(|&(_, up)| up);
+        self.samples.push((now, healthy));
+        if self.samples.len() > Self::MAX_SAMPLES {
+            self.samples.remove(0);
+        }
+
+        let changed = previous.is_some_and(|up| up != healthy);
+        let recent = self.transitions_since(now.checked_sub(Self::FLAP_WINDOW));
+        if self.flapping {
+            if recent == 0 {
+                self.flapping = false;
+                return HealthChange::Settled(healthy);
+            }
+            return if changed {
+                HealthChange::Suppressed
+            } else {
+                HealthChange::Steady
+            };
+        }
+        if recent > Self::FLAP_TRANSITIONS {
+            self.flapping = true;
+            return HealthChange::StartedFlapping;
+        }
+        match (changed, healthy) {
+            (false, _) => HealthChange::Steady,
+            (true, true) => HealthChange::Up,
+            (true, false) => HealthChange::Down,
+        }
+    }
+
+    // Up/down changes whose later check ran at or after `since` (all of them if None)
+    fn transitions_since(&self, since: Option<std::time::Instant>) -> usize {
+        self.samples
+            .windows(2)
+            .filter(|pair| pair[0].1 != pair[1].1 && since.is_none_or(|s| pair[1].0 >= s))
+            .count()
+    }
+}
+
+// Incremental reader for a growing log file that remembers its last offset
+#[derive(Debug)]
+pub struct LogTail {
+    path: std::path::PathBuf,
+    offset: u64,
+    // Trailing text without a newline yet, completed on the next read
+    partial: String,
+}
+
+impl LogTail {
+    // Start following from the current end of the file
+    pub fn new(path: std::path::PathBuf) -> Self {
+        let offset = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Self {
+            path,
+            offset,
+            partial: String::new(),
+        }
+    }
+
+    // Read lines appended since the last call. Returns None if the file was
+    // truncated or replaced, in which case the offset restarts at zero.
+    pub fn read_new(&mut self) -> io::Result<Option<Vec<String>>> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        let mut file = match std::fs::File::open(&self.path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Some(Vec::new())),
+            Err(e) => return Err(e),
+        };
+
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            self.offset = 0;
+            self.partial.clear();
+            return Ok(None);
+        }
+        if len == self.offset {
+            return Ok(Some(Vec::new()));
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        self.offset += buf.len() as u64;
+
+        self.partial.push_str(&String::from_utf8_lossy(&buf));
+        let mut lines: Vec<String> = self.partial.split('\n').map(String::from).collect();
+        // The last piece is either empty (text ended with a newline) or incomplete
+        self.partial = lines.pop().unwrap_or_default();
+
+        Ok(Some(lines))
+    }
+}
+
 // A tunnel entry with its runtime status
 #[derive(Debug, Clone)]
 pub struct TunnelEntry {
@@ -529,7 +937,14 @@ pub struct TunnelEntry {
     pub kind: TunnelKind,
     pub metrics: Option<TunnelMetrics>,
     pub metrics_history: MetricsHistory,
+    pub health_history: HealthHistory,
+    // Bytes moved over the tunnel's lifetime (transfer.toml), once it's been sampled
+    pub lifetime_bytes: Option<u64>,
     pub health: HealthStatus,
+    // PID/uptime/memory, fetched lazily while the tunnel is selected
+    pub daemon_info: Option<daemon::DaemonInfo>,
+    // Cloudflare's view of an ephemeral tunnel (status, edge connections)
+    pub remote: Option<cloudflare::Tunnel>,
 }
 
 // Application state
@@ -542,16 +957,12 @@ pub struct App {
     pub selected: usize,
     // Log lines for the selected tunnel
     pub logs: Vec<String>,
-    // Input buffer for add dialog
+    // Input buffer for the dialogs' text fields
     pub input: String,
-    // Temporary storage for new tunnel name during add flow
-    pub new_tunnel_name: Option<String>,
-    // Temporary storage for new tunnel target during add flow
-    pub new_tunnel_target: Option<String>,
     // Available zones for selection
     pub zones: Vec<config::ZoneConfig>,
-    // Selected zone index during add flow
-    pub zone_selected: usize,
+    // Selected row in the info modal
+    pub info_selected: usize,
     // Confirmation message
     pub confirm_message: Option<String>,
     // Action to perform on confirmation
@@ -562,28 +973,71 @@ pub struct App {
     pub should_quit: bool,
     // Config loaded
     pub config: Option<config::Config>,
-    // Whether we're importing (vs adding) a tunnel
-    pub is_importing: bool,
     // Available accounts
     pub accounts: Vec<Account>,
     // Selected account index
     pub selected_account_idx: usize,
-    // Name of tunnel being edited (for edit flow)
-    pub editing_tunnel_name: Option<String>,
-    // Original zone ID (for DNS cleanup if zone changes during edit)
-    pub original_zone_id: Option<String>,
-    // Original hostname (for DNS cleanup if zone changes during edit)
-    pub original_hostname: Option<String>,
     // Spinner for async operations
     pub spinner: Spinner,
     // Demo mode flag (synthetic data, no real API calls)
     pub demo: bool,
+    // Persistent warning shown above the layout (bad token, missing cloudflared)
+    pub warning_banner: Option<String>,
+    // Whether `cloudflared --version` ran. Checked once it's found; until then
+    // every refresh checks again, so the banner clears once it's installed.
+    pub cloudflared_installed: Option<bool>,
+    // Live tail of the selected tunnel's log file (None when not following)
+    pub log_tail: Option<LogTail>,
+    // Lines scrolled up from the bottom of the log panel (0 = following)
+    pub log_scroll: usize,
+    // Show tunnels from every account in one list instead of just the current one
+    pub all_accounts: bool,
+    // Account of the tunnel being edited/imported (may differ from the current
+    // account in the all-accounts view)
+    pub flow_account_name: Option<String>,
+    // Show only the selected tunnel full-screen (Enter to zoom in, Esc to return)
+    pub focused: bool,
+    // Show only the selected tunnel's logs (`l`); on terminals too narrow for the
+    // split view this is where the logs are
+    pub log_view: bool,
+    // Account whose rejected token is being replaced in the token dialog
+    pub token_account: Option<String>,
+    // Accounts the token dialog already opened for on its own (once per session)
+    pub token_prompted: Vec<String>,
+    // Accounts whose credentials Cloudflare rejected on the last refresh
+    pub rejected_accounts: Vec<String>,
+    // Managed hostnames the last background DNS check found missing or pointing
+    // elsewhere, shown in the warning banner
+    pub dns_problems: Vec<String>,
+    // The DNS check running in the background, if any
+    pub dns_check: Option<tokio::task::JoinHandle<Result<Option<Vec<String>>>>>,
+    // `/` filter; only matching tunnels are listed (empty shows all)
+    pub filter: String,
+    // Plain ASCII without colors, for terminals that can't show more
+    pub ascii: bool,
+    // Colors for the light/dark/high-contrast terminal it runs in
+    pub theme: Theme,
+    // Share of the split view's width given to the tunnel list (`<`/`>`)
+    pub list_percent: u16,
+    // Show the metrics panel (`M`)
+    pub show_metrics: bool,
+    // The layout changed while another ytunnel held the config lock; saved with
+    // the next change, or on quit
+    pub layout_unsaved: bool,
+    // Show log lines exactly as cloudflared wrote them instead of formatting its
+    // JSON output (`L`)
+    pub raw_logs: bool,
 }
 
 // Actions that require confirmation
 #[derive(Debug, Clone)]
 pub enum PendingAction {
-    Delete(String),
+    // (tunnel name, account name)
+    Delete(String, String),
+    // Import the selected ephemeral tunnel
+    Import,
+    // Delete these unused ephemeral tunnels: (account name, tunnel ID)
+    CleanEphemeral(Vec<(String, String)>),
 }
 
 impl App {
@@ -603,6 +1057,7 @@ impl App {
         } else {
             (None, Vec::new(), 0)
         };
+        let layout = config.as_ref().map(|c| c.tui.clone()).unwrap_or_default();
 
         Self {
             input_mode: InputMode::Normal,
@@ -610,23 +1065,37 @@ impl App {
             selected: 0,
             logs: vec!["Select a tunnel to view logs".to_string()],
             input: String::new(),
-            new_tunnel_name: None,
-            new_tunnel_target: None,
             zones: Vec::new(),
-            zone_selected: 0,
+            info_selected: 0,
             confirm_message: None,
             pending_action: None,
             status_message: None,
             should_quit: false,
             config,
-            is_importing: false,
             accounts,
             selected_account_idx,
-            editing_tunnel_name: None,
-            original_zone_id: None,
-            original_hostname: None,
             spinner: Spinner::new(),
             demo: false,
+            warning_banner: None,
+            cloudflared_installed: None,
+            log_tail: None,
+            log_scroll: 0,
+            all_accounts: false,
+            flow_account_name: None,
+            focused: false,
+            log_view: false,
+            token_account: None,
+            token_prompted: Vec::new(),
+            rejected_accounts: Vec::new(),
+            dns_problems: Vec::new(),
+            dns_check: None,
+            filter: String::new(),
+            ascii: false,
+            theme: Theme::DARK,
+            list_percent: layout.list_percent,
+            show_metrics: layout.show_metrics,
+            layout_unsaved: false,
+            raw_logs: false,
         }
     }
 
@@ -635,6 +1104,8 @@ impl App {
         let demo_account = Account {
             name: "demo".to_string(),
             api_token: String::new(),
+            api_key: None,
+            email: None,
             account_id: String::new(),
             default_zone_id: "zone-1".to_string(),
             default_zone_name: "example.com".to_string(),
@@ -656,23 +1127,37 @@ impl App {
             selected: 0,
             logs: vec!["Select a tunnel to view logs".to_string()],
             input: String::new(),
-            new_tunnel_name: None,
-            new_tunnel_target: None,
             zones: demo_account.zones.clone(),
-            zone_selected: 0,
+            info_selected: 0,
             confirm_message: None,
             pending_action: None,
             status_message: None,
             should_quit: false,
             config: None,
-            is_importing: false,
             accounts: vec![demo_account],
             selected_account_idx: 0,
-            editing_tunnel_name: None,
-            original_zone_id: None,
-            original_hostname: None,
             spinner: Spinner::new(),
             demo: true,
+            warning_banner: None,
+            cloudflared_installed: None,
+            log_tail: None,
+            log_scroll: 0,
+            all_accounts: false,
+            flow_account_name: None,
+            focused: false,
+            log_view: false,
+            token_account: None,
+            token_prompted: Vec::new(),
+            rejected_accounts: Vec::new(),
+            dns_problems: Vec::new(),
+            dns_check: None,
+            filter: String::new(),
+            ascii: false,
+            theme: Theme::DARK,
+            list_percent: config::TuiConfig::default().list_percent,
+            show_metrics: true,
+            layout_unsaved: false,
+            raw_logs: false,
         }
     }
 
@@ -763,6 +1248,20 @@ impl App {
                 enabled: status == TunnelStatus::Running,
                 auto_start,
                 metrics_port: None,
+                protocol: None,
+                log_level: None,
+                extra_hostnames: Vec::new(),
+                manage_dns: true,
+                description: match name {
+                    "webapp" => Some("Customer-facing storefront".to_string()),
+                    "staging" => Some("QA copy of the storefront".to_string()),
+                    _ => None,
+                },
+                connect_time_ms: None,
+                maintenance: None,
+                fallback: None,
+                backends: Vec::new(),
+                dns_verified: None,
             };
 
             // Build pre-seeded metrics for running managed tunnels
@@ -775,7 +1274,7 @@ impl App {
                             8u64,
                             4u64,
                             vec![(200u16, 11500u64), (301, 420), (404, 85), (500, 23)],
-                            vec!["dfw08", "den01", "iad02", "lax01"],
+                            vec![("dfw08", 1), ("den01", 1), ("iad02", 1), ("lax01", 1)],
                             vec![
                                 45, 52, 38, 61, 55, 48, 72, 65, 43, 58, 51, 67, 44, 53, 60, 47, 56,
                                 42, 63, 50,
@@ -787,7 +1286,7 @@ impl App {
                             3,
                             4,
                             vec![(200, 4800), (201, 320), (400, 52), (404, 30), (500, 7)],
-                            vec!["dfw08", "den01", "iad02", "lax01"],
+                            vec![("dfw08", 2), ("den01", 2)],
                             vec![
                                 20, 18, 25, 22, 15, 28, 19, 24, 17, 21, 26, 14, 23, 20, 27, 16, 22,
                                 25, 18, 24,
@@ -799,7 +1298,7 @@ impl App {
                             1,
                             2,
                             vec![(200, 280), (404, 15), (502, 35), (503, 6)],
-                            vec!["dfw08", "den01"],
+                            vec![("dfw08", 1), ("den01", 1)],
                             vec![3, 5, 2, 4, 1, 6, 2, 3, 5, 1, 4, 2, 3, 5, 2, 4, 1, 6, 3, 2],
                         ),
                         "docs" => (
@@ -808,7 +1307,7 @@ impl App {
                             0,
                             4,
                             vec![(200, 82), (304, 7)],
-                            vec!["dfw08", "den01", "iad02", "lax01"],
+                            vec![("dfw08", 1), ("den01", 1), ("iad02", 1), ("lax01", 1)],
                             vec![1, 0, 2, 1, 0, 1, 0, 0, 1, 2, 0, 1, 0, 1, 0, 0, 1, 0, 2, 1],
                         ),
                         _ => (0, 0, 0, 0, vec![], vec![], vec![]),
@@ -825,7 +1324,15 @@ impl App {
                         concurrent_requests: concurrent,
                         ha_connections: ha,
                         response_codes,
-                        edge_locations: locations.into_iter().map(|s| s.to_string()).collect(),
+                        edge_locations: locations
+                            .into_iter()
+                            .map(|(name, connections)| EdgeLocation {
+                                name: name.to_string(),
+                                connections,
+                            })
+                            .collect(),
+                        bytes_sent: total * 2_400,
+                        bytes_received: total * 38_000,
                         available: true,
                     };
 
@@ -843,13 +1350,44 @@ impl App {
                     (None, MetricsHistory::default())
                 };
 
+            // Plausible process details for running managed tunnels
+            let daemon_info = (status == TunnelStatus::Running && kind == TunnelKind::Managed)
+                .then(|| daemon::DaemonInfo {
+                    pid: Some(rng.random_range(20_000..60_000)),
+                    uptime: Some(Duration::from_secs(rng.random_range(600..300_000))),
+                    memory_bytes: Some(rng.random_range(25u64..60) * 1024 * 1024),
+                });
+
+            // Months of traffic for the tunnels with metrics
+            let lifetime_bytes = metrics
+                .as_ref()
+                .map(|m| m.bytes_total() * rng.random_range(40u64..120));
+
+            // A few minutes of health checks; staging's origin keeps dropping out
+            let mut health_history = HealthHistory::default();
+            if status == TunnelStatus::Running && kind == TunnelKind::Managed {
+                let pattern: &[bool] = match name {
+                    "staging" => &[true, true, false, true, false, true, false, true, false],
+                    _ => &[true; 9],
+                };
+                let now = std::time::Instant::now();
+                for (i, &up) in pattern.iter().enumerate() {
+                    let ago = Duration::from_secs(30 * (pattern.len() - i) as u64);
+                    health_history.record(up, now.checked_sub(ago).unwrap_or(now));
+                }
+            }
+
             self.tunnels.push(TunnelEntry {
                 tunnel,
                 status,
                 kind,
                 metrics,
                 metrics_history,
+                health_history,
+                lifetime_bytes,
                 health,
+                daemon_info,
+                remote: None,
             });
         }
 
@@ -858,7 +1396,7 @@ impl App {
 
     // Generate cloudflared-style log lines for demo tunnels
     fn refresh_demo_logs(&mut self) {
-        if let Some(entry) = self.tunnels.get(self.selected) {
+        if let Some(entry) = self.selected_entry() {
             match entry.kind {
                 TunnelKind::Ephemeral => {
                     self.logs = vec![
@@ -945,6 +1483,11 @@ impl App {
                     m.request_errors += 1;
                 }
                 m.concurrent_requests = rng.random_range(0u64..=req_delta.min(12));
+                let bytes = req_delta * rng.random_range(20_000u64..60_000);
+                m.bytes_received += bytes;
+                if let Some(lifetime) = entry.lifetime_bytes.as_mut() {
+                    *lifetime += bytes;
+                }
 
                 // Record to history for sparkline
                 entry.metrics_history.request_samples.push(req_delta);
@@ -979,169 +1522,521 @@ impl App {
         self.accounts.get(self.selected_account_idx)
     }
 
-    // Switch to the next account
-    pub fn next_account(&mut self) {
-        if !self.accounts.is_empty() {
-            self.selected_account_idx = (self.selected_account_idx + 1) % self.accounts.len();
-            self.status_message = Some(format!(
-                "Switched to account: {}",
-                self.current_account_name()
-            ));
-        }
+    // Timeout for health check requests ([tui] health_timeout_secs)
+    fn health_timeout(&self) -> std::time::Duration {
+        self.config
+            .as_ref()
+            .map(|c| c.tui.clone())
+            .unwrap_or_default()
+            .health_timeout()
     }
 
-    // Switch to the previous account
-    // Load tunnels and their statuses
-    pub async fn load_tunnels(&mut self) -> Result<()> {
-        // Load config
-        self.config = config::load_config().ok();
-        if let Some(ref cfg) = self.config {
-            self.accounts = cfg.accounts.clone();
-            // Update zones from current account
-            if let Some(acct) = self.current_account() {
-                self.zones = acct.zones.clone();
-            }
-        }
-
-        // Get current account name for filtering
-        let current_account_name = self.current_account_name().to_string();
+    // Timeout for metrics scraping ([metrics] timeout_secs)
+    fn metrics_timeout(&self) -> std::time::Duration {
+        self.config
+            .as_ref()
+            .map(|c| c.metrics.clone())
+            .unwrap_or_default()
+            .timeout()
+    }
 
-        // Load tunnel state with migration (assigns empty account_name to first account,
-        // since that's typically the original account before multi-account was added)
-        let first_account = self
-            .accounts
-            .first()
-            .map(|a| a.name.as_str())
-            .unwrap_or(&current_account_name);
-        let state = TunnelState::load_and_migrate(first_account)?;
-        // Only get tunnels for the current account
-        let managed_tunnels: Vec<_> = state.tunnels_for_account(&current_account_name);
-        let managed_names: std::collections::HashSet<String> =
-            managed_tunnels.iter().map(|t| t.name.clone()).collect();
+    // Cloudflare client for an account ([api] timeout_secs)
+    fn api_client(&self, account: &Account) -> cloudflare::Client {
+        match &self.config {
+            Some(cfg) => cfg.client(account),
+            None => cloudflare::Client::for_account(account),
+        }
+    }
 
-        // Get status for each managed tunnel
-        let mut entries = Vec::new();
-        for tunnel in managed_tunnels.into_iter().cloned() {
-            let status = daemon::get_daemon_status(&tunnel).await;
-            // Fetch metrics for running tunnels
-            let (metrics, mut history) = if status == TunnelStatus::Running {
-                let m = TunnelMetrics::fetch(&tunnel.metrics_url()).await;
-                if m.available {
-                    let mut h = MetricsHistory::default();
-                    h.record(m.total_requests);
-                    (Some(m), h)
-                } else {
-                    (None, MetricsHistory::default())
-                }
-            } else {
-                (None, MetricsHistory::default())
-            };
+    // Look up a configured account by name
+    pub fn account_named(&self, name: &str) -> Option<&Account> {
+        self.accounts.iter().find(|a| a.name == name)
+    }
 
-            // Preserve existing history and health if we have it
-            let mut health = HealthStatus::Unknown;
-            if let Some(existing) = self.tunnels.iter().find(|e| e.tunnel.name == tunnel.name) {
-                history = existing.metrics_history.clone();
-                health = existing.health;
-                if let Some(ref m) = metrics {
-                    history.record(m.total_requests);
-                }
-            }
+    // Account the current edit/import flow applies to
+    pub fn flow_account(&self) -> Option<&Account> {
+        self.flow_account_name
+            .as_deref()
+            .and_then(|name| self.account_named(name))
+            .or_else(|| self.current_account())
+    }
 
-            entries.push(TunnelEntry {
-                tunnel,
-                status,
-                kind: TunnelKind::Managed,
-                metrics,
-                metrics_history: history,
-                health,
-            });
+    // Point the add/edit/import flow at an account, using its zones for selection
+    fn set_flow_account(&mut self, name: Option<String>) {
+        self.flow_account_name = name;
+        if let Some(acct) = self.flow_account() {
+            self.zones = acct.zones.clone();
         }
+    }
 
-        // Query Cloudflare for ephemeral tunnels (ytunnel-* not in state)
-        if let Some(acct) = self.current_account() {
-            let client = cloudflare::Client::new(&acct.api_token);
-            if let Ok(cf_tunnels) = client.list_tunnels(&acct.account_id).await {
-                for cf_tunnel in cf_tunnels {
-                    // Skip deleted tunnels
-                    if cf_tunnel.deleted_at.is_some() {
-                        continue;
-                    }
+    // Zoom into the selected tunnel; does nothing when the list is empty
+    pub fn focus_selected(&mut self) {
+        self.focused = self.is_visible(self.selected);
+    }
 
-                    // Only consider ytunnel-* tunnels
-                    if !cf_tunnel.name.starts_with("ytunnel-") {
-                        continue;
-                    }
+    // Switch between the full-screen logs and the previous view; does nothing when
+    // the list is empty
+    pub fn toggle_log_view(&mut self) {
+        self.log_view = !self.log_view && self.is_visible(self.selected);
+    }
 
-                    // Extract the short name (without ytunnel- prefix)
-                    let short_name = cf_tunnel
-                        .name
-                        .strip_prefix("ytunnel-")
-                        .unwrap_or(&cf_tunnel.name);
+    // Widen (positive steps) or narrow the tunnel list by SPLIT_STEP percent per
+    // step, within config's bounds, and remember the new width
+    pub fn resize_split(&mut self, steps: i16) {
+        let percent = (self.list_percent as i16 + steps * SPLIT_STEP as i16).clamp(
+            config::MIN_LIST_PERCENT as i16,
+            config::MAX_LIST_PERCENT as i16,
+        ) as u16;
+        if percent == self.list_percent {
+            return;
+        }
+        self.list_percent = percent;
+        self.status_message = Some(format!("Tunnel list: {}% of the width", percent));
+        self.save_layout();
+    }
 
-                    // Skip if already managed
-                    if managed_names.contains(short_name) {
-                        continue;
+    // Show or hide the metrics panel, and remember the choice
+    pub fn toggle_metrics_panel(&mut self) {
+        self.show_metrics = !self.show_metrics;
+        self.status_message = Some(
+            if self.show_metrics {
+                "Metrics panel shown"
+            } else {
+                "Metrics panel hidden (M to show)"
+            }
+            .to_string(),
+        );
+        self.save_layout();
+    }
+
+    // Switch the log panel between formatted and raw lines (for copying them as
+    // written). Only JSON lines look different; plain ones are shown as-is.
+    pub fn toggle_raw_logs(&mut self) {
+        self.raw_logs = !self.raw_logs;
+        self.status_message = Some(
+            if self.raw_logs {
+                "Logs: raw (L to format)"
+            } else {
+                "Logs: formatted"
+            }
+            .to_string(),
+        );
+    }
+
+    // Write the split width and metrics panel choice to [tui] in config.toml (not
+    // in demo mode, which has no config of its own). Skipped rather than waited
+    // for while another ytunnel holds the config lock.
+    fn save_layout(&mut self) {
+        if self.demo {
+            return;
+        }
+        let (list_percent, show_metrics) = (self.list_percent, self.show_metrics);
+        let saved = config::try_lock().and_then(|_lock| {
+            config::update_config(|cfg| {
+                cfg.tui.list_percent = list_percent;
+                cfg.tui.show_metrics = show_metrics;
+                Ok(())
+            })
+        });
+        self.layout_unsaved = false;
+        match saved {
+            Ok(()) => {
+                if let Some(cfg) = &mut self.config {
+                    cfg.tui.list_percent = list_percent;
+                    cfg.tui.show_metrics = show_metrics;
+                }
+            }
+            Err(e) if error::kind_of(&e) == Some(error::ErrorKind::Conflict) => {
+                self.layout_unsaved = true
+            }
+            Err(e) => self.status_message = Some(format!("Failed to save layout: {:#}", e)),
+        }
+    }
+
+    // Whether a tunnel matches the `/` filter: a case-insensitive substring of its
+    // name, hostname, target, or description
+    pub fn matches_filter(&self, entry: &TunnelEntry) -> bool {
+        if self.filter.is_empty() {
+            return true;
+        }
+        let needle = self.filter.to_lowercase();
+        let tunnel = &entry.tunnel;
+        let target = tunnel.target_spec();
+        [
+            Some(tunnel.name.as_str()),
+            Some(tunnel.hostname.as_str()),
+            Some(target.as_str()),
+            tunnel.description.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|field| field.to_lowercase().contains(&needle))
+            // IDN hostnames also match by their Unicode form
+            || idn::to_unicode(&tunnel.hostname)
+                .to_lowercase()
+                .contains(&needle)
+    }
+
+    pub fn is_visible(&self, index: usize) -> bool {
+        self.tunnels
+            .get(index)
+            .is_some_and(|e| self.matches_filter(e))
+    }
+
+    // Indices of the tunnels the list currently shows
+    pub fn visible_tunnels(&self) -> Vec<usize> {
+        (0..self.tunnels.len())
+            .filter(|&i| self.is_visible(i))
+            .collect()
+    }
+
+    pub fn start_filter(&mut self) {
+        self.input_mode = InputMode::Filter;
+    }
+
+    pub fn push_filter(&mut self, c: char) {
+        self.filter.push(c);
+        self.select_visible();
+    }
+
+    pub fn pop_filter(&mut self) {
+        self.filter.pop();
+        self.select_visible();
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    // The selected tunnel, unless the filter hides it (then there is nothing for
+    // actions to act on)
+    pub fn selected_entry(&self) -> Option<&TunnelEntry> {
+        self.tunnels
+            .get(self.selected)
+            .filter(|e| self.matches_filter(e))
+    }
+
+    fn selected_entry_mut(&mut self) -> Option<&mut TunnelEntry> {
+        if !self.is_visible(self.selected) {
+            return None;
+        }
+        self.tunnels.get_mut(self.selected)
+    }
+
+    // Move the selection onto the first match when the filter hides it
+    fn select_visible(&mut self) {
+        if self.is_visible(self.selected) {
+            return;
+        }
+        if let Some(&first) = self.visible_tunnels().first() {
+            self.select(first);
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = index;
+        // Process details from an earlier visit are stale (the run loop refetches)
+        if !self.demo {
+            if let Some(entry) = self.tunnels.get_mut(index) {
+                entry.daemon_info = None;
+            }
+        }
+        self.refresh_logs();
+    }
+
+    // Toggle between the current account's tunnels and every account's
+    pub fn toggle_all_accounts(&mut self) {
+        self.all_accounts = !self.all_accounts;
+        self.status_message = Some(if self.all_accounts {
+            "Showing tunnels from all accounts".to_string()
+        } else {
+            format!("Showing account: {}", self.current_account_name())
+        });
+    }
+
+    // Switch to the next account
+    pub fn next_account(&mut self) {
+        if !self.accounts.is_empty() {
+            self.selected_account_idx = (self.selected_account_idx + 1) % self.accounts.len();
+            self.status_message = Some(format!(
+                "Switched to account: {}",
+                self.current_account_name()
+            ));
+        }
+    }
+
+    // Switch to the previous account
+    // Load tunnels and their statuses
+    pub async fn load_tunnels(&mut self) -> Result<()> {
+        // Load config
+        self.config = config::load_config().ok();
+        if let Some(ref cfg) = self.config {
+            self.accounts = cfg.accounts.clone();
+            // Update zones from current account
+            if let Some(acct) = self.current_account() {
+                self.zones = acct.zones.clone();
+            }
+        }
+
+        // Get current account name for filtering
+        let current_account_name = self.current_account_name().to_string();
+
+        // Accounts whose tunnels are listed
+        let shown_accounts: Vec<Account> = if self.all_accounts {
+            self.accounts.clone()
+        } else {
+            self.current_account().cloned().into_iter().collect()
+        };
+        let shown_account_names: Vec<String> = if self.all_accounts {
+            shown_accounts.iter().map(|a| a.name.clone()).collect()
+        } else {
+            vec![current_account_name.clone()]
+        };
+
+        // Load tunnel state with migration (assigns empty account_name to first account,
+        // since that's typically the original account before multi-account was added)
+        let first_account = self
+            .accounts
+            .first()
+            .map(|a| a.name.as_str())
+            .unwrap_or(&current_account_name);
+        let state = TunnelState::load_and_migrate(first_account)?;
+        // Only get tunnels for the shown accounts
+        let managed_tunnels: Vec<_> = shown_account_names
+            .iter()
+            .flat_map(|name| state.tunnels_for_account(name))
+            .collect();
+        let managed_names: std::collections::HashSet<(String, String)> = managed_tunnels
+            .iter()
+            .map(|t| (t.account_name.clone(), t.name.clone()))
+            .collect();
+
+        // Get status for each managed tunnel
+        let metrics_timeout = self.metrics_timeout();
+        let mut entries = Vec::new();
+        for tunnel in managed_tunnels.into_iter().cloned() {
+            let status = daemon::get_daemon_status(&tunnel).await;
+            // Fetch metrics for running tunnels
+            let (metrics, mut history) = if status == TunnelStatus::Running {
+                let m =
+                    TunnelMetrics::fetch(tunnel.metrics_url().as_deref(), metrics_timeout).await;
+                if m.available {
+                    let mut h = MetricsHistory::default();
+                    h.record(m.total_requests);
+                    (Some(m), h)
+                } else {
+                    (None, MetricsHistory::default())
+                }
+            } else {
+                (None, MetricsHistory::default())
+            };
+
+            // Preserve existing history and health if we have it
+            let mut health = HealthStatus::Unknown;
+            let mut health_history = HealthHistory::default();
+            if let Some(existing) = self.tunnels.iter().find(|e| {
+                e.tunnel.name == tunnel.name && e.tunnel.account_name == tunnel.account_name
+            }) {
+                history = existing.metrics_history.clone();
+                health = existing.health;
+                health_history = existing.health_history.clone();
+                if let Some(ref m) = metrics {
+                    history.record(m.total_requests);
+                }
+            }
+
+            entries.push(TunnelEntry {
+                tunnel,
+                status,
+                kind: TunnelKind::Managed,
+                metrics,
+                metrics_history: history,
+                health_history,
+                lifetime_bytes: None,
+                health,
+                daemon_info: None,
+                remote: None,
+            });
+        }
+
+        // Fold the running tunnels' byte counters into their lifetime totals
+        // (best-effort; the panel just goes without)
+        let samples: Vec<(&str, u64)> = entries
+            .iter()
+            .filter_map(|e| {
+                e.metrics
+                    .as_ref()
+                    .map(|m| (e.tunnel.tunnel_id.as_str(), m.bytes_total()))
+            })
+            .collect();
+        if let Some(totals) = transfer::try_record(&samples) {
+            for entry in &mut entries {
+                entry.lifetime_bytes = totals.lifetime(&entry.tunnel.tunnel_id);
+            }
+        }
+
+        // Setup problems get a persistent banner instead of a status message that scrolls away
+        let mut warnings = Vec::new();
+        let mut rejected_accounts: Vec<String> = Vec::new();
+        if self.cloudflared_installed != Some(true) {
+            self.cloudflared_installed = Some(tunnel::is_cloudflared_installed().await);
+        }
+        if self.cloudflared_installed == Some(false) {
+            warnings.push(format!(
+                "cloudflared not found — {}",
+                tunnel::CLOUDFLARED_INSTALL_HINT
+            ));
+        }
+
+        // Query each shown account's Cloudflare API for ephemeral tunnels (<tunnel_prefix>*
+        // not in state)
+        let mut leaked_runs = 0;
+        let mut offline = false;
+        for acct in &shown_accounts {
+            let client = self.api_client(acct);
+            let cf_tunnels = match client.list_tunnels(&acct.account_id).await {
+                Ok(t) => t,
+                Err(e) => {
+                    if cloudflare::is_auth_error(&e) {
+                        warnings.push(format!(
+                            "API token for account '{}' is invalid or expired — press T to update it",
+                            acct.name
+                        ));
+                        rejected_accounts.push(acct.name.clone());
+                    } else if cloudflare::is_unreachable(&e) {
+                        offline = true;
                     }
+                    Vec::new()
+                }
+            };
+            for cf_tunnel in cf_tunnels {
+                // Skip deleted tunnels
+                if cf_tunnel.deleted_at.is_some() {
+                    continue;
+                }
 
-                    // This is an ephemeral tunnel - try to read its config file
-                    let (hostname, target) = parse_ephemeral_config(&cf_tunnel.id)
-                        .unwrap_or_else(|| (short_name.to_string(), "unknown".to_string()));
-
-                    // Try to determine the zone from the hostname
-                    let (zone_id, zone_name) = if hostname.contains('.') {
-                        // Try to match hostname to a known zone
-                        acct.zones
-                            .iter()
-                            .find(|z| hostname.ends_with(&z.name))
-                            .map(|z| (z.id.clone(), z.name.clone()))
-                            .unwrap_or_default()
-                    } else {
-                        (String::new(), String::new())
-                    };
+                // Only consider ytunnel's own tunnels, by their short name (without
+                // the tunnel_prefix)
+                let Some(short_name) = config::short_tunnel_name(&cf_tunnel.name) else {
+                    continue;
+                };
 
-                    let ephemeral = PersistentTunnel {
-                        name: short_name.to_string(),
-                        account_name: current_account_name.clone(),
-                        target,
-                        zone_id,
-                        zone_name,
-                        hostname,
-                        tunnel_id: cf_tunnel.id.clone(),
-                        enabled: false,
-                        auto_start: false,
-                        metrics_port: None,
-                    };
+                // Skip if already managed
+                if managed_names.contains(&(acct.name.clone(), short_name.to_string())) {
+                    continue;
+                }
 
-                    // Check if config file exists (means tunnel is actively running)
-                    let config_dir = crate::config::config_dir().ok();
-                    let config_exists = config_dir
-                        .map(|d| d.join(format!("tunnel-{}.yml", cf_tunnel.id)).exists())
-                        .unwrap_or(false);
+                // This is an ephemeral tunnel - try to read its config file
+                let (hostname, target) = tunnel::parse_ephemeral_config(&cf_tunnel.id)
+                    .unwrap_or_else(|| (short_name.to_string(), "unknown".to_string()));
+
+                // Try to determine the zone from the hostname (Unicode or punycode)
+                let (zone_id, zone_name) = acct
+                    .zone_for_hostname(&hostname)
+                    .map(|z| (z.id.clone(), z.name.clone()))
+                    .unwrap_or_default();
+
+                let ephemeral = PersistentTunnel {
+                    name: short_name.to_string(),
+                    account_name: acct.name.clone(),
+                    target,
+                    zone_id,
+                    zone_name,
+                    hostname,
+                    tunnel_id: cf_tunnel.id.clone(),
+                    enabled: false,
+                    auto_start: false,
+                    metrics_port: None,
+                    protocol: None,
+                    log_level: None,
+                    extra_hostnames: Vec::new(),
+                    manage_dns: true,
+                    description: None,
+                    connect_time_ms: None,
+                    maintenance: None,
+                    fallback: None,
+                    backends: Vec::new(),
+                    dns_verified: None,
+                };
 
-                    let status = if config_exists {
-                        TunnelStatus::Running
-                    } else {
-                        TunnelStatus::Stopped
-                    };
+                // A config owned by a live run means the tunnel is actively running
+                // here; a connector on the edge means it runs somewhere
+                let config_exists = tunnel::ephemeral_configs(&cf_tunnel.id)
+                    .iter()
+                    .any(|c| c.is_live());
 
-                    entries.push(TunnelEntry {
-                        tunnel: ephemeral,
-                        status,
-                        kind: TunnelKind::Ephemeral,
-                        metrics: None,
-                        metrics_history: MetricsHistory::default(),
-                        health: HealthStatus::Unknown,
-                    });
+                let status = if config_exists || cf_tunnel.is_connected() {
+                    TunnelStatus::Running
+                } else {
+                    TunnelStatus::Stopped
+                };
+                if !config_exists && !cf_tunnel.is_connected() && !tunnel::is_kept(&cf_tunnel.id) {
+                    leaked_runs += 1;
                 }
+
+                entries.push(TunnelEntry {
+                    tunnel: ephemeral,
+                    status,
+                    kind: TunnelKind::Ephemeral,
+                    metrics: None,
+                    metrics_history: MetricsHistory::default(),
+                    health_history: HealthHistory::default(),
+                    lifetime_bytes: None,
+                    health: HealthStatus::Unknown,
+                    daemon_info: None,
+                    remote: Some(cf_tunnel),
+                });
             }
         }
 
+        warnings.extend(self.dns_problems.iter().cloned());
+
+        // Managed tunnels only need the API to start; everything listed above is local
+        if offline {
+            warnings.push(
+                "Cloudflare unreachable — ephemeral discovery skipped, showing managed tunnels only"
+                    .to_string(),
+            );
+        }
+
+        if leaked_runs > 0 {
+            warnings.push(format!(
+                "{} ephemeral tunnel(s) left behind by a `ytunnel run` that didn't exit cleanly — select and press d to clean up (D for all)",
+                leaked_runs
+            ));
+        }
+
         self.tunnels = entries;
+        self.warning_banner = if warnings.is_empty() {
+            None
+        } else {
+            Some(warnings.join("  |  "))
+        };
+
+        // Offer to replace a rejected token right away (once per account per session)
+        if let Some(name) = rejected_accounts
+            .iter()
+            .find(|name| !self.token_prompted.contains(name))
+            .cloned()
+        {
+            if matches!(self.input_mode, InputMode::Normal) {
+                self.token_prompted.push(name.clone());
+                self.start_token_entry(name);
+            }
+        }
+        self.rejected_accounts = rejected_accounts;
 
         // Ensure selected index is valid
         if self.selected >= self.tunnels.len() && !self.tunnels.is_empty() {
             self.selected = self.tunnels.len() - 1;
         }
+        self.select_visible();
+        // Nothing left to focus on (e.g. the last tunnel was deleted)
+        if self.tunnels.is_empty() {
+            self.focused = false;
+            self.log_view = false;
+        }
 
         // Load logs for selected tunnel
         self.refresh_logs();
@@ -1155,17 +2050,28 @@ impl App {
             self.refresh_demo_logs();
             return;
         }
-        if let Some(entry) = self.tunnels.get(self.selected) {
+        self.log_tail = None;
+        self.log_scroll = 0;
+        if let Some(entry) = self.selected_entry().cloned() {
             match entry.kind {
-                TunnelKind::Managed => match daemon::read_log_tail(&entry.tunnel, 100) {
-                    Ok(lines) => self.logs = lines,
-                    Err(e) => self.logs = vec![format!("Error reading logs: {}", e)],
-                },
+                TunnelKind::Managed => {
+                    match daemon::read_log_tail(&entry.tunnel, LOG_INITIAL_LINES) {
+                        Ok(lines) => self.logs = lines,
+                        Err(e) => self.logs = vec![format!("Error reading logs: {}", e)],
+                    }
+                    if let Ok(path) = entry.tunnel.existing_log_path() {
+                        self.log_tail = Some(LogTail::new(path));
+                    }
+                }
                 TunnelKind::Ephemeral => {
                     let has_config =
                         entry.tunnel.target != "unknown" && !entry.tunnel.target.is_empty();
+                    let remote = entry
+                        .remote
+                        .as_ref()
+                        .map(|r| format!("Cloudflare: {}", r.remote_summary()));
                     self.logs = if has_config {
-                        vec![
+                        let mut lines = vec![
                             "Ephemeral tunnel (created with `ytunnel run`)".to_string(),
                             String::new(),
                             format!("Hostname: {}", entry.tunnel.hostname),
@@ -1175,19 +2081,32 @@ impl App {
                             } else {
                                 "Zone:     (will prompt)".to_string()
                             },
+                        ];
+                        lines.extend(remote);
+                        lines.extend([
                             String::new(),
                             "Press [m] to import as managed tunnel".to_string(),
                             "Press [d] to delete from Cloudflare".to_string(),
-                        ]
+                        ]);
+                        lines
                     } else {
-                        vec![
+                        let connected = entry.remote.as_ref().is_some_and(|r| r.is_connected());
+                        let mut lines = vec![
                             "Ephemeral tunnel (created with `ytunnel run`)".to_string(),
                             String::new(),
-                            "Config not found - tunnel may not be running.".to_string(),
+                            if connected {
+                                "Config not found - running on another machine.".to_string()
+                            } else {
+                                "Config not found - tunnel may not be running.".to_string()
+                            },
+                        ];
+                        lines.extend(remote);
+                        lines.extend([
                             String::new(),
                             "Press [m] to import (will prompt for target)".to_string(),
                             "Press [d] to delete from Cloudflare".to_string(),
-                        ]
+                        ]);
+                        lines
                     };
                 }
             }
@@ -1196,28 +2115,168 @@ impl App {
         }
     }
 
+    // Append lines written to the selected tunnel's log since the last tick
+    pub fn follow_logs(&mut self) {
+        let Some(tail) = self.log_tail.as_mut() else {
+            return;
+        };
+
+        let new_lines = match tail.read_new() {
+            Ok(Some(lines)) => lines,
+            // Log was truncated or rotated, reload from the start
+            Ok(None) => {
+                self.refresh_logs();
+                return;
+            }
+            Err(_) => return,
+        };
+        if new_lines.is_empty() {
+            return;
+        }
+
+        // Replace the "No logs yet" placeholder once output appears
+        if self.logs.len() == 1 && self.logs[0] == "No logs yet" {
+            self.logs.clear();
+        }
+
+        // Keep the view anchored while the user is scrolled up
+        if self.log_scroll > 0 {
+            self.log_scroll += new_lines.len();
+        }
+        self.logs.extend(new_lines);
+
+        if self.logs.len() > LOG_MAX_LINES {
+            let excess = self.logs.len() - LOG_MAX_LINES;
+            self.logs.drain(..excess);
+        }
+        self.log_scroll = self.log_scroll.min(self.logs.len().saturating_sub(1));
+    }
+
+    // Whether the log panel is tailing new output
+    pub fn is_following_logs(&self) -> bool {
+        self.log_tail.is_some() && self.log_scroll == 0
+    }
+
+    // Scroll the log panel up (towards older lines), pausing follow
+    pub fn scroll_logs_up(&mut self, lines: usize) {
+        self.log_scroll = (self.log_scroll + lines).min(self.logs.len().saturating_sub(1));
+    }
+
+    // Scroll the log panel down; reaching the bottom resumes follow
+    pub fn scroll_logs_down(&mut self, lines: usize) {
+        self.log_scroll = self.log_scroll.saturating_sub(lines);
+    }
+
     // Refresh metrics for the selected tunnel
     pub async fn refresh_metrics(&mut self) {
         if self.demo {
             self.refresh_demo_metrics();
             return;
         }
-        if let Some(entry) = self.tunnels.get_mut(self.selected) {
+        let metrics_timeout = self.metrics_timeout();
+        if let Some(entry) = self.selected_entry_mut() {
             if entry.kind == TunnelKind::Managed && entry.status == TunnelStatus::Running {
-                let metrics = TunnelMetrics::fetch(&entry.tunnel.metrics_url()).await;
+                let metrics =
+                    TunnelMetrics::fetch(entry.tunnel.metrics_url().as_deref(), metrics_timeout)
+                        .await;
                 if metrics.available {
                     entry.metrics_history.record(metrics.total_requests);
+                    if let Some(totals) =
+                        transfer::try_record(&[(&entry.tunnel.tunnel_id, metrics.bytes_total())])
+                    {
+                        entry.lifetime_bytes = totals.lifetime(&entry.tunnel.tunnel_id);
+                    }
                     entry.metrics = Some(metrics);
                 } else {
                     entry.metrics = None;
                 }
             }
         }
+        self.refresh_daemon_info().await;
+    }
+
+    // Whether the selected tunnel's process details still need fetching
+    pub fn selected_needs_daemon_info(&self) -> bool {
+        self.selected_entry().is_some_and(|e| {
+            e.kind == TunnelKind::Managed
+                && e.status == TunnelStatus::Running
+                && e.daemon_info.is_none()
+        })
+    }
+
+    // Look up PID/uptime/memory for the selected tunnel only; the list itself
+    // sticks to the cheap status check
+    pub async fn refresh_daemon_info(&mut self) {
+        if self.demo {
+            return;
+        }
+        if let Some(entry) = self.selected_entry_mut() {
+            if entry.kind == TunnelKind::Managed && entry.status == TunnelStatus::Running {
+                entry.daemon_info = Some(daemon::get_daemon_info(&entry.tunnel).await);
+            }
+        }
+    }
+
+    // "Started web (connected in 1.8s)" once the selected tunnel's start was timed
+    fn started_message(&self, verb: &str, name: &str) -> String {
+        match self.selected_connect_time() {
+            Some(ms) => format!(
+                "{} {} (connected in {})",
+                verb,
+                name,
+                crate::format_connect_time(ms)
+            ),
+            None => format!("{} {}", verb, name),
+        }
+    }
+
+    pub fn selected_connect_time(&self) -> Option<u64> {
+        self.selected_entry().and_then(|e| e.tunnel.connect_time_ms)
+    }
+
+    pub fn selected_daemon_info(&self) -> Option<&daemon::DaemonInfo> {
+        self.selected_entry()
+            .and_then(|e| e.daemon_info.as_ref())
+            .filter(|info| info.pid.is_some())
+    }
+
+    // Start confirming the running tunnels' DNS records in the background (see
+    // check_dns_op), unless a check is still going
+    pub fn check_dns(&mut self) {
+        if self.demo || self.dns_check.is_some() {
+            return;
+        }
+        let tunnels = self
+            .tunnels
+            .iter()
+            .filter(|e| e.kind == TunnelKind::Managed && e.status == TunnelStatus::Running)
+            .map(|e| e.tunnel.clone())
+            .collect();
+        self.dns_check = Some(tokio::spawn(check_dns_op(tunnels)));
+    }
+
+    // Show the background DNS check's findings once it's done
+    pub async fn finish_dns_check(&mut self) {
+        let Some(check) = self.dns_check.take_if(|c| c.is_finished()) else {
+            return;
+        };
+        let result = match check.await.map_err(anyhow::Error::from).and_then(|r| r) {
+            // Offline or rejected; the refresh reports those
+            Ok(None) => return,
+            Ok(Some(problems)) => {
+                self.dns_problems = problems;
+                self.load_tunnels().await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            self.status_message = Some(format!("DNS check: {:#}", e));
+        }
     }
 
     // Check health of the selected tunnel by making an HTTP request
     pub async fn check_health(&mut self) {
-        if self.demo {
+        if self.demo || !self.is_visible(self.selected) {
             return;
         }
         self.check_health_for_index(self.selected).await;
@@ -1237,6 +2296,7 @@ impl App {
 
     // Check health for a specific tunnel by index
     async fn check_health_for_index(&mut self, index: usize) {
+        let health_timeout = self.health_timeout();
         if let Some(entry) = self.tunnels.get_mut(index) {
             if entry.status != TunnelStatus::Running {
                 entry.health = HealthStatus::Unknown;
@@ -1246,7 +2306,6 @@ impl App {
                 return;
             }
 
-            let previous_health = entry.health;
             let tunnel_name = entry.tunnel.name.clone();
             let hostname = entry.tunnel.hostname.clone();
             let is_selected = index == self.selected;
@@ -1256,43 +2315,27 @@ impl App {
                 self.status_message = Some(format!("Checking health of {}...", tunnel_name));
             }
 
-            let url = format!("https://{}", hostname);
-
-            // Simple HTTP HEAD request with short timeout
-            let client = reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(5))
-                .danger_accept_invalid_certs(true) // In case of self-signed certs
-                .build();
-
-            let result = match client {
-                Ok(c) => c.head(&url).send().await,
-                Err(_) => {
-                    if let Some(entry) = self.tunnels.get_mut(index) {
-                        entry.health = HealthStatus::Unhealthy;
-                    }
-                    self.show_health_result(&tunnel_name, previous_health, HealthStatus::Unhealthy);
-                    return;
-                }
-            };
-
-            let new_health = match result {
-                Ok(resp) if resp.status().is_success() || resp.status().is_redirection() => {
-                    HealthStatus::Healthy
-                }
-                Ok(resp) if resp.status().is_server_error() => HealthStatus::Unhealthy,
-                Ok(_) => HealthStatus::Healthy, // 4xx is still "reachable"
-                Err(_) => HealthStatus::Unhealthy,
+            let new_health = if health::check_hostname(&hostname, health_timeout).await {
+                HealthStatus::Healthy
+            } else {
+                HealthStatus::Unhealthy
             };
 
+            let mut change = HealthChange::Steady;
             if let Some(entry) = self.tunnels.get_mut(index) {
                 entry.health = new_health;
+                change = entry.health_history.record(
+                    new_health == HealthStatus::Healthy,
+                    std::time::Instant::now(),
+                );
             }
-            self.show_health_result(&tunnel_name, previous_health, new_health);
+            self.show_health_result(&tunnel_name, change, new_health);
         }
     }
 
-    // Show health check result and send notifications for state changes
-    fn show_health_result(&mut self, tunnel_name: &str, old: HealthStatus, new: HealthStatus) {
+    // Show health check result and send notifications for state changes. A
+    // flapping tunnel gets one notice when it starts and one when it settles.
+    fn show_health_result(&mut self, tunnel_name: &str, change: HealthChange, new: HealthStatus) {
         // Always show the result in status bar
         match new {
             HealthStatus::Healthy => {
@@ -1305,22 +2348,43 @@ impl App {
         }
 
         // Send system notification only on meaningful transitions
-        match (old, new) {
-            (HealthStatus::Healthy, HealthStatus::Unhealthy) => {
+        match change {
+            HealthChange::Down => {
                 self.status_message = Some(format!("⚠️  Tunnel '{}' is DOWN!", tunnel_name));
                 self.send_system_notification(
                     &format!("Tunnel Down: {}", tunnel_name),
                     "The tunnel is no longer reachable",
                 );
             }
-            (HealthStatus::Unhealthy, HealthStatus::Healthy) => {
+            HealthChange::Up => {
                 self.status_message = Some(format!("✓ Tunnel '{}' is back UP", tunnel_name));
                 self.send_system_notification(
                     &format!("Tunnel Up: {}", tunnel_name),
                     "The tunnel is now reachable",
                 );
             }
-            _ => {}
+            HealthChange::StartedFlapping => {
+                self.status_message = Some(format!(
+                    "⚠️  Tunnel '{}' is flapping; up/down notifications paused",
+                    tunnel_name
+                ));
+                self.send_system_notification(
+                    &format!("Tunnel Flapping: {}", tunnel_name),
+                    "The tunnel keeps going up and down; further changes won't be notified until it settles",
+                );
+            }
+            HealthChange::Settled(up) => {
+                let state = if up { "up" } else { "down" };
+                self.status_message = Some(format!(
+                    "Tunnel '{}' stopped flapping and is {}",
+                    tunnel_name, state
+                ));
+                self.send_system_notification(
+                    &format!("Tunnel Settled: {}", tunnel_name),
+                    &format!("The tunnel stopped flapping and is {}", state),
+                );
+            }
+            HealthChange::Steady | HealthChange::Suppressed => {}
         }
     }
 
@@ -1356,76 +2420,216 @@ impl App {
         }
     }
 
+    // Health timeline for the selected tunnel, once it's been checked
+    pub fn selected_health_history(&self) -> Option<&HealthHistory> {
+        self.selected_entry()
+            .map(|e| &e.health_history)
+            .filter(|h| !h.samples.is_empty())
+    }
+
     // Get health status for the selected tunnel
     pub fn selected_health(&self) -> HealthStatus {
-        self.tunnels
-            .get(self.selected)
+        self.selected_entry()
             .map(|e| e.health)
             .unwrap_or(HealthStatus::Unknown)
     }
 
+    // Lifetime bytes for the selected tunnel
+    pub fn selected_lifetime_bytes(&self) -> Option<u64> {
+        self.selected_entry().and_then(|e| e.lifetime_bytes)
+    }
+
     // Get metrics for the selected tunnel
     pub fn selected_metrics(&self) -> Option<&TunnelMetrics> {
-        self.tunnels
-            .get(self.selected)
-            .and_then(|e| e.metrics.as_ref())
+        self.selected_entry().and_then(|e| e.metrics.as_ref())
     }
 
     // Get sparkline for the selected tunnel
     pub fn selected_sparkline(&self) -> String {
-        self.tunnels
-            .get(self.selected)
-            .map(|e| e.metrics_history.sparkline())
+        self.selected_entry()
+            .map(|e| match self.ascii {
+                true => e.metrics_history.gauge(),
+                false => e.metrics_history.sparkline(),
+            })
             .unwrap_or_default()
     }
 
-    // Get the selected tunnel's details (target and hostname)
-    pub fn selected_tunnel_details(&self) -> Option<(&str, &str)> {
-        self.tunnels
-            .get(self.selected)
-            .map(|e| (e.tunnel.target.as_str(), e.tunnel.hostname.as_str()))
+    // Status marker for the list and details (R/S/X in ASCII mode)
+    pub fn status_symbol(&self, status: TunnelStatus) -> &'static str {
+        match self.ascii {
+            true => status.ascii_symbol(),
+            false => status.symbol(),
+        }
+    }
+
+    // Switch to plain ASCII output without colors
+    pub fn set_ascii(&mut self, ascii: bool) {
+        self.ascii = ascii;
+        if ascii {
+            self.spinner = Spinner::ascii();
+        }
     }
 
-    // Move selection up
+    // Get the selected tunnel's details (target as displayed and hostname)
+    pub fn selected_tunnel_details(&self) -> Option<(String, &str)> {
+        self.selected_entry()
+            .map(|e| (e.tunnel.display_target(), e.tunnel.hostname.as_str()))
+    }
+
+    pub fn selected_description(&self) -> Option<&str> {
+        self.selected_entry()
+            .and_then(|e| e.tunnel.description.as_deref())
+    }
+
+    // Cloudflare zone of the selected managed tunnel's hostname
+    pub fn selected_zone(&self) -> Option<&str> {
+        self.selected_entry()
+            .filter(|e| e.kind == TunnelKind::Managed && !e.tunnel.zone_name.is_empty())
+            .map(|e| e.tunnel.zone_name.as_str())
+    }
+
+    // What the selected tunnel serves instead of its target while in maintenance mode
+    pub fn selected_maintenance(&self) -> Option<&str> {
+        self.selected_entry()
+            .and_then(|e| e.tunnel.maintenance.as_deref())
+    }
+
+    // Metrics endpoint of the selected managed tunnel (ephemeral runs don't set one)
+    pub fn selected_metrics_url(&self) -> Option<String> {
+        self.selected_entry()
+            .filter(|e| e.kind == TunnelKind::Managed)
+            .and_then(|e| e.tunnel.metrics_url())
+    }
+
+    // Move selection up (skipping tunnels hidden by the filter)
     pub fn select_previous(&mut self) -> bool {
-        if !self.tunnels.is_empty() && self.selected > 0 {
-            self.selected -= 1;
-            self.refresh_logs();
+        let previous = self
+            .visible_tunnels()
+            .into_iter()
+            .rev()
+            .find(|&i| i < self.selected);
+        if let Some(i) = previous {
+            self.select(i);
             return true; // Selection changed
         }
         false
     }
 
-    // Move selection down
+    // Move selection down (skipping tunnels hidden by the filter)
     pub fn select_next(&mut self) -> bool {
-        if !self.tunnels.is_empty() && self.selected < self.tunnels.len() - 1 {
-            self.selected += 1;
-            self.refresh_logs();
+        let next = self
+            .visible_tunnels()
+            .into_iter()
+            .find(|&i| i > self.selected);
+        if let Some(i) = next {
+            self.select(i);
             return true; // Selection changed
         }
         false
     }
 
+    // Select a visible tunnel by its place in the list: `g` the first, `G` the last,
+    // `1`-`9` the Nth. Returns true if the selection changed.
+    pub fn jump_to(&mut self, key: char) -> bool {
+        let visible = self.visible_tunnels();
+        let target = match key {
+            'g' => visible.first(),
+            'G' => visible.last(),
+            n => n
+                .to_digit(10)
+                .and_then(|n| (n as usize).checked_sub(1))
+                .and_then(|n| visible.get(n)),
+        };
+        match target {
+            Some(&i) if i != self.selected => {
+                self.select(i);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // The Normal-mode key another key stands in for. Space starts the selected
+    // tunnel when it isn't running and stops it when it is, via `s`/`S` so it gets
+    // their checks and messages.
+    pub fn resolve_key(&self, code: KeyCode, modifiers: KeyModifiers) -> KeyCode {
+        match code {
+            // Ctrl+h/l resize the split like `<`/`>`
+            KeyCode::Char('h') if modifiers.contains(KeyModifiers::CONTROL) => KeyCode::Char('<'),
+            KeyCode::Char('l') if modifiers.contains(KeyModifiers::CONTROL) => KeyCode::Char('>'),
+            KeyCode::Char(' ') => match self.selected_entry() {
+                Some(e) if e.status == TunnelStatus::Running => KeyCode::Char('S'),
+                _ => KeyCode::Char('s'),
+            },
+            code => code,
+        }
+    }
+
     // Check if selected tunnel needs a health check (unknown or stale)
     pub fn selected_needs_health_check(&self) -> bool {
-        self.tunnels
-            .get(self.selected)
+        self.selected_entry()
             .map(|e| e.status == TunnelStatus::Running && e.health == HealthStatus::Unknown)
             .unwrap_or(false)
     }
 
-    // Start the add tunnel flow
-    pub fn start_add(&mut self) {
+    // Start the add tunnel flow
+    pub fn start_add(&mut self) {
+        if self.config.is_none() {
+            self.status_message = Some("Run 'ytunnel init' first".to_string());
+            return;
+        }
+        self.input.clear();
+        // New tunnels go to the current account
+        self.set_flow_account(None);
+        if self.zones.is_empty() {
+            self.status_message = Some(self.no_zones_message());
+            return;
+        }
+        self.input_mode = InputMode::Add(AddFlow {
+            step: AddStep::Name,
+            clone_source: None,
+        });
+    }
+
+    // Start the add flow prefilled from the selected tunnel's settings
+    pub fn start_clone(&mut self) {
+        let source = match self.selected_entry() {
+            Some(e) if e.kind == TunnelKind::Ephemeral => {
+                self.status_message =
+                    Some("Cannot clone ephemeral tunnel. Import it first with 'm'.".to_string());
+                return;
+            }
+            Some(e) => e.tunnel.clone(),
+            None => {
+                self.status_message = Some("No tunnel selected".to_string());
+                return;
+            }
+        };
         if self.config.is_none() {
             self.status_message = Some("Run 'ytunnel init' first".to_string());
             return;
         }
-        self.input_mode = InputMode::AddName;
-        self.input.clear();
-        self.new_tunnel_name = None;
-        self.new_tunnel_target = None;
-        self.zone_selected = 0;
-        self.is_importing = false;
+        // The clone lives next to its source, in the source's account
+        self.set_flow_account(Some(source.account_name.clone()));
+        if self.zones.is_empty() {
+            self.status_message = Some(self.no_zones_message());
+            self.set_flow_account(None);
+            return;
+        }
+        self.input = format!("{}-copy", source.name);
+        self.input_mode = InputMode::Add(AddFlow {
+            step: AddStep::Name,
+            clone_source: Some(Box::new(source)),
+        });
+    }
+
+    // Why tunnels can't be added here: with no zones there is nothing to pick
+    fn no_zones_message(&self) -> String {
+        format!(
+            "Account '{}' has no zones. Use `ytunnel add <hostname> <target>` with a hostname in another account's zone.",
+            self.flow_account()
+                .map_or_else(|| self.current_account_name(), |a| a.name.as_str())
+        )
     }
 
     // Start the edit tunnel flow
@@ -1435,7 +2639,7 @@ impl App {
             return;
         }
 
-        let entry = match self.tunnels.get(self.selected) {
+        let entry = match self.selected_entry() {
             Some(e) => e,
             None => {
                 self.status_message = Some("No tunnel selected".to_string());
@@ -1450,141 +2654,443 @@ impl App {
             return;
         }
 
-        // Store original values for comparison/cleanup
-        self.editing_tunnel_name = Some(entry.tunnel.name.clone());
-        self.original_zone_id = Some(entry.tunnel.zone_id.clone());
-        self.original_hostname = Some(entry.tunnel.hostname.clone());
-
-        // Pre-fill input with current target
-        self.input = entry.tunnel.target.clone();
+        // Edit within the tunnel's own account (zones, API token)
+        let tunnel = entry.tunnel.clone();
+        self.set_flow_account(Some(tunnel.account_name.clone()));
+
+        // Pre-fill input with current target (all backends of a balanced tunnel)
+        self.input = tunnel.target_spec();
+        self.input_mode = InputMode::Edit(EditFlow {
+            name: tunnel.name,
+            original_zone_id: tunnel.zone_id,
+            original_hostname: tunnel.hostname,
+            step: EditStep::Target,
+        });
+    }
 
-        // Pre-select current zone in zone list
-        self.zone_selected = self
-            .zones
-            .iter()
-            .position(|z| z.id == entry.tunnel.zone_id)
-            .unwrap_or(0);
+    // Move to next step in edit flow (target -> description -> zone)
+    pub fn next_edit_step(&mut self) {
+        let description = self.selected_description().unwrap_or_default().to_string();
+        let InputMode::Edit(flow) = &mut self.input_mode else {
+            return;
+        };
+        match &flow.step {
+            EditStep::Target if !self.input.is_empty() => {
+                flow.step = EditStep::Description {
+                    target: std::mem::take(&mut self.input),
+                };
+                // Pre-fill with the current description
+                self.input = description;
+            }
+            EditStep::Description { target } => {
+                // Pre-select the current zone
+                let selected = self
+                    .zones
+                    .iter()
+                    .position(|z| z.id == flow.original_zone_id)
+                    .unwrap_or(0);
+                flow.step = EditStep::Zone {
+                    target: target.clone(),
+                    description: normalize_description(&self.input),
+                    selected,
+                };
+                self.input.clear();
+            }
+            _ => {}
+        }
+    }
 
-        // Store tunnel name and start edit flow
-        self.new_tunnel_name = Some(entry.tunnel.name.clone());
-        self.input_mode = InputMode::EditTarget;
+    // Open the token dialog for an account
+    pub fn start_token_entry(&mut self, account_name: String) {
+        self.input.clear();
+        self.token_account = Some(account_name);
+        self.input_mode = InputMode::TokenEntry;
     }
 
-    // Move to next step in edit flow (target -> zone)
-    pub fn next_edit_step(&mut self) {
-        if self.input_mode == InputMode::EditTarget && !self.input.is_empty() {
-            self.new_tunnel_target = Some(self.input.clone());
-            self.input.clear();
-            self.input_mode = InputMode::EditZone;
+    // Verify the token typed into the dialog and save it to the config
+    pub async fn submit_token(&mut self) -> Result<()> {
+        let Some(name) = self.token_account.clone() else {
+            return Ok(());
+        };
+        let secret = self.input.trim().to_string();
+        if secret.is_empty() {
+            self.status_message = Some("Token cannot be empty".to_string());
+            return Ok(());
         }
+
+        let mut cfg = config::load_config()?;
+        let credentials = match cfg.get_account(Some(&name))?.credentials() {
+            cloudflare::Credentials::ApiKey { email, .. } => {
+                cloudflare::Credentials::ApiKey { key: secret, email }
+            }
+            cloudflare::Credentials::Token(_) => cloudflare::Credentials::Token(secret),
+        };
+        cfg.update_credentials(&name, credentials, false).await?;
+        // Only this account changed; write it into a fresh copy of the config
+        let updated = cfg.get_account(Some(&name))?.clone();
+        let _lock = config::try_lock()?;
+        config::update_config(|latest| {
+            *latest.get_account_mut(Some(&name))? = updated;
+            Ok(())
+        })?;
+
+        self.cancel_input();
+        self.status_message = Some(format!("Updated API token for account '{}'", name));
+        self.load_tunnels().await
     }
 
     // Cancel current input
     pub fn cancel_input(&mut self) {
         self.input_mode = InputMode::Normal;
         self.input.clear();
-        self.new_tunnel_name = None;
-        self.new_tunnel_target = None;
         self.confirm_message = None;
         self.pending_action = None;
-        self.editing_tunnel_name = None;
-        self.original_zone_id = None;
-        self.original_hostname = None;
+        self.token_account = None;
+        self.set_flow_account(None);
     }
 
-    // Move to next step in add flow
+    // Move to next step in add/import flow (name -> target -> zone)
     pub fn next_add_step(&mut self) {
-        match self.input_mode {
-            InputMode::AddName => {
-                if !self.input.is_empty() {
+        if self.input.is_empty() {
+            return;
+        }
+        match &mut self.input_mode {
+            InputMode::Add(flow) => match &flow.step {
+                AddStep::Name => {
+                    // Unicode names become their punycode DNS label
+                    let name = match idn::to_ascii(&self.input) {
+                        Ok(name) => name,
+                        Err(e) => {
+                            self.status_message = Some(e.to_string());
+                            return;
+                        }
+                    };
                     // Check if name already exists
-                    if self.tunnels.iter().any(|t| t.tunnel.name == self.input) {
+                    if self.tunnels.iter().any(|t| t.tunnel.name == name) {
                         self.status_message =
                             Some(format!("Tunnel '{}' already exists", self.input));
                         return;
                     }
-                    self.new_tunnel_name = Some(self.input.clone());
-                    self.input.clear();
-                    self.input_mode = InputMode::AddTarget;
+                    self.input = flow
+                        .clone_source
+                        .as_ref()
+                        .map(|src| src.target_spec())
+                        .unwrap_or_default();
+                    flow.step = AddStep::Target { name };
                 }
-            }
-            InputMode::AddTarget => {
-                if !self.input.is_empty() {
-                    self.new_tunnel_target = Some(self.input.clone());
-                    self.input.clear();
-                    self.input_mode = InputMode::AddZone;
+                AddStep::Target { name } => {
+                    // A clone starts in its source's zone
+                    let selected = flow
+                        .clone_source
+                        .as_ref()
+                        .and_then(|src| self.zones.iter().position(|z| z.id == src.zone_id))
+                        .unwrap_or(0);
+                    flow.step = AddStep::Zone {
+                        name: name.clone(),
+                        target: std::mem::take(&mut self.input),
+                        selected,
+                    };
                 }
+                AddStep::Zone { .. } => {}
+            },
+            InputMode::Import(flow) if flow.step == ImportStep::Target => {
+                flow.step = ImportStep::Zone {
+                    target: std::mem::take(&mut self.input),
+                    selected: 0,
+                };
             }
             _ => {}
         }
     }
 
-    // Select zone in add flow (moves selection or confirms)
+    // Whether the current dialog is asking for text (rather than a zone)
+    pub fn has_text_field(&self) -> bool {
+        match self.input_mode {
+            InputMode::Add(_) | InputMode::Import(_) | InputMode::Edit(_) => {
+                self.zone_selected().is_none()
+            }
+            InputMode::TokenEntry => true,
+            _ => false,
+        }
+    }
+
+    // Zone highlighted in the current dialog, when it's at its zone step
+    pub fn zone_selected(&self) -> Option<usize> {
+        match &self.input_mode {
+            InputMode::Add(AddFlow {
+                step: AddStep::Zone { selected, .. },
+                ..
+            })
+            | InputMode::Import(ImportFlow {
+                step: ImportStep::Zone { selected, .. },
+                ..
+            })
+            | InputMode::Edit(EditFlow {
+                step: EditStep::Zone { selected, .. },
+                ..
+            }) => Some(*selected),
+            _ => None,
+        }
+    }
+
+    fn zone_selected_mut(&mut self) -> Option<&mut usize> {
+        match &mut self.input_mode {
+            InputMode::Add(AddFlow {
+                step: AddStep::Zone { selected, .. },
+                ..
+            })
+            | InputMode::Import(ImportFlow {
+                step: ImportStep::Zone { selected, .. },
+                ..
+            })
+            | InputMode::Edit(EditFlow {
+                step: EditStep::Zone { selected, .. },
+                ..
+            }) => Some(selected),
+            _ => None,
+        }
+    }
+
     pub fn select_zone_next(&mut self) {
-        if !self.zones.is_empty() && self.zone_selected < self.zones.len() - 1 {
-            self.zone_selected += 1;
+        let count = self.zones.len();
+        if let Some(selected) = self.zone_selected_mut() {
+            if *selected + 1 < count {
+                *selected += 1;
+            }
         }
     }
 
     pub fn select_zone_prev(&mut self) {
-        if self.zone_selected > 0 {
-            self.zone_selected -= 1;
+        if let Some(selected) = self.zone_selected_mut() {
+            *selected = selected.saturating_sub(1);
+        }
+    }
+
+    // Enter at a dialog's zone step: close the dialog and return what to do. Anywhere
+    // else, or without a zone or account to use, nothing happens.
+    pub fn finish_flow(&mut self) -> Option<FlowRequest> {
+        let mode = std::mem::replace(&mut self.input_mode, InputMode::Normal);
+        let request = match mode {
+            InputMode::Add(AddFlow {
+                step:
+                    AddStep::Zone {
+                        name,
+                        target,
+                        selected,
+                    },
+                clone_source,
+            }) => {
+                let (zone, account) = self.flow_destination(selected)?;
+                FlowRequest::Create {
+                    name,
+                    target,
+                    zone,
+                    account,
+                    template: clone_source,
+                }
+            }
+            InputMode::Import(ImportFlow {
+                name,
+                tunnel_id,
+                step: ImportStep::Zone { target, selected },
+            }) => {
+                let (zone, account) = self.flow_destination(selected)?;
+                FlowRequest::Import {
+                    name,
+                    target,
+                    zone,
+                    tunnel_id,
+                    account,
+                }
+            }
+            InputMode::Edit(EditFlow {
+                name,
+                original_zone_id,
+                original_hostname,
+                step:
+                    EditStep::Zone {
+                        target,
+                        description,
+                        selected,
+                    },
+            }) => {
+                let (zone, account) = self.flow_destination(selected)?;
+                FlowRequest::Edit {
+                    name,
+                    target,
+                    description,
+                    zone,
+                    original_zone_id,
+                    original_hostname,
+                    account,
+                }
+            }
+            other => {
+                self.input_mode = other;
+                return None;
+            }
+        };
+        Some(request)
+    }
+
+    // Report how a dialog's operation went and select the tunnel it created or changed
+    async fn show_flow_result(
+        &mut self,
+        result: Result<String>,
+        done: impl FnOnce(&str) -> String,
+    ) -> Result<()> {
+        match result {
+            Ok(name) => {
+                self.status_message = Some(done(&name));
+                self.load_tunnels().await?;
+                if let Some(pos) = self.tunnels.iter().position(|t| t.tunnel.name == name) {
+                    self.selected = pos;
+                    self.refresh_logs();
+                }
+            }
+            Err(e) if e.to_string() == "Cancelled" => {
+                self.status_message = Some("Cancelled".to_string());
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Error: {}", e));
+            }
+        }
+        Ok(())
+    }
+
+    // The zone picked at a dialog's last step and the account the dialog applies
+    // to; resets the flow account either way
+    fn flow_destination(&mut self, selected: usize) -> Option<(config::ZoneConfig, Account)> {
+        let zone = self.zones.get(selected).cloned();
+        let account = self.flow_account().cloned();
+        self.input.clear();
+        self.set_flow_account(None);
+        match (zone, account) {
+            (Some(zone), Some(account)) => Some((zone, account)),
+            (None, _) => {
+                self.status_message = Some("No zone selected".to_string());
+                None
+            }
+            (_, None) => {
+                self.status_message = Some("No account selected".to_string());
+                None
+            }
         }
     }
 
     // Check if selected tunnel is ephemeral
     pub fn is_selected_ephemeral(&self) -> bool {
-        self.tunnels
-            .get(self.selected)
+        self.selected_entry()
             .map(|e| e.kind == TunnelKind::Ephemeral)
             .unwrap_or(false)
     }
 
-    // Copy the selected tunnel's URL to clipboard
-    pub fn copy_url_to_clipboard(&mut self) {
-        if let Some(entry) = self.tunnels.get(self.selected) {
-            let url = format!("https://{}", entry.tunnel.hostname);
-            self.status_message = Some(format!("Copying {}...", url));
+    // What the copy menu offers for the selected tunnel: (key, label, value)
+    pub fn copy_choices(&self) -> Vec<(char, &'static str, String)> {
+        let Some(entry) = self.selected_entry() else {
+            return Vec::new();
+        };
+        let t = &entry.tunnel;
+        vec![
+            ('u', "URL", format!("https://{}", t.hostname)),
+            ('t', "Target", t.target_spec()),
+            ('h', "Hostname", t.hostname.clone()),
+            ('i', "Tunnel ID", t.tunnel_id.clone()),
+        ]
+    }
 
-            // Use pbcopy on macOS
-            use std::io::Write;
-            use std::process::{Command, Stdio};
+    // Open the copy menu (`c`)
+    pub fn open_copy_menu(&mut self) {
+        if self.selected_entry().is_none() {
+            self.status_message = Some("No tunnel selected".to_string());
+            return;
+        }
+        self.input_mode = InputMode::Copy;
+    }
 
-            let result = Command::new("pbcopy")
-                .stdin(Stdio::piped())
-                .spawn()
-                .and_then(|mut child| {
-                    if let Some(mut stdin) = child.stdin.take() {
-                        stdin.write_all(url.as_bytes())?;
-                    }
-                    child.wait()
-                });
+    // Copy the menu entry for `key` and close the menu; other keys leave it open
+    pub fn copy_choice(&mut self, key: char) {
+        let Some((_, label, value)) = self.copy_choices().into_iter().find(|(k, _, _)| *k == key)
+        else {
+            return;
+        };
+        self.input_mode = InputMode::Normal;
+        self.copy_to_clipboard(label, &value);
+    }
 
-            match result {
-                Ok(status) if status.success() => {
-                    self.status_message = Some(format!("Copied: {}", url));
-                }
-                _ => {
-                    self.status_message = Some("Failed to copy to clipboard".to_string());
+    fn copy_to_clipboard(&mut self, label: &str, text: &str) {
+        self.status_message = Some(match crate::clipboard::copy(text) {
+            Ok(()) => format!("Copied {}: {}", label.to_lowercase(), text),
+            Err(e) => format!("Failed to copy to clipboard: {}", e),
+        });
+    }
+
+    // Local files and ids of the selected tunnel for the info modal. Only paths:
+    // the credentials file's secret is never read. Ephemeral runs have no log file
+    // and their metrics port isn't recorded.
+    pub fn tunnel_info(&self) -> Vec<(&'static str, String)> {
+        let Some(entry) = self.selected_entry() else {
+            return Vec::new();
+        };
+        let t = &entry.tunnel;
+        let path = |p: Result<std::path::PathBuf>| {
+            p.map_or_else(
+                |e| format!("unavailable ({})", e),
+                |p| p.display().to_string(),
+            )
+        };
+        let mut info = vec![
+            ("Tunnel ID", t.tunnel_id.clone()),
+            ("Credentials", path(t.credentials_path())),
+        ];
+        match entry.kind {
+            TunnelKind::Managed => {
+                info.push(("Config", path(t.config_path())));
+                info.push(("Log", path(t.existing_log_path())));
+                info.push(("Metrics", t.metrics_display()));
+            }
+            TunnelKind::Ephemeral => {
+                if let Some(config) = tunnel::ephemeral_configs(&t.tunnel_id).first() {
+                    info.push(("Config", config.path.display().to_string()));
                 }
             }
-        } else {
+        }
+        info
+    }
+
+    pub fn show_info(&mut self) {
+        if self.selected_entry().is_none() {
             self.status_message = Some("No tunnel selected".to_string());
+            return;
+        }
+        self.info_selected = 0;
+        self.input_mode = InputMode::Info;
+    }
+
+    pub fn select_info_next(&mut self) {
+        if self.info_selected + 1 < self.tunnel_info().len() {
+            self.info_selected += 1;
+        }
+    }
+
+    pub fn select_info_prev(&mut self) {
+        self.info_selected = self.info_selected.saturating_sub(1);
+    }
+
+    // Copy the selected info row's value
+    pub fn copy_info(&mut self) {
+        if let Some((label, value)) = self.tunnel_info().into_iter().nth(self.info_selected) {
+            self.copy_to_clipboard(label, &value);
         }
     }
 
     // Open the selected tunnel's URL in browser
     pub fn open_in_browser(&mut self) {
-        if let Some(entry) = self.tunnels.get(self.selected) {
+        if let Some(entry) = self.selected_entry() {
             let url = format!("https://{}", entry.tunnel.hostname);
             self.status_message = Some(format!("Opening {}...", url));
 
-            // Use open command on macOS
-            use std::process::Command;
-
-            let result = Command::new("open").arg(&url).spawn();
-
-            match result {
+            match crate::browser::open_url(&url) {
                 Ok(_) => {
                     self.status_message = Some(format!("Opened: {}", url));
                 }
@@ -1599,7 +3105,7 @@ impl App {
 
     // Toggle auto-start on login for the selected tunnel
     pub async fn toggle_auto_start(&mut self) -> Result<()> {
-        if let Some(entry) = self.tunnels.get(self.selected) {
+        if let Some(entry) = self.selected_entry() {
             if entry.kind == TunnelKind::Ephemeral {
                 self.status_message = Some(
                     "Cannot set auto-start for ephemeral tunnel. Import it first.".to_string(),
@@ -1608,17 +3114,22 @@ impl App {
             }
 
             let name = entry.tunnel.name.clone();
+            let account_name = entry.tunnel.account_name.clone();
             let new_auto_start = !entry.tunnel.auto_start;
 
             // Update state
-            let mut state = TunnelState::load()?;
-            if let Some(t) = state.find_mut(&name) {
-                t.auto_start = new_auto_start;
-            }
-            state.save()?;
+            let updated = update_state(|state| {
+                Ok(state.find_for_account_mut(&name, &account_name).map(|t| {
+                    t.auto_start = new_auto_start;
+                    t.clone()
+                }))
+            })?;
+            let Some(tunnel) = updated else {
+                self.status_message = Some(format!("Tunnel '{}' not found in state", name));
+                return Ok(());
+            };
 
             // Reinstall daemon with new config
-            let tunnel = state.find(&name).unwrap().clone();
             daemon::install_daemon(&tunnel).await?;
 
             let status = if new_auto_start { "ON" } else { "OFF" };
@@ -1630,6 +3141,69 @@ impl App {
         Ok(())
     }
 
+    // Step the selected tunnel's cloudflared log level through LOG_LEVELS and back
+    // to cloudflared's default. A running tunnel picks it up when restarted.
+    pub async fn cycle_log_level(&mut self) -> Result<()> {
+        let Some(entry) = self.selected_entry() else {
+            return Ok(());
+        };
+        if entry.kind == TunnelKind::Ephemeral {
+            self.status_message = Some(
+                "Cannot set the log level of an ephemeral tunnel. Import it first.".to_string(),
+            );
+            return Ok(());
+        }
+
+        let name = entry.tunnel.name.clone();
+        let account_name = entry.tunnel.account_name.clone();
+        let running = entry.status == TunnelStatus::Running;
+        let new_level = next_log_level(entry.tunnel.log_level.as_deref());
+
+        let updated = update_state(|state| {
+            Ok(state.find_for_account_mut(&name, &account_name).map(|t| {
+                t.log_level = new_level.clone();
+                t.clone()
+            }))
+        })?;
+        let Some(tunnel) = updated else {
+            self.status_message = Some(format!("Tunnel '{}' not found in state", name));
+            return Ok(());
+        };
+
+        // Reinstall daemon with the new cloudflared arguments
+        daemon::install_daemon(&tunnel).await?;
+
+        self.status_message = Some(format!(
+            "Log level {}: {}{}",
+            new_level.as_deref().unwrap_or("default"),
+            name,
+            if running { " (press R to apply)" } else { "" }
+        ));
+        self.load_tunnels().await?;
+        Ok(())
+    }
+
+    // Ask before importing, naming the Cloudflare account the ephemeral tunnel
+    // was found in (the same <tunnel_prefix><name> can exist in several accounts)
+    pub fn request_import(&mut self) {
+        if !self.is_selected_ephemeral() {
+            self.status_message = Some("Only ephemeral tunnels can be imported".to_string());
+            return;
+        }
+        if let Some(entry) = self.selected_entry() {
+            let account_id = self
+                .account_named(&entry.tunnel.account_name)
+                .map(|a| a.account_id.clone())
+                .unwrap_or_default();
+            self.confirm_message = Some(format!(
+                "Import ephemeral tunnel '{}' into account '{}' ({})? (y/n)",
+                entry.tunnel.name, entry.tunnel.account_name, account_id
+            ));
+            self.pending_action = Some(PendingAction::Import);
+            self.input_mode = InputMode::Confirm;
+        }
+    }
+
     // Start import flow for ephemeral tunnel
     // Returns true if import was started (either directly or via dialog)
     pub async fn start_import(&mut self) -> Result<()> {
@@ -1642,33 +3216,43 @@ impl App {
             return Ok(());
         }
 
-        let entry = match self.tunnels.get(self.selected) {
+        let entry = match self.selected_entry() {
             Some(e) => e.clone(),
             None => return Ok(()),
         };
 
+        // Import into the account the ephemeral tunnel was found in
+        self.set_flow_account(Some(entry.tunnel.account_name.clone()));
+
         // Check if we have all the info needed for direct import
         let has_target = !entry.tunnel.target.is_empty() && entry.tunnel.target != "unknown";
         let has_zone = !entry.tunnel.zone_id.is_empty();
 
-        if has_target && has_zone {
+        if !has_zone && self.zones.is_empty() {
+            // The zone picker would have nothing to offer
+            self.status_message = Some(self.no_zones_message());
+            self.set_flow_account(None);
+        } else if has_target && has_zone {
             // We have everything - import directly
             self.status_message = Some(format!("Importing {}...", entry.tunnel.name));
             self.direct_import(&entry.tunnel).await?;
-        } else if has_target {
-            // Have target but need zone - go to zone selection
-            self.new_tunnel_name = Some(entry.tunnel.name.clone());
-            self.new_tunnel_target = Some(entry.tunnel.target.clone());
-            self.zone_selected = 0;
-            self.is_importing = true;
-            self.input_mode = InputMode::AddZone;
         } else {
-            // Need target - go to target input
-            self.new_tunnel_name = Some(entry.tunnel.name.clone());
-            self.new_tunnel_target = None;
-            self.zone_selected = 0;
-            self.is_importing = true;
-            self.input_mode = InputMode::AddTarget;
+            // Have target but need zone - go to zone selection; otherwise ask for
+            // the target first
+            let step = if has_target {
+                ImportStep::Zone {
+                    target: entry.tunnel.target.clone(),
+                    selected: 0,
+                }
+            } else {
+                ImportStep::Target
+            };
+            self.input.clear();
+            self.input_mode = InputMode::Import(ImportFlow {
+                name: entry.tunnel.name,
+                tunnel_id: entry.tunnel.tunnel_id,
+                step,
+            });
         }
 
         Ok(())
@@ -1677,10 +3261,12 @@ impl App {
     // Directly import an ephemeral tunnel without prompts
     async fn direct_import(&mut self, ephemeral: &PersistentTunnel) -> Result<()> {
         let acct = self
-            .current_account()
+            .account_named(&ephemeral.account_name)
+            .or_else(|| self.current_account())
             .ok_or_else(|| anyhow::anyhow!("No account selected"))?
             .clone();
-        let client = cloudflare::Client::new(&acct.api_token);
+        self.set_flow_account(None);
+        let client = self.api_client(&acct);
 
         // Ensure DNS record exists
         client
@@ -1703,6 +3289,16 @@ impl App {
             enabled: true,
             auto_start: false,
             metrics_port: None,
+            protocol: None,
+            log_level: None,
+            extra_hostnames: Vec::new(),
+            manage_dns: true,
+            description: None,
+            connect_time_ms: None,
+            maintenance: None,
+            fallback: None,
+            backends: Vec::new(),
+            dns_verified: None,
         };
 
         // Write tunnel config for daemon
@@ -1712,9 +3308,10 @@ impl App {
         daemon::install_daemon(&persistent).await?;
 
         // Save to state
-        let mut state = TunnelState::load()?;
-        state.add(persistent);
-        state.save()?;
+        update_state(|state| {
+            state.add(persistent);
+            Ok(())
+        })?;
 
         // Note: Don't start daemon yet - the ephemeral tunnel is still running
         // User should stop the ephemeral one first (Ctrl+C) then start via TUI
@@ -1728,11 +3325,9 @@ impl App {
         self.load_tunnels().await?;
 
         // Select the imported tunnel
-        if let Some(pos) = self
-            .tunnels
-            .iter()
-            .position(|t| t.tunnel.name == ephemeral.name)
-        {
+        if let Some(pos) = self.tunnels.iter().position(|t| {
+            t.tunnel.name == ephemeral.name && t.tunnel.account_name == ephemeral.account_name
+        }) {
             self.selected = pos;
             self.refresh_logs();
         }
@@ -1742,7 +3337,7 @@ impl App {
 
     // Request deletion of selected tunnel
     pub fn request_delete(&mut self) {
-        if let Some(entry) = self.tunnels.get(self.selected) {
+        if let Some(entry) = self.selected_entry().cloned() {
             let msg = if entry.kind == TunnelKind::Ephemeral {
                 format!(
                     "Delete ephemeral tunnel '{}'? This will remove it from Cloudflare. (y/n)",
@@ -1755,31 +3350,67 @@ impl App {
                 )
             };
             self.confirm_message = Some(msg);
-            self.pending_action = Some(PendingAction::Delete(entry.tunnel.name.clone()));
+            self.pending_action = Some(PendingAction::Delete(
+                entry.tunnel.name.clone(),
+                entry.tunnel.account_name.clone(),
+            ));
             self.input_mode = InputMode::Confirm;
         }
     }
+
+    // Request deletion of every ephemeral tunnel not running here
+    pub fn request_clean_ephemeral(&mut self) {
+        let unused: Vec<&TunnelEntry> = self
+            .tunnels
+            .iter()
+            .filter(|e| e.kind == TunnelKind::Ephemeral && e.status != TunnelStatus::Running)
+            .collect();
+        if unused.is_empty() {
+            self.status_message = Some("No unused ephemeral tunnels".to_string());
+            return;
+        }
+        // The count asked about is exactly what gets deleted
+        let tunnels: Vec<(String, String)> = unused
+            .iter()
+            .map(|e| (e.tunnel.account_name.clone(), e.tunnel.tunnel_id.clone()))
+            .collect();
+        self.confirm_message = Some(format!(
+            "Delete {} unused ephemeral tunnel(s) and their DNS records from Cloudflare? (y/n)",
+            tunnels.len()
+        ));
+        self.pending_action = Some(PendingAction::CleanEphemeral(tunnels));
+        self.input_mode = InputMode::Confirm;
+    }
+}
+
+// Whether to draw in ASCII: asked for, or a terminal that declares itself dumb
+fn ascii_mode(requested: bool) -> bool {
+    requested || std::env::var("TERM").is_ok_and(|term| term == "dumb")
 }
 
 // Run the TUI application
-pub async fn run_tui(initial_account: Option<&str>) -> Result<()> {
+pub async fn run_tui(
+    initial_account: Option<&str>,
+    ascii: bool,
+    theme: Option<&str>,
+) -> Result<()> {
     // Check if ytunnel is initialized
-    if !crate::config::config_path()?.exists() {
+    if !ytunnel::config::config_path()?.exists() {
         anyhow::bail!(
             "ytunnel is not initialized.\n\n\
              Run `ytunnel init` to set up your Cloudflare API credentials."
         );
     }
 
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    // Setup terminal (restored when the guard drops, or by the panic hook)
+    let _guard = TerminalGuard::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
 
     // Create app and load data
     let mut app = App::new(initial_account);
+    app.set_ascii(ascii_mode(ascii));
+    let configured = app.config.as_ref().and_then(|c| c.tui.theme.clone());
+    app.theme = Theme::resolve(theme.or(configured.as_deref()));
     if let Err(e) = app.load_tunnels().await {
         // Still show TUI even if load fails
         app.status_message = Some(format!("Error loading tunnels: {}", e));
@@ -1789,46 +3420,68 @@ pub async fn run_tui(initial_account: Option<&str>) -> Result<()> {
     app.check_all_health().await;
 
     // Main loop
-    let result = run_app(&mut terminal, &mut app).await;
-
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableBracketedPaste
-    )?;
-    terminal.show_cursor()?;
-
-    result
+    run_app(&mut terminal, &mut app).await
 }
 
 // Run the TUI in demo mode with synthetic data (no config required)
-pub async fn run_demo_tui() -> Result<()> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+pub async fn run_demo_tui(ascii: bool, theme: Option<&str>) -> Result<()> {
+    // Setup terminal (restored when the guard drops, or by the panic hook)
+    let _guard = TerminalGuard::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
 
     // Create demo app with fake tunnels
     let mut app = App::new_demo();
+    app.set_ascii(ascii_mode(ascii));
+    // The demo has no account of its own but still follows a configured theme
+    let configured = config::load_config().ok().and_then(|c| c.tui.theme);
+    app.theme = Theme::resolve(theme.or(configured.as_deref()));
     app.load_demo_tunnels();
 
     // Main loop
-    let result = run_app(&mut terminal, &mut app).await;
+    run_app(&mut terminal, &mut app).await
+}
 
-    // Restore terminal
-    disable_raw_mode()?;
+// Raw mode and the alternate screen for as long as the TUI runs. Dropping the guard
+// (normal exit, an early `?`, unwinding) restores the terminal; the panic hook does
+// it before the panic message is printed, which would otherwise be drawn into the
+// alternate screen and lost along with a usable shell.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            previous(info);
+        }));
+        enable_raw_mode()?;
+        let guard = TerminalGuard;
+        execute!(io::stdout(), EnterAlternateScreen, EnableBracketedPaste)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+        // Back to the default hook (take_hook can't be called while panicking)
+        if !std::thread::panicking() {
+            let _ = std::panic::take_hook();
+        }
+    }
+}
+
+// Undo everything the TUI may have switched on; harmless to repeat
+fn restore_terminal() {
+    disable_raw_mode().ok();
     execute!(
-        terminal.backend_mut(),
+        io::stdout(),
         LeaveAlternateScreen,
-        DisableBracketedPaste
-    )?;
-    terminal.show_cursor()?;
-
-    result
+        DisableBracketedPaste,
+        DisableMouseCapture,
+        cursor::Show
+    )
+    .ok();
 }
 
 async fn run_app(
@@ -1837,8 +3490,10 @@ async fn run_app(
 ) -> Result<()> {
     let mut last_metrics_refresh = std::time::Instant::now();
     let mut last_health_check = std::time::Instant::now();
+    let mut last_dns_check = std::time::Instant::now();
     let metrics_refresh_interval = Duration::from_secs(5);
     let health_check_interval = Duration::from_secs(30);
+    let dns_check_interval = Duration::from_secs(10 * 60);
 
     loop {
         terminal.draw(|f| ui::render(f, app))?;
@@ -1852,12 +3507,30 @@ async fn run_app(
             last_metrics_refresh = std::time::Instant::now();
         }
 
+        // Append new log lines for the selected tunnel on every tick
+        if !app.spinner.is_active() {
+            app.follow_logs();
+        }
+
+        // Fetch process details once a tunnel is selected (refreshed with metrics)
+        if !app.spinner.is_active() && app.selected_needs_daemon_info() {
+            app.refresh_daemon_info().await;
+        }
+
         // Check health of all running tunnels less frequently (skip if spinner is active)
         if !app.spinner.is_active() && last_health_check.elapsed() >= health_check_interval {
             app.check_all_health().await;
             last_health_check = std::time::Instant::now();
         }
 
+        // Confirm the running tunnels' DNS records now and then, since starting
+        // a recently verified tunnel no longer does
+        if !app.spinner.is_active() && last_dns_check.elapsed() >= dns_check_interval {
+            app.check_dns();
+            last_dns_check = std::time::Instant::now();
+        }
+        app.finish_dns_check().await;
+
         // Poll for events - use shorter timeout when spinner is active for smooth animation
         let poll_timeout = if app.spinner.is_active() {
             Duration::from_millis(80)
@@ -1869,13 +3542,18 @@ async fn run_app(
         if event::poll(poll_timeout)? {
             let event = event::read()?;
 
+            // Redraw at the new size right away rather than on the next tick
+            if let Event::Resize(..) = event {
+                terminal.autoresize()?;
+                continue;
+            }
+
             // Handle paste events (some remote desktop software sends text as paste)
             if let Event::Paste(text) = &event {
-                if matches!(
-                    app.input_mode,
-                    InputMode::AddName | InputMode::AddTarget | InputMode::EditTarget
-                ) {
+                if app.has_text_field() {
                     app.input.push_str(text);
+                } else if matches!(app.input_mode, InputMode::Filter) {
+                    text.chars().for_each(|c| app.push_filter(c));
                 }
                 continue;
             }
@@ -1893,6 +3571,13 @@ async fn run_app(
                     continue;
                 }
 
+                // Deliberate panic for checking that the terminal is restored
+                // (cargo run --features debug-panic)
+                #[cfg(feature = "debug-panic")]
+                if key.code == KeyCode::Char('p') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                    panic!("debug-panic: Ctrl+P pressed");
+                }
+
                 // Handle Ctrl+Z to suspend
                 if key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL) {
                     // Restore terminal before suspending
@@ -1925,7 +3610,10 @@ async fn run_app(
                 }
 
                 match app.input_mode {
-                    InputMode::Normal => match key.code {
+                    // Key handlers call demo_guard() for its side effect (status
+                    // message), so they keep nested ifs instead of match guards
+                    #[allow(clippy::collapsible_match)]
+                    InputMode::Normal => match app.resolve_key(key.code, key.modifiers) {
                         KeyCode::Char('q') => {
                             app.should_quit = true;
                         }
@@ -1939,9 +3627,14 @@ async fn run_app(
                                 app.start_edit();
                             }
                         }
+                        KeyCode::Char('C') => {
+                            if !app.demo_guard() {
+                                app.start_clone();
+                            }
+                        }
                         KeyCode::Char('s') => {
                             if !app.demo_guard() {
-                                if let Some(entry) = app.tunnels.get(app.selected) {
+                                if let Some(entry) = app.selected_entry() {
                                     if entry.kind == TunnelKind::Ephemeral {
                                         app.status_message = Some(
                                         "Cannot start ephemeral tunnel. Import it first with 'm'."
@@ -1983,15 +3676,20 @@ async fn run_app(
                                         app.spinner.stop();
                                         match result {
                                             Ok(name) => {
-                                                app.status_message =
-                                                    Some(format!("Started {}", name));
                                                 app.load_tunnels().await?;
+                                                app.status_message =
+                                                    Some(app.started_message("Started", &name));
                                             }
                                             Err(e) if e.to_string() == "Cancelled" => {
                                                 app.status_message = Some("Cancelled".to_string());
                                             }
                                             Err(e) => {
-                                                app.status_message = Some(format!("Error: {}", e));
+                                                // Show cloudflared's own error and the Error status
+                                                app.status_message = Some(format!(
+                                                    "Error: {}",
+                                                    daemon::start_error_summary(&e)
+                                                ));
+                                                app.load_tunnels().await?;
                                             }
                                         }
                                     }
@@ -2000,19 +3698,18 @@ async fn run_app(
                         }
                         KeyCode::Char('S') => {
                             if !app.demo_guard() {
-                                if let Some(entry) = app.tunnels.get(app.selected) {
+                                if let Some(entry) = app.selected_entry() {
                                     if entry.kind == TunnelKind::Ephemeral {
                                         app.status_message = Some(
                                         "Cannot stop ephemeral tunnel from TUI. Use Ctrl+C in its terminal."
                                             .to_string(),
                                     );
                                     } else {
-                                        let name = entry.tunnel.name.clone();
-                                        let account_name = entry.tunnel.account_name.clone();
+                                        let tunnel = entry.tunnel.clone();
 
-                                        app.spinner.start(&format!("Stopping {}...", name));
+                                        app.spinner.start(&format!("Stopping {}...", tunnel.name));
 
-                                        let fut = stop_tunnel_op(name.clone(), account_name);
+                                        let fut = stop_tunnel_op(tunnel);
                                         tokio::pin!(fut);
 
                                         let result: Result<String> = loop {
@@ -2037,9 +3734,8 @@ async fn run_app(
 
                                         app.spinner.stop();
                                         match result {
-                                            Ok(name) => {
-                                                app.status_message =
-                                                    Some(format!("Stopped {}", name));
+                                            Ok(message) => {
+                                                app.status_message = Some(message);
                                                 app.load_tunnels().await?;
                                             }
                                             Err(e) if e.to_string() == "Cancelled" => {
@@ -2058,11 +3754,14 @@ async fn run_app(
                                 app.request_delete();
                             }
                         }
+                        KeyCode::Char('D') => {
+                            if !app.demo_guard() {
+                                app.request_clean_ephemeral();
+                            }
+                        }
                         KeyCode::Char('m') => {
                             if !app.demo_guard() {
-                                if let Err(e) = app.start_import().await {
-                                    app.status_message = Some(format!("Error: {}", e));
-                                }
+                                app.request_import();
                             }
                         }
                         KeyCode::Char('r') => {
@@ -2086,7 +3785,7 @@ async fn run_app(
                         }
                         KeyCode::Char('R') => {
                             if !app.demo_guard() {
-                                if let Some(entry) = app.tunnels.get(app.selected) {
+                                if let Some(entry) = app.selected_entry() {
                                     if entry.kind == TunnelKind::Ephemeral {
                                         app.status_message = Some(
                                         "Cannot restart ephemeral tunnel. Import it first with 'm'."
@@ -2126,15 +3825,20 @@ async fn run_app(
                                         app.spinner.stop();
                                         match result {
                                             Ok(name) => {
-                                                app.status_message =
-                                                    Some(format!("Restarted {}", name));
                                                 app.load_tunnels().await?;
+                                                app.status_message =
+                                                    Some(app.started_message("Restarted", &name));
                                             }
                                             Err(e) if e.to_string() == "Cancelled" => {
                                                 app.status_message = Some("Cancelled".to_string());
                                             }
                                             Err(e) => {
-                                                app.status_message = Some(format!("Error: {}", e));
+                                                // Show cloudflared's own error and the Error status
+                                                app.status_message = Some(format!(
+                                                    "Error: {}",
+                                                    daemon::start_error_summary(&e)
+                                                ));
+                                                app.load_tunnels().await?;
                                             }
                                         }
                                     }
@@ -2142,7 +3846,10 @@ async fn run_app(
                             }
                         }
                         KeyCode::Char('c') => {
-                            app.copy_url_to_clipboard();
+                            app.open_copy_menu();
+                        }
+                        KeyCode::Char('i') => {
+                            app.show_info();
                         }
                         KeyCode::Char('o') => {
                             if !app.demo_guard() {
@@ -2154,6 +3861,22 @@ async fn run_app(
                                 app.check_health().await;
                             }
                         }
+                        KeyCode::Char('T') => {
+                            if !app.demo_guard() {
+                                // The rejected account first, otherwise the current one
+                                let name = app
+                                    .rejected_accounts
+                                    .first()
+                                    .cloned()
+                                    .or_else(|| app.current_account().map(|a| a.name.clone()));
+                                match name {
+                                    Some(name) => app.start_token_entry(name),
+                                    None => {
+                                        app.status_message = Some("No account selected".to_string())
+                                    }
+                                }
+                            }
+                        }
                         KeyCode::Char('A') => {
                             if !app.demo_guard() {
                                 if let Err(e) = app.toggle_auto_start().await {
@@ -2161,9 +3884,56 @@ async fn run_app(
                                 }
                             }
                         }
+                        KeyCode::Char('v') => {
+                            if !app.demo_guard() {
+                                if let Err(e) = app.cycle_log_level().await {
+                                    app.status_message = Some(format!("Error: {}", e));
+                                }
+                            }
+                        }
                         KeyCode::Char('?') => {
                             app.input_mode = InputMode::Help;
                         }
+                        KeyCode::Enter => {
+                            app.focus_selected();
+                        }
+                        KeyCode::Char('/') => {
+                            app.start_filter();
+                        }
+                        KeyCode::Char('l') => {
+                            app.toggle_log_view();
+                        }
+                        KeyCode::Char('<') => {
+                            app.resize_split(-1);
+                        }
+                        KeyCode::Char('>') => {
+                            app.resize_split(1);
+                        }
+                        KeyCode::Char('M') => {
+                            app.toggle_metrics_panel();
+                        }
+                        KeyCode::Char('L') => {
+                            app.toggle_raw_logs();
+                        }
+                        KeyCode::Esc => {
+                            // Leave the log or focused view first, then drop the filter
+                            if app.log_view {
+                                app.log_view = false;
+                            } else if app.focused {
+                                app.focused = false;
+                            } else {
+                                app.filter.clear();
+                            }
+                        }
+                        KeyCode::PageUp => {
+                            app.scroll_logs_up(LOG_PAGE_LINES);
+                        }
+                        KeyCode::PageDown => {
+                            app.scroll_logs_down(LOG_PAGE_LINES);
+                        }
+                        KeyCode::End => {
+                            app.log_scroll = 0;
+                        }
                         KeyCode::Up | KeyCode::Char('k') => {
                             if app.select_previous()
                                 && !app.demo
@@ -2177,6 +3947,19 @@ async fn run_app(
                                 app.check_health().await;
                             }
                         }
+                        KeyCode::Char(c @ ('g' | 'G' | '1'..='9')) => {
+                            if app.jump_to(c) && !app.demo && app.selected_needs_health_check() {
+                                app.check_health().await;
+                            }
+                        }
+                        KeyCode::Char(':') => {
+                            if !app.demo_guard() && app.accounts.len() > 1 {
+                                app.toggle_all_accounts();
+                                if let Err(e) = app.load_tunnels().await {
+                                    app.status_message = Some(format!("Error: {}", e));
+                                }
+                            }
+                        }
                         KeyCode::Char(';') => {
                             if !app.demo_guard() {
                                 // Cycle to next account
@@ -2196,12 +3979,70 @@ async fn run_app(
                         }
                         _ => {}
                     },
-                    InputMode::AddName | InputMode::AddTarget => match key.code {
+                    InputMode::Info => match key.code {
+                        KeyCode::Esc | KeyCode::Char('i') | KeyCode::Char('q') => {
+                            app.input_mode = InputMode::Normal;
+                        }
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            app.select_info_prev();
+                        }
+                        KeyCode::Down | KeyCode::Char('j') => {
+                            app.select_info_next();
+                        }
+                        KeyCode::Enter | KeyCode::Char('c') => {
+                            app.copy_info();
+                        }
+                        _ => {}
+                    },
+                    InputMode::Copy => match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            app.input_mode = InputMode::Normal;
+                        }
+                        // `c c` and `c Enter` copy the URL, as `c` alone used to
+                        KeyCode::Enter | KeyCode::Char('c') => {
+                            app.copy_choice('u');
+                        }
+                        KeyCode::Char(c) => {
+                            app.copy_choice(c);
+                        }
+                        _ => {}
+                    },
+                    InputMode::Filter => match key.code {
+                        KeyCode::Esc => {
+                            app.clear_filter();
+                        }
+                        KeyCode::Enter => {
+                            // Keep the filter and go back to navigating the matches
+                            app.input_mode = InputMode::Normal;
+                        }
+                        KeyCode::Up => {
+                            app.select_previous();
+                        }
+                        KeyCode::Down => {
+                            app.select_next();
+                        }
+                        KeyCode::Backspace => {
+                            app.pop_filter();
+                        }
+                        KeyCode::Char(c) => {
+                            app.push_filter(c);
+                        }
+                        _ => {}
+                    },
+                    InputMode::TokenEntry => match key.code {
                         KeyCode::Esc => {
                             app.cancel_input();
                         }
                         KeyCode::Enter => {
-                            app.next_add_step();
+                            app.spinner.start("Verifying token...");
+                            terminal.draw(|f| ui::render(f, app))?;
+                            let result = app.submit_token().await;
+                            app.spinner.stop();
+                            if let Err(e) = result {
+                                // Keep the dialog open so the token can be fixed
+                                app.input.clear();
+                                app.status_message = Some(format!("Error: {:#}", e));
+                            }
                         }
                         KeyCode::Backspace => {
                             app.input.pop();
@@ -2211,82 +4052,65 @@ async fn run_app(
                         }
                         _ => {}
                     },
-                    InputMode::AddZone => match key.code {
+                    // Zone step of the add/import/edit dialogs
+                    _ if app.zone_selected().is_some() => match key.code {
                         KeyCode::Esc => {
                             app.cancel_input();
                         }
-                        KeyCode::Enter => {
-                            // Extract all data before creating future
-                            let name = match app.new_tunnel_name.clone() {
-                                Some(n) => n,
-                                None => {
-                                    app.status_message = Some("No tunnel name".to_string());
-                                    app.input_mode = InputMode::Normal;
-                                    continue;
-                                }
-                            };
-                            let target = match app.new_tunnel_target.clone() {
-                                Some(t) => t,
-                                None => {
-                                    app.status_message = Some("No target URL".to_string());
-                                    app.input_mode = InputMode::Normal;
-                                    continue;
-                                }
-                            };
-                            let zone: config::ZoneConfig = match app.zones.get(app.zone_selected) {
-                                Some(z) => z.clone(),
-                                None => {
-                                    app.status_message = Some("No zone selected".to_string());
-                                    app.input_mode = InputMode::Normal;
-                                    continue;
-                                }
-                            };
-                            let account: Account = match app.current_account() {
-                                Some(a) => a.clone(),
-                                None => {
-                                    app.status_message = Some("No account selected".to_string());
-                                    app.input_mode = InputMode::Normal;
-                                    continue;
-                                }
-                            };
+                        KeyCode::Enter => match app.finish_flow() {
+                            None => {}
+                            Some(FlowRequest::Create {
+                                name,
+                                target,
+                                zone,
+                                account,
+                                template,
+                            }) => {
+                                app.spinner.start(&format!("Creating {}...", name));
+                                let fut = create_tunnel_op(
+                                    name,
+                                    target,
+                                    zone,
+                                    account,
+                                    template.map(|t| *t),
+                                );
+                                tokio::pin!(fut);
 
-                            let is_importing = app.is_importing;
-                            let msg = if is_importing {
-                                format!("Importing {}...", name)
-                            } else {
-                                format!("Creating {}...", name)
-                            };
-                            app.spinner.start(&msg);
+                                let result: Result<String> = loop {
+                                    terminal.draw(|f| ui::render(f, app))?;
 
-                            // Get tunnel_id for import (from ephemeral tunnel)
-                            let tunnel_id = if is_importing {
-                                app.tunnels
-                                    .iter()
-                                    .find(|e| {
-                                        e.tunnel.name == name && e.kind == TunnelKind::Ephemeral
-                                    })
-                                    .map(|e| e.tunnel.tunnel_id.clone())
-                            } else {
-                                None
-                            };
+                                    if event::poll(Duration::from_millis(10))? {
+                                        if let Event::Key(k) = event::read()? {
+                                            if is_cancel_key(&k) {
+                                                break Err(anyhow::anyhow!("Cancelled"));
+                                            }
+                                        }
+                                    }
 
-                            // Create and pin the appropriate future
-                            let result: Result<String> = if is_importing {
-                                let tid = match tunnel_id {
-                                    Some(t) => t,
-                                    None => {
-                                        app.spinner.stop();
-                                        app.status_message =
-                                            Some("Ephemeral tunnel not found".to_string());
-                                        app.input_mode = InputMode::Normal;
-                                        continue;
+                                    tokio::select! {
+                                        biased;
+                                        res = &mut fut => break res.map(|(n, _)| n),
+                                        _ = tokio::time::sleep(Duration::from_millis(70)) => {
+                                            app.spinner.tick();
+                                        }
                                     }
                                 };
-                                let fut =
-                                    import_tunnel_op(name.clone(), target, zone, tid, account);
+                                app.spinner.stop();
+                                app.show_flow_result(result, |n| format!("Created tunnel '{}'", n))
+                                    .await?;
+                            }
+                            Some(FlowRequest::Import {
+                                name,
+                                target,
+                                zone,
+                                tunnel_id,
+                                account,
+                            }) => {
+                                app.spinner.start(&format!("Importing {}...", name));
+                                let fut = import_tunnel_op(name, target, zone, tunnel_id, account);
                                 tokio::pin!(fut);
 
-                                loop {
+                                let result: Result<String> = loop {
                                     terminal.draw(|f| ui::render(f, app))?;
 
                                     if event::poll(Duration::from_millis(10))? {
@@ -2304,12 +4128,51 @@ async fn run_app(
                                             app.spinner.tick();
                                         }
                                     }
-                                }
-                            } else {
-                                let fut = create_tunnel_op(name.clone(), target, zone, account);
+                                };
+                                app.spinner.stop();
+                                app.show_flow_result(result, |n| {
+                                    format!("Imported tunnel '{}'", n)
+                                })
+                                .await?;
+                            }
+                            Some(FlowRequest::Edit {
+                                name,
+                                target,
+                                description,
+                                zone,
+                                original_zone_id,
+                                original_hostname,
+                                account,
+                            }) => {
+                                // Find tunnel info
+                                let entry = match app.tunnels.iter().find(|e| {
+                                    e.tunnel.name == name && e.tunnel.account_name == account.name
+                                }) {
+                                    Some(e) => e,
+                                    None => {
+                                        app.status_message = Some("Tunnel not found".to_string());
+                                        continue;
+                                    }
+                                };
+                                let was_running = entry.status == TunnelStatus::Running;
+                                let tunnel_id = entry.tunnel.tunnel_id.clone();
+
+                                app.spinner.start(&format!("Updating {}...", name));
+
+                                let fut = edit_tunnel_op(
+                                    name,
+                                    target,
+                                    description,
+                                    zone,
+                                    original_zone_id,
+                                    original_hostname,
+                                    tunnel_id,
+                                    was_running,
+                                    account,
+                                );
                                 tokio::pin!(fut);
 
-                                loop {
+                                let result: Result<String> = loop {
                                     terminal.draw(|f| ui::render(f, app))?;
 
                                     if event::poll(Duration::from_millis(10))? {
@@ -2322,41 +4185,17 @@ async fn run_app(
 
                                     tokio::select! {
                                         biased;
-                                        res = &mut fut => break res.map(|(n, _)| n),
+                                        res = &mut fut => break res,
                                         _ = tokio::time::sleep(Duration::from_millis(70)) => {
                                             app.spinner.tick();
                                         }
                                     }
-                                }
-                            };
-
-                            app.spinner.stop();
-                            app.new_tunnel_name = None;
-                            app.new_tunnel_target = None;
-                            app.input_mode = InputMode::Normal;
-
-                            match result {
-                                Ok(name) => {
-                                    let action = if is_importing { "Imported" } else { "Created" };
-                                    app.status_message =
-                                        Some(format!("{} tunnel '{}'", action, name));
-                                    app.load_tunnels().await?;
-                                    // Select the new tunnel
-                                    if let Some(pos) =
-                                        app.tunnels.iter().position(|t| t.tunnel.name == name)
-                                    {
-                                        app.selected = pos;
-                                        app.refresh_logs();
-                                    }
-                                }
-                                Err(e) if e.to_string() == "Cancelled" => {
-                                    app.status_message = Some("Cancelled".to_string());
-                                }
-                                Err(e) => {
-                                    app.status_message = Some(format!("Error: {}", e));
-                                }
+                                };
+                                app.spinner.stop();
+                                app.show_flow_result(result, |n| format!("Tunnel '{}' updated", n))
+                                    .await?;
                             }
-                        }
+                        },
                         KeyCode::Up | KeyCode::Char('k') => {
                             app.select_zone_prev();
                         }
@@ -2365,12 +4204,12 @@ async fn run_app(
                         }
                         _ => {}
                     },
-                    InputMode::EditTarget => match key.code {
+                    InputMode::Add(_) | InputMode::Import(_) => match key.code {
                         KeyCode::Esc => {
                             app.cancel_input();
                         }
                         KeyCode::Enter => {
-                            app.next_edit_step();
+                            app.next_add_step();
                         }
                         KeyCode::Backspace => {
                             app.input.pop();
@@ -2380,160 +4219,98 @@ async fn run_app(
                         }
                         _ => {}
                     },
-                    InputMode::EditZone => match key.code {
+                    InputMode::Edit(_) => match key.code {
                         KeyCode::Esc => {
                             app.cancel_input();
                         }
                         KeyCode::Enter => {
-                            // Extract all data before creating future
-                            let name = match app.editing_tunnel_name.clone() {
-                                Some(n) => n,
-                                None => {
-                                    app.status_message = Some("No tunnel name".to_string());
-                                    app.input_mode = InputMode::Normal;
-                                    continue;
-                                }
-                            };
-                            let new_target = match app.new_tunnel_target.clone() {
-                                Some(t) => t,
-                                None => {
-                                    app.status_message = Some("No target URL".to_string());
-                                    app.input_mode = InputMode::Normal;
-                                    continue;
-                                }
-                            };
-                            let new_zone: config::ZoneConfig =
-                                match app.zones.get(app.zone_selected) {
-                                    Some(z) => z.clone(),
-                                    None => {
-                                        app.status_message = Some("No zone selected".to_string());
-                                        app.input_mode = InputMode::Normal;
-                                        continue;
-                                    }
-                                };
-                            let original_zone_id = match app.original_zone_id.clone() {
-                                Some(z) => z,
-                                None => {
-                                    app.status_message = Some("Missing original zone".to_string());
-                                    app.input_mode = InputMode::Normal;
-                                    continue;
-                                }
-                            };
-                            let original_hostname = match app.original_hostname.clone() {
-                                Some(h) => h,
-                                None => {
-                                    app.status_message =
-                                        Some("Missing original hostname".to_string());
-                                    app.input_mode = InputMode::Normal;
-                                    continue;
-                                }
-                            };
-                            let account: Account = match app.current_account() {
-                                Some(a) => a.clone(),
-                                None => {
-                                    app.status_message = Some("No account selected".to_string());
-                                    app.input_mode = InputMode::Normal;
-                                    continue;
-                                }
-                            };
-
-                            // Find tunnel info
-                            let entry = match app.tunnels.iter().find(|e| e.tunnel.name == name) {
-                                Some(e) => e,
-                                None => {
-                                    app.status_message = Some("Tunnel not found".to_string());
-                                    app.input_mode = InputMode::Normal;
-                                    continue;
+                            app.next_edit_step();
+                        }
+                        KeyCode::Backspace => {
+                            app.input.pop();
+                        }
+                        KeyCode::Char(c) => {
+                            app.input.push(c);
+                        }
+                        _ => {}
+                    },
+                    InputMode::Confirm => match key.code {
+                        KeyCode::Char('y') | KeyCode::Char('Y') => {
+                            if matches!(app.pending_action, Some(PendingAction::Import)) {
+                                app.pending_action = None;
+                                app.confirm_message = None;
+                                app.input_mode = InputMode::Normal;
+                                if let Err(e) = app.start_import().await {
+                                    app.status_message = Some(format!("Error: {}", e));
                                 }
-                            };
-                            let was_running = entry.status == TunnelStatus::Running;
-                            let tunnel_id = entry.tunnel.tunnel_id.clone();
+                            } else if let Some(PendingAction::CleanEphemeral(tunnels)) =
+                                app.pending_action.clone()
+                            {
+                                app.pending_action = None;
+                                app.confirm_message = None;
+                                app.input_mode = InputMode::Normal;
 
-                            app.spinner.start(&format!("Updating {}...", name));
+                                let mut names: Vec<&String> =
+                                    tunnels.iter().map(|(account, _)| account).collect();
+                                names.sort();
+                                names.dedup();
+                                let accounts: Vec<Account> = names
+                                    .iter()
+                                    .filter_map(|n| app.account_named(n).cloned())
+                                    .collect();
+                                let tunnel_ids = tunnels.into_iter().map(|(_, id)| id).collect();
+                                app.spinner.start("Deleting ephemeral tunnels...");
 
-                            let fut = edit_tunnel_op(
-                                name.clone(),
-                                new_target,
-                                new_zone,
-                                original_zone_id,
-                                original_hostname,
-                                tunnel_id,
-                                was_running,
-                                account,
-                            );
-                            tokio::pin!(fut);
+                                let fut = clean_ephemeral_op(accounts, tunnel_ids);
+                                tokio::pin!(fut);
 
-                            let result: Result<String> = loop {
-                                terminal.draw(|f| ui::render(f, app))?;
+                                let result: Result<usize> = loop {
+                                    terminal.draw(|f| ui::render(f, app))?;
 
-                                if event::poll(Duration::from_millis(10))? {
-                                    if let Event::Key(k) = event::read()? {
-                                        if is_cancel_key(&k) {
-                                            break Err(anyhow::anyhow!("Cancelled"));
+                                    if event::poll(Duration::from_millis(10))? {
+                                        if let Event::Key(k) = event::read()? {
+                                            if is_cancel_key(&k) {
+                                                break Err(anyhow::anyhow!("Cancelled"));
+                                            }
                                         }
                                     }
-                                }
 
-                                tokio::select! {
-                                    biased;
-                                    res = &mut fut => break res,
-                                    _ = tokio::time::sleep(Duration::from_millis(70)) => {
-                                        app.spinner.tick();
+                                    tokio::select! {
+                                        biased;
+                                        res = &mut fut => break res,
+                                        _ = tokio::time::sleep(Duration::from_millis(70)) => {
+                                            app.spinner.tick();
+                                        }
                                     }
-                                }
-                            };
-
-                            app.spinner.stop();
-                            app.editing_tunnel_name = None;
-                            app.new_tunnel_target = None;
-                            app.original_zone_id = None;
-                            app.original_hostname = None;
-                            app.input_mode = InputMode::Normal;
+                                };
 
-                            match result {
-                                Ok(name) => {
-                                    app.status_message = Some(format!("Tunnel '{}' updated", name));
-                                    app.load_tunnels().await?;
-                                    // Select the edited tunnel
-                                    if let Some(pos) =
-                                        app.tunnels.iter().position(|t| t.tunnel.name == name)
-                                    {
-                                        app.selected = pos;
-                                        app.refresh_logs();
+                                app.spinner.stop();
+                                app.status_message = Some(match result {
+                                    Ok(count) => format!("Deleted {} ephemeral tunnel(s)", count),
+                                    Err(e) if e.to_string() == "Cancelled" => {
+                                        "Cancelled".to_string()
                                     }
-                                }
-                                Err(e) if e.to_string() == "Cancelled" => {
-                                    app.status_message = Some("Cancelled".to_string());
-                                }
-                                Err(e) => {
-                                    app.status_message = Some(format!("Error: {}", e));
-                                }
-                            }
-                        }
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            app.select_zone_prev();
-                        }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            app.select_zone_next();
-                        }
-                        _ => {}
-                    },
-                    InputMode::Confirm => match key.code {
-                        KeyCode::Char('y') | KeyCode::Char('Y') => {
-                            if let Some(PendingAction::Delete(name)) = app.pending_action.take() {
+                                    Err(e) => format!("Error: {}", e),
+                                });
+                                app.load_tunnels().await?;
+                            } else if let Some(PendingAction::Delete(name, account_name)) =
+                                app.pending_action.take()
+                            {
                                 app.confirm_message = None;
                                 app.input_mode = InputMode::Normal;
 
                                 // Extract info from tunnel entry
-                                let entry = app.tunnels.iter().find(|e| e.tunnel.name == name);
+                                let entry = app.tunnels.iter().find(|e| {
+                                    e.tunnel.name == name && e.tunnel.account_name == account_name
+                                });
                                 let is_ephemeral = entry
                                     .map(|e| e.kind == TunnelKind::Ephemeral)
                                     .unwrap_or(false);
                                 let tunnel_id = entry.map(|e| e.tunnel.tunnel_id.clone());
-                                let account_name = entry
-                                    .map(|e| e.tunnel.account_name.clone())
-                                    .unwrap_or_else(|| app.current_account_name().to_string());
-                                let account = app.current_account().cloned();
+                                let account = app
+                                    .account_named(&account_name)
+                                    .or_else(|| app.current_account())
+                                    .cloned();
 
                                 app.spinner.start(&format!("Deleting {}...", name));
 
@@ -2591,7 +4368,503 @@ async fn run_app(
         }
 
         if app.should_quit {
+            if app.layout_unsaved {
+                app.save_layout();
+            }
             return Ok(());
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(app: &App) -> Vec<&str> {
+        app.visible_tunnels()
+            .into_iter()
+            .map(|i| app.tunnels[i].tunnel.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_next_log_level_cycles_back_to_the_default() {
+        let mut level = None;
+        let mut seen = Vec::new();
+        for _ in 0..config::LOG_LEVELS.len() {
+            level = next_log_level(level.as_deref());
+            seen.push(level.clone().unwrap());
+        }
+        assert_eq!(seen, config::LOG_LEVELS);
+        assert_eq!(next_log_level(level.as_deref()), None);
+        // A level written by hand that ytunnel doesn't know goes back to the default
+        assert_eq!(next_log_level(Some("trace")), None);
+    }
+
+    #[test]
+    fn test_filter_matches_descriptions_and_skips_hidden_tunnels() {
+        let mut app = App::new_demo();
+        app.load_demo_tunnels();
+        let all = app.tunnels.len();
+
+        // Case-insensitive, and descriptions count
+        "STOREFRONT".chars().for_each(|c| app.push_filter(c));
+        assert_eq!(names(&app), ["webapp", "staging"]);
+        assert_eq!(app.tunnels[app.selected].tunnel.name, "webapp");
+        assert!(app.select_next());
+        assert_eq!(app.tunnels[app.selected].tunnel.name, "staging");
+        assert!(!app.select_next());
+
+        // Narrowing the filter moves the selection onto a match
+        app.filter.clear();
+        "qa".chars().for_each(|c| app.push_filter(c));
+        assert_eq!(names(&app), ["staging"]);
+        app.pop_filter();
+        app.pop_filter();
+
+        // With nothing matching, actions have no tunnel to act on
+        "no such tunnel".chars().for_each(|c| app.push_filter(c));
+        assert!(names(&app).is_empty());
+        assert!(app.selected_entry().is_none());
+        app.request_delete();
+        assert!(app.pending_action.is_none());
+        assert!(matches!(app.input_mode, InputMode::Normal));
+
+        app.clear_filter();
+        assert_eq!(app.visible_tunnels().len(), all);
+        assert!(matches!(app.input_mode, InputMode::Normal));
+    }
+
+    // A demo app with a config, so the add/edit dialogs open
+    fn flow_app() -> App {
+        let mut app = App::new_demo();
+        app.load_demo_tunnels();
+        app.config = Some(config::Config::new("demo".to_string()));
+        app
+    }
+
+    fn type_input(app: &mut App, text: &str) {
+        app.input = text.to_string();
+    }
+
+    #[test]
+    fn test_add_flow_carries_values_to_the_request() {
+        let mut app = flow_app();
+        app.start_add();
+        assert!(app.has_text_field());
+
+        // Empty and taken names keep the dialog at the name step
+        app.next_add_step();
+        type_input(&mut app, "webapp");
+        app.next_add_step();
+        assert!(app
+            .status_message
+            .as_deref()
+            .unwrap()
+            .contains("already exists"));
+        // Enter before reaching the zone step does nothing
+        assert!(app.finish_flow().is_none());
+        assert!(matches!(
+            app.input_mode,
+            InputMode::Add(AddFlow {
+                step: AddStep::Name,
+                ..
+            })
+        ));
+
+        type_input(&mut app, "shop");
+        app.next_add_step();
+        assert!(app.input.is_empty());
+        type_input(&mut app, "localhost:5000");
+        app.next_add_step();
+        assert_eq!(app.zone_selected(), Some(0));
+        assert!(!app.has_text_field());
+        app.select_zone_next();
+        app.select_zone_next();
+        assert_eq!(app.zone_selected(), Some(1));
+
+        match app.finish_flow() {
+            Some(FlowRequest::Create {
+                name,
+                target,
+                zone,
+                account,
+                template,
+            }) => {
+                assert_eq!(name, "shop");
+                assert_eq!(target, "localhost:5000");
+                assert_eq!(zone.id, "zone-2");
+                assert_eq!(account.name, "demo");
+                assert!(template.is_none());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(app.input_mode, InputMode::Normal));
+        // A second Enter (e.g. a key repeat) finds no dialog
+        assert!(app.finish_flow().is_none());
+    }
+
+    #[test]
+    fn test_clone_and_edit_flows_prefill_from_the_selection() {
+        let mut app = flow_app();
+        app.selected = app
+            .tunnels
+            .iter()
+            .position(|e| e.tunnel.name == "staging")
+            .unwrap();
+
+        app.start_clone();
+        assert_eq!(app.input, "staging-copy");
+        app.next_add_step();
+        assert_eq!(app.input, "localhost:3001");
+        app.next_add_step();
+        // Starts in the source's zone
+        assert_eq!(app.zone_selected(), Some(1));
+        assert!(matches!(
+            app.finish_flow(),
+            Some(FlowRequest::Create {
+                template: Some(_),
+                ..
+            })
+        ));
+
+        app.start_edit();
+        assert_eq!(app.input, "localhost:3001");
+        type_input(&mut app, "localhost:3002");
+        app.next_edit_step();
+        assert_eq!(app.input, "QA copy of the storefront");
+        type_input(&mut app, "  ");
+        app.next_edit_step();
+        assert_eq!(app.zone_selected(), Some(1));
+        app.select_zone_prev();
+        match app.finish_flow() {
+            Some(FlowRequest::Edit {
+                name,
+                target,
+                description,
+                zone,
+                original_zone_id,
+                ..
+            }) => {
+                assert_eq!(name, "staging");
+                assert_eq!(target, "localhost:3002");
+                assert_eq!(description, None);
+                assert_eq!(zone.id, "zone-1");
+                assert_eq!(original_zone_id, "zone-2");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // Esc then Enter: nothing left to submit
+        app.start_edit();
+        app.next_edit_step();
+        app.next_edit_step();
+        app.cancel_input();
+        assert!(app.finish_flow().is_none());
+        assert!(matches!(app.input_mode, InputMode::Normal));
+    }
+
+    #[test]
+    fn test_import_flow_asks_for_missing_target() {
+        let mut app = flow_app();
+        app.input_mode = InputMode::Import(ImportFlow {
+            name: "preview".to_string(),
+            tunnel_id: "tid".to_string(),
+            step: ImportStep::Target,
+        });
+        app.next_add_step();
+        assert!(app.has_text_field());
+        type_input(&mut app, "localhost:5173");
+        app.next_add_step();
+        match app.finish_flow() {
+            Some(FlowRequest::Import {
+                target, tunnel_id, ..
+            }) => {
+                assert_eq!(target, "localhost:5173");
+                assert_eq!(tunnel_id, "tid");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ascii_mode_draws_only_plain_ascii() {
+        let mut app = App::new_demo();
+        app.set_ascii(true);
+        app.load_demo_tunnels();
+        app.spinner.start("Working...");
+
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| ui::render(f, &app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        assert!(buffer.content.iter().all(|cell| cell.symbol().is_ascii()));
+        assert!(buffer
+            .content
+            .iter()
+            .all(|cell| cell.fg == ratatui::style::Color::Reset));
+    }
+
+    #[test]
+    fn test_split_resizes_in_steps_within_bounds() {
+        let mut app = App::new_demo();
+        assert_eq!(app.list_percent, 40);
+        app.resize_split(1);
+        assert_eq!(app.list_percent, 45);
+        for _ in 0..20 {
+            app.resize_split(1);
+        }
+        assert_eq!(app.list_percent, config::MAX_LIST_PERCENT);
+        for _ in 0..20 {
+            app.resize_split(-1);
+        }
+        assert_eq!(app.list_percent, config::MIN_LIST_PERCENT);
+
+        assert_eq!(
+            app.resolve_key(KeyCode::Char('l'), KeyModifiers::CONTROL),
+            KeyCode::Char('>')
+        );
+        assert_eq!(
+            app.resolve_key(KeyCode::Char('l'), KeyModifiers::NONE),
+            KeyCode::Char('l')
+        );
+
+        app.toggle_metrics_panel();
+        assert!(!app.show_metrics);
+    }
+
+    #[test]
+    fn test_jumps_follow_the_visible_list() {
+        let mut app = App::new_demo();
+        app.load_demo_tunnels();
+        let last = app.tunnels.len() - 1;
+
+        assert!(app.jump_to('G'));
+        assert_eq!(app.selected, last);
+        assert!(app.jump_to('g'));
+        assert_eq!(app.selected, 0);
+        assert!(!app.jump_to('g'));
+        assert!(app.jump_to('3'));
+        assert_eq!(app.selected, 2);
+        // Past the end of the list: stay put
+        assert!(app.tunnels.len() < 9);
+        assert!(!app.jump_to('9'));
+        assert_eq!(app.selected, 2);
+
+        // Numbers count the filtered list, not the full one
+        app.filter = "docs".to_string();
+        assert!(app.jump_to('1'));
+        assert_eq!(app.tunnels[app.selected].tunnel.name, "docs");
+    }
+
+    #[test]
+    fn test_space_starts_or_stops_by_status() {
+        let mut app = App::new_demo();
+        app.load_demo_tunnels();
+        for i in 0..app.tunnels.len() {
+            app.selected = i;
+            let expected = if app.tunnels[i].status == TunnelStatus::Running {
+                'S'
+            } else {
+                's'
+            };
+            assert_eq!(
+                app.resolve_key(KeyCode::Char(' '), KeyModifiers::NONE),
+                KeyCode::Char(expected)
+            );
+        }
+        assert_eq!(
+            app.resolve_key(KeyCode::Char('s'), KeyModifiers::NONE),
+            KeyCode::Char('s')
+        );
+    }
+
+    #[test]
+    fn test_list_columns_adapt_to_width() {
+        use ui::{list_columns, ListColumns};
+
+        // Wide: every wanted column at its full width
+        assert_eq!(
+            list_columns(120, 8, Some(7), Some(15), 30),
+            ListColumns {
+                account: Some(7),
+                name: 12,
+                hostname: 30,
+                zone: Some(15),
+            }
+        );
+        // Columns that aren't wanted never show
+        assert_eq!(
+            list_columns(120, 8, None, None, 30),
+            ListColumns {
+                account: None,
+                name: 12,
+                hostname: 30,
+                zone: None,
+            }
+        );
+        // Narrower: the zone goes first, keeping the account
+        let columns = list_columns(60, 8, Some(7), Some(15), 40);
+        assert_eq!((columns.account, columns.zone), (Some(7), None));
+        assert_eq!(columns.hostname, 60 - 6 - 13 - 8);
+        // Narrower still: only name and a shortened hostname
+        let columns = list_columns(40, 8, Some(7), Some(15), 30);
+        assert_eq!((columns.account, columns.zone), (None, None));
+        assert_eq!(columns.hostname, 40 - 6 - 13);
+        // Long names and values are capped
+        let columns = list_columns(200, 40, Some(30), Some(50), 30);
+        assert_eq!(columns.name, 20);
+        assert_eq!((columns.account, columns.zone), (Some(12), Some(24)));
+
+        // The demo spans two zones, so a wide list gets a zone column
+        let mut app = App::new_demo();
+        app.load_demo_tunnels();
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(160, 30)).unwrap();
+        terminal.draw(|f| ui::render(f, &app)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("staging.dev.example.com   dev.example.com"));
+        assert!(screen.contains("Zone:"));
+    }
+
+    #[test]
+    fn test_layout_falls_back_on_small_terminals() {
+        let mut app = App::new_demo();
+        app.load_demo_tunnels();
+        let screen = |app: &App, width, height| {
+            let mut terminal =
+                Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+            terminal.draw(|f| ui::render(f, app)).unwrap();
+            let buffer = terminal.backend().buffer();
+            buffer
+                .content
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+        };
+
+        assert!(screen(&app, 60, 15).contains("Terminal too small"));
+
+        // Too narrow to split: the list alone, logs one key away
+        let narrow = screen(&app, 80, 24);
+        assert!(narrow.contains("Tunnels"));
+        assert!(!narrow.contains(" Logs: "));
+        app.toggle_log_view();
+        assert!(screen(&app, 80, 24).contains(" Logs: "));
+        app.toggle_log_view();
+        assert!(!app.log_view);
+
+        let wide = screen(&app, 120, 40);
+        assert!(wide.contains(" Details ") && wide.contains(" Logs: "));
+    }
+
+    #[test]
+    fn test_flapping_health_notifies_once_until_it_settles() {
+        let mut history = HealthHistory::default();
+        let start = std::time::Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(history.record(true, at(0)), HealthChange::Steady);
+        assert_eq!(history.record(false, at(30)), HealthChange::Down);
+        assert_eq!(history.record(true, at(60)), HealthChange::Up);
+        assert_eq!(history.record(false, at(90)), HealthChange::Down);
+        // The fourth change within ten minutes
+        assert_eq!(history.record(true, at(120)), HealthChange::StartedFlapping);
+        assert_eq!(history.record(false, at(150)), HealthChange::Suppressed);
+        assert_eq!(history.record(false, at(180)), HealthChange::Steady);
+        // Quiet once the last change is out of the window
+        assert_eq!(history.record(false, at(800)), HealthChange::Settled(false));
+        assert!(!history.flapping);
+        assert_eq!(history.record(true, at(830)), HealthChange::Up);
+
+        // Changes spread out over more than the window never count as flapping
+        let mut history = HealthHistory::default();
+        for i in 0..10u64 {
+            let change = history.record(i % 2 == 0, at(i * 300));
+            assert_ne!(change, HealthChange::StartedFlapping);
+        }
+    }
+
+    #[test]
+    fn test_gauge_shows_latest_sample_against_peak() {
+        let mut history = MetricsHistory::default();
+        assert_eq!(history.gauge(), "");
+        history.request_samples = vec![10, 80, 40];
+        assert_eq!(history.gauge(), "[####----] 40/80");
+        history.request_samples = vec![0];
+        assert_eq!(history.gauge(), "[--------] 0/1");
+    }
+
+    #[test]
+    fn test_copy_menu_offers_url_target_hostname_and_id() {
+        let mut app = App::new_demo();
+        app.load_demo_tunnels();
+        let tunnel = app.tunnels[app.selected].tunnel.clone();
+        let choices = app.copy_choices();
+        let keys: Vec<char> = choices.iter().map(|(key, _, _)| *key).collect();
+        assert_eq!(keys, ['u', 't', 'h', 'i']);
+        assert_eq!(choices[0].2, format!("https://{}", tunnel.hostname));
+        assert_eq!(choices[1].2, tunnel.target_spec());
+        assert_eq!(choices[2].2, tunnel.hostname);
+        assert_eq!(choices[3].2, tunnel.tunnel_id);
+
+        // Unknown keys keep the menu open (a real choice would touch the clipboard)
+        app.open_copy_menu();
+        app.copy_choice('x');
+        assert!(matches!(app.input_mode, InputMode::Copy));
+    }
+
+    #[test]
+    fn test_info_lists_ids_and_paths_of_the_selection() {
+        let mut app = App::new_demo();
+        app.load_demo_tunnels();
+        app.show_info();
+        assert!(matches!(app.input_mode, InputMode::Info));
+
+        let tunnel = app.tunnels[app.selected].tunnel.clone();
+        let info = app.tunnel_info();
+        let labels: Vec<&str> = info.iter().map(|(label, _)| *label).collect();
+        assert_eq!(
+            labels,
+            ["Tunnel ID", "Credentials", "Config", "Log", "Metrics"]
+        );
+        assert_eq!(info[0].1, tunnel.tunnel_id);
+        assert_eq!(info[4].1, tunnel.metrics_display());
+
+        // Selection stays on the rows
+        app.select_info_prev();
+        assert_eq!(app.info_selected, 0);
+        (0..10).for_each(|_| app.select_info_next());
+        assert_eq!(app.info_selected, info.len() - 1);
+    }
+
+    #[test]
+    fn test_log_tail_reads_appended_lines_and_detects_truncation() {
+        let path = std::env::temp_dir().join(format!("ytunnel-logtail-{}.log", std::process::id()));
+        std::fs::write(&path, "old line\n").unwrap();
+
+        let mut tail = LogTail::new(path.clone());
+        assert_eq!(tail.read_new().unwrap(), Some(Vec::new()));
+
+        // Incomplete lines are held back until their newline arrives
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        std::io::Write::write_all(&mut file, b"first\nsec").unwrap();
+        assert_eq!(tail.read_new().unwrap(), Some(vec!["first".to_string()]));
+        std::io::Write::write_all(&mut file, b"ond\n").unwrap();
+        assert_eq!(tail.read_new().unwrap(), Some(vec!["second".to_string()]));
+
+        // Truncation restarts from the beginning
+        std::fs::write(&path, "new\n").unwrap();
+        assert_eq!(tail.read_new().unwrap(), None);
+        assert_eq!(tail.read_new().unwrap(), Some(vec!["new".to_string()]));
+
+        std::fs::remove_file(&path).ok();
+    }
+}
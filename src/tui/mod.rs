@@ -1,4 +1,5 @@
 mod app;
+mod theme;
 mod ui;
 
 pub use app::{run_demo_tui, run_tui};
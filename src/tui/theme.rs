@@ -0,0 +1,121 @@
+use ratatui::style::Color;
+
+// Colors the TUI draws with, by role. ui.rs never names a color itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    // Background of the selected list row
+    pub selection_bg: Color,
+    // Name and secondary text on the selected row
+    pub selection_fg: Color,
+    pub selection_dim: Color,
+    // Running, healthy, success messages
+    pub ok: Color,
+    // Stopped, prompts, warnings
+    pub warn: Color,
+    pub error: Color,
+    // Borders, titles, key names
+    pub accent: Color,
+    // Field labels
+    pub label: Color,
+    // Hostnames, descriptions, hints
+    pub dim: Color,
+    // Values
+    pub text: Color,
+    // Accounts and edge locations
+    pub highlight: Color,
+}
+
+impl Theme {
+    pub const DARK: Theme = Theme {
+        selection_bg: Color::Rgb(40, 60, 80),
+        selection_fg: Color::White,
+        selection_dim: Color::Rgb(150, 150, 150),
+        ok: Color::Green,
+        warn: Color::Yellow,
+        error: Color::Red,
+        accent: Color::Cyan,
+        label: Color::Gray,
+        dim: Color::DarkGray,
+        text: Color::White,
+        highlight: Color::Magenta,
+    };
+
+    // Darker shades that hold up on white and light gray backgrounds (yellow, cyan,
+    // and white text disappear there)
+    pub const LIGHT: Theme = Theme {
+        selection_bg: Color::Rgb(200, 220, 240),
+        selection_fg: Color::Black,
+        selection_dim: Color::Rgb(70, 70, 70),
+        ok: Color::Rgb(0, 120, 0),
+        warn: Color::Rgb(150, 90, 0),
+        error: Color::Rgb(180, 0, 0),
+        accent: Color::Rgb(0, 90, 160),
+        label: Color::Rgb(90, 90, 90),
+        dim: Color::Rgb(120, 120, 120),
+        text: Color::Black,
+        highlight: Color::Rgb(140, 0, 140),
+    };
+
+    // Bright colors only, and a selection that inverts the row
+    pub const HIGH_CONTRAST: Theme = Theme {
+        selection_bg: Color::White,
+        selection_fg: Color::Black,
+        selection_dim: Color::Black,
+        ok: Color::LightGreen,
+        warn: Color::LightYellow,
+        error: Color::LightRed,
+        accent: Color::LightCyan,
+        label: Color::White,
+        dim: Color::Gray,
+        text: Color::White,
+        highlight: Color::LightMagenta,
+    };
+
+    // A theme by its name in config::THEMES
+    pub fn named(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Self::DARK),
+            "light" => Some(Self::LIGHT),
+            "high-contrast" => Some(Self::HIGH_CONTRAST),
+            _ => None,
+        }
+    }
+
+    // The requested theme (--theme, then [tui] theme), else light or dark to match
+    // the terminal's background
+    pub fn resolve(requested: Option<&str>) -> Theme {
+        requested
+            .and_then(Self::named)
+            .unwrap_or_else(|| Self::detect(std::env::var("COLORFGBG").ok().as_deref()))
+    }
+
+    // COLORFGBG (set by rxvt, Konsole, iTerm2, and others) is "<fg>;<bg>" or
+    // "<fg>;<other>;<bg>" in ANSI color numbers; a white or light gray background
+    // means a light terminal. Anything else, or no variable, is taken as dark.
+    pub fn detect(colorfgbg: Option<&str>) -> Theme {
+        let background = colorfgbg
+            .and_then(|value| value.rsplit(';').next())
+            .and_then(|bg| bg.trim().parse::<u8>().ok());
+        match background {
+            Some(7 | 15) => Self::LIGHT,
+            _ => Self::DARK,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_reads_the_background_from_colorfgbg() {
+        assert_eq!(Theme::detect(Some("0;15")), Theme::LIGHT);
+        assert_eq!(Theme::detect(Some("0;default;7")), Theme::LIGHT);
+        assert_eq!(Theme::detect(Some("15;0")), Theme::DARK);
+        assert_eq!(Theme::detect(Some("garbage")), Theme::DARK);
+        assert_eq!(Theme::detect(None), Theme::DARK);
+
+        assert_eq!(Theme::named("high-contrast"), Some(Theme::HIGH_CONTRAST));
+        assert_eq!(Theme::named("solarized"), None);
+    }
+}
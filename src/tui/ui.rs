@@ -1,16 +1,129 @@
 use ratatui::{
+    buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
     Frame,
 };
 
-use super::app::{App, HealthStatus, InputMode, TunnelKind};
-use crate::metrics::TunnelMetrics;
-use crate::state::TunnelStatus;
+use super::app::{
+    AddStep, App, EditFlow, EditStep, HealthHistory, HealthStatus, ImportStep, InputMode,
+    TunnelEntry, TunnelKind,
+};
+use super::theme::Theme;
+use crate::daemon::DaemonInfo;
+use crate::logfmt::{self, Level};
+use std::collections::HashSet;
+use ytunnel::idn;
+use ytunnel::metrics::TunnelMetrics;
+use ytunnel::state::TunnelStatus;
+
+// Layout sizes and breakpoints, in terminal cells. Below MIN_WIDTH x MIN_HEIGHT only
+// a "too small" notice is drawn; below SPLIT_MIN_WIDTH the tunnel list and the
+// selected tunnel's panes don't fit side by side, so the list shows alone (`l` for
+// full-screen logs).
+const MIN_WIDTH: u16 = 70;
+const MIN_HEIGHT: u16 = 20;
+const SPLIT_MIN_WIDTH: u16 = 100;
+const METRICS_HEIGHT: u16 = 6;
+// Details panel and focused summary before their optional rows (metrics URL,
+// description, process)
+const DETAILS_HEIGHT: u16 = 4;
+const SUMMARY_HEIGHT: u16 = 6;
+
+// Tunnel list columns, in characters. Names pad to at least NAME_MIN_WIDTH and are
+// cut at NAME_MAX_WIDTH; the account and zone columns only show while the hostname
+// keeps HOSTNAME_MIN_WIDTH.
+const NAME_MIN_WIDTH: usize = 12;
+const NAME_MAX_WIDTH: usize = 20;
+const ACCOUNT_MAX_WIDTH: usize = 12;
+const ZONE_MAX_WIDTH: usize = 24;
+const HOSTNAME_MIN_WIDTH: usize = 20;
+// Status symbol and its space, plus the auto-start and health indicators
+const ROW_FIXED_WIDTH: usize = 6;
+
+// Widths of the tunnel list's columns; None for a column that's hidden
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListColumns {
+    pub account: Option<usize>,
+    pub name: usize,
+    pub hostname: usize,
+    pub zone: Option<usize>,
+}
+
+// Fit the list's columns into a row `width` characters wide, given the longest
+// value each would show (account and zone are None when not wanted: a single
+// account's or a single zone's tunnels). The description goes first (it only fills
+// leftover space), then the zone column, then the account column; after that the
+// hostname is shortened in the middle.
+pub fn list_columns(
+    width: usize,
+    name: usize,
+    account: Option<usize>,
+    zone: Option<usize>,
+    hostname: usize,
+) -> ListColumns {
+    let name = name.clamp(NAME_MIN_WIDTH, NAME_MAX_WIDTH);
+    let mut room = width.saturating_sub(ROW_FIXED_WIDTH + name + 1);
+    let mut fit = |wanted: Option<usize>, max: usize| {
+        let column = wanted?.min(max);
+        if room < HOSTNAME_MIN_WIDTH + column + 1 {
+            return None;
+        }
+        room -= column + 1;
+        Some(column)
+    };
+    let account = fit(account, ACCOUNT_MAX_WIDTH);
+    let zone = fit(zone, ZONE_MAX_WIDTH);
+    ListColumns {
+        account,
+        name,
+        hostname: hostname.min(room),
+        zone,
+    }
+}
+
+// How much of the layout fits in a terminal of this size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutMode {
+    TooSmall,
+    SinglePane,
+    Split,
+}
+
+pub fn layout_mode(area: Rect) -> LayoutMode {
+    if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+        LayoutMode::TooSmall
+    } else if area.width < SPLIT_MIN_WIDTH {
+        LayoutMode::SinglePane
+    } else {
+        LayoutMode::Split
+    }
+}
 
 pub fn render(f: &mut Frame, app: &App) {
+    let mode = layout_mode(f.area());
+    if mode == LayoutMode::TooSmall {
+        render_too_small(f, &app.theme);
+        if app.ascii {
+            asciify(f.buffer_mut());
+        }
+        return;
+    }
+
+    // Reserve a line at the top for the warning banner when there is one
+    let area = if let Some(ref warning) = app.warning_banner {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(f.area());
+        render_warning_banner(f, &app.theme, warning, chunks[0]);
+        chunks[1]
+    } else {
+        f.area()
+    };
+
     // Main layout: tunnels on left, logs/metrics on right, status line, help bar at bottom
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -19,27 +132,168 @@ pub fn render(f: &mut Frame, app: &App) {
             Constraint::Length(1), // Status line
             Constraint::Length(1), // Help bar
         ])
-        .split(f.area());
+        .split(area);
+
+    if app.log_view && app.selected_entry().is_some() {
+        render_logs(f, app, main_chunks[0]);
+    } else if app.focused && app.selected_entry().is_some() {
+        render_focused(f, app, main_chunks[0]);
+    } else if mode == LayoutMode::SinglePane {
+        render_tunnels(f, app, main_chunks[0]);
+    } else {
+        render_list_view(f, app, main_chunks[0]);
+    }
+
+    // Render status line
+    render_status_line(f, app, main_chunks[1]);
+
+    // Render help bar
+    render_help_bar(f, app, mode, main_chunks[2]);
+
+    // Render modals/dialogs on top
+    match &app.input_mode {
+        InputMode::Add(flow) => match &flow.step {
+            AddStep::Name => {
+                render_add_dialog(f, &app.theme, "Enter tunnel name:", &app.input, false)
+            }
+            AddStep::Target { .. } => render_add_dialog(
+                f,
+                &app.theme,
+                "Enter target (e.g., localhost:3000):",
+                &app.input,
+                false,
+            ),
+            AddStep::Zone {
+                name,
+                target,
+                selected,
+            } => render_zone_dialog(f, app, " Select Zone ", name, target, *selected),
+        },
+        InputMode::Import(flow) => match &flow.step {
+            ImportStep::Target => render_add_dialog(
+                f,
+                &app.theme,
+                "Enter target (e.g., localhost:3000):",
+                &app.input,
+                true,
+            ),
+            ImportStep::Zone { target, selected } => render_zone_dialog(
+                f,
+                app,
+                " Import: Select Zone ",
+                &flow.name,
+                target,
+                *selected,
+            ),
+        },
+        InputMode::Edit(flow) => match &flow.step {
+            EditStep::Target => render_edit_dialog(f, app, &flow.name, "Edit target URL:"),
+            EditStep::Description { .. } => {
+                render_edit_dialog(f, app, &flow.name, "Edit description (optional):")
+            }
+            EditStep::Zone {
+                target, selected, ..
+            } => render_edit_zone_dialog(f, app, flow, target, *selected),
+        },
+        InputMode::Confirm => {
+            if let Some(ref msg) = app.confirm_message {
+                render_confirm_dialog(f, &app.theme, msg);
+            }
+        }
+        InputMode::Help => render_help_modal(f, &app.theme),
+        InputMode::Info => render_info_modal(f, app),
+        InputMode::Copy => render_copy_menu(f, app),
+        InputMode::TokenEntry => render_token_dialog(f, app),
+        InputMode::Normal | InputMode::Filter => {}
+    }
+
+    if app.ascii {
+        asciify(f.buffer_mut());
+    }
+}
+
+// Plain rendering for terminals without Unicode or color: borders and symbols get
+// ASCII stand-ins, anything else outside ASCII becomes '?', and styling is dropped
+fn asciify(buf: &mut Buffer) {
+    for cell in buf.content.iter_mut() {
+        if !cell.symbol().is_ascii() {
+            let c = cell.symbol().chars().next().map_or(' ', ascii_stand_in);
+            cell.set_char(c);
+        }
+        cell.set_style(Style::reset());
+    }
+}
+
+fn ascii_stand_in(c: char) -> char {
+    match c {
+        '─' | '━' | '═' => '-',
+        '│' | '┃' | '║' => '|',
+        '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' | '╭' | '╮' | '╰' | '╯' => {
+            '+'
+        }
+        '·' | '•' => '-',
+        '…' => '.',
+        '↑' => '^',
+        '↓' => 'v',
+        '⟳' => '@',
+        '⚠' => '!',
+        '✓' => '+',
+        '✗' => 'x',
+        '▁' => '_',
+        '█' => '#',
+        _ => '?',
+    }
+}
 
+// Shown instead of the layout when the terminal is below MIN_WIDTH x MIN_HEIGHT
+fn render_too_small(f: &mut Frame, theme: &Theme) {
+    let area = f.area();
+    let message = format!(
+        "Terminal too small (needs at least {}x{}, is {}x{})",
+        MIN_WIDTH, MIN_HEIGHT, area.width, area.height
+    );
+    let [row] = Layout::vertical([Constraint::Length(1)])
+        .flex(ratatui::layout::Flex::Center)
+        .areas(area);
+    f.render_widget(
+        Paragraph::new(message)
+            .style(Style::default().fg(theme.warn))
+            .alignment(ratatui::layout::Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        row,
+    );
+}
+
+// Tunnel list on the left, details/logs/metrics for the selection on the right
+fn render_list_view(f: &mut Frame, app: &App, area: Rect) {
     let content_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
-        .split(main_chunks[0]);
+        .constraints([
+            Constraint::Percentage(app.list_percent),
+            Constraint::Percentage(100 - app.list_percent),
+        ])
+        .split(area);
 
     // Render tunnels list
     render_tunnels(f, app, content_chunks[0]);
 
     // Right panel: details (fixed), logs (flexible), and optional metrics (fixed)
-    let has_metrics = app.selected_metrics().is_some();
+    let has_metrics = app.show_metrics && app.selected_metrics().is_some();
     let has_details = app.selected_tunnel_details().is_some();
+    let details_height = DETAILS_HEIGHT
+        + u16::from(app.selected_zone().is_some())
+        + u16::from(app.selected_metrics_url().is_some())
+        + u16::from(app.selected_description().is_some())
+        + u16::from(app.selected_health_history().is_some())
+        + u16::from(app.selected_daemon_info().is_some());
 
     if has_details && has_metrics {
         let right_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(4), // Details panel (fixed)
-                Constraint::Min(0),    // Logs panel (flexible)
-                Constraint::Length(6), // Metrics panel (fixed)
+                Constraint::Length(details_height), // Details panel (fixed)
+                Constraint::Min(0),                 // Logs panel (flexible)
+                Constraint::Length(METRICS_HEIGHT), // Metrics panel (fixed)
             ])
             .split(content_chunks[1]);
 
@@ -47,17 +301,19 @@ pub fn render(f: &mut Frame, app: &App) {
         render_logs(f, app, right_chunks[1]);
         render_metrics(
             f,
+            &app.theme,
             app.selected_metrics(),
             &app.selected_sparkline(),
             app.selected_health(),
+            app.selected_lifetime_bytes(),
             right_chunks[2],
         );
     } else if has_details {
         let right_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(4), // Details panel (fixed)
-                Constraint::Min(0),    // Logs panel (flexible)
+                Constraint::Length(details_height), // Details panel (fixed)
+                Constraint::Min(0),                 // Logs panel (flexible)
             ])
             .split(content_chunks[1]);
 
@@ -66,51 +322,281 @@ pub fn render(f: &mut Frame, app: &App) {
     } else if has_metrics {
         let right_chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(0), Constraint::Length(6)])
+            .constraints([Constraint::Min(0), Constraint::Length(METRICS_HEIGHT)])
             .split(content_chunks[1]);
 
         render_logs(f, app, right_chunks[0]);
         render_metrics(
             f,
+            &app.theme,
             app.selected_metrics(),
             &app.selected_sparkline(),
             app.selected_health(),
+            app.selected_lifetime_bytes(),
             right_chunks[1],
         );
     } else {
         // Just render logs panel
         render_logs(f, app, content_chunks[1]);
     }
+}
 
-    // Render status line
-    render_status_line(f, app, main_chunks[1]);
-
-    // Render help bar
-    render_help_bar(f, app, main_chunks[2]);
+// Full-screen view of the selected tunnel with larger log and metrics panes
+fn render_focused(f: &mut Frame, app: &App, area: Rect) {
+    let has_metrics = app.show_metrics && app.selected_metrics().is_some();
+    let summary_height = SUMMARY_HEIGHT
+        + u16::from(app.selected_zone().is_some())
+        + u16::from(app.selected_metrics_url().is_some())
+        + u16::from(app.selected_description().is_some())
+        + u16::from(app.selected_health_history().is_some())
+        + u16::from(app.selected_daemon_info().is_some());
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(summary_height), // Summary panel (fixed)
+            Constraint::Min(0),                 // Logs panel (flexible)
+            Constraint::Length(if has_metrics { METRICS_HEIGHT } else { 0 }),
+        ])
+        .split(area);
 
-    // Render modals/dialogs on top
-    match app.input_mode {
-        InputMode::AddName => render_add_dialog(f, "Enter tunnel name:", &app.input, false),
-        InputMode::AddTarget => render_add_dialog(
+    render_focused_summary(f, app, chunks[0]);
+    render_logs(f, app, chunks[1]);
+    if has_metrics {
+        render_metrics(
             f,
-            "Enter target (e.g., localhost:3000):",
-            &app.input,
-            app.is_importing,
-        ),
-        InputMode::AddZone => render_zone_dialog(f, app),
-        InputMode::EditTarget => render_edit_dialog(f, app, "Edit target URL:"),
-        InputMode::EditZone => render_edit_zone_dialog(f, app),
-        InputMode::Confirm => {
-            if let Some(ref msg) = app.confirm_message {
-                render_confirm_dialog(f, msg);
-            }
-        }
-        InputMode::Help => render_help_modal(f),
-        InputMode::Normal => {}
+            &app.theme,
+            app.selected_metrics(),
+            &app.selected_sparkline(),
+            app.selected_health(),
+            app.selected_lifetime_bytes(),
+            chunks[2],
+        );
+    }
+}
+
+fn render_focused_summary(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let entry = match app.selected_entry() {
+        Some(entry) => entry,
+        None => return,
+    };
+    let tunnel = &entry.tunnel;
+
+    let (status_color, status_text) = match entry.status {
+        TunnelStatus::Running => (theme.ok, "running"),
+        TunnelStatus::Stopped => (theme.warn, "stopped"),
+        TunnelStatus::Error => (theme.error, "error"),
+    };
+    let target_url = tunnel.display_target();
+    let kind = match entry.kind {
+        TunnelKind::Managed if tunnel.auto_start => "managed ⟳",
+        TunnelKind::Managed => "managed",
+        TunnelKind::Ephemeral => "ephemeral",
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Status:      ", Style::default().fg(theme.label)),
+            Span::styled(
+                format!("{} {}", app.status_symbol(entry.status), status_text),
+                Style::default().fg(status_color),
+            ),
+            Span::styled(format!("  ({})", kind), Style::default().fg(theme.dim)),
+        ]),
+        Line::from(vec![
+            Span::styled("Destination: ", Style::default().fg(theme.label)),
+            Span::styled(target_url, Style::default().fg(theme.warn)),
+            maintenance_span(tunnel.maintenance.as_deref(), theme),
+        ]),
+        Line::from(vec![
+            Span::styled("Public URL:  ", Style::default().fg(theme.label)),
+            Span::styled(
+                format!("https://{}", idn::to_unicode(&tunnel.hostname)),
+                Style::default().fg(theme.accent),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Account:     ", Style::default().fg(theme.label)),
+            Span::styled(&tunnel.account_name, Style::default().fg(theme.highlight)),
+        ]),
+    ];
+    if let Some(zone) = app.selected_zone() {
+        lines.insert(3, zone_line(zone, theme));
+    }
+    if let Some(metrics_url) = app.selected_metrics_url() {
+        lines.push(Line::from(vec![
+            Span::styled("Metrics:     ", Style::default().fg(theme.label)),
+            Span::styled(metrics_url, Style::default().fg(theme.highlight)),
+        ]));
+    }
+    if let Some(description) = &tunnel.description {
+        lines.push(Line::from(vec![
+            Span::styled("Description: ", Style::default().fg(theme.label)),
+            Span::raw(description),
+        ]));
+    }
+    if let Some(history) = app.selected_health_history() {
+        lines.push(health_line(history, theme));
     }
+    if let Some(info) = app.selected_daemon_info() {
+        lines.push(process_line(info, app.selected_connect_time(), theme));
+    }
+
+    let summary = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} · Esc to return ", tunnel.name))
+            .border_style(Style::default().fg(theme.accent)),
+    );
+
+    f.render_widget(summary, area);
+}
+
+// "Health: █████▁█▁██ flapping": one block per recent check, oldest first
+fn health_line(history: &HealthHistory, theme: &Theme) -> Line<'static> {
+    let mut spans = vec![Span::styled(
+        "Health:      ",
+        Style::default().fg(theme.label),
+    )];
+    for &(_, up) in &history.samples {
+        let (block, color) = if up {
+            ('█', theme.ok)
+        } else {
+            ('▁', theme.error)
+        };
+        spans.push(Span::styled(block.to_string(), Style::default().fg(color)));
+    }
+    if history.flapping {
+        spans.push(Span::styled(
+            "  flapping (notifications paused)",
+            Style::default().fg(theme.warn),
+        ));
+    }
+    Line::from(spans)
+}
+
+// "Process: pid 4242 · up 3h 2m · 31.2 MB · connected in 1.8s" (whichever
+// parts are known)
+fn process_line(info: &DaemonInfo, connect_time_ms: Option<u64>, theme: &Theme) -> Line<'static> {
+    let parts: Vec<String> = [
+        info.pid.map(|pid| format!("pid {}", pid)),
+        info.uptime
+            .map(|u| format!("up {}", crate::format_uptime(u))),
+        info.memory_bytes.map(crate::format_memory),
+        connect_time_ms.map(|ms| format!("connected in {}", crate::format_connect_time(ms))),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    Line::from(vec![
+        Span::styled("Process:     ", Style::default().fg(theme.label)),
+        Span::styled(parts.join(" · "), Style::default().fg(theme.dim)),
+    ])
+}
+
+fn render_warning_banner(f: &mut Frame, theme: &Theme, warning: &str, area: Rect) {
+    // Reversed, so the text takes the terminal's own background color
+    let banner = Paragraph::new(format!(" ⚠ {}", warning)).style(
+        Style::default()
+            .fg(theme.error)
+            .add_modifier(Modifier::REVERSED | Modifier::BOLD),
+    );
+    f.render_widget(banner, area);
+}
+
+// Tunnel ID, credentials/config/log paths and metrics URL of the selection
+fn render_info_modal(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(80, 40, f.area());
+
+    // Clear the area
+    f.render_widget(Clear, area);
+
+    let name = app
+        .selected_entry()
+        .map(|e| e.tunnel.name.as_str())
+        .unwrap_or("");
+    let block = Block::default()
+        .title(format!(" {} - Enter to copy, Esc to close ", name))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+
+    let info = app.tunnel_info();
+    let width = info.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    let lines: Vec<Line> = info
+        .iter()
+        .enumerate()
+        .map(|(i, (label, value))| {
+            let selected = i == app.info_selected;
+            let prefix = if selected { "> " } else { "  " };
+            let style = if selected {
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.label)
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{}{:<width$}  ", prefix, label, width = width),
+                    style,
+                ),
+                Span::styled(value.clone(), Style::default().fg(theme.ok)),
+            ])
+        })
+        .collect();
+
+    let content = Paragraph::new(lines)
+        .block(block.padding(ratatui::widgets::Padding::new(1, 1, 1, 1)))
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(content, area);
+}
+
+// What `c` can copy, one key each
+fn render_copy_menu(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let choices = app.copy_choices();
+    let area = centered_rect(60, 20, f.area());
+    let area = Rect {
+        height: (choices.len() as u16 + 4).min(area.height),
+        ..area
+    };
+
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Copy - Esc to cancel ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+
+    let width = choices
+        .iter()
+        .map(|(_, label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<Line> = choices
+        .iter()
+        .map(|(key, label, value)| {
+            Line::from(vec![
+                Span::styled(format!("{}  ", key), Style::default().fg(theme.accent)),
+                Span::styled(
+                    format!("{:<width$}  ", label, width = width),
+                    Style::default().fg(theme.label),
+                ),
+                Span::styled(value.clone(), Style::default().fg(theme.ok)),
+            ])
+        })
+        .collect();
+
+    let content = Paragraph::new(lines)
+        .block(block.padding(ratatui::widgets::Padding::new(1, 1, 1, 1)))
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(content, area);
 }
 
-fn render_help_modal(f: &mut Frame) {
+fn render_help_modal(f: &mut Frame, theme: &Theme) {
     let area = centered_rect(70, 80, f.area());
 
     // Clear the area
@@ -119,7 +605,7 @@ fn render_help_modal(f: &mut Frame) {
     let block = Block::default()
         .title(" Help - Press Esc to close ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(theme.accent));
 
     let inner = block.inner(area);
     f.render_widget(block, area);
@@ -127,118 +613,181 @@ fn render_help_modal(f: &mut Frame) {
     let help_text = vec![
         Line::from(Span::styled(
             "NAVIGATION",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.warn).add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
         Line::from(vec![
-            Span::styled("  ↑/k      ", Style::default().fg(Color::Cyan)),
+            Span::styled("  ↑/k      ", Style::default().fg(theme.accent)),
             Span::raw("Move selection up"),
         ]),
         Line::from(vec![
-            Span::styled("  ↓/j      ", Style::default().fg(Color::Cyan)),
+            Span::styled("  ↓/j      ", Style::default().fg(theme.accent)),
             Span::raw("Move selection down"),
         ]),
         Line::from(vec![
-            Span::styled("  q        ", Style::default().fg(Color::Cyan)),
+            Span::styled("  g/G      ", Style::default().fg(theme.accent)),
+            Span::raw("Jump to the first/last tunnel"),
+        ]),
+        Line::from(vec![
+            Span::styled("  1-9      ", Style::default().fg(theme.accent)),
+            Span::raw("Jump to the Nth tunnel in the list"),
+        ]),
+        Line::from(vec![
+            Span::styled("  PgUp/PgDn", Style::default().fg(theme.accent)),
+            Span::raw(" Scroll logs (pauses live tail)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  End      ", Style::default().fg(theme.accent)),
+            Span::raw("Jump to newest logs and resume live tail"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Enter    ", Style::default().fg(theme.accent)),
+            Span::raw("Focus selected tunnel full-screen (Esc to return)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  l        ", Style::default().fg(theme.accent)),
+            Span::raw("Show only the selected tunnel's logs (Esc to return)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  L        ", Style::default().fg(theme.accent)),
+            Span::raw("Show raw log lines instead of formatted JSON logs"),
+        ]),
+        Line::from(vec![
+            Span::styled("  </>      ", Style::default().fg(theme.accent)),
+            Span::raw("Narrow/widen the tunnel list (also Ctrl+h/l)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  M        ", Style::default().fg(theme.accent)),
+            Span::raw("Show or hide the metrics panel"),
+        ]),
+        Line::from(vec![
+            Span::styled("  /        ", Style::default().fg(theme.accent)),
+            Span::raw("Filter by name, hostname, target, or description"),
+        ]),
+        Line::from(vec![
+            Span::styled("  q        ", Style::default().fg(theme.accent)),
             Span::raw("Quit ytunnel"),
         ]),
         Line::from(""),
         Line::from(Span::styled(
             "TUNNEL MANAGEMENT",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.warn).add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
         Line::from(vec![
-            Span::styled("  a        ", Style::default().fg(Color::Cyan)),
+            Span::styled("  a        ", Style::default().fg(theme.accent)),
             Span::raw("Add a new tunnel"),
         ]),
         Line::from(vec![
-            Span::styled("  e        ", Style::default().fg(Color::Cyan)),
-            Span::raw("Edit tunnel (target URL and zone)"),
+            Span::styled("  e        ", Style::default().fg(theme.accent)),
+            Span::raw("Edit tunnel (target URL, description, zone)"),
         ]),
         Line::from(vec![
-            Span::styled("  s        ", Style::default().fg(Color::Cyan)),
+            Span::styled("  C        ", Style::default().fg(theme.accent)),
+            Span::raw("Clone selected tunnel's settings into a new tunnel"),
+        ]),
+        Line::from(vec![
+            Span::styled("  s        ", Style::default().fg(theme.accent)),
             Span::raw("Start selected tunnel"),
         ]),
         Line::from(vec![
-            Span::styled("  S        ", Style::default().fg(Color::Cyan)),
+            Span::styled("  S        ", Style::default().fg(theme.accent)),
             Span::raw("Stop selected tunnel"),
         ]),
         Line::from(vec![
-            Span::styled("  R        ", Style::default().fg(Color::Cyan)),
+            Span::styled("  Space    ", Style::default().fg(theme.accent)),
+            Span::raw("Start or stop selected tunnel, whichever applies"),
+        ]),
+        Line::from(vec![
+            Span::styled("  R        ", Style::default().fg(theme.accent)),
             Span::raw("Restart tunnel (updates daemon config)"),
         ]),
         Line::from(vec![
-            Span::styled("  d        ", Style::default().fg(Color::Cyan)),
+            Span::styled("  d        ", Style::default().fg(theme.accent)),
             Span::raw("Delete selected tunnel"),
         ]),
         Line::from(vec![
-            Span::styled("  m        ", Style::default().fg(Color::Cyan)),
+            Span::styled("  D        ", Style::default().fg(theme.accent)),
+            Span::raw("Delete all unused ephemeral tunnels"),
+        ]),
+        Line::from(vec![
+            Span::styled("  m        ", Style::default().fg(theme.accent)),
             Span::raw("Import ephemeral tunnel as managed"),
         ]),
         Line::from(vec![
-            Span::styled("  A        ", Style::default().fg(Color::Cyan)),
+            Span::styled("  A        ", Style::default().fg(theme.accent)),
             Span::raw("Toggle auto-start on login (⟳ = enabled)"),
         ]),
+        Line::from(vec![
+            Span::styled("  v        ", Style::default().fg(theme.accent)),
+            Span::raw("Cycle cloudflared log level (R applies it)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  T        ", Style::default().fg(theme.accent)),
+            Span::raw("Enter a new API token for the account"),
+        ]),
         Line::from(""),
         Line::from(Span::styled(
             "QUICK ACTIONS",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.warn).add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
         Line::from(vec![
-            Span::styled("  c        ", Style::default().fg(Color::Cyan)),
-            Span::raw("Copy tunnel URL to clipboard"),
+            Span::styled("  c        ", Style::default().fg(theme.accent)),
+            Span::raw("Copy URL (u), target (t), hostname (h), or tunnel ID (i)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  i        ", Style::default().fg(theme.accent)),
+            Span::raw("Show tunnel ID and file paths (copy any of them)"),
         ]),
         Line::from(vec![
-            Span::styled("  o        ", Style::default().fg(Color::Cyan)),
+            Span::styled("  o        ", Style::default().fg(theme.accent)),
             Span::raw("Open tunnel URL in browser"),
         ]),
         Line::from(vec![
-            Span::styled("  h        ", Style::default().fg(Color::Cyan)),
+            Span::styled("  h        ", Style::default().fg(theme.accent)),
             Span::raw("Check tunnel health now"),
         ]),
         Line::from(vec![
-            Span::styled("  r        ", Style::default().fg(Color::Cyan)),
+            Span::styled("  r        ", Style::default().fg(theme.accent)),
             Span::raw("Refresh tunnel list and status"),
         ]),
         Line::from(""),
         Line::from(Span::styled(
             "ACCOUNTS",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.warn).add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
         Line::from(vec![
-            Span::styled("  ;        ", Style::default().fg(Color::Cyan)),
+            Span::styled("  ;        ", Style::default().fg(theme.accent)),
             Span::raw("Cycle through accounts"),
         ]),
+        Line::from(vec![
+            Span::styled("  :        ", Style::default().fg(theme.accent)),
+            Span::raw("Toggle tunnels from all accounts"),
+        ]),
         Line::from(""),
         Line::from(Span::styled(
             "METRICS",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.warn).add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
         Line::from(vec![
             Span::raw("  Metrics auto-refresh every "),
-            Span::styled("5 seconds", Style::default().fg(Color::Green)),
+            Span::styled("5 seconds", Style::default().fg(theme.ok)),
         ]),
         Line::from(vec![
             Span::raw("  Health checks run every "),
-            Span::styled("30 seconds", Style::default().fg(Color::Green)),
+            Span::styled("30 seconds", Style::default().fg(theme.ok)),
         ]),
         Line::from(vec![
             Span::raw("  System notifications on tunnel "),
-            Span::styled("down/up", Style::default().fg(Color::Red)),
+            Span::styled("down/up", Style::default().fg(theme.error)),
+        ]),
+        Line::from(vec![
+            Span::raw("  A tunnel that "),
+            Span::styled("flaps", Style::default().fg(theme.warn)),
+            Span::raw(" (4+ changes in 10 minutes) gets one notice until it settles"),
         ]),
     ];
 
@@ -248,63 +797,121 @@ fn render_help_modal(f: &mut Frame) {
 }
 
 fn render_tunnels(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let visible = app.visible_tunnels();
+    // "matches/total" while the filter hides some tunnels
+    let count = if app.filter.is_empty() {
+        app.tunnels.len().to_string()
+    } else {
+        format!("{}/{}", visible.len(), app.tunnels.len())
+    };
+
     // Show account name in title if there are multiple accounts
-    let title = if app.demo {
-        format!(" Tunnels ({}) [demo] ", app.tunnels.len())
-    } else if app.accounts.len() > 1 {
+    let mut title = if app.demo {
+        format!(" Tunnels ({}) [demo] ", count)
+    } else if app.all_accounts {
         format!(
-            " Tunnels ({}) [{}] ",
-            app.tunnels.len(),
+            " Tunnels ({}) [all accounts, adding to {}] ",
+            count,
             app.current_account_name()
         )
+    } else if app.accounts.len() > 1 {
+        format!(" Tunnels ({}) [{}] ", count, app.current_account_name())
     } else {
-        format!(" Tunnels ({}) ", app.tunnels.len())
+        format!(" Tunnels ({}) ", count)
+    };
+    if !app.filter.is_empty() {
+        title.push_str(&format!("· /{} ", app.filter));
+    }
+    // Always show where new tunnels will be created
+    match app.current_account() {
+        Some(acct) if acct.has_zones() => {
+            title.push_str(&format!(
+                "· default: {} ",
+                idn::to_unicode(&acct.default_zone_name)
+            ));
+        }
+        Some(_) => title.push_str("· no zones "),
+        None => {}
+    }
+
+    // Room for a row's contents inside the borders
+    let inner_width = usize::from(area.width.saturating_sub(2));
+
+    // Size the columns from the tunnels being listed. Zones only get a column when
+    // the list spans more than one, accounts only in the all-accounts view.
+    let hostname_display = |entry: &TunnelEntry| match entry.kind {
+        TunnelKind::Managed => idn::to_unicode(&entry.tunnel.hostname),
+        TunnelKind::Ephemeral => format!("{} [ephemeral]", idn::to_unicode(&entry.tunnel.name)),
+    };
+    let zone_display = |entry: &TunnelEntry| match entry.kind {
+        TunnelKind::Managed => idn::to_unicode(&entry.tunnel.zone_name),
+        TunnelKind::Ephemeral => String::new(),
     };
+    let longest = |width: &dyn Fn(&TunnelEntry) -> usize| {
+        visible
+            .iter()
+            .map(|&i| width(&app.tunnels[i]))
+            .max()
+            .unwrap_or(0)
+    };
+    let zones: HashSet<String> = visible
+        .iter()
+        .map(|&i| zone_display(&app.tunnels[i]))
+        .filter(|zone| !zone.is_empty())
+        .collect();
+    let columns = list_columns(
+        inner_width.saturating_sub(usize::from(app.ascii)),
+        longest(&|e| idn::to_unicode(&e.tunnel.name).chars().count()),
+        app.all_accounts
+            .then(|| longest(&|e| e.tunnel.account_name.chars().count())),
+        (zones.len() > 1).then(|| longest(&|e| zone_display(e).chars().count())),
+        longest(&|e| hostname_display(e).chars().count()),
+    );
 
-    let items: Vec<ListItem> = app
-        .tunnels
+    let items: Vec<ListItem> = visible
         .iter()
-        .enumerate()
-        .map(|(i, entry)| {
+        .map(|&i| {
+            let entry = &app.tunnels[i];
             let (status_color, status_symbol) = match entry.status {
-                TunnelStatus::Running => (Color::Green, entry.status.symbol()),
-                TunnelStatus::Stopped => (Color::Yellow, entry.status.symbol()),
-                TunnelStatus::Error => (Color::Red, entry.status.symbol()),
+                TunnelStatus::Running => (theme.ok, app.status_symbol(entry.status)),
+                TunnelStatus::Stopped => (theme.warn, app.status_symbol(entry.status)),
+                TunnelStatus::Error => (theme.error, app.status_symbol(entry.status)),
             };
 
             let selected = i == app.selected;
 
             // Base style with optional selection background
             let base_style = if selected {
-                Style::default().bg(Color::Rgb(40, 60, 80)) // Subtle blue background
+                Style::default().bg(theme.selection_bg)
             } else {
                 Style::default()
             };
 
             let name_style = if selected {
-                base_style.fg(Color::White).add_modifier(Modifier::BOLD)
+                base_style
+                    .fg(theme.selection_fg)
+                    .add_modifier(Modifier::BOLD)
             } else {
-                base_style.fg(Color::Gray)
+                base_style.fg(theme.label)
             };
 
             // Show ephemeral tunnels with italic
-            let (final_name_style, hostname_display) = match entry.kind {
-                TunnelKind::Managed => (name_style, entry.tunnel.hostname.clone()),
-                TunnelKind::Ephemeral => (
-                    name_style.add_modifier(Modifier::ITALIC),
-                    format!("{} [ephemeral]", entry.tunnel.name),
-                ),
+            let final_name_style = match entry.kind {
+                TunnelKind::Managed => name_style,
+                TunnelKind::Ephemeral => name_style.add_modifier(Modifier::ITALIC),
             };
+            let hostname = elide_middle(&hostname_display(entry), columns.hostname);
 
             let hostname_style = if selected {
-                base_style.fg(Color::Rgb(150, 150, 150))
+                base_style.fg(theme.selection_dim)
             } else {
-                base_style.fg(Color::DarkGray)
+                base_style.fg(theme.dim)
             };
 
             // Auto-start indicator (only for managed tunnels)
             let auto_start_span = if entry.kind == TunnelKind::Managed && entry.tunnel.auto_start {
-                Span::styled(" ⟳", base_style.fg(Color::Cyan))
+                Span::styled(" ⟳", base_style.fg(theme.accent))
             } else {
                 Span::raw("")
             };
@@ -313,19 +920,78 @@ fn render_tunnels(f: &mut Frame, app: &App, area: Rect) {
             let health_span = if entry.status == TunnelStatus::Running
                 && entry.health == HealthStatus::Unhealthy
             {
-                Span::styled(" ⚠", base_style.fg(Color::Red))
+                Span::styled(" ⚠", base_style.fg(theme.error))
+            } else {
+                Span::raw("")
+            };
+
+            // Maintenance indicator (the hostnames answer 503 or a maintenance origin)
+            let maintenance_span = if entry.tunnel.maintenance.is_some() {
+                Span::styled(" [maintenance]", base_style.fg(theme.error))
             } else {
                 Span::raw("")
             };
 
-            let line = Line::from(vec![
+            // Owning account, only when tunnels from several accounts are listed
+            let account_span = match columns.account {
+                Some(width) => Span::styled(
+                    format!(
+                        "{:<width$} ",
+                        truncate(&entry.tunnel.account_name, width),
+                        width = width
+                    ),
+                    base_style.fg(theme.highlight),
+                ),
+                None => Span::raw(""),
+            };
+            // The hostname pads out to line the zones up
+            let (hostname, zone_span) = match columns.zone {
+                Some(width) => (
+                    format!("{:<width$} ", hostname, width = columns.hostname),
+                    Span::styled(
+                        truncate(&zone_display(entry), width),
+                        base_style.fg(theme.accent),
+                    ),
+                ),
+                None => (hostname, Span::raw("")),
+            };
+
+            // Without colors the selection needs a marker of its own
+            let marker = match (app.ascii, selected) {
+                (true, true) => ">",
+                (true, false) => " ",
+                (false, _) => "",
+            };
+            let mut line = Line::from(vec![
+                Span::raw(marker),
                 Span::styled(format!("{} ", status_symbol), base_style.fg(status_color)),
-                Span::styled(format!("{:<12}", entry.tunnel.name), final_name_style),
-                Span::styled(hostname_display, hostname_style),
+                account_span,
+                Span::styled(
+                    format!(
+                        "{:<width$} ",
+                        truncate(&idn::to_unicode(&entry.tunnel.name), columns.name),
+                        width = columns.name
+                    ),
+                    final_name_style,
+                ),
+                Span::styled(hostname, hostname_style),
+                zone_span,
                 auto_start_span,
                 health_span,
+                maintenance_span,
             ]);
 
+            // Description as a dimmed suffix when the pane has room for some of it
+            if let Some(description) = &entry.tunnel.description {
+                let room = inner_width.saturating_sub(line.width() + 2);
+                if room >= 8 {
+                    line.push_span(Span::styled(
+                        format!("  {}", truncate(description, room)),
+                        base_style.fg(theme.dim).add_modifier(Modifier::DIM),
+                    ));
+                }
+            }
+
             ListItem::new(line).style(base_style)
         })
         .collect();
@@ -334,40 +1000,43 @@ fn render_tunnels(f: &mut Frame, app: &App, area: Rect) {
         Block::default()
             .borders(Borders::ALL)
             .title(title)
-            .border_style(Style::default().fg(Color::Cyan)),
+            .border_style(Style::default().fg(theme.accent)),
     );
 
     f.render_widget(tunnels_list, area);
 }
 
 fn render_logs(f: &mut Frame, app: &App, area: Rect) {
-    let title = if let Some(entry) = app.tunnels.get(app.selected) {
-        format!(" Logs: {} ", entry.tunnel.name)
-    } else {
-        " Logs ".to_string()
-    };
+    let theme = &app.theme;
+    let mut title_spans = vec![Span::raw(match app.selected_entry() {
+        Some(entry) => format!(" Logs: {} ", entry.tunnel.name),
+        None => " Logs ".to_string(),
+    })];
+    if app.is_following_logs() {
+        title_spans.push(Span::styled("● live ", Style::default().fg(theme.ok)));
+    } else if app.log_scroll > 0 {
+        title_spans.push(Span::styled(
+            format!("paused (+{}) End to resume ", app.log_scroll),
+            Style::default().fg(theme.warn),
+        ));
+    }
 
-    // Take last N lines that fit in the area
+    // Take the N lines that fit in the area, ending log_scroll lines above the bottom
     let available_height = area.height.saturating_sub(2) as usize; // -2 for borders
-    let start = if app.logs.len() > available_height {
-        app.logs.len() - available_height
-    } else {
-        0
-    };
+    let end = app.logs.len().saturating_sub(app.log_scroll);
+    let start = end.saturating_sub(available_height);
 
-    let log_lines: Vec<Line> = app.logs[start..]
+    let log_lines: Vec<Line> = app.logs[start..end]
         .iter()
         .map(|line| {
-            let color = if line.contains("ERR") {
-                Color::Red
-            } else if line.contains("WRN") {
-                Color::Yellow
-            } else if line.contains("INF") {
-                Color::Green
-            } else {
-                Color::Gray
+            let (level, text) = logfmt::render(line, !app.raw_logs);
+            let color = match level {
+                Level::Error => theme.error,
+                Level::Warn => theme.warn,
+                Level::Info => theme.ok,
+                Level::Other => theme.label,
             };
-            Line::from(Span::styled(line.clone(), Style::default().fg(color)))
+            Line::from(Span::styled(text.into_owned(), Style::default().fg(color)))
         })
         .collect();
 
@@ -375,46 +1044,73 @@ fn render_logs(f: &mut Frame, app: &App, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(title)
-                .border_style(Style::default().fg(Color::Cyan)),
+                .title(Line::from(title_spans))
+                .border_style(Style::default().fg(theme.accent)),
         )
         .wrap(Wrap { trim: false });
 
     f.render_widget(logs, area);
 }
 
+// Suffix for the destination of a tunnel in maintenance mode (what it serves instead)
+fn maintenance_span(maintenance: Option<&str>, theme: &Theme) -> Span<'static> {
+    match maintenance {
+        Some(service) => Span::styled(
+            format!("  [maintenance: {}]", service),
+            Style::default().fg(theme.error),
+        ),
+        None => Span::raw(""),
+    }
+}
+
 fn render_details(f: &mut Frame, app: &App, area: Rect) {
-    let (target, hostname) = match app.selected_tunnel_details() {
+    let theme = &app.theme;
+    let (target_url, hostname) = match app.selected_tunnel_details() {
         Some(details) => details,
         None => return,
     };
 
-    // Format target URL for display
-    let target_url = if target.starts_with("http://") || target.starts_with("https://") {
-        target.to_string()
-    } else {
-        format!("http://{}", target)
-    };
-
-    let lines = vec![
+    let mut lines = vec![
         Line::from(vec![
-            Span::styled("Destination: ", Style::default().fg(Color::Gray)),
-            Span::styled(&target_url, Style::default().fg(Color::Yellow)),
+            Span::styled("Destination: ", Style::default().fg(theme.label)),
+            Span::styled(&target_url, Style::default().fg(theme.warn)),
+            maintenance_span(app.selected_maintenance(), theme),
         ]),
         Line::from(vec![
-            Span::styled("Public URL:  ", Style::default().fg(Color::Gray)),
+            Span::styled("Public URL:  ", Style::default().fg(theme.label)),
             Span::styled(
-                format!("https://{}", hostname),
-                Style::default().fg(Color::Cyan),
+                format!("https://{}", idn::to_unicode(hostname)),
+                Style::default().fg(theme.accent),
             ),
         ]),
     ];
+    if let Some(zone) = app.selected_zone() {
+        lines.push(zone_line(zone, theme));
+    }
+    if let Some(metrics_url) = app.selected_metrics_url() {
+        lines.push(Line::from(vec![
+            Span::styled("Metrics:     ", Style::default().fg(theme.label)),
+            Span::styled(metrics_url, Style::default().fg(theme.highlight)),
+        ]));
+    }
+    if let Some(description) = app.selected_description() {
+        lines.push(Line::from(vec![
+            Span::styled("Description: ", Style::default().fg(theme.label)),
+            Span::raw(description),
+        ]));
+    }
+    if let Some(history) = app.selected_health_history() {
+        lines.push(health_line(history, theme));
+    }
+    if let Some(info) = app.selected_daemon_info() {
+        lines.push(process_line(info, app.selected_connect_time(), theme));
+    }
 
     let details = Paragraph::new(lines).block(
         Block::default()
             .borders(Borders::ALL)
             .title(" Details ")
-            .border_style(Style::default().fg(Color::Cyan)),
+            .border_style(Style::default().fg(theme.accent)),
     );
 
     f.render_widget(details, area);
@@ -422,9 +1118,11 @@ fn render_details(f: &mut Frame, app: &App, area: Rect) {
 
 fn render_metrics(
     f: &mut Frame,
+    theme: &Theme,
     metrics: Option<&TunnelMetrics>,
     sparkline: &str,
     health: HealthStatus,
+    lifetime_bytes: Option<u64>,
     area: Rect,
 ) {
     let metrics = match metrics {
@@ -443,77 +1141,80 @@ fn render_metrics(
 
     // Health status formatting
     let (health_symbol, health_color, health_text) = match health {
-        HealthStatus::Unknown => ("?", Color::Gray, "unknown"),
-        HealthStatus::Healthy => ("✓", Color::Green, "healthy"),
-        HealthStatus::Unhealthy => ("✗", Color::Red, "unreachable"),
-        HealthStatus::Checking => ("…", Color::Yellow, "checking"),
+        HealthStatus::Unknown => ("?", theme.label, "unknown"),
+        HealthStatus::Healthy => ("✓", theme.ok, "healthy"),
+        HealthStatus::Unhealthy => ("✗", theme.error, "unreachable"),
+        HealthStatus::Checking => ("…", theme.warn, "checking"),
     };
 
     let lines = vec![
         Line::from(vec![
-            Span::styled("Requests: ", Style::default().fg(Color::Gray)),
+            Span::styled("Requests: ", Style::default().fg(theme.label)),
             Span::styled(
                 format!("{}", metrics.total_requests),
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
             ),
-            Span::styled("    Errors: ", Style::default().fg(Color::Gray)),
+            Span::styled("    Errors: ", Style::default().fg(theme.label)),
             Span::styled(
                 format!("{}", metrics.request_errors),
                 Style::default().fg(if metrics.request_errors > 0 {
-                    Color::Red
+                    theme.error
                 } else {
-                    Color::Green
+                    theme.ok
                 }),
             ),
-            Span::styled("    Active: ", Style::default().fg(Color::Gray)),
+            Span::styled("    Active: ", Style::default().fg(theme.label)),
             Span::styled(
                 format!("{}", metrics.concurrent_requests),
-                Style::default().fg(Color::Cyan),
+                Style::default().fg(theme.accent),
             ),
-            Span::styled("    Health: ", Style::default().fg(Color::Gray)),
+            Span::styled("    Health: ", Style::default().fg(theme.label)),
             Span::styled(
                 format!("{} {}", health_symbol, health_text),
                 Style::default().fg(health_color),
             ),
         ]),
         Line::from(vec![
-            Span::styled("HA Connections: ", Style::default().fg(Color::Gray)),
+            Span::styled("HA Connections: ", Style::default().fg(theme.label)),
             Span::styled(
                 format!("{}", metrics.ha_connections),
                 Style::default().fg(if metrics.ha_connections >= 4 {
-                    Color::Green
+                    theme.ok
                 } else {
-                    Color::Yellow
+                    theme.warn
                 }),
             ),
-            Span::styled("    Edge: ", Style::default().fg(Color::Gray)),
+            Span::styled("    Edge: ", Style::default().fg(theme.label)),
             Span::styled(
                 metrics.locations_string(),
-                Style::default().fg(Color::Magenta),
+                Style::default().fg(theme.highlight),
             ),
         ]),
         Line::from(vec![
-            Span::styled("Status Codes: ", Style::default().fg(Color::Gray)),
+            Span::styled("Status Codes: ", Style::default().fg(theme.label)),
             Span::styled(
                 if codes_str.is_empty() {
                     "none".to_string()
                 } else {
                     codes_str
                 },
-                Style::default().fg(Color::White),
+                Style::default().fg(theme.text),
             ),
         ]),
         Line::from(vec![
-            Span::styled("Traffic: ", Style::default().fg(Color::Gray)),
+            Span::styled("Traffic: ", Style::default().fg(theme.label)),
             Span::styled(
                 if sparkline.is_empty() {
                     "waiting...".to_string()
                 } else {
                     sparkline.to_string()
                 },
-                Style::default().fg(Color::Green),
+                Style::default().fg(theme.ok),
+            ),
+            Span::styled("    Lifetime: ", Style::default().fg(theme.label)),
+            Span::styled(
+                lifetime_bytes.map_or("-".to_string(), ytunnel::transfer::format_bytes),
+                Style::default().fg(theme.text),
             ),
         ]),
     ];
@@ -522,28 +1223,29 @@ fn render_metrics(
         Block::default()
             .borders(Borders::ALL)
             .title(" Metrics ")
-            .border_style(Style::default().fg(Color::Cyan)),
+            .border_style(Style::default().fg(theme.accent)),
     );
 
     f.render_widget(metrics_widget, area);
 }
 
 fn render_status_line(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     // Show spinner if active, otherwise show status message
     let (status_text, style) = if let Some(spinner_text) = app.spinner.display() {
-        (spinner_text, Style::default().fg(Color::Cyan))
+        (spinner_text, Style::default().fg(theme.accent))
     } else {
         let text = app.status_message.as_deref().unwrap_or("").to_string();
         let style = if text.starts_with("Error") {
-            Style::default().fg(Color::Red)
+            Style::default().fg(theme.error)
         } else if text.contains("Imported")
             || text.contains("Started")
             || text.contains("Deleted")
             || text.contains("updated")
         {
-            Style::default().fg(Color::Green)
+            Style::default().fg(theme.ok)
         } else {
-            Style::default().fg(Color::Yellow)
+            Style::default().fg(theme.warn)
         };
         (text, style)
     };
@@ -552,53 +1254,77 @@ fn render_status_line(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(status, area);
 }
 
-fn render_help_bar(f: &mut Frame, app: &App, area: Rect) {
+fn render_help_bar(f: &mut Frame, app: &App, mode: LayoutMode, area: Rect) {
+    let theme = &app.theme;
     let help_text = match app.input_mode {
         InputMode::Normal => {
             if app.demo {
-                " (demo) \u{2191}\u{2193}/jk navigate  [c]opy  [r]efresh  [?]help  [q]uit"
+                " (demo) \u{2191}\u{2193}/jk navigate  [/]filter  [c]opy  [r]efresh  [?]help  [q]uit"
                     .to_string()
             } else {
                 // Show different help based on whether an ephemeral tunnel is selected
                 let is_ephemeral = app
-                    .tunnels
-                    .get(app.selected)
+                    .selected_entry()
                     .map(|e| e.kind == TunnelKind::Ephemeral)
                     .unwrap_or(false);
 
                 // Show account switching hint if multiple accounts
                 let account_hint = if app.accounts.len() > 1 {
-                    " [;]account"
+                    " [;]account [:]all"
                 } else {
                     ""
                 };
+                let focus_hint = if app.focused || app.log_view {
+                    " [Esc]back"
+                } else if mode == LayoutMode::SinglePane {
+                    " [Enter]focus [l]ogs"
+                } else {
+                    " [Enter]focus"
+                };
 
                 if is_ephemeral {
                     format!(
-                        " [m]anage [c]opy [o]pen [h]ealth [d]elete [r]efresh{} [?]help [q]uit",
-                        account_hint
+                        " [m]anage [c]opy [i]nfo [o]pen [h]ealth [d]elete [D]elete all [r]efresh [/]filter{}{} [?]help [q]uit",
+                        focus_hint, account_hint
                     )
                 } else {
-                    format!(" [a]dd [e]dit [s]tart [S]top [R]estart [A]utostart [c]opy [o]pen [h]ealth [d]elete [r]efresh{} [?]help [q]uit", account_hint)
+                    format!(" [a]dd [e]dit [C]lone [s]tart [S]top [R]estart [A]utostart [c]opy [i]nfo [o]pen [h]ealth [d]elete [r]efresh [/]filter{}{} [?]help [q]uit", focus_hint, account_hint)
                 }
             }
         }
-        InputMode::AddName | InputMode::AddTarget => {
+        InputMode::Add(_) | InputMode::Import(_) | InputMode::Edit(_)
+            if app.zone_selected().is_some() =>
+        {
+            " ↑/↓ select zone  Enter confirm  Esc cancel".to_string()
+        }
+        InputMode::Add(_) | InputMode::Import(_) => {
             " Enter value, then press Enter. Esc to cancel.".to_string()
         }
-        InputMode::AddZone => " ↑/↓ select zone  Enter confirm  Esc cancel".to_string(),
-        InputMode::EditTarget => " Edit target URL, then press Enter. Esc to cancel.".to_string(),
-        InputMode::EditZone => " ↑/↓ select zone  Enter confirm  Esc cancel".to_string(),
+        InputMode::Edit(EditFlow {
+            step: EditStep::Target,
+            ..
+        }) => " Edit target URL, then press Enter. Esc to cancel.".to_string(),
+        InputMode::Edit(_) => {
+            " Describe the tunnel (leave empty for none), then press Enter. Esc to cancel."
+                .to_string()
+        }
         InputMode::Confirm => " y confirm  n/Esc cancel".to_string(),
         InputMode::Help => " Press Esc or ? to close help".to_string(),
+        InputMode::Info => " ↑/↓ select  Enter/c copy  Esc close".to_string(),
+        InputMode::Copy => " u URL  t target  h hostname  i tunnel ID  Esc cancel".to_string(),
+        InputMode::TokenEntry => " Paste the new token, then press Enter. Esc to skip.".to_string(),
+        InputMode::Filter => format!(
+            " /{}\u{2581}  Enter keep filter  Esc clear  \u{2191}\u{2193} navigate",
+            app.filter
+        ),
     };
 
-    let help = Paragraph::new(help_text).style(Style::default().fg(Color::DarkGray));
+    let help = Paragraph::new(help_text).style(Style::default().fg(theme.dim));
 
     f.render_widget(help, area);
 }
 
-fn render_add_dialog(f: &mut Frame, prompt: &str, input: &str, is_importing: bool) {
+fn render_add_dialog(f: &mut Frame, theme: &Theme, prompt: &str, input: &str, is_importing: bool) {
     let area = centered_rect(60, 25, f.area());
 
     // Clear the area
@@ -612,23 +1338,23 @@ fn render_add_dialog(f: &mut Frame, prompt: &str, input: &str, is_importing: boo
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(theme.accent));
 
     f.render_widget(block, area);
 
     // Build styled content matching zone dialog style
     let lines = vec![
-        Line::from(Span::styled(prompt, Style::default().fg(Color::Yellow))),
+        Line::from(Span::styled(prompt, Style::default().fg(theme.warn))),
         Line::from(""),
         Line::from(vec![
             Span::styled(
                 "> ",
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(theme.accent)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(input, Style::default().fg(Color::Green)),
-            Span::styled("_", Style::default().fg(Color::White)),
+            Span::styled(input, Style::default().fg(theme.ok)),
+            Span::styled("_", Style::default().fg(theme.text)),
         ]),
     ];
 
@@ -638,21 +1364,71 @@ fn render_add_dialog(f: &mut Frame, prompt: &str, input: &str, is_importing: boo
     f.render_widget(text, area);
 }
 
-fn render_zone_dialog(f: &mut Frame, app: &App) {
+// Masked input for replacing a rejected API token
+fn render_token_dialog(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(60, 30, f.area());
+
+    // Clear the area
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Update API Token ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.warn));
+
+    f.render_widget(block, area);
+
+    let account = app.token_account.as_deref().unwrap_or("");
+    let lines = vec![
+        Line::from(Span::styled(
+            format!(
+                "Cloudflare rejected the credentials for account '{}'.",
+                account
+            ),
+            Style::default().fg(theme.warn),
+        )),
+        Line::from("Enter a new API token to verify and save it:"),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                "> ",
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                "•".repeat(app.input.chars().count()),
+                Style::default().fg(theme.ok),
+            ),
+            Span::styled("_", Style::default().fg(theme.text)),
+        ]),
+    ];
+
+    let text = Paragraph::new(lines)
+        .block(Block::default().padding(ratatui::widgets::Padding::new(2, 2, 1, 1)));
+
+    f.render_widget(text, area);
+}
+
+fn render_zone_dialog(
+    f: &mut Frame,
+    app: &App,
+    title: &str,
+    name: &str,
+    target: &str,
+    zone_selected: usize,
+) {
+    let theme = &app.theme;
     let area = centered_rect(60, 50, f.area());
 
     // Clear the area
     f.render_widget(Clear, area);
 
-    let title = if app.is_importing {
-        " Import: Select Zone "
-    } else {
-        " Select Zone "
-    };
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(theme.accent));
 
     f.render_widget(block, area);
 
@@ -661,39 +1437,33 @@ fn render_zone_dialog(f: &mut Frame, app: &App) {
     let mut lines: Vec<Line> = vec![
         Line::from(vec![
             Span::raw("Name: "),
-            Span::styled(
-                app.new_tunnel_name.as_deref().unwrap_or(""),
-                Style::default().fg(Color::Green),
-            ),
+            Span::styled(name, Style::default().fg(theme.ok)),
         ]),
         Line::from(vec![
             Span::raw("Target: "),
-            Span::styled(
-                app.new_tunnel_target.as_deref().unwrap_or(""),
-                Style::default().fg(Color::Green),
-            ),
+            Span::styled(target, Style::default().fg(theme.ok)),
         ]),
         Line::from(""),
         Line::from(Span::styled(
             "Select zone:",
-            Style::default().fg(Color::Yellow),
+            Style::default().fg(theme.warn),
         )),
         Line::from(""),
     ];
 
     // Add zone options
     for (i, zone) in app.zones.iter().enumerate() {
-        let selected = i == app.zone_selected;
+        let selected = i == zone_selected;
         let prefix = if selected { "> " } else { "  " };
         let style = if selected {
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Gray)
+            Style::default().fg(theme.label)
         };
         lines.push(Line::from(Span::styled(
-            format!("{}{}", prefix, zone.name),
+            format!("{}{}", prefix, idn::to_unicode(&zone.name)),
             style,
         )));
     }
@@ -703,8 +1473,8 @@ fn render_zone_dialog(f: &mut Frame, app: &App) {
     let available_height = area.height.saturating_sub(4) as usize;
     let scroll = if available_height > header_lines {
         let visible_zones = available_height - header_lines;
-        if app.zone_selected >= visible_zones {
-            (app.zone_selected - visible_zones + 1) as u16
+        if zone_selected >= visible_zones {
+            (zone_selected - visible_zones + 1) as u16
         } else {
             0
         }
@@ -719,7 +1489,8 @@ fn render_zone_dialog(f: &mut Frame, app: &App) {
     f.render_widget(content, area);
 }
 
-fn render_edit_dialog(f: &mut Frame, app: &App, prompt: &str) {
+fn render_edit_dialog(f: &mut Frame, app: &App, name: &str, prompt: &str) {
+    let theme = &app.theme;
     let area = centered_rect(60, 30, f.area());
 
     // Clear the area
@@ -728,7 +1499,7 @@ fn render_edit_dialog(f: &mut Frame, app: &App, prompt: &str) {
     let block = Block::default()
         .title(" Edit Tunnel ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(theme.accent));
 
     f.render_widget(block, area);
 
@@ -736,23 +1507,20 @@ fn render_edit_dialog(f: &mut Frame, app: &App, prompt: &str) {
     let lines = vec![
         Line::from(vec![
             Span::raw("Editing: "),
-            Span::styled(
-                app.editing_tunnel_name.as_deref().unwrap_or(""),
-                Style::default().fg(Color::Green),
-            ),
+            Span::styled(name, Style::default().fg(theme.ok)),
         ]),
         Line::from(""),
-        Line::from(Span::styled(prompt, Style::default().fg(Color::Yellow))),
+        Line::from(Span::styled(prompt, Style::default().fg(theme.warn))),
         Line::from(""),
         Line::from(vec![
             Span::styled(
                 "> ",
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(theme.accent)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(&app.input, Style::default().fg(Color::Green)),
-            Span::styled("_", Style::default().fg(Color::White)),
+            Span::styled(&app.input, Style::default().fg(theme.ok)),
+            Span::styled("_", Style::default().fg(theme.text)),
         ]),
     ];
 
@@ -762,7 +1530,14 @@ fn render_edit_dialog(f: &mut Frame, app: &App, prompt: &str) {
     f.render_widget(text, area);
 }
 
-fn render_edit_zone_dialog(f: &mut Frame, app: &App) {
+fn render_edit_zone_dialog(
+    f: &mut Frame,
+    app: &App,
+    flow: &EditFlow,
+    target: &str,
+    zone_selected: usize,
+) {
+    let theme = &app.theme;
     let area = centered_rect(60, 50, f.area());
 
     // Clear the area
@@ -771,7 +1546,7 @@ fn render_edit_zone_dialog(f: &mut Frame, app: &App) {
     let block = Block::default()
         .title(" Edit: Select Zone ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(theme.accent));
 
     f.render_widget(block, area);
 
@@ -781,42 +1556,39 @@ fn render_edit_zone_dialog(f: &mut Frame, app: &App) {
         Line::from(vec![
             Span::raw("Editing: "),
             Span::styled(
-                app.editing_tunnel_name.as_deref().unwrap_or(""),
+                flow.name.as_str(),
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(theme.accent)
                     .add_modifier(Modifier::BOLD),
             ),
         ]),
         Line::from(vec![
             Span::raw("New Target: "),
-            Span::styled(
-                app.new_tunnel_target.as_deref().unwrap_or(""),
-                Style::default().fg(Color::Green),
-            ),
+            Span::styled(target, Style::default().fg(theme.ok)),
         ]),
         Line::from(""),
         Line::from(Span::styled(
             "Select zone:",
-            Style::default().fg(Color::Yellow),
+            Style::default().fg(theme.warn),
         )),
         Line::from(""),
     ];
 
     // Add zone options
     for (i, zone) in app.zones.iter().enumerate() {
-        let selected = i == app.zone_selected;
-        let is_original = app.original_zone_id.as_deref() == Some(&zone.id);
+        let selected = i == zone_selected;
+        let is_original = flow.original_zone_id == zone.id;
         let prefix = if selected { "> " } else { "  " };
         let suffix = if is_original { " (current)" } else { "" };
         let style = if selected {
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Gray)
+            Style::default().fg(theme.label)
         };
         lines.push(Line::from(Span::styled(
-            format!("{}{}{}", prefix, zone.name, suffix),
+            format!("{}{}{}", prefix, idn::to_unicode(&zone.name), suffix),
             style,
         )));
     }
@@ -825,8 +1597,8 @@ fn render_edit_zone_dialog(f: &mut Frame, app: &App) {
     let available_height = area.height.saturating_sub(4) as usize;
     let scroll = if available_height > header_lines {
         let visible_zones = available_height - header_lines;
-        if app.zone_selected >= visible_zones {
-            (app.zone_selected - visible_zones + 1) as u16
+        if zone_selected >= visible_zones {
+            (zone_selected - visible_zones + 1) as u16
         } else {
             0
         }
@@ -841,7 +1613,7 @@ fn render_edit_zone_dialog(f: &mut Frame, app: &App) {
     f.render_widget(content, area);
 }
 
-fn render_confirm_dialog(f: &mut Frame, message: &str) {
+fn render_confirm_dialog(f: &mut Frame, theme: &Theme, message: &str) {
     let area = centered_rect(60, 15, f.area());
 
     // Clear the area
@@ -850,18 +1622,51 @@ fn render_confirm_dialog(f: &mut Frame, message: &str) {
     let block = Block::default()
         .title(" Confirm ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Red));
+        .border_style(Style::default().fg(theme.error));
 
     let inner = block.inner(area);
     f.render_widget(block, area);
 
     let text = Paragraph::new(message)
-        .style(Style::default().fg(Color::Yellow))
+        .style(Style::default().fg(theme.warn))
         .wrap(Wrap { trim: false })
         .block(Block::default().borders(Borders::NONE));
     f.render_widget(text, inner);
 }
 
+// "Zone:        example.com" in the details panel and focused summary
+fn zone_line(zone: &str, theme: &Theme) -> Line<'static> {
+    Line::from(vec![
+        Span::styled("Zone:        ", Style::default().fg(theme.label)),
+        Span::styled(idn::to_unicode(zone), Style::default().fg(theme.accent)),
+    ])
+}
+
+// Shorten to `max` characters by cutting out the middle, e.g. "api.sta…mple.com",
+// so both the leading label and the zone stay readable
+fn elide_middle(text: &str, max: usize) -> String {
+    let count = text.chars().count();
+    if count <= max || max < 3 {
+        return truncate(text, max);
+    }
+    let tail = (max - 1) / 2;
+    let head = max - 1 - tail;
+    let mut out: String = text.chars().take(head).collect();
+    out.push('\u{2026}');
+    out.extend(text.chars().skip(count - tail));
+    out
+}
+
+// Shorten text to at most `max` characters, marking the cut with an ellipsis
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut out: String = text.chars().take(max.saturating_sub(1)).collect();
+    out.push('\u{2026}');
+    out
+}
+
 // Create a centered rect of given percentage of the parent
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
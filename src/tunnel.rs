@@ -1,10 +1,24 @@
 use anyhow::{Context, Result};
 use std::fs;
+use std::path::PathBuf;
 use std::process::Stdio;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 
-use crate::config;
+use ytunnel::cloudflare;
+use ytunnel::config::{self, Account, Config};
+use ytunnel::state::{self, TunnelState};
+
+// How to install cloudflared on this platform, for "not installed" messages
+#[cfg(target_os = "macos")]
+pub const CLOUDFLARED_INSTALL_HINT: &str =
+    "install it with `brew install cloudflare/cloudflare/cloudflared`";
+#[cfg(target_os = "windows")]
+pub const CLOUDFLARED_INSTALL_HINT: &str =
+    "install it with `winget install --id Cloudflare.cloudflared`";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub const CLOUDFLARED_INSTALL_HINT: &str = "install it from your distribution's Cloudflare \
+     repository (https://pkg.cloudflare.com) or https://github.com/cloudflare/cloudflared/releases";
 
 pub async fn is_cloudflared_installed() -> bool {
     Command::new("cloudflared")
@@ -17,52 +31,395 @@ pub async fn is_cloudflared_installed() -> bool {
         .unwrap_or(false)
 }
 
-pub async fn run_tunnel(
+// A per-run config file written by `ytunnel run` (tunnel-<id>-<pid>.yml)
+pub struct EphemeralConfig {
+    pub path: PathBuf,
+    // None for legacy tunnel-<id>.yml files written before per-run naming
+    pub pid: Option<u32>,
+}
+
+impl EphemeralConfig {
+    // Whether the owning `ytunnel run` process is still alive. Legacy files carry
+    // no owner and are left behind by old or crashed runs, so they count as stale;
+    // otherwise their tunnel would never be cleaned up or offered for removal.
+    pub fn is_live(&self) -> bool {
+        self.pid.is_some_and(pid_alive)
+    }
+}
+
+// Path of the config file owned by this process for the given tunnel
+pub fn ephemeral_config_path(tunnel_id: &str) -> Result<PathBuf> {
+    let config_dir = config::config_dir()?;
+    Ok(config_dir.join(format!("tunnel-{}-{}.yml", tunnel_id, std::process::id())))
+}
+
+// All ephemeral config files for a tunnel, including legacy and stale ones
+pub fn ephemeral_configs(tunnel_id: &str) -> Vec<EphemeralConfig> {
+    let Ok(config_dir) = config::config_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&config_dir) else {
+        return Vec::new();
+    };
+
+    let prefix = format!("tunnel-{}", tunnel_id);
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name();
+            let rest = file_name
+                .to_str()?
+                .strip_prefix(&prefix)?
+                .strip_suffix(".yml")?;
+            let pid = if rest.is_empty() {
+                None
+            } else {
+                Some(rest.strip_prefix('-')?.parse::<u32>().ok()?)
+            };
+            Some(EphemeralConfig {
+                path: entry.path(),
+                pid,
+            })
+        })
+        .collect()
+}
+
+// Ephemeral configs for a tunnel owned by other live `ytunnel run` processes
+pub fn other_live_ephemeral_configs(tunnel_id: &str) -> Vec<EphemeralConfig> {
+    let own_pid = std::process::id();
+    ephemeral_configs(tunnel_id)
+        .into_iter()
+        .filter(|c| c.pid != Some(own_pid) && c.is_live())
+        .collect()
+}
+
+// Parse an ephemeral tunnel's config file to extract hostname and target
+// (a run that kept its tunnel on exit leaves no config, but a kept-runs entry)
+pub fn parse_ephemeral_config(tunnel_id: &str) -> Option<(String, String)> {
+    // Prefer a config owned by a live run, fall back to any leftover one
+    let mut configs = ephemeral_configs(tunnel_id);
+    configs.sort_by_key(|c| !c.is_live());
+    let Some(config) = configs.first() else {
+        return kept_run(tunnel_id);
+    };
+
+    let content = std::fs::read_to_string(&config.path).ok()?;
+    parse_ingress(&content)
+}
+
+// Tunnels a `ytunnel run` kept on exit for the next run live in
+// kept-runs/<tunnel-id> (hostname and target lines), so they aren't taken for leaks
+fn kept_run_path(tunnel_id: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join("kept-runs").join(tunnel_id))
+}
+
+pub fn mark_kept(tunnel_id: &str, hostname: &str, target: &str) -> Result<()> {
+    let path = kept_run_path(tunnel_id)?;
+    state::ensure_parent_dir(&path)?;
+    fs::write(&path, format!("{}\n{}\n", hostname, target))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+pub fn unmark_kept(tunnel_id: &str) {
+    if let Ok(path) = kept_run_path(tunnel_id) {
+        fs::remove_file(path).ok();
+    }
+}
+
+pub fn is_kept(tunnel_id: &str) -> bool {
+    kept_run_path(tunnel_id).is_ok_and(|p| p.exists())
+}
+
+// Hostname and target a kept run was serving
+fn kept_run(tunnel_id: &str) -> Option<(String, String)> {
+    let content = fs::read_to_string(kept_run_path(tunnel_id).ok()?).ok()?;
+    let mut lines = content.lines();
+    Some((lines.next()?.to_string(), lines.next()?.to_string()))
+}
+
+// The keep/delete answer given on exit by an earlier run from the same shell, so
+// it's asked once per terminal session (run-sessions/<shell pid>, true = keep)
+pub fn session_exit_choice() -> Option<bool> {
+    match fs::read_to_string(session_file()?).ok()?.trim() {
+        "keep" => Some(true),
+        "delete" => Some(false),
+        _ => None,
+    }
+}
+
+pub fn remember_session_exit_choice(keep: bool) -> Result<()> {
+    let Some(path) = session_file() else {
+        return Ok(());
+    };
+    state::ensure_parent_dir(&path)?;
+    // Forget the answers of shells that have exited
+    if let Some(entries) = path.parent().and_then(|dir| fs::read_dir(dir).ok()) {
+        for entry in entries.flatten() {
+            let pid = entry
+                .file_name()
+                .to_str()
+                .and_then(|n| n.parse::<u32>().ok());
+            if pid.is_some_and(|pid| !pid_alive(pid)) {
+                fs::remove_file(entry.path()).ok();
+            }
+        }
+    }
+    fs::write(&path, if keep { "keep\n" } else { "delete\n" })
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(unix)]
+fn session_file() -> Option<PathBuf> {
+    let shell_pid = std::os::unix::process::parent_id();
+    Some(
+        config::config_dir()
+            .ok()?
+            .join("run-sessions")
+            .join(shell_pid.to_string()),
+    )
+}
+
+#[cfg(not(unix))]
+fn session_file() -> Option<PathBuf> {
+    None
+}
+
+// The first hostname and real service from an ephemeral config's ingress rules
+fn parse_ingress(content: &str) -> Option<(String, String)> {
+    // Parse simple YAML - look for hostname and service lines
+    // Format is:
+    //   ingress:
+    //     - hostname: example.com
+    //       service: http://localhost:8080
+    //     - service: http_status:404
+    let mut hostname = None;
+    let mut service = None;
+
+    for line in content.lines() {
+        // Strip leading whitespace and list marker
+        let line = line.trim().trim_start_matches('-').trim();
+
+        if line.starts_with("hostname:") {
+            hostname = line.strip_prefix("hostname:").map(|s| s.trim().to_string());
+        } else if line.starts_with("service:") {
+            let svc = line.strip_prefix("service:").map(|s| s.trim().to_string());
+            // Skip the fallback http_status:404 service, take first real service
+            if let Some(ref s) = svc {
+                if !s.contains("http_status") && service.is_none() {
+                    service = svc;
+                }
+            }
+        }
+    }
+
+    match (hostname, service) {
+        (Some(h), Some(s)) => Some((h, s)),
+        _ => None,
+    }
+}
+
+// A tunnel a `ytunnel run` created but never cleaned up because it was killed
+// (SIGKILL) or crashed
+pub struct LeakedRun {
+    pub tunnel_id: String,
+    // <tunnel_prefix><name>
+    pub tunnel_name: String,
+    // From the run's leftover config file, when there is one
+    pub hostname: Option<String>,
+}
+
+// <tunnel_prefix>* tunnels in the account that aren't managed, have no live `ytunnel run`
+// on this machine, and have no connector anywhere (so a run elsewhere isn't
+// mistaken for a leak). Includes runs kept for reuse.
+pub async fn find_idle_ephemeral(
+    client: &cloudflare::Client,
+    acct: &Account,
+    state: &TunnelState,
+) -> Result<Vec<cloudflare::Tunnel>> {
+    Ok(client
+        .list_tunnels(&acct.account_id)
+        .await?
+        .into_iter()
+        .filter(|t| t.deleted_at.is_none() && !t.is_connected())
+        .filter(|t| {
+            let Some(short_name) = config::short_tunnel_name(&t.name) else {
+                return false;
+            };
+            let managed = state.find_for_account(short_name, &acct.name).is_some()
+                || state.tunnels.iter().any(|m| m.tunnel_id == t.id);
+            let running = ephemeral_configs(&t.id).iter().any(|c| c.is_live());
+            !managed && !running
+        })
+        .collect())
+}
+
+// Idle ephemeral tunnels that weren't kept on purpose
+pub async fn find_leaked_runs(
+    client: &cloudflare::Client,
+    acct: &Account,
+    state: &TunnelState,
+) -> Result<Vec<LeakedRun>> {
+    Ok(find_idle_ephemeral(client, acct, state)
+        .await?
+        .into_iter()
+        .filter(|t| !is_kept(&t.id))
+        .map(|t| LeakedRun {
+            hostname: parse_ephemeral_config(&t.id).map(|(hostname, _)| hostname),
+            tunnel_id: t.id,
+            tunnel_name: t.name,
+        })
+        .collect())
+}
+
+// Tunnel CNAMEs in all of the account's zones (zone_id filled in), to match
+// against several tunnels without listing each zone once per tunnel
+pub async fn tunnel_dns_records(
+    cfg: &Config,
+    acct: &Account,
+) -> Result<Vec<cloudflare::DnsRecord>> {
+    let mut records = Vec::new();
+    for zone in &acct.zones {
+        let dns_client = cfg.client(cfg.dns_account(acct, &zone.id));
+        records.extend(
+            dns_client
+                .list_dns_records(&zone.id, &cloudflare::DnsRecordFilter::cnames())
+                .await?
+                .into_iter()
+                .filter(|r| r.tunnel_id().is_some())
+                .map(|mut r| {
+                    r.zone_id = zone.id.clone();
+                    r
+                }),
+        );
+    }
+    Ok(records)
+}
+
+// Do what the interrupted run would have on exit: remove the DNS records routing
+// to the tunnel (in any of the account's zones), the tunnel, and its local files
+pub async fn clean_up_leaked_run(cfg: &Config, acct: &Account, leak: &LeakedRun) -> Result<()> {
+    let records: Vec<_> = tunnel_dns_records(cfg, acct)
+        .await?
+        .into_iter()
+        .filter(|r| r.tunnel_id() == Some(leak.tunnel_id.as_str()))
+        .collect();
+    remove_ephemeral(cfg, acct, &leak.tunnel_id, &records).await
+}
+
+// Delete an ephemeral tunnel along with the given DNS records routing to it, its
+// credentials, stale run configs, and kept-run marker
+pub async fn remove_ephemeral(
+    cfg: &Config,
+    acct: &Account,
     tunnel_id: &str,
-    credentials_path: &std::path::Path,
-    hostname: &str,
-    target: &str,
+    records: &[cloudflare::DnsRecord],
 ) -> Result<()> {
-    // Normalize target URL
-    let target_url = if target.starts_with("http://") || target.starts_with("https://") {
-        target.to_string()
-    } else {
-        format!("http://{}", target)
-    };
+    for record in records {
+        cfg.client(cfg.dns_account(acct, &record.zone_id))
+            .delete_dns_record_by_id(&record.zone_id, &record.id)
+            .await?;
+    }
 
-    // Create a temporary config file for this tunnel
-    let config_dir = config::config_dir()?;
-    let config_path = config_dir.join(format!("tunnel-{}.yml", tunnel_id));
+    cfg.client(acct)
+        .delete_tunnel(&acct.account_id, tunnel_id)
+        .await?;
+
+    let credentials_dir = config::credentials_dir()?;
+    fs::remove_file(credentials_dir.join(format!("{}.json", tunnel_id))).ok();
+    for stale in ephemeral_configs(tunnel_id) {
+        fs::remove_file(&stale.path).ok();
+    }
+    unmark_kept(tunnel_id);
+    Ok(())
+}
+
+#[cfg(unix)]
+fn pid_alive(pid: u32) -> bool {
+    // Signal 0 only checks that the process exists; EPERM still means it is alive
+    let ret = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    ret == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
 
-    let config_content = format!(
+#[cfg(not(unix))]
+fn pid_alive(_pid: u32) -> bool {
+    true
+}
+
+// Check whether any cloudflared process is already serving this tunnel ID
+pub async fn is_cloudflared_running_for(tunnel_id: &str) -> bool {
+    Command::new("pgrep")
+        .args(["-f", &format!("cloudflared.*{}", tunnel_id)])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+// The cloudflared config for a `ytunnel run` (read back by parse_ephemeral_config)
+fn ephemeral_config(
+    tunnel_id: &str,
+    credentials_path: &std::path::Path,
+    hostname: &str,
+    target: &str,
+    fallback: Option<&str>,
+) -> String {
+    format!(
         r#"tunnel: {tunnel_id}
 credentials-file: {credentials_path}
 ingress:
   - hostname: {hostname}
     service: {target_url}
-  - service: http_status:404
+  - service: {fallback}
 "#,
         tunnel_id = tunnel_id,
         credentials_path = credentials_path.display(),
         hostname = hostname,
-        target_url = target_url
-    );
+        target_url = state::service_url(target),
+        fallback = fallback.unwrap_or(state::DEFAULT_FALLBACK)
+    )
+}
+
+// Run cloudflared in the foreground until Ctrl+C, cloudflared exits, or the timeout
+// passes. Returns true when the timeout ended it.
+pub async fn run_tunnel(
+    tunnel_id: &str,
+    credentials_path: &std::path::Path,
+    hostname: &str,
+    target: &str,
+    fallback: Option<&str>,
+    timeout: Option<std::time::Duration>,
+) -> Result<bool> {
+    // Create a config file owned by this run so concurrent runs don't clobber it
+    let config_path = ephemeral_config_path(tunnel_id)?;
+    let config_content = ephemeral_config(tunnel_id, credentials_path, hostname, target, fallback);
 
     fs::write(&config_path, &config_content)
         .with_context(|| format!("Failed to write tunnel config to {}", config_path.display()))?;
 
     // Run cloudflared with the config
-    let mut child = Command::new("cloudflared")
+    let spawned = Command::new("cloudflared")
         .arg("tunnel")
         .arg("--config")
         .arg(&config_path)
         .arg("run")
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .spawn()
-        .context("Failed to start cloudflared")?;
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            fs::remove_file(&config_path).ok();
+            return Err(e).context("Failed to start cloudflared");
+        }
+    };
 
-    println!("Tunnel running: https://{} -> {}", hostname, target_url);
+    println!(
+        "Tunnel running: https://{} -> {}",
+        hostname,
+        state::service_url(target)
+    );
     println!("{}", "─".repeat(50));
 
     // Stream stderr (cloudflared logs to stderr)
@@ -73,6 +430,16 @@ ingress:
     let ctrl_c = tokio::signal::ctrl_c();
     tokio::pin!(ctrl_c);
 
+    // Without a timeout this never fires
+    let deadline = async {
+        match timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(deadline);
+    let mut timed_out = false;
+
     loop {
         tokio::select! {
             line = reader.next_line() => {
@@ -95,13 +462,22 @@ ingress:
                 child.kill().await.ok();
                 break;
             }
+            _ = &mut deadline => {
+                println!(
+                    "\n\nTimeout of {} reached. Shutting down tunnel...",
+                    crate::format_uptime(timeout.unwrap_or_default())
+                );
+                child.kill().await.ok();
+                timed_out = true;
+                break;
+            }
         }
     }
 
-    // Clean up config file
+    // Clean up only the config file this run created
     fs::remove_file(&config_path).ok();
 
-    Ok(())
+    Ok(timed_out)
 }
 
 fn should_display_log(line: &str) -> bool {
@@ -113,3 +489,51 @@ fn should_display_log(line: &str) -> bool {
         || line.contains("registered")
         || line.contains("Tunnel")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ephemeral_config_without_owner_is_stale() {
+        let config = |pid| EphemeralConfig {
+            path: PathBuf::from("tunnel-t1.yml"),
+            pid,
+        };
+        assert!(config(Some(std::process::id())).is_live());
+        assert!(!config(None).is_live());
+    }
+
+    #[test]
+    fn test_ephemeral_config_keeps_targets_exactly() {
+        let creds = std::path::Path::new("/tmp/t1.json");
+        for (target, service) in [
+            ("[::1]:3000", "http://[::1]:3000"),
+            ("https://[2001:db8::1]:8443", "https://[2001:db8::1]:8443"),
+            ("192.168.1.40:8080", "http://192.168.1.40:8080"),
+            ("localhost:3000", "http://localhost:3000"),
+            ("https://app.internal/api", "https://app.internal/api"),
+        ] {
+            let config = ephemeral_config("t1", creds, "api.example.com", target, None);
+            assert_eq!(
+                parse_ingress(&config),
+                Some(("api.example.com".to_string(), service.to_string())),
+                "{}",
+                target
+            );
+        }
+
+        let config = ephemeral_config(
+            "t1",
+            creds,
+            "api.example.com",
+            "localhost:3000",
+            Some("http_status:403"),
+        );
+        assert!(
+            config.ends_with("  - service: http_status:403\n"),
+            "{}",
+            config
+        );
+    }
+}
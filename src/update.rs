@@ -1,9 +1,12 @@
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 const GITHUB_REPO_OWNER: &str = "yetidevworks";
 const GITHUB_REPO_NAME: &str = "ytunnel";
 const CHECK_INTERVAL_SECS: u64 = 24 * 60 * 60;
+// How often the download progress line is redrawn
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
 
 // ---------- version helpers ----------
 
@@ -30,8 +33,8 @@ struct UpdateCache {
 
 fn cache_path() -> Option<PathBuf> {
     Some(
-        dirs::config_dir()?
-            .join("ytunnel")
+        ytunnel::config::config_dir()
+            .ok()?
             .join("update-check.json"),
     )
 }
@@ -125,10 +128,36 @@ fn detect_install_method() -> InstallMethod {
 
 // ---------- public entry points ----------
 
+/// Whether update checks are allowed by the environment and config.
+/// Disabled by `YTUNNEL_NO_UPDATE_CHECK=1` or `check_updates = false` in config.toml.
+pub fn update_checks_enabled() -> bool {
+    if let Ok(value) = std::env::var("YTUNNEL_NO_UPDATE_CHECK") {
+        if !value.is_empty() && value != "0" {
+            return false;
+        }
+    }
+
+    // Read the raw file so a missing or unmigrated config never blocks this check
+    ytunnel::config::config_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| contents.parse::<toml::Table>().ok())
+        .and_then(|table| table.get("check_updates").and_then(|v| v.as_bool()))
+        .unwrap_or(true)
+}
+
 /// `ytunnel update [--check]`
-pub async fn cmd_update(check_only: bool) -> Result<()> {
+pub async fn cmd_update(check_only: bool, enabled: bool) -> Result<()> {
     let current = env!("CARGO_PKG_VERSION");
 
+    if !enabled {
+        eprintln!(
+            "Update checks are disabled (YTUNNEL_NO_UPDATE_CHECK, check_updates = false, \
+             or --version-check never)."
+        );
+        return Ok(());
+    }
+
     eprintln!("Checking for updates...");
     let latest = fetch_latest_version().await?;
 
@@ -215,9 +244,9 @@ async fn perform_update(exe_path: &Path, version: &str) -> Result<()> {
 
     let archive_path = tmp.join(&asset_name);
 
-    // Download
+    // Download, streaming the body so slow connections show progress
     let client = reqwest::Client::new();
-    let bytes = client
+    let mut response = client
         .get(&download_url)
         .header(
             "User-Agent",
@@ -225,10 +254,30 @@ async fn perform_update(exe_path: &Path, version: &str) -> Result<()> {
         )
         .send()
         .await
-        .context("Failed to download release")?
-        .bytes()
+        .context("Failed to download release")?;
+
+    let total = response.content_length();
+    let started = Instant::now();
+    let mut bytes = Vec::with_capacity(total.unwrap_or(0) as usize);
+    let mut last_report: Option<Instant> = None;
+    while let Some(chunk) = response
+        .chunk()
         .await
-        .context("Failed to read response body")?;
+        .context("Failed to read response body")?
+    {
+        bytes.extend_from_slice(&chunk);
+        if last_report.is_none_or(|t| t.elapsed() >= PROGRESS_INTERVAL) {
+            eprint!(
+                "\r{}",
+                format_progress(bytes.len() as u64, total, started.elapsed())
+            );
+            last_report = Some(Instant::now());
+        }
+    }
+    eprintln!(
+        "\r{}",
+        format_progress(bytes.len() as u64, total, started.elapsed())
+    );
 
     std::fs::write(&archive_path, &bytes)?;
 
@@ -259,6 +308,38 @@ async fn perform_update(exe_path: &Path, version: &str) -> Result<()> {
     Ok(())
 }
 
+// One-line download progress: a bar with percentage and ETA when the size is
+// known, otherwise just the amount received so far
+fn format_progress(received: u64, total: Option<u64>, elapsed: Duration) -> String {
+    const BAR_WIDTH: usize = 30;
+    let mb = |b: u64| b as f64 / (1024.0 * 1024.0);
+
+    match total {
+        Some(total) if total > 0 => {
+            let fraction = (received as f64 / total as f64).min(1.0);
+            let filled = (fraction * BAR_WIDTH as f64) as usize;
+            let eta = if received >= total {
+                "done".to_string()
+            } else if received == 0 || elapsed.is_zero() {
+                "ETA --".to_string()
+            } else {
+                let rate = received as f64 / elapsed.as_secs_f64();
+                format!("ETA {}s", ((total - received) as f64 / rate).ceil() as u64)
+            };
+            format!(
+                "[{}{}] {:>3}% {:.1}/{:.1} MB {}",
+                "#".repeat(filled),
+                " ".repeat(BAR_WIDTH - filled),
+                (fraction * 100.0) as u64,
+                mb(received),
+                mb(total),
+                eta
+            )
+        }
+        _ => format!("{:.1} MB downloaded", mb(received)),
+    }
+}
+
 fn replace_binary(new_bin: &Path, exe_path: &Path) -> Result<()> {
     #[cfg(unix)]
     {
@@ -309,4 +390,22 @@ mod tests {
     fn test_platform_target_is_some() {
         assert!(platform_target().is_some());
     }
+
+    #[test]
+    fn test_format_progress() {
+        let mb = 1024 * 1024;
+        assert_eq!(
+            format_progress(mb, Some(4 * mb), Duration::from_secs(1)),
+            format!(
+                "[{}{}]  25% 1.0/4.0 MB ETA 3s",
+                "#".repeat(7),
+                " ".repeat(23)
+            )
+        );
+        assert!(format_progress(4 * mb, Some(4 * mb), Duration::from_secs(4)).ends_with("done"));
+        assert_eq!(
+            format_progress(mb, None, Duration::ZERO),
+            "1.0 MB downloaded"
+        );
+    }
 }
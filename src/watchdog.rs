@@ -0,0 +1,252 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::daemon;
+use ytunnel::cloudflare;
+use ytunnel::config;
+use ytunnel::metrics::TunnelMetrics;
+use ytunnel::state::{PersistentTunnel, TunnelState, TunnelStatus};
+use ytunnel::transfer;
+
+// Tracks how long each running tunnel has been without edge connections. The
+// service manager restarts cloudflared when it exits, not when it's alive but
+// disconnected (network blip, QUIC blocked), which is what this catches.
+pub struct Watchdog {
+    grace: Duration,
+    // (account, name) -> when the tunnel was first seen without edge connections
+    down_since: HashMap<(String, String), Instant>,
+}
+
+// What a tunnel's connection count means for it
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Connected,
+    // Connections came back after being down for this long
+    Recovered(Duration),
+    // Down for this long, still within the grace period
+    Waiting(Duration),
+    // Down for this long, past the grace period
+    Restart(Duration),
+    // The metrics endpoint didn't answer, so there's no count to go by
+    Unknown,
+}
+
+impl Watchdog {
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            grace,
+            down_since: HashMap::new(),
+        }
+    }
+
+    // Record a tunnel's edge connection count (None when metrics were unavailable).
+    // After a restart the clock starts over, so a tunnel that stays down is
+    // restarted once per grace period rather than on every check.
+    pub fn observe(
+        &mut self,
+        key: (String, String),
+        connections: Option<u64>,
+        now: Instant,
+    ) -> Verdict {
+        match connections {
+            None => Verdict::Unknown,
+            Some(0) => {
+                let down = now.duration_since(*self.down_since.entry(key.clone()).or_insert(now));
+                if down >= self.grace {
+                    self.down_since.insert(key, now);
+                    Verdict::Restart(down)
+                } else {
+                    Verdict::Waiting(down)
+                }
+            }
+            Some(_) => match self.down_since.remove(&key) {
+                Some(since) => Verdict::Recovered(now.duration_since(since)),
+                None => Verdict::Connected,
+            },
+        }
+    }
+
+    // Stop tracking a tunnel that isn't running (stopped by hand, or deleted)
+    pub fn forget(&mut self, key: &(String, String)) {
+        self.down_since.remove(key);
+    }
+}
+
+// `ytunnel watch`: check every running tunnel each interval until interrupted
+pub async fn run(account: Option<&str>, interval: Duration, grace: Duration) -> Result<()> {
+    // Edge connections are read from cloudflared's metrics; without them every
+    // check would come back Unknown and nothing would ever restart
+    if config::load_config()?.daemon.metrics_bind().is_none() {
+        anyhow::bail!(
+            "cloudflared's metrics are off ([daemon] metrics_bind = \"{}\"), so the watchdog \
+             can't see edge connections. Remove metrics_bind or set it to an address, then \
+             restart your tunnels.",
+            config::METRICS_OFF
+        );
+    }
+    let mut watchdog = Watchdog::new(grace);
+    let log = log_path()?;
+    log_action(
+        &log,
+        &format!(
+            "watching {} every {}s; restarting after {}s without edge connections",
+            account.map_or("all accounts".to_string(), |a| format!("account '{}'", a)),
+            interval.as_secs(),
+            grace.as_secs()
+        ),
+    );
+    println!("Logging to {} (Ctrl+C to stop)", log.display());
+
+    loop {
+        let timeout = config::load_config()?.metrics.timeout();
+        let state = TunnelState::load()?;
+        for tunnel in state
+            .tunnels
+            .iter()
+            .filter(|t| account.is_none_or(|a| t.account_name == a))
+        {
+            let key = (tunnel.account_name.clone(), tunnel.name.clone());
+            if daemon::get_daemon_status(tunnel).await != TunnelStatus::Running {
+                watchdog.forget(&key);
+                continue;
+            }
+            let metrics = TunnelMetrics::fetch(tunnel.metrics_url().as_deref(), timeout).await;
+            let connections = metrics.available.then_some(metrics.ha_connections);
+            // Regular samples keep the lifetime transfer totals close to exact
+            if metrics.available {
+                transfer::record(&[(&tunnel.tunnel_id, metrics.bytes_total())]).ok();
+            }
+            match watchdog.observe(key, connections, Instant::now()) {
+                Verdict::Waiting(down) if down.is_zero() => log_action(
+                    &log,
+                    &format!(
+                        "{}: no edge connections; restarting if none come back within {}s",
+                        tunnel.name,
+                        grace.as_secs()
+                    ),
+                ),
+                Verdict::Restart(down) => {
+                    log_action(
+                        &log,
+                        &format!(
+                            "{}: no edge connections for {}, restarting",
+                            tunnel.name,
+                            crate::format_uptime(down)
+                        ),
+                    );
+                    match restart(tunnel).await {
+                        Ok(true) => log_action(&log, &format!("{}: restarted", tunnel.name)),
+                        Ok(false) => log_action(
+                            &log,
+                            &format!("{}: stopped meanwhile, not restarting", tunnel.name),
+                        ),
+                        Err(e) => {
+                            log_action(&log, &format!("{}: restart failed: {:#}", tunnel.name, e))
+                        }
+                    }
+                }
+                Verdict::Recovered(down) => log_action(
+                    &log,
+                    &format!(
+                        "{}: {} edge connection(s) back after {}",
+                        tunnel.name,
+                        metrics.ha_connections,
+                        crate::format_uptime(down)
+                    ),
+                ),
+                _ => {}
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+// Restart just the daemon; DNS and config haven't changed. Under the lock, so a
+// `ytunnel stop` or `delete` can't interleave with it; a tunnel one of those
+// stopped while we waited stays stopped. Returns whether it restarted.
+async fn restart(tunnel: &PersistentTunnel) -> Result<bool> {
+    let _lock = config::lock()?;
+    let state = TunnelState::load()?;
+    let Some(current) = state.find_for_account(&tunnel.name, &tunnel.account_name) else {
+        return Ok(false);
+    };
+    if daemon::get_daemon_status(current).await != TunnelStatus::Running {
+        return Ok(false);
+    }
+    daemon::stop_daemon_fully(current, false).await?;
+    daemon::start_daemon(&current.name, &current.account_name).await?;
+    Ok(true)
+}
+
+// logs/watchdog.log in the config directory
+fn log_path() -> Result<PathBuf> {
+    let dir = config::config_dir()?.join("logs");
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    Ok(dir.join("watchdog.log"))
+}
+
+// Print a timestamped line and append it to the watchdog log (best-effort)
+fn log_action(path: &PathBuf, message: &str) {
+    let line = format!(
+        "{} {}",
+        cloudflare::format_timestamp(crate::now_secs()),
+        message
+    );
+    println!("{}", line);
+    if let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+    {
+        writeln!(file, "{}", line).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchdog_restarts_once_per_grace_period() {
+        let mut watchdog = Watchdog::new(Duration::from_secs(60));
+        let key = || ("default".to_string(), "api".to_string());
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(watchdog.observe(key(), Some(4), at(0)), Verdict::Connected);
+        assert_eq!(
+            watchdog.observe(key(), Some(0), at(10)),
+            Verdict::Waiting(Duration::ZERO)
+        );
+        // No metrics says nothing either way
+        assert_eq!(watchdog.observe(key(), None, at(40)), Verdict::Unknown);
+        assert_eq!(
+            watchdog.observe(key(), Some(0), at(70)),
+            Verdict::Restart(Duration::from_secs(60))
+        );
+        // Still down right after the restart: wait another grace period
+        assert_eq!(
+            watchdog.observe(key(), Some(0), at(85)),
+            Verdict::Waiting(Duration::from_secs(15))
+        );
+        assert_eq!(
+            watchdog.observe(key(), Some(2), at(90)),
+            Verdict::Recovered(Duration::from_secs(20))
+        );
+        assert_eq!(
+            watchdog.observe(key(), Some(2), at(100)),
+            Verdict::Connected
+        );
+
+        // A stopped tunnel starts over
+        watchdog.observe(key(), Some(0), at(110));
+        watchdog.forget(&key());
+        assert_eq!(
+            watchdog.observe(key(), Some(0), at(200)),
+            Verdict::Waiting(Duration::ZERO)
+        );
+    }
+}
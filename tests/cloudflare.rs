@@ -0,0 +1,321 @@
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::time::Duration;
+
+use ytunnel::cloudflare::{self, Client, Credentials, DnsChange};
+use ytunnel::config::{Account, Config, ZoneConfig};
+use ytunnel::error;
+
+// Serve canned HTTP responses, one per connection in order, returning the base URL
+fn serve(bodies: &[&'static str]) -> String {
+    let responses: Vec<_> = bodies.iter().map(|body| ("200 OK", *body)).collect();
+    serve_with_status(&responses)
+}
+
+fn serve_with_status(responses: &[(&'static str, &'static str)]) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let responses = responses.to_vec();
+    std::thread::spawn(move || {
+        for (status, body) in responses {
+            let Ok((mut stream, _)) = listener.accept() else {
+                return;
+            };
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    format!("http://{}/client/v4", addr)
+}
+
+fn serve_once(body: &'static str) -> String {
+    serve(&[body])
+}
+
+#[tokio::test]
+async fn test_client_uses_base_url() {
+    let base = serve_once(
+        r#"{"success":true,"errors":[],"result":[{"id":"z1","name":"example.com","account":{"id":"a1"}}]}"#,
+    );
+    let client = Client::new("token").with_base_url(&base);
+
+    let zones = client.list_zones().await.unwrap();
+    assert_eq!(zones.len(), 1);
+    assert_eq!(zones[0].name, "example.com");
+    assert_eq!(zones[0].account_id, "a1");
+}
+
+#[tokio::test]
+async fn test_client_reports_timeouts() {
+    // Accepts the connection but never answers
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let _held = listener.accept();
+        std::thread::sleep(Duration::from_secs(5));
+    });
+
+    let client = Client::new("token")
+        .with_base_url(&format!("http://{}", addr))
+        .with_timeout(Duration::from_millis(200));
+    let Err(err) = client.list_zones().await else {
+        panic!("expected an error");
+    };
+    assert!(
+        format!("{:#}", err).contains("did not respond"),
+        "{:#}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_client_reports_connection_refused() {
+    // Grab a free port, then close it so nothing is listening
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let client = Client::new("token").with_base_url(&format!("http://{}", addr));
+    let Err(err) = client.list_zones().await else {
+        panic!("expected an error");
+    };
+    assert!(
+        format!("{:#}", err).contains("could not connect"),
+        "{:#}",
+        err
+    );
+    // Offline, not rejected: callers skip the API instead of asking for a new token
+    assert!(cloudflare::is_unreachable(&err));
+    assert!(!cloudflare::is_auth_error(&err));
+    assert_eq!(error::exit_code(&err), 6);
+}
+
+fn account(account_id: &str) -> Config {
+    let mut cfg = Config::new("work".to_string());
+    cfg.add_account(Account {
+        name: "work".to_string(),
+        api_token: "old".to_string(),
+        api_key: None,
+        email: None,
+        account_id: account_id.to_string(),
+        default_zone_id: "z-old".to_string(),
+        default_zone_name: "old.example".to_string(),
+        zones: vec![ZoneConfig {
+            id: "z-old".to_string(),
+            name: "old.example".to_string(),
+        }],
+    })
+    .unwrap();
+    cfg
+}
+
+#[tokio::test]
+async fn test_rotating_credentials_refreshes_zones() {
+    let base = serve(&[
+        r#"{"success":true,"errors":[],"result":[{"id":"z1","name":"example.com","account":{"id":"a1"}},{"id":"z2","name":"other.com","account":{"id":"a2"}}]}"#,
+        r#"{"success":true,"errors":[],"result":[]}"#,
+    ]);
+    let client =
+        Client::with_credentials(Credentials::Token("new".to_string())).with_base_url(&base);
+    let mut cfg = account("a1");
+
+    let update = cfg
+        .update_credentials_with("work", &client, false)
+        .await
+        .unwrap();
+    assert_eq!(update.zones_added, vec!["example.com"]);
+    assert_eq!(update.zones_removed, vec!["old.example"]);
+    assert!(update.previous_account.is_none());
+
+    let acct = cfg.get_account(Some("work")).unwrap();
+    assert_eq!(acct.api_token, "new");
+    assert_eq!(acct.account_id, "a1");
+    // Zones of other accounts the token can see aren't adopted
+    assert_eq!(acct.zones.len(), 1);
+    assert_eq!(acct.default_zone_name, "example.com");
+}
+
+#[tokio::test]
+async fn test_rotating_credentials_refuses_another_account() {
+    let body = r#"{"success":true,"errors":[],"result":[{"id":"z9","name":"elsewhere.com","account":{"id":"a9"}}]}"#;
+    let client = Client::new("new").with_base_url(&serve_once(body));
+    let mut cfg = account("a1");
+
+    let Err(err) = cfg.update_credentials_with("work", &client, false).await else {
+        panic!("expected an error");
+    };
+    assert!(format!("{:#}", err).contains("a9"), "{:#}", err);
+    assert_eq!(cfg.get_account(Some("work")).unwrap().api_token, "old");
+
+    // --force can't pick between several other accounts
+    let two = r#"{"success":true,"errors":[],"result":[{"id":"z8","name":"first.com","account":{"id":"a8"}},{"id":"z9","name":"elsewhere.com","account":{"id":"a9"}}]}"#;
+    let client = Client::new("new").with_base_url(&serve_once(two));
+    let Err(err) = cfg.update_credentials_with("work", &client, true).await else {
+        panic!("expected an error");
+    };
+    assert!(format!("{:#}", err).contains("a8, a9"), "{:#}", err);
+    assert_eq!(cfg.get_account(Some("work")).unwrap().account_id, "a1");
+
+    // With a single one, it moves the account over: id, zones, and default zone
+    let client = Client::new("new").with_base_url(&serve_once(body));
+    let update = cfg
+        .update_credentials_with("work", &client, true)
+        .await
+        .unwrap();
+    assert_eq!(update.previous_account.as_deref(), Some("a1"));
+    let acct = cfg.get_account(Some("work")).unwrap();
+    assert_eq!(acct.api_token, "new");
+    assert_eq!(acct.account_id, "a9");
+    assert_eq!(acct.zones.len(), 1);
+    assert_eq!(acct.default_zone_name, "elsewhere.com");
+}
+
+#[tokio::test]
+async fn test_ensure_dns_record_lists_every_shadowing_record() {
+    // The tunnel CNAME is right, but stale address records would keep winning
+    let base = serve_once(
+        r#"{"success":true,"errors":[],"result":[
+            {"id":"r1","name":"app.example.com","type":"CNAME","content":"t1.cfargotunnel.com"},
+            {"id":"r2","name":"app.example.com","type":"A","content":"203.0.113.10"},
+            {"id":"r3","name":"app.example.com","type":"AAAA","content":"2001:db8::1"},
+            {"id":"r4","name":"app.example.com","type":"TXT","content":"v=spf1 -all"}
+        ],"result_info":{"total_pages":1}}"#,
+    );
+    let client = Client::new("token").with_base_url(&base);
+
+    let err = client
+        .ensure_dns_record("z1", "app.example.com", "t1")
+        .await
+        .unwrap_err();
+    let message = format!("{:#}", err);
+    assert!(message.contains("A 203.0.113.10 (id r2)"), "{}", message);
+    assert!(message.contains("AAAA 2001:db8::1 (id r3)"), "{}", message);
+    assert!(!message.contains("TXT"), "{}", message);
+    assert_eq!(ytunnel::error::exit_code(&err), 8);
+}
+
+#[tokio::test]
+async fn test_create_or_get_tunnel_reuses_only_tunnels_with_credentials() {
+    let dir = std::env::temp_dir().join(format!("ytunnel-cloudflare-{}", std::process::id()));
+    ytunnel::config::set_config_dir(dir.clone()).unwrap();
+    let base = serve(&[
+        // Nothing by that name yet, so it's created
+        r#"{"success":true,"errors":[],"result":[]}"#,
+        r#"{"success":true,"errors":[],"result":{"id":"t-new","name":"ytunnel-app","deleted_at":null}}"#,
+        // Found again, with the credentials file written above
+        r#"{"success":true,"errors":[],"result":[{"id":"t-new","name":"ytunnel-app","deleted_at":null}]}"#,
+        // Created elsewhere: no credentials here
+        r#"{"success":true,"errors":[],"result":[{"id":"t-other","name":"ytunnel-web","deleted_at":null}]}"#,
+    ]);
+    let client = Client::new("token").with_base_url(&base);
+
+    let created = client
+        .create_or_get_tunnel("a1", "ytunnel-app")
+        .await
+        .unwrap();
+    assert!(created.created);
+    assert_eq!(created.credentials_path, dir.join("t-new.json"));
+    assert!(created.credentials_path.exists());
+
+    let reused = client
+        .create_or_get_tunnel("a1", "ytunnel-app")
+        .await
+        .unwrap();
+    assert!(!reused.created);
+    assert_eq!(reused.tunnel.id, "t-new");
+    assert_eq!(reused.credentials_path, created.credentials_path);
+
+    let Err(err) = client.create_or_get_tunnel("a1", "ytunnel-web").await else {
+        panic!("expected an error");
+    };
+    let message = format!("{:#}", err);
+    assert!(
+        message.contains("Credentials file not found"),
+        "{}",
+        message
+    );
+    assert!(message.contains("ytunnel delete web"), "{}", message);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn test_ensure_dns_record_reports_what_it_changed() {
+    let base = serve(&[
+        // Already pointing at the tunnel: nothing to write
+        r#"{"success":true,"errors":[],"result":[
+            {"id":"r1","name":"app.example.com","type":"CNAME","content":"t1.cfargotunnel.com"}
+        ],"result_info":{"total_pages":1}}"#,
+        // Still pointing at a previous tunnel: repointed
+        r#"{"success":true,"errors":[],"result":[
+            {"id":"r1","name":"app.example.com","type":"CNAME","content":"t0.cfargotunnel.com"}
+        ],"result_info":{"total_pages":1}}"#,
+        r#"{"success":true,"errors":[],"result":{"id":"r1","name":"app.example.com","type":"CNAME","content":"t1.cfargotunnel.com"}}"#,
+        // No record at all: created
+        r#"{"success":true,"errors":[],"result":[],"result_info":{"total_pages":1}}"#,
+        r#"{"success":true,"errors":[],"result":{"id":"r2","name":"app.example.com","type":"CNAME","content":"t1.cfargotunnel.com"}}"#,
+    ]);
+    let client = Client::new("token").with_base_url(&base);
+
+    let mut changes = Vec::new();
+    for _ in 0..3 {
+        changes.push(
+            client
+                .ensure_dns_record("z1", "app.example.com", "t1")
+                .await
+                .unwrap(),
+        );
+    }
+    assert_eq!(
+        changes,
+        vec![
+            DnsChange::Unchanged,
+            DnsChange::Updated {
+                previous: "t0.cfargotunnel.com".to_string()
+            },
+            DnsChange::Created,
+        ]
+    );
+    assert_eq!(
+        changes[1].describe("app.example.com"),
+        "Repointed DNS record: app.example.com (was t0.cfargotunnel.com)"
+    );
+}
+
+#[tokio::test]
+async fn test_get_tunnel_treats_deleted_and_unknown_ids_as_missing() {
+    let base = serve_with_status(&[
+        (
+            "200 OK",
+            r#"{"success":true,"errors":[],"result":{"id":"t1","name":"ytunnel-app","deleted_at":null,"status":"healthy"}}"#,
+        ),
+        (
+            "200 OK",
+            r#"{"success":true,"errors":[],"result":{"id":"t1","name":"ytunnel-app","deleted_at":"2025-01-01T00:00:00Z"}}"#,
+        ),
+        (
+            "404 Not Found",
+            r#"{"success":false,"errors":[{"code":1003,"message":"Tunnel not found"}],"result":null}"#,
+        ),
+        (
+            "403 Forbidden",
+            r#"{"success":false,"errors":[{"code":10000,"message":"Authentication error"}],"result":null}"#,
+        ),
+    ]);
+    let client = Client::new("token").with_base_url(&base);
+
+    let found = client.get_tunnel("a1", "t1").await.unwrap();
+    assert_eq!(found.map(|t| t.id).as_deref(), Some("t1"));
+    assert!(client.get_tunnel("a1", "t1").await.unwrap().is_none());
+    assert!(client.get_tunnel("a1", "t1").await.unwrap().is_none());
+    // Other failures are still errors, not a missing tunnel
+    assert!(client.get_tunnel("a1", "t1").await.is_err());
+}
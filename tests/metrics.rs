@@ -0,0 +1,121 @@
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::time::Duration;
+
+use ytunnel::metrics::{self, TunnelMetrics};
+
+#[test]
+fn test_parse_through_library_api() {
+    let text = r#"
+# TYPE cloudflared_tunnel_total_requests counter
+cloudflared_tunnel_total_requests 42
+cloudflared_tunnel_ha_connections 4
+cloudflared_tunnel_response_by_code{status_code="200"} 40
+cloudflared_tunnel_response_by_code{status_code="502"} 2
+cloudflared_tunnel_server_locations{connection_id="0",edge_location="dfw08"} 1
+cloudflared_tunnel_server_locations{connection_id="1",edge_location="den01"} 1
+"#;
+
+    let metrics = TunnelMetrics::parse(text);
+    assert!(metrics.available);
+    assert_eq!(metrics.total_requests, 42);
+    assert_eq!(metrics.ha_connections, 4);
+    assert_eq!(metrics.response_codes.get(&502), Some(&2));
+    assert_eq!(metrics.locations_string(), "den01 (1), dfw08 (1)");
+}
+
+#[test]
+fn test_exposition_round_trips_through_the_parser() {
+    let text = r#"
+cloudflared_tunnel_total_requests 42
+cloudflared_tunnel_request_errors 3
+cloudflared_tunnel_ha_connections 4
+cloudflared_tunnel_concurrent_requests_per_tunnel 2
+cloudflared_tunnel_response_by_code{status_code="502"} 2
+cloudflared_tunnel_response_by_code{status_code="200"} 40
+cloudflared_tunnel_server_locations{connection_id="0",edge_location="dfw08"} 1
+"#;
+    let metrics = TunnelMetrics::parse(text);
+    let exposition = metrics.to_exposition();
+    assert!(exposition.contains("# TYPE cloudflared_tunnel_total_requests counter\n"));
+    assert!(exposition.contains("ytunnel_edge_location_connections{edge_location=\"dfw08\"} 1\n"));
+    // Status codes in a stable order
+    let ok = exposition.find("status_code=\"200\"").unwrap();
+    let bad = exposition.find("status_code=\"502\"").unwrap();
+    assert!(ok < bad);
+
+    let reparsed = TunnelMetrics::parse(&exposition);
+    assert_eq!(reparsed.total_requests, 42);
+    assert_eq!(reparsed.request_errors, 3);
+    assert_eq!(reparsed.ha_connections, 4);
+    assert_eq!(reparsed.concurrent_requests, 2);
+    assert_eq!(reparsed.response_codes, metrics.response_codes);
+}
+
+#[tokio::test]
+async fn test_push_groups_by_tunnel_and_account() {
+    assert_eq!(
+        metrics::pushgateway_url("http://gw:9091/", "api", "work")
+            .unwrap()
+            .as_str(),
+        "http://gw:9091/metrics/job/ytunnel/tunnel/api/account/work"
+    );
+
+    // Capture the request a Pushgateway would receive
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_millis(500)))
+            .unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        while let Ok(n) = stream.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..n]);
+            if String::from_utf8_lossy(&request).contains("ha_connections 4") {
+                break;
+            }
+        }
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        String::from_utf8_lossy(&request).to_string()
+    });
+
+    let metrics = TunnelMetrics::parse("cloudflared_tunnel_ha_connections 4\n");
+    metrics::push(
+        &format!("http://{}", addr),
+        "api",
+        "work",
+        &metrics,
+        Duration::from_secs(2),
+    )
+    .await
+    .unwrap();
+    let request = server.join().unwrap();
+    assert!(
+        request.starts_with("POST /metrics/job/ytunnel/tunnel/api/account/work HTTP/1.1"),
+        "{}",
+        request
+    );
+    assert!(request.contains("cloudflared_tunnel_ha_connections 4\n"));
+
+    // Nothing listening: an error, not a panic
+    let closed = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    assert!(metrics::push(
+        &format!("http://{}", closed),
+        "api",
+        "work",
+        &metrics,
+        Duration::from_secs(2)
+    )
+    .await
+    .is_err());
+}
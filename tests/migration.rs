@@ -0,0 +1,217 @@
+// Upgrades must never lose data: load config.toml and tunnels.toml as older
+// releases wrote them, through the same code path the CLI uses, and check what
+// ends up on disk.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use ytunnel::config::{self, CONFIG_VERSION};
+use ytunnel::state::{TunnelState, STATE_VERSION};
+
+// The config directory can only be set once per process, so every test shares it
+// and each one only touches its own file
+fn config_dir() -> &'static PathBuf {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let dir = std::env::temp_dir().join(format!("ytunnel-migration-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        config::set_config_dir(dir.clone()).unwrap();
+        dir
+    })
+}
+
+fn toml_value(text: &str) -> toml::Value {
+    toml::from_str(text).unwrap()
+}
+
+#[test]
+fn test_config_files_migrate_and_round_trip() {
+    let path = config_dir().join("config.toml");
+
+    // The original single-account layout
+    fs::write(
+        &path,
+        r#"
+api_token = "tok"
+account_id = "acct"
+default_zone_id = "z1"
+default_zone_name = "example.com"
+
+[[zones]]
+id = "z1"
+name = "example.com"
+
+[[zones]]
+id = "z2"
+name = "example.org"
+"#,
+    )
+    .unwrap();
+    let cfg = config::load_config().unwrap();
+    assert_eq!(cfg.version, CONFIG_VERSION);
+    assert_eq!(cfg.selected_account, "default");
+    assert_eq!(cfg.accounts.len(), 1);
+    let acct = cfg.get_account(None).unwrap();
+    assert_eq!(acct.name, "default");
+    assert_eq!(acct.api_token, "tok");
+    assert_eq!(acct.account_id, "acct");
+    assert_eq!(acct.default_zone_id, "z1");
+    let zones: Vec<&str> = acct.zones.iter().map(|z| z.name.as_str()).collect();
+    assert_eq!(zones, ["example.com", "example.org"]);
+
+    // The migrated layout was written back, and loading it again changes nothing
+    let migrated = fs::read_to_string(&path).unwrap();
+    assert!(migrated.contains("[[accounts]]"), "{}", migrated);
+    assert!(
+        toml_value(&migrated).get("api_token").is_none(),
+        "{}",
+        migrated
+    );
+    config::load_config().unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), migrated);
+
+    // A current file with every section keeps all of it through load and save
+    let current = format!(
+        r#"
+version = {}
+selected_account = "work"
+check_updates = false
+tunnel_prefix = "acme-"
+
+[[accounts]]
+name = "work"
+api_token = ""
+api_key = "key"
+email = "ops@example.com"
+account_id = "acct-2"
+default_zone_id = "z9"
+default_zone_name = "example.net"
+
+[[accounts.zones]]
+id = "z9"
+name = "example.net"
+
+[tui]
+health_timeout_secs = 9
+list_percent = 35
+show_metrics = false
+
+[metrics]
+timeout_secs = 4
+
+[api]
+timeout_secs = 45
+
+[run]
+on_exit = "delete"
+detect_ports = [3000, 9000]
+
+[daemon]
+launchd_prefix = "com.acme.tunnels"
+systemd_prefix = "acme-tunnel-"
+metrics_bind = "::1"
+
+[presets.staging]
+zone = "example.net"
+protocol = "http2"
+start = true
+wait_healthy_secs = 30
+auto_start = false
+manage_dns = false
+description = "Staging services"
+"#,
+        CONFIG_VERSION
+    );
+    fs::write(&path, &current).unwrap();
+    let cfg = config::load_config().unwrap();
+    config::save_config(&cfg).unwrap();
+    let saved = fs::read_to_string(&path).unwrap();
+    assert_eq!(toml_value(&saved), toml_value(&current), "{}", saved);
+}
+
+#[test]
+fn test_tunnel_state_migrates_and_round_trips() {
+    let path = config_dir().join("tunnels.toml");
+
+    // Written before multiple accounts: no version and no account_name
+    fs::write(
+        &path,
+        r#"
+[[tunnels]]
+name = "api"
+target = "localhost:3000"
+zone_id = "z1"
+zone_name = "example.com"
+hostname = "api.example.com"
+tunnel_id = "id-1"
+enabled = true
+
+[[tunnels]]
+name = "web"
+account_name = ""
+target = "localhost:8080"
+zone_id = "z1"
+zone_name = "example.com"
+hostname = "web.example.com"
+tunnel_id = "id-2"
+enabled = false
+auto_start = true
+"#,
+    )
+    .unwrap();
+    let state = TunnelState::load_and_migrate("default").unwrap();
+    assert_eq!(state.version, STATE_VERSION);
+    assert!(state.tunnels.iter().all(|t| t.account_name == "default"));
+    assert!(state.tunnels[1].auto_start);
+
+    // The assignment was saved
+    let reloaded = TunnelState::load().unwrap();
+    let accounts: Vec<&str> = reloaded
+        .tunnels
+        .iter()
+        .map(|t| t.account_name.as_str())
+        .collect();
+    assert_eq!(accounts, ["default", "default"]);
+    assert_eq!(reloaded.tunnels[0].target, "localhost:3000");
+    assert_eq!(reloaded.tunnels[1].tunnel_id, "id-2");
+
+    // A current file with every optional field keeps all of it through load and save
+    let current = format!(
+        r#"
+version = {}
+
+[[tunnels]]
+name = "api"
+account_name = "work"
+target = "localhost:3000"
+zone_id = "z1"
+zone_name = "example.com"
+hostname = "api.example.com"
+tunnel_id = "id-1"
+enabled = true
+auto_start = true
+metrics_port = 21500
+protocol = "http2"
+log_level = "debug"
+manage_dns = false
+description = "Webhook receiver"
+connect_time_ms = 850
+maintenance = "http_status:503"
+fallback = "https://example.com"
+backends = ["localhost:3001", "localhost:3002"]
+
+[[tunnels.extra_hostnames]]
+zone_id = "z2"
+zone_name = "example.org"
+hostname = "api.example.org"
+"#,
+        STATE_VERSION
+    );
+    fs::write(&path, &current).unwrap();
+    let state = TunnelState::load_and_migrate("default").unwrap();
+    state.save().unwrap();
+    let saved = fs::read_to_string(&path).unwrap();
+    assert_eq!(toml_value(&saved), toml_value(&current), "{}", saved);
+}